@@ -0,0 +1,199 @@
+#[cfg(feature = "server")]
+use crate::db::models::SavedPreset;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "server")]
+pub mod routes;
+
+/// Cookie holding the visitor's session token
+pub const SESSION_COOKIE: &str = "session";
+
+/// How long a sign-in lasts
+pub const SESSION_DAYS: i64 = 30;
+
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// Argon2 doesn't need more, and it keeps hashing cheap for oversized inputs
+pub const MAX_PASSWORD_LEN: usize = 128;
+
+/// Most presets one account can save
+pub const MAX_PRESETS: usize = 20;
+
+pub const MAX_PRESET_NAME_LEN: usize = 40;
+
+/// Why a registration, sign-in or preset save was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountError {
+    InvalidEmail,
+    WeakPassword,
+    EmailTaken,
+    InvalidCredentials,
+    InvalidPresetName,
+    TooManyPresets,
+}
+
+impl AccountError {
+    /// Short code passed back to the account page as `?status=`
+    pub fn status(&self) -> &'static str {
+        match self {
+            AccountError::InvalidEmail => "invalid_email",
+            AccountError::WeakPassword => "weak_password",
+            AccountError::EmailTaken => "email_taken",
+            AccountError::InvalidCredentials => "invalid_credentials",
+            AccountError::InvalidPresetName => "invalid_preset",
+            AccountError::TooManyPresets => "too_many_presets",
+        }
+    }
+}
+
+/// Message for a `?status=` code on the account page (unknown codes show nothing)
+pub fn status_message(status: &str) -> Option<String> {
+    const ERRORS: &[AccountError] = &[
+        AccountError::InvalidEmail,
+        AccountError::WeakPassword,
+        AccountError::EmailTaken,
+        AccountError::InvalidCredentials,
+        AccountError::InvalidPresetName,
+        AccountError::TooManyPresets,
+    ];
+    if let Some(error) = ERRORS.iter().find(|e| e.status() == status) {
+        return Some(error.to_string());
+    }
+    match status {
+        "deleted" => Some("Preset deleted".to_string()),
+        "error" => Some("Something went wrong, please try again".to_string()),
+        _ => None,
+    }
+}
+
+impl std::fmt::Display for AccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccountError::InvalidEmail => write!(f, "That doesn't look like an email address"),
+            AccountError::WeakPassword => write!(
+                f,
+                "Passwords must be {} to {} characters",
+                MIN_PASSWORD_LEN, MAX_PASSWORD_LEN
+            ),
+            AccountError::EmailTaken => write!(f, "An account with that email already exists"),
+            AccountError::InvalidCredentials => write!(f, "Wrong email or password"),
+            AccountError::InvalidPresetName => write!(
+                f,
+                "Preset names must be 1 to {} characters",
+                MAX_PRESET_NAME_LEN
+            ),
+            AccountError::TooManyPresets => write!(f, "You can save up to {} presets", MAX_PRESETS),
+        }
+    }
+}
+
+impl std::error::Error for AccountError {}
+
+/// Trimmed, lowercased email address, if it looks deliverable
+pub fn normalize_email(email: &str) -> Result<String, AccountError> {
+    let email = email.trim().to_lowercase();
+    let valid = email.len() <= 254
+        && !email.chars().any(char::is_whitespace)
+        && email
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.') && !domain.contains('@'));
+    if valid {
+        Ok(email)
+    } else {
+        Err(AccountError::InvalidEmail)
+    }
+}
+
+pub fn check_password(password: &str) -> Result<(), AccountError> {
+    let len = password.chars().count();
+    if (MIN_PASSWORD_LEN..=MAX_PASSWORD_LEN).contains(&len) {
+        Ok(())
+    } else {
+        Err(AccountError::WeakPassword)
+    }
+}
+
+pub fn check_preset_name(name: &str) -> Result<String, AccountError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_PRESET_NAME_LEN {
+        Err(AccountError::InvalidPresetName)
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+/// A fresh random session token for the session cookie, or None if the OS has no randomness to give
+#[cfg(feature = "server")]
+pub fn new_session_token() -> Option<String> {
+    let mut bytes = [0u8; 32];
+    if let Err(e) = getrandom::fill(&mut bytes) {
+        eprintln!("Failed to generate session token: {}", e);
+        return None;
+    }
+    Some(hex(&bytes))
+}
+
+/// What the DB stores instead of the token, so a leaked DB can't be replayed as cookies
+pub fn session_token_hash(token: &str) -> String {
+    hex(&Sha256::digest(token.as_bytes()))
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The visitor's account state, for the presets bar above the filters
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AccountNav {
+    /// Signed-in email (None for anonymous visitors)
+    pub email: Option<String>,
+    pub presets: Vec<FilterPreset>,
+}
+
+/// A saved search + version + tags combination, as shown in the Filters dropdown
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FilterPreset {
+    /// Record key of the preset
+    pub id: String,
+    pub name: String,
+    pub search: String,
+    /// Version filter parameter ("" = latest, "all", or a prefix)
+    pub version: String,
+    pub tags: Vec<String>,
+}
+
+impl FilterPreset {
+    /// Server list URL with this preset's filters applied
+    pub fn url(&self) -> String {
+        let mut params = Vec::new();
+        if !self.search.is_empty() {
+            params.push(format!("search={}", urlencoding::encode(&self.search)));
+        }
+        if !self.version.is_empty() {
+            params.push(format!("version={}", urlencoding::encode(&self.version)));
+        }
+        if !self.tags.is_empty() {
+            params.push(format!("tags={}", urlencoding::encode(&self.tags.join(","))));
+        }
+
+        if params.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/?{}", params.join("&"))
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl From<SavedPreset> for FilterPreset {
+    fn from(preset: SavedPreset) -> Self {
+        FilterPreset {
+            id: preset.id.map(|id| id.id.to_raw()).unwrap_or_default(),
+            name: preset.name,
+            search: preset.search,
+            version: preset.version,
+            tags: preset.tags,
+        }
+    }
+}
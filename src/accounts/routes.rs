@@ -0,0 +1,308 @@
+use crate::accounts::{
+    check_password, check_preset_name, new_session_token, normalize_email, session_token_hash, AccountError,
+    FilterPreset, MAX_PASSWORD_LEN, MAX_PRESETS, SESSION_COOKIE, SESSION_DAYS,
+};
+use crate::db::models::NewSavedPreset;
+use crate::db::queries::DbClient;
+use crate::filter::VersionFilter;
+use rocket::form::{Form, FromForm};
+use rocket::http::{Cookie, CookieJar, SameSite, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::Redirect;
+use rocket::{get, post, Request, State};
+use std::sync::Arc;
+
+/// Whether accounts are turned on (ACCOUNTS, never in read-only mode), managed for the account routes
+#[derive(Debug, Clone, Copy)]
+pub struct AccountsEnabled(pub bool);
+
+/// Form for signing in or registering
+#[derive(FromForm)]
+pub struct CredentialsForm {
+    email: String,
+    password: String,
+}
+
+/// Form for saving the current filters as a named preset
+#[derive(Debug, FromForm)]
+pub struct PresetForm {
+    name: String,
+    search: String,
+    version: String,
+    tags: String, // Comma-separated list of tags
+}
+
+/// Email of the account signed in via the session cookie
+/// Forwards when accounts are disabled or there's no valid session, so take it as Option<SignedIn>
+pub struct SignedIn(pub String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SignedIn {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if !req.rocket().state::<AccountsEnabled>().is_some_and(|enabled| enabled.0) {
+            return Outcome::Forward(Status::NotFound);
+        }
+        let Some(db) = req.rocket().state::<Arc<DbClient>>() else {
+            return Outcome::Forward(Status::NotFound);
+        };
+        let Some(token) = req.cookies().get(SESSION_COOKIE).map(|c| c.value().to_string()) else {
+            return Outcome::Forward(Status::Unauthorized);
+        };
+        match db.get_session_email(&session_token_hash(&token)).await {
+            Ok(Some(email)) => Outcome::Success(SignedIn(email)),
+            Ok(None) => Outcome::Forward(Status::Unauthorized),
+            Err(e) => {
+                eprintln!("Failed to look up session: {}", e);
+                Outcome::Forward(Status::Unauthorized)
+            }
+        }
+    }
+}
+
+/// Saved presets of an account (empty if they can't be loaded)
+pub async fn load_presets(db: &DbClient, email: &str) -> Vec<FilterPreset> {
+    match db.get_filter_presets(email).await {
+        Ok(presets) => presets.into_iter().map(FilterPreset::from).collect(),
+        Err(e) => {
+            eprintln!("Failed to load filter presets: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Back to the account page with a status message
+fn account_redirect(status: &str) -> Redirect {
+    Redirect::to(format!("/account?status={}", status))
+}
+
+/// Create a session for `email` and set the session cookie
+async fn start_session(db: &DbClient, cookies: &CookieJar<'_>, email: &str) -> Result<(), String> {
+    let token = new_session_token().ok_or("no session token")?;
+    let expires_at = (chrono::Utc::now() + chrono::Duration::days(SESSION_DAYS)).to_rfc3339();
+    db.create_session(&session_token_hash(&token), email, &expires_at)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let cookie = Cookie::build((SESSION_COOKIE, token))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .max_age(rocket::time::Duration::days(SESSION_DAYS));
+    cookies.add(cookie);
+    Ok(())
+}
+
+/// Create an account and sign it in
+#[post("/account/register", data = "<form>")]
+pub async fn register_account(
+    db: &State<Arc<DbClient>>,
+    enabled: &State<AccountsEnabled>,
+    cookies: &CookieJar<'_>,
+    form: Form<CredentialsForm>,
+) -> Redirect {
+    if !enabled.0 {
+        return Redirect::to("/");
+    }
+    let email = match normalize_email(&form.email) {
+        Ok(email) => email,
+        Err(e) => return account_redirect(e.status()),
+    };
+    if let Err(e) = check_password(&form.password) {
+        return account_redirect(e.status());
+    }
+
+    match db.create_account(&email, &form.password).await {
+        Ok(true) => {}
+        Ok(false) => return account_redirect(AccountError::EmailTaken.status()),
+        Err(e) => {
+            eprintln!("Failed to create account: {}", e);
+            return account_redirect("error");
+        }
+    }
+    if let Err(e) = start_session(db, cookies, &email).await {
+        eprintln!("Failed to start session: {}", e);
+        return account_redirect("error");
+    }
+
+    Redirect::to("/")
+}
+
+/// Sign in with email and password
+#[post("/account/login", data = "<form>")]
+pub async fn sign_in(
+    db: &State<Arc<DbClient>>,
+    enabled: &State<AccountsEnabled>,
+    cookies: &CookieJar<'_>,
+    form: Form<CredentialsForm>,
+) -> Redirect {
+    if !enabled.0 {
+        return Redirect::to("/");
+    }
+    let invalid = || account_redirect(AccountError::InvalidCredentials.status());
+    let Ok(email) = normalize_email(&form.email) else {
+        return invalid();
+    };
+    // Don't hash arbitrarily long input just to reject it
+    if form.password.chars().count() > MAX_PASSWORD_LEN {
+        return invalid();
+    }
+
+    match db.verify_account(&email, &form.password).await {
+        Ok(true) => {}
+        Ok(false) => return invalid(),
+        Err(e) => {
+            eprintln!("Failed to verify account: {}", e);
+            return account_redirect("error");
+        }
+    }
+    if let Err(e) = start_session(db, cookies, &email).await {
+        eprintln!("Failed to start session: {}", e);
+        return account_redirect("error");
+    }
+
+    Redirect::to("/")
+}
+
+/// End the current session
+#[post("/account/logout")]
+pub async fn sign_out(db: &State<Arc<DbClient>>, cookies: &CookieJar<'_>) -> Redirect {
+    if let Some(token) = cookies.get(SESSION_COOKIE).map(|c| c.value().to_string()) {
+        if let Err(e) = db.delete_session(&session_token_hash(&token)).await {
+            eprintln!("Failed to delete session: {}", e);
+        }
+        cookies.remove(Cookie::build(SESSION_COOKIE).path("/"));
+    }
+
+    Redirect::to("/")
+}
+
+/// Save the current search, version and tags as a named preset, then show them
+#[post("/presets", data = "<form>")]
+pub async fn save_preset(db: &State<Arc<DbClient>>, signed_in: Option<SignedIn>, form: Form<PresetForm>) -> Redirect {
+    let Some(SignedIn(email)) = signed_in else {
+        return Redirect::to("/account");
+    };
+    let name = match check_preset_name(&form.name) {
+        Ok(name) => name,
+        Err(e) => return account_redirect(e.status()),
+    };
+
+    // Saving under an existing name replaces that preset, so only new names count toward the limit
+    let existing = load_presets(db, &email).await;
+    if existing.len() >= MAX_PRESETS && !existing.iter().any(|p| p.name == name) {
+        return account_redirect(AccountError::TooManyPresets.status());
+    }
+
+    let preset = NewSavedPreset {
+        email,
+        name,
+        search: form.search.trim().to_string(),
+        version: VersionFilter::parse(&form.version).as_param().to_string(),
+        tags: form
+            .tags
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let url = FilterPreset {
+        search: preset.search.clone(),
+        version: preset.version.clone(),
+        tags: preset.tags.clone(),
+        ..Default::default()
+    }
+    .url();
+
+    match db.save_filter_preset(preset).await {
+        Ok(()) => Redirect::to(url),
+        Err(e) => {
+            eprintln!("Failed to save filter preset: {}", e);
+            account_redirect("error")
+        }
+    }
+}
+
+/// Presets dropdown target: go to the server list with the preset's filters
+#[get("/presets/load?<id>")]
+pub async fn load_preset(db: &State<Arc<DbClient>>, signed_in: Option<SignedIn>, id: Option<String>) -> Redirect {
+    let Some(SignedIn(email)) = signed_in else {
+        return Redirect::to("/account");
+    };
+    let presets = load_presets(db, &email).await;
+    let url = presets
+        .iter()
+        .find(|p| Some(&p.id) == id.as_ref())
+        .map(FilterPreset::url)
+        .unwrap_or_else(|| "/".to_string());
+
+    Redirect::to(url)
+}
+
+/// Delete one of the signed-in account's presets
+#[post("/presets/<id>/delete")]
+pub async fn delete_preset(db: &State<Arc<DbClient>>, signed_in: Option<SignedIn>, id: &str) -> Redirect {
+    let Some(SignedIn(email)) = signed_in else {
+        return Redirect::to("/account");
+    };
+    if let Err(e) = db.delete_filter_preset(&email, id).await {
+        eprintln!("Failed to delete filter preset: {}", e);
+        return account_redirect("error");
+    }
+
+    account_redirect("deleted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocket::http::ContentType;
+    use rocket::local::asynchronous::Client;
+
+    async fn client() -> Client {
+        let db = DbClient::connect("mem://", "test", "test", None, None).await.expect("in-memory database");
+        let rocket = rocket::build()
+            .manage(Arc::new(db))
+            .manage(AccountsEnabled(true))
+            .mount("/", rocket::routes![register_account, sign_in, sign_out, save_preset]);
+        Client::tracked(rocket).await.expect("valid rocket")
+    }
+
+    async fn post(client: &Client, path: &'static str, body: &'static str) -> String {
+        let response = client.post(path).header(ContentType::Form).body(body).dispatch().await;
+        response.headers().get_one("Location").unwrap_or_default().to_string()
+    }
+
+    #[rocket::async_test]
+    async fn register_sign_out_and_in() {
+        let client = client().await;
+        assert_eq!(post(&client, "/account/register", "email=a@example.com&password=hunter22").await, "/");
+        let token = client.cookies().get(SESSION_COOKIE).expect("session cookie").value().to_string();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, "0".repeat(64));
+
+        // Presets need a session
+        assert_eq!(post(&client, "/presets", "name=Mine&search=space&version=&tags=").await, "/?search=space");
+        assert_eq!(post(&client, "/account/logout", "").await, "/");
+        assert!(client.cookies().get(SESSION_COOKIE).is_none());
+        assert_eq!(post(&client, "/presets", "name=Mine&search=space&version=&tags=").await, "/account");
+
+        assert_eq!(
+            post(&client, "/account/login", "email=a@example.com&password=wrong-password").await,
+            "/account?status=invalid_credentials"
+        );
+        assert_eq!(post(&client, "/account/login", "email=A@example.com&password=hunter22").await, "/");
+        assert_ne!(client.cookies().get(SESSION_COOKIE).expect("new session").value(), token);
+    }
+
+    #[rocket::async_test]
+    async fn registration_checks_input() {
+        let client = client().await;
+        assert_eq!(post(&client, "/account/register", "email=nope&password=hunter22").await, "/account?status=invalid_email");
+        assert_eq!(post(&client, "/account/register", "email=a@example.com&password=short").await, "/account?status=weak_password");
+        assert_eq!(post(&client, "/account/register", "email=a@example.com&password=hunter22").await, "/");
+        assert_eq!(post(&client, "/account/register", "email=a@example.com&password=hunter22").await, "/account?status=email_taken");
+    }
+}
@@ -0,0 +1,210 @@
+use crate::db::models::{CachedServer, DowntimeAlert};
+use chrono::{DateTime, Duration, Utc};
+
+/// How long a server must sit at 0 players before an alert is sent
+pub const EMPTY_ALERT_MINUTES: i64 = 30;
+
+/// How long an operator has to put the claim code in the server's description
+pub const CLAIM_HOURS: i64 = 24;
+
+/// An alert is removed once its server has been unlisted this long
+pub const UNLISTED_REMOVE_DAYS: i64 = 7;
+
+/// Outcome of evaluating a downtime alert against the current listing
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertAction {
+    /// Nothing changed
+    None,
+    /// Update tracking state without notifying
+    Update { empty_since: Option<String>, alerted: bool },
+    /// Send a notification and update tracking state
+    Notify {
+        message: String,
+        empty_since: Option<String>,
+        alerted: bool,
+    },
+    /// Send a final notification and delete the alert (the server has been gone for days)
+    NotifyAndRemove { message: String },
+    /// The claim code showed up in the description: confirm and mark the alert claimed
+    Claim { message: String },
+    /// Delete an alert that was never claimed
+    Remove,
+}
+
+/// A new claim code for an alert, e.g. "alerts-3f9a1c07"; None when the OS has no randomness
+#[cfg(feature = "server")]
+pub fn new_claim_code() -> Option<String> {
+    let mut bytes = [0u8; 4];
+    if let Err(e) = getrandom::fill(&mut bytes) {
+        eprintln!("Failed to generate alert claim code: {}", e);
+        return None;
+    }
+    Some(format!("alerts-{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>()))
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Decide what to do with an alert given the server's current state (None = not listed)
+/// Unclaimed alerts only wait for their claim code, so nothing is sent to a webhook the server's
+/// operator hasn't vouched for
+pub fn evaluate(alert: &DowntimeAlert, server: Option<&CachedServer>, now: DateTime<Utc>) -> AlertAction {
+    let name = &alert.server_name;
+
+    if !alert.claimed {
+        if server.is_some_and(|s| s.description.contains(&alert.claim_code)) {
+            return AlertAction::Claim {
+                message: format!(
+                    "✅ Downtime alerts are on for **{}**. You can take the code out of the description now.",
+                    name
+                ),
+            };
+        }
+        let expired = parse_time(&alert.created_at).is_none_or(|at| now - at > Duration::hours(CLAIM_HOURS));
+        return if expired { AlertAction::Remove } else { AlertAction::None };
+    }
+
+    let Some(server) = server else {
+        // empty_since also tracks how long the server has been unlisted
+        if !alert.alerted {
+            return AlertAction::Notify {
+                message: format!("⚠ **{}** has disappeared from the Factorio server listing.", name),
+                empty_since: alert.empty_since.clone().or_else(|| Some(now.to_rfc3339())),
+                alerted: true,
+            };
+        }
+        let Some(since) = alert.empty_since.as_deref().and_then(parse_time) else {
+            return AlertAction::Update {
+                empty_since: Some(now.to_rfc3339()),
+                alerted: true,
+            };
+        };
+        if now - since > Duration::days(UNLISTED_REMOVE_DAYS) {
+            return AlertAction::NotifyAndRemove {
+                message: format!(
+                    "⚠ **{}** hasn't been listed for {} days. This alert has been removed.",
+                    name, UNLISTED_REMOVE_DAYS
+                ),
+            };
+        }
+        return AlertAction::None;
+    };
+
+    if server.player_count > 0 {
+        if alert.alerted {
+            return AlertAction::Notify {
+                message: format!("✅ **{}** is back with {} player(s) online.", name, server.player_count),
+                empty_since: None,
+                alerted: false,
+            };
+        }
+        if alert.empty_since.is_some() {
+            return AlertAction::Update { empty_since: None, alerted: false };
+        }
+        return AlertAction::None;
+    }
+
+    // Server is listed but empty
+    let Some(empty_since) = alert.empty_since.as_deref().and_then(parse_time) else {
+        return AlertAction::Update {
+            empty_since: Some(now.to_rfc3339()),
+            alerted: alert.alerted,
+        };
+    };
+
+    let empty_for = now - empty_since;
+    if !alert.alerted && empty_for > Duration::minutes(EMPTY_ALERT_MINUTES) {
+        return AlertAction::Notify {
+            message: format!(
+                "⚠ **{}** has had 0 players for over {} minutes.",
+                name, EMPTY_ALERT_MINUTES
+            ),
+            empty_since: alert.empty_since.clone(),
+            alerted: true,
+        };
+    }
+
+    AlertAction::None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(time: &str) -> DateTime<Utc> {
+        parse_time(time).unwrap()
+    }
+
+    fn alert(claimed: bool, empty_since: Option<&str>, alerted: bool) -> DowntimeAlert {
+        DowntimeAlert {
+            id: None,
+            server_id: "abc".into(),
+            server_name: "Big Base".to_string(),
+            webhook_url: "https://discord.com/api/webhooks/1/x".to_string(),
+            claim_code: "alerts-0123abcd".to_string(),
+            claimed,
+            empty_since: empty_since.map(str::to_string),
+            alerted,
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn server(description: &str, player_count: usize) -> CachedServer {
+        serde_json::from_value(serde_json::json!({
+            "game_id": 1,
+            "server_id": "abc",
+            "name": "Big Base",
+            "description": description,
+            "max_players": 10,
+            "player_count": player_count,
+            "game_time_elapsed": 0,
+            "has_password": false,
+            "game_version": "2.0.28",
+            "build_version": 0,
+            "cached_at": "2026-01-01T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn unclaimed_alerts_only_wait_for_their_code() {
+        let now = at("2026-01-01T01:00:00Z");
+        assert_eq!(evaluate(&alert(false, None, false), None, now), AlertAction::None);
+        assert_eq!(evaluate(&alert(false, None, false), Some(&server("Welcome!", 0)), now), AlertAction::None);
+        assert!(matches!(
+            evaluate(&alert(false, None, false), Some(&server("Welcome! alerts-0123abcd", 0)), now),
+            AlertAction::Claim { .. }
+        ));
+        assert_eq!(
+            evaluate(&alert(false, None, false), Some(&server("Welcome!", 0)), at("2026-01-02T01:00:00Z")),
+            AlertAction::Remove
+        );
+    }
+
+    #[test]
+    fn unlisted_servers_are_kept_until_gone_for_days() {
+        let now = at("2026-01-01T01:00:00Z");
+        assert!(matches!(
+            evaluate(&alert(true, None, false), None, now),
+            AlertAction::Notify { alerted: true, empty_since: Some(_), .. }
+        ));
+        let unlisted = alert(true, Some("2026-01-01T00:00:00Z"), true);
+        assert_eq!(evaluate(&unlisted, None, now), AlertAction::None);
+        assert!(matches!(evaluate(&unlisted, None, at("2026-01-09T00:00:00Z")), AlertAction::NotifyAndRemove { .. }));
+        // Back after a restart, under a new game_id but the same server_id
+        assert!(matches!(evaluate(&unlisted, Some(&server("", 3)), now), AlertAction::Notify { alerted: false, .. }));
+    }
+
+    #[test]
+    fn empty_servers_alert_once() {
+        let claimed = alert(true, Some("2026-01-01T00:00:00Z"), false);
+        assert_eq!(evaluate(&claimed, Some(&server("", 0)), at("2026-01-01T00:10:00Z")), AlertAction::None);
+        assert!(matches!(
+            evaluate(&claimed, Some(&server("", 0)), at("2026-01-01T00:31:00Z")),
+            AlertAction::Notify { alerted: true, .. }
+        ));
+        let alerted = alert(true, Some("2026-01-01T00:00:00Z"), true);
+        assert_eq!(evaluate(&alerted, Some(&server("", 0)), at("2026-01-01T02:00:00Z")), AlertAction::None);
+    }
+}
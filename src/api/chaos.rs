@@ -0,0 +1,122 @@
+use crate::api::factorio::{ApiError, GameDetails, GameServer, GameTime};
+use crate::api::source::ServerSource;
+use crate::ids::GameId;
+use rocket::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Fault injection settings for exercising upstream failures (local testing only)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ChaosConfig {
+    /// Fraction of requests that fail outright (0.0-1.0)
+    pub error_rate: f64,
+    /// Fraction of get-games responses that come back with no servers
+    pub empty_rate: f64,
+    /// Fraction of servers in a listing that get a malformed field
+    pub malformed_rate: f64,
+    /// Extra delay before every request
+    pub latency: Duration,
+}
+
+impl ChaosConfig {
+    /// Read CHAOS_ERROR_RATE, CHAOS_EMPTY_RATE, CHAOS_MALFORMED_RATE and CHAOS_LATENCY_MS
+    /// Unset or invalid variables inject nothing
+    pub fn from_env() -> Self {
+        let env_rate = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .map(|r| r.clamp(0.0, 1.0))
+                .unwrap_or(0.0)
+        };
+        Self {
+            error_rate: env_rate("CHAOS_ERROR_RATE"),
+            empty_rate: env_rate("CHAOS_EMPTY_RATE"),
+            malformed_rate: env_rate("CHAOS_MALFORMED_RATE"),
+            latency: Duration::from_millis(
+                std::env::var("CHAOS_LATENCY_MS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0),
+            ),
+        }
+    }
+
+    /// Whether any fault is configured
+    pub fn is_active(&self) -> bool {
+        self.error_rate > 0.0 || self.empty_rate > 0.0 || self.malformed_rate > 0.0 || !self.latency.is_zero()
+    }
+}
+
+/// Random number in [0, 1)
+fn random_unit() -> f64 {
+    let mut bytes = [0u8; 4];
+    if getrandom::fill(&mut bytes).is_err() {
+        return 1.0;
+    }
+    u32::from_le_bytes(bytes) as f64 / (u32::MAX as f64 + 1.0)
+}
+
+fn roll(rate: f64) -> bool {
+    rate > 0.0 && random_unit() < rate
+}
+
+/// Break one field of a server the way a bad upstream entry might
+fn malform(server: &mut GameServer) {
+    match (random_unit() * 6.0) as u32 {
+        0 => server.name = String::new(),
+        1 => server.max_players = 0,
+        2 => server.application_version.game_version = "not-a-version".to_string(),
+        3 => server.game_time_elapsed = GameTime::String("garbage".to_string()),
+        4 => server.players = vec![String::new(); server.max_players as usize + 10],
+        _ => server.tags = vec!["[color=red][font=default-large]".to_string(); 50],
+    }
+}
+
+/// ServerSource wrapper that injects failures, latency, and malformed entries
+/// Only built with the `chaos` feature, where it wraps the real source when CHAOS_* variables are
+/// set, to check the site stays up, the error banner shows, and the cache keeps its last good listing
+pub struct ChaosSource {
+    inner: Arc<dyn ServerSource>,
+    config: ChaosConfig,
+}
+
+impl ChaosSource {
+    pub fn new_shared(inner: Arc<dyn ServerSource>, config: ChaosConfig) -> Arc<Self> {
+        Arc::new(Self { inner, config })
+    }
+
+    /// Apply the configured latency, then maybe fail
+    async fn disrupt(&self) -> Result<(), ApiError> {
+        if !self.config.latency.is_zero() {
+            tokio::time::sleep(self.config.latency).await;
+        }
+        if roll(self.config.error_rate) {
+            return Err(ApiError::InvalidResponse("chaos: injected failure".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ServerSource for ChaosSource {
+    async fn get_games(&self) -> Result<Vec<GameServer>, ApiError> {
+        self.disrupt().await?;
+        if roll(self.config.empty_rate) {
+            return Ok(Vec::new());
+        }
+
+        let mut servers = self.inner.get_games().await?;
+        for server in &mut servers {
+            if roll(self.config.malformed_rate) {
+                malform(server);
+            }
+        }
+        Ok(servers)
+    }
+
+    async fn get_game_details(&self, game_id: GameId) -> Result<GameDetails, ApiError> {
+        self.disrupt().await?;
+        self.inner.get_game_details(game_id).await
+    }
+}
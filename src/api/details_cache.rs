@@ -0,0 +1,42 @@
+use crate::api::factorio::GameDetails;
+use crate::ids::GameId;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Recent get-game-details responses by game_id, so repeated views of a popular server share one
+/// upstream call
+#[derive(Debug)]
+pub struct DetailsCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<GameId, (Instant, GameDetails)>>,
+}
+
+impl DetailsCache {
+    /// Keep each response for `ttl` (zero turns the cache off)
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached response for a game, if it hasn't expired
+    pub fn get(&self, game_id: GameId) -> Option<GameDetails> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(&game_id)
+            .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+            .map(|(_, details)| details.clone())
+    }
+
+    /// Keep a fresh response, dropping expired ones so the map stays small
+    pub fn insert(&self, game_id: GameId, details: GameDetails) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (fetched, _)| fetched.elapsed() < self.ttl);
+        entries.insert(game_id, (Instant::now(), details));
+    }
+}
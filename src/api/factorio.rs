@@ -1,6 +1,13 @@
+use crate::api::details_cache::DetailsCache;
+use crate::api::rate_limit::TokenBucket;
+use crate::api::recorder::ResponseRecorder;
+use crate::ids::{GameId, ServerId};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 const BASE_URL: &str = "https://multiplayer.factorio.com";
 
@@ -27,12 +34,40 @@ impl From<GameTime> for u64 {
     }
 }
 
+/// get-game-details calls allowed per minute on average, and back to back, unless configured
+pub const DEFAULT_DETAILS_PER_MINUTE: u32 = 60;
+pub const DEFAULT_DETAILS_BURST: u32 = 10;
+
+/// How long a get-game-details response is reused, unless configured
+pub const DEFAULT_DETAILS_CACHE_TTL: Duration = Duration::from_secs(45);
+
 /// Factorio API client for the matchmaking API
 #[derive(Clone)]
 pub struct FactorioClient {
     client: Client,
+    base_url: String,
     username: String,
     token: String,
+    /// Keeps raw get-games responses for replaying later
+    recorder: Option<Arc<ResponseRecorder>>,
+    /// Caps get-game-details calls, which page views trigger, so traffic spikes can't get the
+    /// token banned; shared by clones
+    details_limit: Arc<TokenBucket>,
+    /// Recent get-game-details responses, checked before the rate limit; shared by clones
+    details_cache: Arc<DetailsCache>,
+}
+
+/// Builder for a FactorioClient pointed at a mirror, the mock API, or through a proxy
+pub struct FactorioClientBuilder {
+    base_url: String,
+    username: String,
+    token: String,
+    headers: Vec<(String, String)>,
+    proxy: Option<String>,
+    recorder: Option<Arc<ResponseRecorder>>,
+    details_per_minute: u32,
+    details_burst: u32,
+    details_cache_ttl: Duration,
 }
 
 /// Application version information
@@ -47,7 +82,7 @@ pub struct ApplicationVersion {
 /// Server information from the get-games endpoint
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct GameServer {
-    pub game_id: u64,
+    pub game_id: GameId,
     pub name: String,
     #[serde(default)]
     pub description: String,
@@ -68,13 +103,16 @@ pub struct GameServer {
     #[serde(default)]
     pub headless_server: bool,
     #[serde(default)]
-    pub server_id: Option<String>,
+    pub server_id: Option<ServerId>,
+    /// Fields the API sent that this struct doesn't know about yet (tracked as schema drift)
+    #[serde(flatten, skip_serializing_if = "BTreeMap::is_empty")]
+    pub unknown_fields: BTreeMap<String, serde_json::Value>,
 }
 
 /// Detailed server information from get-game-details endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameDetails {
-    pub game_id: u64,
+    pub game_id: GameId,
     pub name: String,
     #[serde(default)]
     pub description: String,
@@ -109,6 +147,12 @@ pub enum ApiError {
     RequestFailed(reqwest::Error),
     InvalidResponse(String),
     AuthenticationFailed,
+    /// The API doesn't know this game (the server went offline or restarted)
+    NotFound,
+    /// The client was built with an invalid header or proxy
+    InvalidConfig(String),
+    /// 429 Too Many Requests, with the Retry-After delay when one was sent
+    RateLimited(Option<Duration>),
 }
 
 impl std::fmt::Display for ApiError {
@@ -117,6 +161,10 @@ impl std::fmt::Display for ApiError {
             ApiError::RequestFailed(e) => write!(f, "Request failed: {}", e),
             ApiError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
             ApiError::AuthenticationFailed => write!(f, "Authentication failed"),
+            ApiError::NotFound => write!(f, "Not found"),
+            ApiError::InvalidConfig(msg) => write!(f, "Invalid client configuration: {}", msg),
+            ApiError::RateLimited(Some(delay)) => write!(f, "Rate limited, retry after {}s", delay.as_secs()),
+            ApiError::RateLimited(None) => write!(f, "Rate limited"),
         }
     }
 }
@@ -129,21 +177,112 @@ impl From<reqwest::Error> for ApiError {
     }
 }
 
+impl FactorioClientBuilder {
+    /// Use another API root instead of multiplayer.factorio.com
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Send an extra header with every request
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Route all requests through a proxy (http:// or https:// URL)
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Allow this many get-game-details calls a minute on average and `burst` back to back;
+    /// calls over the limit fail with ApiError::RateLimited instead of reaching the API
+    pub fn details_rate_limit(mut self, per_minute: u32, burst: u32) -> Self {
+        self.details_per_minute = per_minute;
+        self.details_burst = burst;
+        self
+    }
+
+    /// Reuse a get-game-details response for this long (zero fetches every time)
+    pub fn details_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.details_cache_ttl = ttl;
+        self
+    }
+
+    /// Store every raw get-games response with this recorder
+    pub fn recorder(mut self, recorder: Arc<ResponseRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    pub fn build(self) -> Result<FactorioClient, ApiError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| ApiError::InvalidConfig(format!("invalid header name '{}'", name)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|_| ApiError::InvalidConfig(format!("invalid value for header '{}'", name)))?;
+            headers.insert(name, value);
+        }
+
+        let mut builder = Client::builder().default_headers(headers);
+        if let Some(ref proxy) = self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|_| ApiError::InvalidConfig(format!("invalid proxy URL '{}'", proxy)))?;
+            builder = builder.proxy(proxy);
+        }
+
+        Ok(FactorioClient {
+            client: builder.build()?,
+            base_url: self.base_url,
+            username: self.username,
+            token: self.token,
+            recorder: self.recorder,
+            details_limit: Arc::new(TokenBucket::new(self.details_per_minute, self.details_burst)),
+            details_cache: Arc::new(DetailsCache::new(self.details_cache_ttl)),
+        })
+    }
+
+    /// Build and wrap in Arc for sharing
+    pub fn build_shared(self) -> Result<Arc<FactorioClient>, ApiError> {
+        self.build().map(Arc::new)
+    }
+}
+
 impl FactorioClient {
-    /// Create a new client wrapped in Arc for sharing
-    pub fn new_shared(username: String, token: String) -> Arc<Self> {
-        Arc::new(Self {
-            client: Client::new(),
+    /// Start configuring a client for the official API
+    pub fn builder(username: String, token: String) -> FactorioClientBuilder {
+        FactorioClientBuilder {
+            base_url: BASE_URL.to_string(),
             username,
             token,
-        })
+            headers: Vec::new(),
+            proxy: None,
+            recorder: None,
+            details_per_minute: DEFAULT_DETAILS_PER_MINUTE,
+            details_burst: DEFAULT_DETAILS_BURST,
+            details_cache_ttl: DEFAULT_DETAILS_CACHE_TTL,
+        }
+    }
+
+    /// Client for the official API with default settings
+    pub fn new(username: String, token: String) -> Self {
+        Self::builder(username, token)
+            .build()
+            .expect("default HTTP client settings are valid")
+    }
+
+    /// Create a new client wrapped in Arc for sharing
+    pub fn new_shared(username: String, token: String) -> Arc<Self> {
+        Arc::new(Self::new(username, token))
     }
 
     /// Fetch all public game servers (requires authentication)
     pub async fn get_games(&self) -> Result<Vec<GameServer>, ApiError> {
         let url = format!(
             "{}/get-games?username={}&token={}",
-            BASE_URL, self.username, self.token
+            self.base_url, self.username, self.token
         );
 
         let response = self.client.get(&url).send().await?;
@@ -152,26 +291,69 @@ impl FactorioClient {
             return Err(ApiError::AuthenticationFailed);
         }
 
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(ApiError::RateLimited(retry_after(&response)));
+        }
+
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
             return Err(ApiError::InvalidResponse(format!("{}: {}", status, body)));
         }
 
-        Ok(response.json().await?)
+        let body = response.text().await?;
+        if let Some(ref recorder) = self.recorder {
+            let recorder = recorder.clone();
+            let raw = body.clone();
+            tokio::task::spawn_blocking(move || {
+                let now = chrono::Utc::now();
+                if let Err(e) = recorder.record(&raw, now).and_then(|_| recorder.prune(now)) {
+                    eprintln!("Failed to record get-games response: {}", e);
+                }
+            });
+        }
+
+        serde_json::from_str(&body).map_err(|e| ApiError::InvalidResponse(e.to_string()))
     }
 
-    /// Fetch detailed server info (no auth required)
-    pub async fn get_game_details(&self, game_id: u64) -> Result<GameDetails, ApiError> {
-        let url = format!("{}/get-game-details/{}", BASE_URL, game_id);
+    /// Fetch detailed server info (no auth required), reusing a recent response for the same game
+    pub async fn get_game_details(&self, game_id: GameId) -> Result<GameDetails, ApiError> {
+        if let Some(details) = self.details_cache.get(game_id) {
+            return Ok(details);
+        }
+        self.details_limit.try_acquire().map_err(|wait| ApiError::RateLimited(Some(wait)))?;
+        let url = format!("{}/get-game-details/{}", self.base_url, game_id);
         let response = self.client.get(&url).send().await?;
 
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(ApiError::NotFound);
+        }
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(ApiError::RateLimited(retry_after(&response)));
+        }
+
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
             return Err(ApiError::InvalidResponse(format!("{}: {}", status, body)));
         }
 
-        Ok(response.json().await?)
+        let details: GameDetails = response.json().await?;
+        self.details_cache.insert(game_id, details.clone());
+        Ok(details)
     }
 }
+
+/// Retry-After of a response given in seconds (an HTTP date is ignored)
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
@@ -0,0 +1,325 @@
+use crate::api::factorio::ApiError;
+use crate::db::models::{FederatedHistory, FederatedProfile, NewServerHistory};
+use crate::db::queries::{DbClient, DbError};
+use crate::ids::ServerId;
+use crate::tags::TagAliases;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Version of the export format; an importer refuses exports with a version it doesn't know
+pub const FEDERATION_PROTOCOL: u32 = 1;
+
+/// History records per export page (a page is stretched to finish the refresh it ends in)
+pub const EXPORT_PAGE_SIZE: usize = 5000;
+
+/// How often peers are imported from
+pub const FEDERATION_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// A peer's record is only imported when this instance has no refresh of its own this close to it,
+/// so peers fill the gaps while this instance was down instead of doubling up its own history
+const COVERAGE_WINDOW_MINUTES: i64 = 10;
+
+/// Pages read from one peer per run, so a peer with a huge backlog can't hold up the others
+const MAX_PAGES_PER_RUN: usize = 50;
+
+/// Exports can be large, so allow more time than the other clients
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// One page of /api/federation/export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FederationExport {
+    pub protocol: u32,
+    pub generated_at: String,
+    /// Servers listed since `since`; only sent on the first page
+    #[serde(default)]
+    pub profiles: Vec<FederatedProfile>,
+    /// Oldest first
+    #[serde(default)]
+    pub history: Vec<FederatedHistory>,
+    /// Pass as `after` to get the next page; None on the last one
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+/// Error type for importing from a peer
+#[derive(Debug)]
+pub enum FederationError {
+    Peer(ApiError),
+    Db(DbError),
+    /// The peer speaks a different protocol version
+    UnsupportedProtocol(u32),
+}
+
+impl std::fmt::Display for FederationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FederationError::Peer(e) => write!(f, "Peer request failed: {}", e),
+            FederationError::Db(e) => write!(f, "Database error: {}", e),
+            FederationError::UnsupportedProtocol(version) => write!(f, "Unsupported protocol version {}", version),
+        }
+    }
+}
+
+impl std::error::Error for FederationError {}
+
+impl From<ApiError> for FederationError {
+    fn from(err: ApiError) -> Self {
+        FederationError::Peer(err)
+    }
+}
+
+impl From<DbError> for FederationError {
+    fn from(err: DbError) -> Self {
+        FederationError::Db(err)
+    }
+}
+
+/// Result of one import run from a peer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub profiles: usize,
+    /// History records stored (the rest were already covered by this instance)
+    pub history: usize,
+    /// Where the next run picks up: the last history record read from the peer
+    pub cursor: String,
+}
+
+/// Client for other instances' federation exports
+#[derive(Clone)]
+pub struct FederationClient {
+    client: Client,
+    /// Sent to peers that require an API key for the export
+    api_key: Option<String>,
+}
+
+impl FederationClient {
+    /// Create a new client wrapped in Arc for sharing
+    pub fn new_shared(api_key: Option<String>) -> Arc<Self> {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("default HTTP client settings are valid");
+        Arc::new(Self { client, api_key })
+    }
+
+    /// Fetch one page of a peer's export
+    pub async fn export_page(&self, peer: &str, since: &str, after: Option<&str>) -> Result<FederationExport, ApiError> {
+        let url = format!("{}/api/federation/export", peer.trim_end_matches('/'));
+        let mut request = self.client.get(&url).query(&[("since", since)]);
+        if let Some(after) = after {
+            request = request.query(&[("after", after)]);
+        }
+        if let Some(ref key) = self.api_key {
+            request = request.header("X-Api-Key", key);
+        }
+        let response = request.send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::InvalidResponse(format!("{}: {}", status, body)));
+        }
+
+        Ok(response.json().await?)
+    }
+}
+
+/// Import a peer's profiles and history recorded after `since`
+/// Records dated in the future are dropped and profile tags normalized like this instance's own
+pub async fn import_peer(
+    db: &DbClient,
+    client: &FederationClient,
+    peer: &str,
+    since: &str,
+    tag_aliases: &TagAliases,
+) -> Result<ImportSummary, FederationError> {
+    let mut summary = ImportSummary {
+        profiles: 0,
+        history: 0,
+        cursor: since.to_string(),
+    };
+    let mut after: Option<String> = None;
+
+    for _ in 0..MAX_PAGES_PER_RUN {
+        let page = client.export_page(peer, since, after.as_deref()).await?;
+        if page.protocol != FEDERATION_PROTOCOL {
+            return Err(FederationError::UnsupportedProtocol(page.protocol));
+        }
+
+        let now = Utc::now();
+        let profiles = clean_profiles(page.profiles, now, tag_aliases);
+        summary.profiles += profiles.len();
+        db.merge_federated_profiles(profiles).await?;
+
+        let mut history = page.history;
+        history.retain(|h| parse_time(&h.recorded_at).is_some_and(|at| at <= now));
+        if let Some(last) = history.last() {
+            summary.cursor = last.recorded_at.clone();
+        }
+        let records = missing_history(db, history).await?;
+        summary.history += records.len();
+        db.insert_history(records).await?;
+
+        match page.next {
+            Some(next) => after = Some(next),
+            None => break,
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Peer profiles fit to merge: first/last seen parsed as RFC 3339 and rewritten in UTC like this
+/// instance's own times, dropping profiles with unparsable, reversed or future times, and tags
+/// normalized with this instance's aliases
+pub fn clean_profiles(
+    profiles: Vec<FederatedProfile>,
+    now: DateTime<Utc>,
+    tag_aliases: &TagAliases,
+) -> Vec<FederatedProfile> {
+    profiles
+        .into_iter()
+        .filter_map(|mut profile| {
+            let first_seen = parse_time(&profile.first_seen)?;
+            let last_seen = parse_time(&profile.last_seen)?;
+            if first_seen > last_seen || last_seen > now {
+                return None;
+            }
+            profile.first_seen = first_seen.to_rfc3339();
+            profile.last_seen = last_seen.to_rfc3339();
+            profile.tags = tag_aliases.normalize_all(&profile.tags);
+            Some(profile)
+        })
+        .collect()
+}
+
+/// The part of a page of peer history this instance doesn't have
+async fn missing_history(db: &DbClient, history: Vec<FederatedHistory>) -> Result<Vec<NewServerHistory>, DbError> {
+    let (Some(first), Some(last)) = (history.first(), history.last()) else {
+        return Ok(Vec::new());
+    };
+    let (Some(from), Some(to)) = (parse_time(&first.recorded_at), parse_time(&last.recorded_at)) else {
+        return Ok(Vec::new());
+    };
+    let window = chrono::Duration::minutes(COVERAGE_WINDOW_MINUTES);
+
+    let mut refreshes: Vec<DateTime<Utc>> = db
+        .get_refresh_times(&(from - window).to_rfc3339(), &(to + window).to_rfc3339())
+        .await?
+        .iter()
+        .filter_map(|t| parse_time(t))
+        .collect();
+    refreshes.sort();
+
+    let server_ids: Vec<ServerId> = history
+        .iter()
+        .map(|h| h.server_id.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let stored: HashSet<(ServerId, String)> = db
+        .get_stored_history(server_ids, &first.recorded_at, &last.recorded_at)
+        .await?
+        .into_iter()
+        .map(|h| (h.server_id, h.recorded_at))
+        .collect();
+
+    Ok(uncovered_history(history, &refreshes, &stored))
+}
+
+/// Peer records from times this instance wasn't refreshing (no refresh of its own within
+/// COVERAGE_WINDOW_MINUTES), minus ones already stored; `refreshes` must be sorted
+pub fn uncovered_history(
+    history: Vec<FederatedHistory>,
+    refreshes: &[DateTime<Utc>],
+    stored: &HashSet<(ServerId, String)>,
+) -> Vec<NewServerHistory> {
+    let window = chrono::Duration::minutes(COVERAGE_WINDOW_MINUTES);
+    history
+        .into_iter()
+        .filter(|h| !stored.contains(&(h.server_id.clone(), h.recorded_at.clone())))
+        .filter(|h| {
+            let Some(at) = parse_time(&h.recorded_at) else {
+                return false;
+            };
+            // The first refresh at or after the window's start is the only one that can fall inside it
+            let i = refreshes.partition_point(|r| *r < at - window);
+            refreshes.get(i).is_none_or(|r| *r > at + window)
+        })
+        .map(|h| NewServerHistory {
+            game_id: h.game_id,
+            server_id: Some(h.server_id),
+            player_count: h.player_count,
+            recorded_at: h.recorded_at,
+        })
+        .collect()
+}
+
+fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time).ok().map(|t| t.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(first_seen: &str, last_seen: &str, tags: &[&str]) -> FederatedProfile {
+        FederatedProfile {
+            server_id: ServerId::from("abc".to_string()),
+            game_id: 1.into(),
+            name: "Peer server".to_string(),
+            description: String::new(),
+            game_version: "2.0.28".to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            max_players: 10,
+            mod_count: 0,
+            peak_players: 3,
+            first_seen: first_seen.to_string(),
+            last_seen: last_seen.to_string(),
+        }
+    }
+
+    #[test]
+    fn peer_times_are_rewritten_in_utc() {
+        let now = parse_time("2026-01-02T00:00:00Z").unwrap();
+        let cleaned = clean_profiles(
+            vec![profile("2026-01-01T10:00:00+02:00", "2026-01-01T23:30:00-00:30", &[])],
+            now,
+            &TagAliases::default(),
+        );
+        assert_eq!(cleaned[0].first_seen, "2026-01-01T08:00:00+00:00");
+        assert_eq!(cleaned[0].last_seen, "2026-01-02T00:00:00+00:00");
+    }
+
+    #[test]
+    fn future_reversed_and_unparsable_profiles_are_dropped() {
+        let now = parse_time("2026-01-02T00:00:00Z").unwrap();
+        let profiles = vec![
+            profile("2026-01-01T00:00:00Z", "2099-01-01T00:00:00Z", &[]),
+            profile("2026-01-01T12:00:00Z", "2026-01-01T00:00:00Z", &[]),
+            profile("yesterday", "2026-01-01T00:00:00Z", &[]),
+            profile("2026-01-01T00:00:00Z", "", &[]),
+        ];
+        assert!(clean_profiles(profiles, now, &TagAliases::default()).is_empty());
+    }
+
+    #[test]
+    fn peer_tags_are_normalized() {
+        let now = parse_time("2026-01-02T00:00:00Z").unwrap();
+        let aliases = TagAliases::parse("player vs player=pvp").unwrap().with_excluded(&["spam".to_string()]);
+        let cleaned = clean_profiles(
+            vec![profile(
+                "2026-01-01T00:00:00Z",
+                "2026-01-01T00:00:00Z",
+                &["[color=red]PvP[/color]", "Player vs Player", "spam", "Vanilla"],
+            )],
+            now,
+            &aliases,
+        );
+        assert_eq!(cleaned[0].tags, vec!["pvp", "vanilla"]);
+    }
+}
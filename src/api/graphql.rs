@@ -0,0 +1,372 @@
+use crate::api::keys::ApiKey;
+use crate::api::routes::PlayerCountHistory;
+use crate::api::source::ServerSource;
+use crate::cache::ServerCache;
+use crate::db::models::ModMetadata;
+use crate::db::queries::DbClient;
+use crate::filter::{versions_newest_first, FilterSpec, ServerFilters};
+use crate::ids::GameId;
+use crate::tags::TagAliases;
+use crate::view::ServerView;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Object, Schema, SimpleObject};
+use rocket::serde::json::Json;
+use rocket::{post, State};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Servers returned when a query doesn't pass `limit`
+const DEFAULT_LIMIT: usize = 100;
+
+/// Most servers one `servers` query returns
+const MAX_LIMIT: usize = 1000;
+
+/// Most hours of player count history one server's `history` returns (the REST API's default is 24)
+const MAX_HISTORY_HOURS: u32 = 24 * 7;
+
+/// Deepest selection a query may nest
+const MAX_DEPTH: usize = 8;
+
+/// Highest total cost a query may add up to; a plain field costs 1, and `servers` multiplies its
+/// selection by `limit`
+const MAX_COMPLEXITY: usize = 5000;
+
+/// Cost of a server's `history`, a DB query
+const HISTORY_COST: usize = 20;
+
+/// Cost of a server's `mods`, a live call to the Factorio API, so a query can ask for about twenty
+const MODS_COST: usize = 250;
+
+/// The GraphQL schema over the cached listing, player count history and mods
+pub type ServerSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// Where the schema's resolvers read from
+pub struct GraphqlSources {
+    pub cache: Arc<ServerCache>,
+    pub db: Arc<DbClient>,
+    /// For `mods`, which the listing doesn't include
+    pub source: Arc<dyn ServerSource>,
+    pub tag_aliases: Arc<TagAliases>,
+    /// Live calls to the Factorio API are allowed (off in read-only mode, like on the server page)
+    pub live_details: bool,
+}
+
+pub fn build_schema(sources: GraphqlSources) -> ServerSchema {
+    Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(sources)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Run a GraphQL query, e.g. `{"query": "{ servers(limit: 5) { name playerCount } }"}`
+/// Behind the same API keys and rate limits as the REST API; errors come back in the response's `errors`
+#[post("/graphql", format = "json", data = "<request>")]
+pub async fn graphql(
+    _key: ApiKey,
+    schema: &State<ServerSchema>,
+    request: Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request.into_inner()).await)
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Listed servers, filtered like `/api/servers`, in the listing's order
+    #[allow(clippy::too_many_arguments)]
+    #[graphql(complexity = "limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) * child_complexity")]
+    async fn servers(
+        &self,
+        ctx: &Context<'_>,
+        search: Option<String>,
+        #[graphql(desc = "Game version prefix, \"all\" for every version; defaults to the latest")] version: Option<String>,
+        has_players: Option<bool>,
+        no_password: Option<bool>,
+        is_dedicated: Option<bool>,
+        has_free_slots: Option<bool>,
+        #[graphql(desc = "Continent code, e.g. \"EU\"")] region: Option<String>,
+        tags: Option<Vec<String>>,
+        #[graphql(desc = "\"any\" (default) or \"all\"")] tag_mode: Option<String>,
+        min_mods: Option<u32>,
+        #[graphql(desc = "Only maps with less game time than this, in minutes")] max_game_time: Option<u64>,
+        #[graphql(desc = "Only servers first listed within this many days")] new_days: Option<u32>,
+        #[graphql(desc = "Game build mode, e.g. \"headless\"")] build_mode: Option<String>,
+        #[graphql(desc = "Server OS, e.g. \"linux64\"")] platform: Option<String>,
+        limit: Option<usize>,
+    ) -> async_graphql::Result<ServerList> {
+        let sources = ctx.data::<GraphqlSources>()?;
+        let filters = ServerFilters {
+            search,
+            version,
+            has_players,
+            no_password,
+            is_dedicated,
+            has_free_slots,
+            region,
+            tags: tags.map(|tags| tags.join(",")),
+            tag_mode,
+            min_mods,
+            max_game_time,
+            new_days,
+            build_mode,
+            platform,
+            limit: None,
+        };
+        let spec = sources.db.with_search(FilterSpec::from(&filters).with_tag_aliases(&sources.tag_aliases)).await;
+
+        let snapshot = sources.cache.load();
+        let latest_version = versions_newest_first(snapshot.servers.iter().map(|s| &s.server))
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        let mut filtered: Vec<ServerView> = snapshot
+            .servers
+            .iter()
+            .filter(|s| spec.matches(&s.server, &latest_version) && spec.matches_view(s))
+            .cloned()
+            .collect();
+        spec.sort_by_relevance(&mut filtered);
+
+        Ok(ServerList {
+            total: filtered.len(),
+            refreshed_at: snapshot.updated_at.map(|t| t.to_rfc3339()),
+            servers: filtered
+                .into_iter()
+                .take(limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT))
+                .map(Server)
+                .collect(),
+        })
+    }
+
+    /// A listed server by game_id
+    async fn server(&self, ctx: &Context<'_>, game_id: u64) -> async_graphql::Result<Option<Server>> {
+        let sources = ctx.data::<GraphqlSources>()?;
+        Ok(sources.cache.load().find(GameId(game_id)).cloned().map(Server))
+    }
+}
+
+/// A page of listed servers
+#[derive(SimpleObject)]
+pub struct ServerList {
+    /// Servers matching the filters, before `limit`
+    total: usize,
+    /// When the listing was last refreshed (RFC 3339)
+    refreshed_at: Option<String>,
+    servers: Vec<Server>,
+}
+
+/// A listed server as of the last refresh
+pub struct Server(ServerView);
+
+#[Object]
+impl Server {
+    /// Changes on every restart of the game
+    async fn game_id(&self) -> u64 {
+        self.0.server.game_id.0
+    }
+
+    /// Stable across restarts
+    async fn server_id(&self) -> Option<&str> {
+        self.0.server.server_id.as_deref()
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.server.name
+    }
+
+    async fn description(&self) -> &str {
+        &self.0.server.description
+    }
+
+    /// 0 without a player limit
+    async fn max_players(&self) -> u32 {
+        self.0.server.max_players
+    }
+
+    async fn player_count(&self) -> usize {
+        self.0.server.player_count
+    }
+
+    async fn players(&self) -> &[String] {
+        &self.0.server.players
+    }
+
+    /// Highest player count in the last 24 hours
+    async fn peak_players(&self) -> Option<usize> {
+        self.0.peak_players
+    }
+
+    /// Players gained over the last hour's average
+    async fn trend(&self) -> i64 {
+        self.0.trend
+    }
+
+    /// In minutes
+    async fn game_time_elapsed(&self) -> u64 {
+        self.0.server.game_time_elapsed
+    }
+
+    async fn has_password(&self) -> bool {
+        self.0.server.has_password
+    }
+
+    async fn headless(&self) -> bool {
+        self.0.server.headless_server
+    }
+
+    /// As the server lists them, rich text included
+    async fn tags(&self) -> &[String] {
+        &self.0.server.tags
+    }
+
+    /// Without rich text, trimmed and deduplicated
+    async fn display_tags(&self) -> &[String] {
+        &self.0.display_tags
+    }
+
+    async fn mod_count(&self) -> u32 {
+        self.0.server.mod_count
+    }
+
+    /// Fingerprint of the exact mod set, once the mods have been looked up
+    async fn modpack(&self) -> Option<&str> {
+        self.0.modpack.as_deref()
+    }
+
+    async fn game_version(&self) -> &str {
+        &self.0.server.game_version
+    }
+
+    async fn build_version(&self) -> u32 {
+        self.0.server.build_version
+    }
+
+    /// e.g. "headless" for the dedicated server build
+    async fn build_mode(&self) -> Option<&str> {
+        self.0.server.build_mode.as_deref()
+    }
+
+    /// e.g. "linux64"
+    async fn platform(&self) -> Option<&str> {
+        self.0.server.platform.as_deref()
+    }
+
+    async fn host_address(&self) -> Option<&str> {
+        self.0.server.host_address.as_deref()
+    }
+
+    /// ISO country code, when a GeoIP database is configured
+    async fn country(&self) -> Option<&str> {
+        self.0.server.country.as_deref()
+    }
+
+    async fn continent(&self) -> Option<&str> {
+        self.0.server.continent.as_deref()
+    }
+
+    async fn first_seen(&self) -> Option<&str> {
+        self.0.server.first_seen.as_deref()
+    }
+
+    async fn last_seen(&self) -> Option<&str> {
+        self.0.server.last_seen.as_deref()
+    }
+
+    /// Ranking for the "Recommended" sort, higher first
+    async fn score(&self) -> i64 {
+        self.0.score
+    }
+
+    /// Player counts over the last `hours` (24 by default, at most a week), newest first
+    #[graphql(complexity = "HISTORY_COST + child_complexity")]
+    async fn history(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 24)] hours: u32,
+    ) -> async_graphql::Result<Vec<PlayerCountHistory>> {
+        let sources = ctx.data::<GraphqlSources>()?;
+        let server = &self.0.server;
+        let history = sources
+            .db
+            .get_server_history(server.game_id, server.server_id.as_ref(), hours.clamp(1, MAX_HISTORY_HOURS))
+            .await
+            .map_err(|e| {
+                eprintln!("Failed to load history for GraphQL: {}", e);
+                async_graphql::Error::new("History is unavailable right now")
+            })?;
+        Ok(history
+            .into_iter()
+            .map(|h| PlayerCountHistory {
+                player_count: h.player_count,
+                recorded_at: h.recorded_at,
+            })
+            .collect())
+    }
+
+    /// Mods the server runs, fetched live from the Factorio API, with Mod Portal details where
+    /// they've been looked up before
+    #[graphql(complexity = "MODS_COST + child_complexity")]
+    async fn mods(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<Mod>> {
+        let sources = ctx.data::<GraphqlSources>()?;
+        if self.0.server.mod_count == 0 {
+            return Ok(Vec::new());
+        }
+        if !sources.live_details {
+            return Err("Mods are unavailable in read-only mode".into());
+        }
+        let details = sources.source.get_game_details(self.0.server.game_id).await.map_err(|e| {
+            eprintln!("Failed to fetch mods for game {}: {}", self.0.server.game_id, e);
+            async_graphql::Error::new("Mods are unavailable right now")
+        })?;
+
+        let names = details.mods.iter().map(|m| m.name.clone()).collect();
+        let mut portal: HashMap<String, ModMetadata> = match sources.db.get_mod_metadata(names).await {
+            Ok(cached) => cached.into_iter().map(|m| (m.name.clone(), m)).collect(),
+            Err(e) => {
+                eprintln!("Failed to load mod metadata: {}", e);
+                HashMap::new()
+            }
+        };
+        Ok(details
+            .mods
+            .into_iter()
+            .map(|m| Mod {
+                portal: portal.remove(&m.name),
+                name: m.name,
+                version: m.version,
+            })
+            .collect())
+    }
+}
+
+/// A mod a server runs
+pub struct Mod {
+    name: String,
+    version: String,
+    portal: Option<ModMetadata>,
+}
+
+#[Object]
+impl Mod {
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Mod Portal title
+    async fn title(&self) -> Option<&str> {
+        self.portal.as_ref().and_then(|p| p.title.as_deref())
+    }
+
+    async fn downloads(&self) -> Option<u64> {
+        self.portal.as_ref().map(|p| p.downloads)
+    }
+
+    async fn category(&self) -> Option<&str> {
+        self.portal.as_ref().and_then(|p| p.category.as_deref())
+    }
+}
@@ -0,0 +1,160 @@
+use crate::accounts::hex;
+use crate::api::rate_limit::TokenBucket;
+use crate::db::models::ApiKeyRecord;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Requests a minute a new key is allowed when the admin doesn't pick a limit
+pub const DEFAULT_KEY_PER_MINUTE: u32 = 120;
+
+/// Highest per-minute limit the admin page accepts
+pub const MAX_KEY_PER_MINUTE: u32 = 60_000;
+
+/// Prefix of generated keys, so a leaked one is recognizable in logs and secret scanners
+const KEY_PREFIX: &str = "fbk_";
+
+/// A fresh random API key; only its hash is stored, so it can be shown once
+pub fn new_api_key() -> Option<String> {
+    let mut bytes = [0u8; 32];
+    if let Err(e) = getrandom::fill(&mut bytes) {
+        eprintln!("Failed to generate API key: {}", e);
+        return None;
+    }
+    Some(format!("{}{}", KEY_PREFIX, hex(&bytes)))
+}
+
+/// Digest an API key is stored and looked up by
+pub fn api_key_hash(key: &str) -> String {
+    hex(&Sha256::digest(key.as_bytes()))
+}
+
+/// Why the API turned a request away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRejection {
+    /// No key sent while keys are required
+    Missing,
+    /// Unknown or revoked key
+    Invalid,
+    /// The key used up its requests for now; the next one is allowed after this long
+    RateLimited(Duration),
+}
+
+/// How long a rate-limited request should wait, kept in the request's local cache for the 429
+/// catcher's `Retry-After`
+#[derive(Debug, Clone, Copy)]
+pub struct RetryAfter(pub Duration);
+
+#[derive(Debug)]
+struct KeyState {
+    id: String,
+    name: String,
+    per_minute: u32,
+    bucket: TokenBucket,
+    /// Requests accepted since startup
+    requests: AtomicU64,
+}
+
+/// Active API keys by hash, each with its own rate limit and request count
+/// Reloaded from the DB at startup and after every change on the admin page
+#[derive(Debug)]
+pub struct ApiKeys {
+    /// Requests without a key are refused
+    required: bool,
+    keys: RwLock<HashMap<String, Arc<KeyState>>>,
+}
+
+impl ApiKeys {
+    pub fn new_shared(required: bool) -> Arc<Self> {
+        Arc::new(Self {
+            required,
+            keys: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    /// Replace the active keys, dropping revoked ones; keys whose limit is unchanged keep their
+    /// bucket and request count
+    pub fn load(&self, records: &[ApiKeyRecord]) {
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        let mut loaded = HashMap::new();
+        for record in records.iter().filter(|r| !r.revoked) {
+            let Some(id) = record.id.as_ref().map(|id| id.id.to_raw()) else {
+                continue;
+            };
+            let state = match keys.remove(&record.key_hash) {
+                Some(state) if state.per_minute == record.per_minute => state,
+                previous => Arc::new(KeyState {
+                    id,
+                    name: record.name.clone(),
+                    per_minute: record.per_minute,
+                    // A minute's worth at once, then the steady rate
+                    bucket: TokenBucket::new(record.per_minute, record.per_minute),
+                    requests: AtomicU64::new(previous.map_or(0, |p| p.requests.load(Ordering::Relaxed))),
+                }),
+            };
+            loaded.insert(record.key_hash.clone(), state);
+        }
+        *keys = loaded;
+    }
+
+    /// Requests accepted since startup, by key record id
+    pub fn usage(&self) -> HashMap<String, u64> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        keys.values()
+            .map(|state| (state.id.clone(), state.requests.load(Ordering::Relaxed)))
+            .collect()
+    }
+
+    /// Name of the key a request is made with (None without one), or why it's refused
+    pub fn check(&self, key: Option<&str>) -> Result<Option<String>, KeyRejection> {
+        let Some(key) = key else {
+            return if self.required { Err(KeyRejection::Missing) } else { Ok(None) };
+        };
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        let state = keys.get(&api_key_hash(key)).ok_or(KeyRejection::Invalid)?;
+        state.bucket.try_acquire().map_err(KeyRejection::RateLimited)?;
+        state.requests.fetch_add(1, Ordering::Relaxed);
+        Ok(Some(state.name.clone()))
+    }
+}
+
+/// Request guard for the JSON API: identifies the caller by `X-Api-Key` or `Authorization: Bearer`
+/// and applies that key's rate limit
+/// Unknown keys get 401 and exhausted ones 429; requests without a key pass unless keys are required
+#[derive(Debug, Clone)]
+pub struct ApiKey(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiKey {
+    type Error = KeyRejection;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(keys) = req.rocket().state::<Arc<ApiKeys>>() else {
+            return Outcome::Success(ApiKey(None));
+        };
+        let key = req
+            .headers()
+            .get_one("X-Api-Key")
+            .or_else(|| req.headers().get_one("Authorization").and_then(|auth| auth.strip_prefix("Bearer ")))
+            .map(str::trim)
+            .filter(|key| !key.is_empty());
+
+        match keys.check(key) {
+            Ok(name) => Outcome::Success(ApiKey(name)),
+            Err(KeyRejection::RateLimited(wait)) => {
+                req.local_cache(|| RetryAfter(wait));
+                Outcome::Error((Status::TooManyRequests, KeyRejection::RateLimited(wait)))
+            }
+            Err(rejection) => Outcome::Error((Status::Unauthorized, rejection)),
+        }
+    }
+}
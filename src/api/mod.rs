@@ -1,3 +1,14 @@
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod details_cache;
 pub mod factorio;
+pub mod federation;
+pub mod graphql;
+pub mod keys;
+pub mod mod_portal;
+pub mod rate_limit;
+pub mod reputation;
+pub mod recorder;
 pub mod routes;
-
+pub mod source;
+pub mod translate;
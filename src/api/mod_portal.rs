@@ -0,0 +1,116 @@
+use crate::api::factorio::ApiError;
+use reqwest::Client;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Public Mod Portal
+pub const MOD_PORTAL_URL: &str = "https://mods.factorio.com";
+
+/// Host serving mod thumbnails (the API returns paths relative to it)
+const ASSETS_URL: &str = "https://assets-mod.factorio.com";
+
+/// Mods requested per call, keeping the namelist query string a sane length
+const NAMES_PER_REQUEST: usize = 50;
+
+/// The details page waits on this, so a slow portal mustn't hold it up for long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Mods that ship with the game and have no portal page
+pub const BUILTIN_MODS: &[&str] = &["base", "core"];
+
+/// Portal metadata for one mod
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortalMod {
+    pub name: String,
+    pub title: String,
+    /// Absolute thumbnail URL, when the mod has one
+    pub thumbnail: Option<String>,
+    pub downloads: u64,
+    pub category: Option<String>,
+}
+
+/// One entry of the /api/mods results
+#[derive(Debug, Deserialize)]
+struct ModResult {
+    name: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
+    downloads_count: u64,
+    #[serde(default)]
+    category: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModListResponse {
+    #[serde(default)]
+    results: Vec<ModResult>,
+}
+
+impl From<ModResult> for PortalMod {
+    fn from(result: ModResult) -> Self {
+        // Mods without an image get a placeholder path ending in "/.thumb.png"
+        let thumbnail = result
+            .thumbnail
+            .filter(|path| !path.is_empty() && !path.ends_with("/.thumb.png"))
+            .map(|path| format!("{}{}", ASSETS_URL, path));
+        let category = result.category.filter(|c| !c.is_empty() && c != "no-category");
+        let title = if result.title.trim().is_empty() { result.name.clone() } else { result.title };
+
+        Self {
+            name: result.name,
+            title,
+            thumbnail,
+            downloads: result.downloads_count,
+            category,
+        }
+    }
+}
+
+/// Client for the Mod Portal's public mod list API (no authentication needed)
+#[derive(Clone)]
+pub struct ModPortalClient {
+    client: Client,
+    base_url: String,
+}
+
+impl ModPortalClient {
+    /// Create a new client wrapped in Arc for sharing
+    pub fn new_shared(base_url: &str) -> Arc<Self> {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("default HTTP client settings are valid");
+        Arc::new(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// Look up mods by name; names the portal doesn't know are left out of the result
+    pub async fn get_mods(&self, names: &[&str]) -> Result<Vec<PortalMod>, ApiError> {
+        let mut mods = Vec::new();
+        for chunk in names.chunks(NAMES_PER_REQUEST) {
+            let url = format!("{}/api/mods", self.base_url);
+            let response = self
+                .client
+                .get(&url)
+                .query(&[("namelist", chunk.join(",")), ("page_size", "max".to_string())])
+                .send()
+                .await?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(ApiError::InvalidResponse(format!("{}: {}", status, body)));
+            }
+
+            let list: ModListResponse = response.json().await?;
+            mods.extend(list.results.into_iter().map(PortalMod::from));
+        }
+        Ok(mods)
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket: up to `burst` requests at once, refilled at a steady rate
+#[derive(Debug)]
+pub struct TokenBucket {
+    burst: f64,
+    /// Tokens added per second
+    rate: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket allowing `per_minute` requests a minute on average and `burst` back to back
+    pub fn new(per_minute: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            burst,
+            rate: f64::from(per_minute.max(1)) / 60.0,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token, or say how long until the next one is available
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let refill = now.saturating_duration_since(state.refilled_at).as_secs_f64() * self.rate;
+        state.tokens = (state.tokens + refill).min(self.burst);
+        state.refilled_at = now;
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - state.tokens) / self.rate))
+        }
+    }
+}
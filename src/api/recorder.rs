@@ -0,0 +1,89 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const FILE_PREFIX: &str = "get-games-";
+const FILE_SUFFIX: &str = ".json.gz";
+/// Receive time in file names, UTC with milliseconds so names sort chronologically
+const TIME_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+/// Keeps every raw get-games response on disk (gzipped) for a number of days,
+/// so ingestion bugs can be reproduced later with `factorio-browser replay`
+#[derive(Debug)]
+pub struct ResponseRecorder {
+    dir: PathBuf,
+    retention: chrono::Duration,
+}
+
+impl ResponseRecorder {
+    /// Recorder writing to `dir` (created if missing)
+    pub fn new(dir: impl Into<PathBuf>, retention_days: i64) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            retention: chrono::Duration::days(retention_days),
+        })
+    }
+
+    pub fn new_shared(dir: impl Into<PathBuf>, retention_days: i64) -> io::Result<Arc<Self>> {
+        Self::new(dir, retention_days).map(Arc::new)
+    }
+
+    /// Compress and store one response body, named by the time it was received
+    pub fn record(&self, body: &str, at: DateTime<Utc>) -> io::Result<PathBuf> {
+        let path = self
+            .dir
+            .join(format!("{}{}{}", FILE_PREFIX, at.format(TIME_FORMAT), FILE_SUFFIX));
+        let mut encoder = GzEncoder::new(File::create(&path)?, Compression::default());
+        encoder.write_all(body.as_bytes())?;
+        encoder.finish()?;
+        Ok(path)
+    }
+
+    /// Delete recordings older than the retention period, returning how many were removed
+    pub fn prune(&self, now: DateTime<Utc>) -> io::Result<usize> {
+        let cutoff = now - self.retention;
+        let mut removed = 0;
+        for (at, path) in list_recordings(&self.dir)? {
+            if at < cutoff {
+                fs::remove_file(path)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// When a recording was received, from its file name
+fn recorded_at(path: &Path) -> Option<DateTime<Utc>> {
+    let name = path.file_name()?.to_str()?;
+    let time = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?;
+    NaiveDateTime::parse_from_str(time, TIME_FORMAT).ok().map(|t| t.and_utc())
+}
+
+/// Recordings in `dir`, oldest first, with the time each response was received
+/// Files that don't look like recordings are ignored
+pub fn list_recordings(dir: &Path) -> io::Result<Vec<(DateTime<Utc>, PathBuf)>> {
+    let mut recordings = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if let Some(at) = recorded_at(&path) {
+            recordings.push((at, path));
+        }
+    }
+    recordings.sort();
+    Ok(recordings)
+}
+
+/// Decompress one recording back into the raw response body
+pub fn read_recording(path: &Path) -> io::Result<String> {
+    let mut body = String::new();
+    GzDecoder::new(File::open(path)?).read_to_string(&mut body)?;
+    Ok(body)
+}
@@ -0,0 +1,92 @@
+use crate::api::factorio::ApiError;
+use reqwest::Client;
+use serde::Deserialize;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Public AbuseIPDB API
+pub const ABUSEIPDB_URL: &str = "https://api.abuseipdb.com";
+
+/// Only reports this recent count, so a host that changed hands isn't blamed for its old owner
+const MAX_AGE_DAYS: u32 = 90;
+
+/// Host IPs checked per refresh, keeping well inside the free plan's daily quota
+pub const REPUTATION_LOOKUPS_PER_REFRESH: usize = 10;
+
+/// Reports an address needs before its servers are flagged, unless ABUSE_REPORTS_THRESHOLD says otherwise
+pub const DEFAULT_ABUSE_REPORTS_THRESHOLD: u32 = 10;
+
+/// Lookups run in the refresh loop, so a slow service mustn't hold it up for long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Abuse reports against one IP address
+#[derive(Debug, Clone, PartialEq)]
+pub struct IpReport {
+    /// Reports in the last MAX_AGE_DAYS
+    pub reports: u32,
+    /// AbuseIPDB's confidence that the address is abusive, 0-100
+    pub confidence: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckData {
+    #[serde(default)]
+    abuse_confidence_score: u32,
+    #[serde(default)]
+    total_reports: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckResponse {
+    data: CheckData,
+}
+
+/// Client for the AbuseIPDB check API (needs an API key)
+#[derive(Clone)]
+pub struct ReputationClient {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl ReputationClient {
+    /// Create a new client wrapped in Arc for sharing
+    pub fn new_shared(base_url: &str, api_key: &str) -> Arc<Self> {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("default HTTP client settings are valid");
+        Arc::new(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        })
+    }
+
+    /// Look up the abuse reports filed against an address
+    pub async fn check(&self, ip: IpAddr) -> Result<IpReport, ApiError> {
+        let url = format!("{}/api/v2/check", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .header("Key", &self.api_key)
+            .header("Accept", "application/json")
+            .query(&[("ipAddress", ip.to_string()), ("maxAgeInDays", MAX_AGE_DAYS.to_string())])
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::InvalidResponse(format!("{}: {}", status, body)));
+        }
+
+        let check: CheckResponse = response.json().await?;
+        Ok(IpReport {
+            reports: check.data.total_reports,
+            confidence: check.data.abuse_confidence_score,
+        })
+    }
+}
@@ -1,32 +1,29 @@
-use crate::db::models::CachedServer;
-use crate::db::queries::DbClient;
-use rocket::form::FromForm;
+use crate::api::keys::ApiKey;
+use crate::cache::ServerCache;
+use crate::changes::{ListingChange, ListingChanges, DEFAULT_CHANGES_LIMIT, MAX_LISTING_CHANGES};
+use crate::db::models::{Materialized, VersionTotals};
+use crate::db::queries::{DbClient, VERSION_HISTORY_RETENTION_HOURS};
+use crate::etag::{etag, IfNoneMatch, Tagged};
+use crate::filter::{versions_newest_first, FilterSpec, ServerFilters};
+use crate::ids::GameId;
+use crate::players::{PlayerSearch, RecentPlayers, MAX_PLAYER_NAME_LEN};
+use crate::stats::{GlobalStats, GLOBAL_STATS_KEY};
+use crate::tags::TagAliases;
+use crate::view::ServerView;
+use rocket::http::uri::Origin;
+use rocket::http::{Header, Status};
+use rocket::response::{self, Responder, Response};
 use rocket::serde::json::Json;
-use rocket::{get, State};
+use rocket::{get, Request, State};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-
-/// Query parameters for server filtering
-#[derive(Debug, FromForm, Default)]
-pub struct ServerFilters {
-    /// Search by server name
-    pub search: Option<String>,
-    /// Filter by game version
-    pub version: Option<String>,
-    /// Only show servers with players
-    pub has_players: Option<bool>,
-    /// Only show servers without password
-    pub no_password: Option<bool>,
-    /// Filter by mod count (minimum)
-    pub min_mods: Option<u32>,
-    /// Maximum number of results
-    pub limit: Option<usize>,
-}
+use std::time::Duration;
+use tokio::sync::RwLock;
 
 /// API response for server list
 #[derive(Debug, Serialize)]
 pub struct ServersResponse {
-    pub servers: Vec<CachedServer>,
+    pub servers: Vec<ServerView>,
     pub total: usize,
     pub cached_at: Option<String>,
 }
@@ -34,99 +31,168 @@ pub struct ServersResponse {
 /// API response for server details
 #[derive(Debug, Serialize)]
 pub struct ServerDetailsResponse {
-    pub server: Option<CachedServer>,
+    pub server: Option<ServerView>,
     pub history: Vec<PlayerCountHistory>,
 }
 
+/// Details of a listed server as of the last background refresh (never fetched on request)
+#[derive(Debug, Serialize)]
+pub struct CachedDetailsResponse {
+    pub server: ServerView,
+    /// When the refresh this comes from was published (RFC 3339)
+    pub refreshed_at: String,
+    /// When the next refresh is due; the data is never older than one refresh interval plus the
+    /// time a refresh takes
+    pub next_refresh_at: String,
+}
+
+/// Longest wait between successful listing refreshes (interval plus jitter), managed for routes that
+/// state how fresh their data is
+#[derive(Debug, Clone, Copy)]
+pub struct RefreshInterval(pub Duration);
+
+/// A response with `Age` counted from the refresh that produced it, and `max-age` of one refresh
+/// interval, so shared caches keep it exactly until newer data can exist
+pub struct Aged<R> {
+    pub inner: R,
+    pub age: Duration,
+    pub max_age: Duration,
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Aged<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'o> {
+        Response::build_from(self.inner.respond_to(req)?)
+            .header(Header::new("Age", self.age.as_secs().to_string()))
+            .header(Header::new("Cache-Control", format!("public, max-age={}", self.max_age.as_secs())))
+            .ok()
+    }
+}
+
+/// /health answers 503 once the last refresh is older than this
+#[derive(Debug, Clone, Copy)]
+pub struct HealthMaxAge(pub Duration);
+
+/// Sanitized message of the last failed refresh (None after a successful one), managed for /health
+#[derive(Debug, Clone)]
+pub struct LastRefreshError(pub Arc<RwLock<Option<String>>>);
+
+/// Health check response, for load balancers and uptime monitors
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    /// "ok", "starting" before the first refresh, or "stale" when the cache is too old
+    pub status: &'static str,
+    /// The database answered a query just now
+    pub database: bool,
+    /// Reads are served by a replica because the primary database is down
+    pub database_replica: bool,
+    /// When the cached listing was last refreshed (RFC 3339)
+    pub last_refresh_at: Option<String>,
+    pub cache_age_secs: Option<u64>,
+    pub last_error: Option<String>,
+    pub cached_servers: usize,
+}
+
 /// Player count history entry
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, async_graphql::SimpleObject)]
 pub struct PlayerCountHistory {
     pub player_count: usize,
     pub recorded_at: String,
 }
 
-/// Health check endpoint
+/// Health check: 200 while the cached listing is fresh, 503 before the first refresh and once it's
+/// older than HEALTH_MAX_AGE_SECS
 #[get("/health")]
-pub fn health() -> &'static str {
-    "OK"
+pub async fn health(
+    db: &State<Arc<DbClient>>,
+    cache: &State<Arc<ServerCache>>,
+    last_error: &State<LastRefreshError>,
+    max_age: &State<HealthMaxAge>,
+) -> (Status, Json<HealthResponse>) {
+    let snapshot = cache.load();
+    let age = snapshot
+        .updated_at
+        .map(|t| (chrono::Utc::now() - t).to_std().unwrap_or_default());
+    let status = match age {
+        None => "starting",
+        Some(age) if age > max_age.0 => "stale",
+        Some(_) => "ok",
+    };
+    let database = match db.ping().await {
+        Ok(()) => true,
+        Err(e) => {
+            eprintln!("Health check failed to reach the database: {}", e);
+            false
+        }
+    };
+
+    let response = HealthResponse {
+        status,
+        database,
+        database_replica: db.reading_from_replica(),
+        last_refresh_at: snapshot.updated_at.map(|t| t.to_rfc3339()),
+        cache_age_secs: age.map(|a| a.as_secs()),
+        last_error: last_error.0.read().await.clone(),
+        cached_servers: snapshot.servers.len(),
+    };
+    let code = if status == "ok" { Status::Ok } else { Status::ServiceUnavailable };
+    (code, Json(response))
 }
 
-/// Get list of cached servers with optional filtering
+/// Get list of listed servers with optional filtering
+/// Served from the in-memory listing, so hidden servers stay out; only a search touches the DB,
+/// and its results come most relevant first
 #[get("/api/servers?<filters..>")]
 pub async fn get_servers(
+    _key: ApiKey,
     db: &State<Arc<DbClient>>,
+    tag_aliases: &State<Arc<TagAliases>>,
+    cache: &State<Arc<ServerCache>>,
     filters: ServerFilters,
-) -> Json<ServersResponse> {
-    let all_servers = db.get_all_servers().await.unwrap_or_default();
+    uri: &Origin<'_>,
+    if_none_match: IfNoneMatch,
+) -> Tagged<Json<ServersResponse>> {
+    // The listing only changes when a refresh publishes a new cache generation
+    let snapshot = cache.load();
+    let etag = etag(&snapshot, &[uri.query().map(|q| q.as_str()).unwrap_or_default()]);
+    if if_none_match.matches(&etag) {
+        return Tagged::NotModified(etag);
+    }
 
-    let filtered: Vec<CachedServer> = all_servers
+    let spec = db.with_search(FilterSpec::from(&filters).with_tag_aliases(tag_aliases)).await;
+    let latest_version = versions_newest_first(snapshot.servers.iter().map(|s| &s.server))
         .into_iter()
-        .filter(|s| {
-            // Search filter
-            if let Some(ref search) = filters.search {
-                let search_lower = search.to_lowercase();
-                let name_matches = s.name.to_lowercase().contains(&search_lower);
-                let desc_matches = s.description.to_lowercase().contains(&search_lower);
-                let tags_match = s.tags.iter().any(|t| t.to_lowercase().contains(&search_lower));
-                if !name_matches && !desc_matches && !tags_match {
-                    return false;
-                }
-            }
-
-            // Version filter
-            if let Some(ref version) = filters.version {
-                if !s.game_version.starts_with(version) {
-                    return false;
-                }
-            }
-
-            // Has players filter
-            if let Some(has_players) = filters.has_players {
-                if has_players && s.player_count == 0 {
-                    return false;
-                }
-            }
-
-            // No password filter
-            if let Some(no_password) = filters.no_password {
-                if no_password && s.has_password {
-                    return false;
-                }
-            }
-
-            // Min mods filter
-            if let Some(min_mods) = filters.min_mods {
-                if s.mod_count < min_mods {
-                    return false;
-                }
-            }
-
-            true
-        })
+        .next()
+        .unwrap_or_default();
+
+    let mut filtered: Vec<ServerView> = snapshot
+        .servers
+        .iter()
+        .filter(|s| spec.matches(&s.server, &latest_version) && spec.matches_view(s))
+        .cloned()
         .collect();
+    spec.sort_by_relevance(&mut filtered);
 
     let total = filtered.len();
-    let servers = if let Some(limit) = filters.limit {
-        filtered.into_iter().take(limit).collect()
-    } else {
-        filtered
-    };
+    let limit = spec.limit.unwrap_or(usize::MAX);
+    filtered.truncate(limit);
+    let servers = filtered;
 
-    let cached_at = servers.first().map(|s| s.cached_at.clone());
+    let cached_at = servers.first().map(|s| s.server.cached_at.clone());
 
-    Json(ServersResponse {
+    Tagged::Fresh(etag, Json(ServersResponse {
         servers,
         total,
         cached_at,
-    })
+    }))
 }
 
 /// Get details for a specific server by game_id
 #[get("/api/servers/<game_id>")]
-pub async fn get_server(db: &State<Arc<DbClient>>, game_id: u64) -> Json<ServerDetailsResponse> {
-    let server = db.get_server(game_id).await.ok().flatten();
+pub async fn get_server(_key: ApiKey, db: &State<Arc<DbClient>>, game_id: GameId) -> Json<ServerDetailsResponse> {
+    let server = db.get_server(game_id).await.ok().flatten().map(ServerView::from);
+    let server_id = server.as_ref().and_then(|s| s.server.server_id.as_ref());
     let history = db
-        .get_server_history(game_id, 24)
+        .get_server_history(game_id, server_id, 24)
         .await
         .unwrap_or_default()
         .into_iter()
@@ -139,16 +205,45 @@ pub async fn get_server(db: &State<Arc<DbClient>>, game_id: u64) -> Json<ServerD
     Json(ServerDetailsResponse { server, history })
 }
 
+/// Cached details for a listed server by game_id, for scrapers and integrations
+/// Served from the last refresh only, so no request here ever reaches the Factorio API; unlike the
+/// server page, which fetches live players and mods
+#[get("/api/servers/<game_id>/details")]
+pub fn get_cached_details(
+    _key: ApiKey,
+    cache: &State<Arc<ServerCache>>,
+    interval: &State<RefreshInterval>,
+    game_id: GameId,
+) -> Option<Aged<Json<CachedDetailsResponse>>> {
+    let snapshot = cache.load();
+    let refreshed_at = snapshot.updated_at?;
+    let server = snapshot.find(game_id)?.clone();
+    let age = (chrono::Utc::now() - refreshed_at).to_std().unwrap_or_default();
+    let next_refresh_at = refreshed_at + chrono::Duration::from_std(interval.0).unwrap_or_default();
+
+    Some(Aged {
+        inner: Json(CachedDetailsResponse {
+            server,
+            refreshed_at: refreshed_at.to_rfc3339(),
+            next_refresh_at: next_refresh_at.to_rfc3339(),
+        }),
+        age,
+        max_age: interval.0,
+    })
+}
+
 /// Get player count history for a server
 #[get("/api/servers/<game_id>/history?<hours>")]
 pub async fn get_server_history(
+    _key: ApiKey,
     db: &State<Arc<DbClient>>,
-    game_id: u64,
+    game_id: GameId,
     hours: Option<u32>,
 ) -> Json<Vec<PlayerCountHistory>> {
     let limit = hours.unwrap_or(24);
+    let server_id = db.get_server(game_id).await.ok().flatten().and_then(|s| s.server_id);
     let history = db
-        .get_server_history(game_id, limit)
+        .get_server_history(game_id, server_id.as_ref(), limit)
         .await
         .unwrap_or_default()
         .into_iter()
@@ -161,3 +256,51 @@ pub async fn get_server_history(
     Json(history)
 }
 
+/// Find which servers a player is on now, or was on in the last few hours
+#[get("/api/players/<name>")]
+pub async fn get_player(
+    _key: ApiKey,
+    cache: &State<Arc<ServerCache>>,
+    recent: &State<Arc<RwLock<RecentPlayers>>>,
+    name: &str,
+) -> Option<Json<PlayerSearch>> {
+    if name.trim().is_empty() || name.len() > MAX_PLAYER_NAME_LEN {
+        return None;
+    }
+    let snapshot = cache.load();
+    Some(Json(recent.read().await.search(&snapshot.servers, name)))
+}
+
+/// Servers that recently appeared in or disappeared from the listing, newest first
+#[get("/api/changes?<limit>")]
+pub async fn get_changes(
+    _key: ApiKey,
+    changes: &State<Arc<RwLock<ListingChanges>>>,
+    limit: Option<usize>,
+) -> Json<Vec<ListingChange>> {
+    let limit = limit.unwrap_or(DEFAULT_CHANGES_LIMIT).min(MAX_LISTING_CHANGES);
+    Json(changes.read().await.latest(limit))
+}
+
+/// Get listing-wide statistics (precomputed by the scheduler)
+#[get("/api/stats")]
+pub async fn get_stats(_key: ApiKey, db: &State<Arc<DbClient>>) -> Option<Json<Materialized<GlobalStats>>> {
+    db.get_materialized(GLOBAL_STATS_KEY)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load stats: {}", e);
+            None
+        })
+        .map(Json)
+}
+
+/// Servers and players per game version per hour, oldest first, to follow how fast releases are adopted
+#[get("/api/versions/history?<days>")]
+pub async fn get_version_history(_key: ApiKey, db: &State<Arc<DbClient>>, days: Option<i64>) -> Json<Vec<VersionTotals>> {
+    let hours = days.map(|d| d * 24).unwrap_or(VERSION_HISTORY_RETENTION_HOURS).clamp(1, VERSION_HISTORY_RETENTION_HOURS);
+    let totals = db.get_version_totals(hours).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load version totals: {}", e);
+        Vec::new()
+    });
+    Json(totals)
+}
@@ -0,0 +1,81 @@
+use crate::api::factorio::{ApiError, FactorioClient, GameDetails, GameServer};
+use crate::ids::GameId;
+use rocket::async_trait;
+
+/// Anonymized snapshot shown when no credentials are configured
+const DEMO_SNAPSHOT: &str = include_str!("../../fixtures/demo_servers.json");
+
+/// Every server_id in the demo snapshot starts with this, so its rows can be told apart from real ones
+pub const DEMO_SERVER_ID_PREFIX: &str = "demo-";
+
+/// Where server listings come from (the live matchmaking API or a fixture)
+#[async_trait]
+pub trait ServerSource: Send + Sync {
+    /// Fetch all public game servers
+    async fn get_games(&self) -> Result<Vec<GameServer>, ApiError>;
+
+    /// Fetch detailed info for a single server
+    async fn get_game_details(&self, game_id: GameId) -> Result<GameDetails, ApiError>;
+}
+
+#[async_trait]
+impl ServerSource for FactorioClient {
+    async fn get_games(&self) -> Result<Vec<GameServer>, ApiError> {
+        FactorioClient::get_games(self).await
+    }
+
+    async fn get_game_details(&self, game_id: GameId) -> Result<GameDetails, ApiError> {
+        FactorioClient::get_game_details(self, game_id).await
+    }
+}
+
+/// Static server list loaded from JSON in the get-games response format
+pub struct FixtureSource {
+    servers: Vec<GameServer>,
+}
+
+impl FixtureSource {
+    /// Load a fixture from get-games JSON
+    pub fn from_json(json: &str) -> Result<Self, ApiError> {
+        let servers = serde_json::from_str(json).map_err(|e| ApiError::InvalidResponse(e.to_string()))?;
+        Ok(Self { servers })
+    }
+
+    /// The bundled demo snapshot
+    pub fn demo() -> Result<Self, ApiError> {
+        Self::from_json(DEMO_SNAPSHOT)
+    }
+}
+
+#[async_trait]
+impl ServerSource for FixtureSource {
+    async fn get_games(&self) -> Result<Vec<GameServer>, ApiError> {
+        Ok(self.servers.clone())
+    }
+
+    async fn get_game_details(&self, game_id: GameId) -> Result<GameDetails, ApiError> {
+        let server = self
+            .servers
+            .iter()
+            .find(|s| s.game_id == game_id)
+            .cloned()
+            .ok_or(ApiError::NotFound)?;
+
+        // Fixtures don't carry mod lists
+        Ok(GameDetails {
+            game_id: server.game_id,
+            name: server.name,
+            description: server.description,
+            max_players: server.max_players,
+            players: server.players,
+            game_time_elapsed: server.game_time_elapsed,
+            has_password: server.has_password,
+            tags: server.tags,
+            application_version: server.application_version,
+            mods: Vec::new(),
+            host_address: server.host_address,
+            has_mods: server.has_mods,
+            headless_server: server.headless_server,
+        })
+    }
+}
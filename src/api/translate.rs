@@ -0,0 +1,146 @@
+use crate::api::factorio::ApiError;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use whatlang::Lang;
+
+/// The details page waits on the translation, so a slow backend mustn't hold it up for long
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Client for a LibreTranslate-compatible translation backend
+#[derive(Clone)]
+pub struct TranslateClient {
+    client: Client,
+    endpoint: String,
+    api_key: Option<String>,
+    target: String,
+}
+
+/// Request body for the LibreTranslate /translate endpoint
+#[derive(Debug, Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+/// Response body from the LibreTranslate /translate endpoint
+#[derive(Debug, Deserialize)]
+struct TranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+impl TranslateClient {
+    /// Create a new client wrapped in Arc for sharing
+    pub fn new_shared(endpoint: String, api_key: Option<String>, target: String) -> Arc<Self> {
+        Arc::new(Self {
+            client: Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("default HTTP client settings are valid"),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key,
+            target,
+        })
+    }
+
+    /// Target language code (e.g. "en")
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Whether `text` is reliably detected as already being in the target language, so there's
+    /// nothing to translate; short or mixed texts and targets without a known language count as not
+    pub fn is_in_target(&self, text: &str) -> bool {
+        let Some(target) = detectable_lang(&self.target) else {
+            return false;
+        };
+        whatlang::detect(text).is_some_and(|info| info.is_reliable() && info.lang() == target)
+    }
+
+    /// Translate text into the target language, auto-detecting the source language
+    pub async fn translate(&self, text: &str) -> Result<String, ApiError> {
+        let url = format!("{}/translate", self.endpoint);
+        let body = TranslateRequest {
+            q: text,
+            source: "auto",
+            target: &self.target,
+            format: "text",
+            api_key: self.api_key.as_deref(),
+        };
+
+        let response = self.client.post(&url).json(&body).send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ApiError::InvalidResponse(format!("{}: {}", status, body)));
+        }
+
+        let translated: TranslateResponse = response.json().await?;
+        Ok(translated.translated_text)
+    }
+}
+
+/// Language of an ISO 639-1 target code as LibreTranslate takes it, for the languages the detector
+/// tells apart reliably
+fn detectable_lang(code: &str) -> Option<Lang> {
+    let lang = match code.split(['-', '_']).next()?.to_ascii_lowercase().as_str() {
+        "en" => Lang::Eng,
+        "de" => Lang::Deu,
+        "fr" => Lang::Fra,
+        "es" => Lang::Spa,
+        "it" => Lang::Ita,
+        "pt" => Lang::Por,
+        "nl" => Lang::Nld,
+        "pl" => Lang::Pol,
+        "cs" => Lang::Ces,
+        "sv" => Lang::Swe,
+        "da" => Lang::Dan,
+        "fi" => Lang::Fin,
+        "hu" => Lang::Hun,
+        "tr" => Lang::Tur,
+        "ru" => Lang::Rus,
+        "uk" => Lang::Ukr,
+        "ja" => Lang::Jpn,
+        "ko" => Lang::Kor,
+        "zh" => Lang::Cmn,
+        _ => return None,
+    };
+    Some(lang)
+}
+
+/// Stable hash of a text used as the translation cache key
+pub fn text_hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(target: &str) -> Arc<TranslateClient> {
+        TranslateClient::new_shared("http://localhost:5000".to_string(), None, target.to_string())
+    }
+
+    #[test]
+    fn only_reliably_detected_target_text_is_skipped() {
+        let english = "Welcome to our friendly server, we are building a megabase together every weekend";
+        let german = "Willkommen auf unserem freundlichen Server, wir bauen jedes Wochenende gemeinsam eine Megabasis";
+        assert!(client("en").is_in_target(english));
+        assert!(!client("en").is_in_target(german));
+        assert!(client("de").is_in_target(german));
+        // Too short to tell, and a target the detector doesn't know
+        assert!(!client("en").is_in_target("PvP"));
+        assert!(!client("eo-x").is_in_target(english));
+    }
+}
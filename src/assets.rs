@@ -0,0 +1,96 @@
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Hex characters of the content hash in fingerprinted file names
+const HASH_LEN: usize = 12;
+
+/// Cache-Control for fingerprinted files: a changed file gets a new name, so this one never changes
+pub const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Cache-Control for files requested by their plain name (links from elsewhere, the service
+/// worker's icon); short, since nothing tells caches when they change
+pub const UNVERSIONED: &str = "public, max-age=3600";
+
+/// Static files under content-hashed names, e.g. `style.css` as `style.3f2a9c1b04de.css`
+/// Built once at startup, so pages link each file by a name that changes exactly when it does and
+/// browsers never need to revalidate
+#[derive(Debug, Default)]
+pub struct AssetManifest {
+    dir: PathBuf,
+    /// Fingerprinted name by path relative to the static directory
+    hashed: HashMap<String, String>,
+    /// Path relative to the static directory by fingerprinted name
+    files: HashMap<String, String>,
+}
+
+impl AssetManifest {
+    /// Hash every file under `dir` (a missing directory gives an empty manifest)
+    pub fn build(dir: &Path) -> Self {
+        let mut manifest = Self {
+            dir: dir.to_path_buf(),
+            ..Default::default()
+        };
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            let entries = match std::fs::read_dir(&current) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    eprintln!("Failed to read static directory {}: {}", current.display(), e);
+                    continue;
+                }
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    pending.push(path);
+                    continue;
+                }
+                let Some(relative) = path.strip_prefix(dir).ok().and_then(|p| p.to_str()).map(|p| p.replace('\\', "/"))
+                else {
+                    continue;
+                };
+                match std::fs::read(&path) {
+                    Ok(contents) => {
+                        let hashed = fingerprinted_name(&relative, &contents);
+                        manifest.files.insert(hashed.clone(), relative.clone());
+                        manifest.hashed.insert(relative, hashed);
+                    }
+                    Err(e) => eprintln!("Failed to hash static file {}: {}", path.display(), e),
+                }
+            }
+        }
+        manifest
+    }
+
+    /// URL of a static file, e.g. `url("style.css")`; files added after startup keep their plain name
+    pub fn url(&self, path: &str) -> String {
+        format!("/static/{}", self.hashed.get(path).map(String::as_str).unwrap_or(path))
+    }
+
+    /// File on disk for a requested name, and whether the name was fingerprinted
+    pub fn resolve(&self, requested: &Path) -> (PathBuf, bool) {
+        let fingerprinted = requested
+            .to_str()
+            .map(|r| r.replace('\\', "/"))
+            .and_then(|r| self.files.get(&r));
+        match fingerprinted {
+            Some(relative) => (self.dir.join(relative), true),
+            None => (self.dir.join(requested), false),
+        }
+    }
+}
+
+/// `dir/name.ext` as `dir/name.<hash>.ext` (`name.<hash>` without an extension)
+fn fingerprinted_name(relative: &str, contents: &[u8]) -> String {
+    let digest = Sha256::digest(contents);
+    let hash: String = digest.iter().map(|b| format!("{:02x}", b)).collect::<String>()[..HASH_LEN].to_string();
+    let (dir, file) = match relative.rsplit_once('/') {
+        Some((dir, file)) => (format!("{}/", dir), file),
+        None => (String::new(), relative),
+    };
+    match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{}{}.{}.{}", dir, stem, hash, ext),
+        _ => format!("{}{}.{}", dir, file, hash),
+    }
+}
@@ -0,0 +1,58 @@
+use crate::utils::escape_xml;
+
+/// Approximate rendered width of badge text (Verdana 11px, shields.io style)
+fn text_width(text: &str) -> usize {
+    text.chars().count() * 7 + 10
+}
+
+/// Badge color for an uptime percentage (shields.io palette)
+pub fn uptime_color(uptime: Option<f64>) -> &'static str {
+    match uptime {
+        None => "#9f9f9f",
+        Some(u) if u >= 99.0 => "#4c1",
+        Some(u) if u >= 95.0 => "#97ca00",
+        Some(u) if u >= 90.0 => "#a4a61d",
+        Some(u) if u >= 75.0 => "#dfb317",
+        Some(u) if u >= 50.0 => "#fe7d37",
+        Some(_) => "#e05d44",
+    }
+}
+
+/// Format an uptime percentage for display on a badge
+pub fn format_uptime(uptime: Option<f64>) -> String {
+    match uptime {
+        None => "unknown".to_string(),
+        Some(u) if u >= 99.95 => "100%".to_string(),
+        Some(u) => format!("{:.1}%", u),
+    }
+}
+
+/// Render a flat two-part badge as SVG (label on grey, value on `color`)
+pub fn render_badge(label: &str, value: &str, color: &str) -> String {
+    let label_width = text_width(label);
+    let value_width = text_width(value);
+    let total_width = label_width + value_width;
+    let label = escape_xml(label);
+    let value = escape_xml(value);
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{total}" height="20" role="img" aria-label="{label}: {value}">
+<title>{label}: {value}</title>
+<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+<clipPath id="r"><rect width="{total}" height="20" rx="3" fill="#fff"/></clipPath>
+<g clip-path="url(#r)"><rect width="{lw}" height="20" fill="#555"/><rect x="{lw}" width="{vw}" height="20" fill="{color}"/><rect width="{total}" height="20" fill="url(#s)"/></g>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+<text x="{lx}" y="15" fill="#010101" fill-opacity=".3">{label}</text><text x="{lx}" y="14">{label}</text>
+<text x="{vx}" y="15" fill="#010101" fill-opacity=".3">{value}</text><text x="{vx}" y="14">{value}</text>
+</g>
+</svg>"##,
+        total = total_width,
+        lw = label_width,
+        vw = value_width,
+        lx = label_width / 2,
+        vx = label_width + value_width / 2,
+        label = label,
+        value = value,
+        color = color,
+    )
+}
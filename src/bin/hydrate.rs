@@ -0,0 +1,26 @@
+//! WASM entry point that hydrates the server-rendered server list (see `make wasm`)
+//! The page embeds the list's props as JSON next to the markup they were rendered from
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use factorio_browser::components::live_list::LiveList;
+    use factorio_browser::components::server_list::ServerListProps;
+
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else { return };
+    let (Some(root), Some(data)) = (document.get_element_by_id("server-list"), document.get_element_by_id("server-list-data")) else {
+        return;
+    };
+    let props: ServerListProps = match serde_json::from_str(&data.text_content().unwrap_or_default()) {
+        Ok(props) => props,
+        Err(e) => {
+            web_sys::console::error_1(&format!("Server list data is unreadable, staying server-rendered: {}", e).into());
+            return;
+        }
+    };
+    yew::Renderer::<LiveList>::with_root_and_props(root, props).hydrate();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    eprintln!("The hydrate binary only runs in the browser; build it with `make wasm`");
+}
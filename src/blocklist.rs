@@ -0,0 +1,186 @@
+use crate::api::factorio::GameServer;
+use crate::db::models::{BlockKind, BlocklistEntry};
+use crate::geo::host_ip;
+use crate::ids::ServerId;
+use crate::utils::strip_all_tags;
+use regex::{Regex, RegexBuilder};
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+
+/// Longest pattern accepted on the admin page
+pub const MAX_PATTERN_LEN: usize = 200;
+
+/// Compiled size limit for name patterns, so one entry can't make every refresh crawl
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// The blocklist compiled for matching, rebuilt whenever an entry is added or removed
+#[derive(Debug, Default)]
+pub struct Blocklist {
+    server_ids: HashSet<ServerId>,
+    /// Bare IPs and "ip:port" addresses, in their normalized form
+    hosts: HashSet<String>,
+    names: Vec<Regex>,
+}
+
+impl Blocklist {
+    /// Compile stored entries; ones that no longer parse are skipped with a warning
+    pub fn new(entries: &[BlocklistEntry]) -> Self {
+        let mut blocklist = Self::default();
+        for entry in entries {
+            match check_pattern(entry.kind, &entry.pattern) {
+                Err(e) => eprintln!("Warning: skipping blocklist entry '{}': {}", entry.pattern, e),
+                Ok(Pattern::ServerId(pattern)) => {
+                    blocklist.server_ids.insert(ServerId(pattern));
+                }
+                Ok(Pattern::Host(pattern)) => {
+                    blocklist.hosts.insert(pattern);
+                }
+                Ok(Pattern::Name(regex)) => blocklist.names.push(regex),
+            }
+        }
+        blocklist
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.server_ids.is_empty() && self.hosts.is_empty() && self.names.is_empty()
+    }
+
+    /// Whether a server matches any entry; the host is unknown for servers off the listing
+    pub fn matches(&self, server_id: Option<&ServerId>, host_address: Option<&str>, name: &str) -> bool {
+        server_id.is_some_and(|id| self.server_ids.contains(id))
+            || host_address.is_some_and(|host| self.blocks_host(host))
+            || self.blocks_name(name)
+    }
+
+    /// Whether a fetched listing matches any entry
+    pub fn blocks(&self, server: &GameServer) -> bool {
+        self.matches(server.server_id.as_ref(), server.host_address.as_deref(), &server.name)
+    }
+
+    fn blocks_host(&self, host_address: &str) -> bool {
+        self.hosts.contains(host_address) || host_ip(host_address).is_some_and(|ip| self.hosts.contains(&ip.to_string()))
+    }
+
+    fn blocks_name(&self, name: &str) -> bool {
+        if self.names.is_empty() {
+            return false;
+        }
+        let name = strip_all_tags(name);
+        self.names.iter().any(|regex| regex.is_match(&name))
+    }
+
+    /// Drop blocked listings, returning the rest and how many were dropped
+    pub fn filter(&self, servers: Vec<GameServer>) -> (Vec<GameServer>, usize) {
+        if self.is_empty() {
+            return (servers, 0);
+        }
+        let before = servers.len();
+        let kept: Vec<GameServer> = servers.into_iter().filter(|server| !self.blocks(server)).collect();
+        let blocked = before - kept.len();
+        (kept, blocked)
+    }
+}
+
+/// A checked blocklist pattern, ready to match
+enum Pattern {
+    ServerId(String),
+    Host(String),
+    Name(Regex),
+}
+
+/// Check a pattern from the admin page and put it in the form it's stored and matched in
+pub fn normalize_pattern(kind: BlockKind, pattern: &str) -> Result<String, String> {
+    Ok(match check_pattern(kind, pattern)? {
+        Pattern::ServerId(pattern) | Pattern::Host(pattern) => pattern,
+        Pattern::Name(regex) => regex.as_str().to_string(),
+    })
+}
+
+fn check_pattern(kind: BlockKind, pattern: &str) -> Result<Pattern, String> {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err("the pattern is empty".to_string());
+    }
+    if pattern.len() > MAX_PATTERN_LEN {
+        return Err(format!("the pattern is longer than {} characters", MAX_PATTERN_LEN));
+    }
+    match kind {
+        BlockKind::ServerId => Ok(Pattern::ServerId(pattern.to_string())),
+        // host_address is always an IP, so hostnames could never match
+        BlockKind::Host => pattern
+            .parse::<SocketAddr>()
+            .map(|addr| addr.to_string())
+            .or_else(|_| pattern.parse::<IpAddr>().map(|ip| ip.to_string()))
+            .map(Pattern::Host)
+            .map_err(|_| "a host must be an IP address, optionally with a port".to_string()),
+        BlockKind::Name => compile_name(pattern).map(Pattern::Name),
+    }
+}
+
+fn compile_name(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        // Parse errors draw a caret under the pattern on the lines above; only the last line reads well in a page
+        .map_err(|e| format!("invalid regular expression ({})", e.to_string().lines().last().unwrap_or_default().trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: BlockKind, pattern: &str) -> BlocklistEntry {
+        BlocklistEntry {
+            id: None,
+            kind,
+            pattern: pattern.to_string(),
+            reason: "spam".to_string(),
+            added_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn hosts_are_normalized() {
+        assert_eq!(normalize_pattern(BlockKind::Host, " 1.2.3.4 ").unwrap(), "1.2.3.4");
+        assert_eq!(normalize_pattern(BlockKind::Host, "1.2.3.4:34197").unwrap(), "1.2.3.4:34197");
+        assert_eq!(normalize_pattern(BlockKind::Host, "2001:DB8::1").unwrap(), "2001:db8::1");
+        assert_eq!(normalize_pattern(BlockKind::Host, "[2001:db8:0::1]:34197").unwrap(), "[2001:db8::1]:34197");
+        assert!(normalize_pattern(BlockKind::Host, "example.com").is_err());
+    }
+
+    #[test]
+    fn a_bare_ip_blocks_every_port_and_an_address_only_its_own() {
+        let blocklist = Blocklist::new(&[entry(BlockKind::Host, "1.2.3.4"), entry(BlockKind::Host, "[2001:db8::1]:34197")]);
+        assert!(blocklist.matches(None, Some("1.2.3.4:34197"), "Base"));
+        assert!(blocklist.matches(None, Some("1.2.3.4:1"), "Base"));
+        assert!(blocklist.matches(None, Some("[2001:db8::1]:34197"), "Base"));
+        assert!(!blocklist.matches(None, Some("[2001:db8::1]:34198"), "Base"));
+        assert!(!blocklist.matches(None, Some("1.2.3.5:34197"), "Base"));
+        assert!(!blocklist.matches(None, None, "Base"));
+    }
+
+    #[test]
+    fn names_are_matched_without_rich_text_tags() {
+        let blocklist = Blocklist::new(&[entry(BlockKind::Name, "(?i)^free ?robux")]);
+        assert!(blocklist.matches(None, None, "[color=red]FREE[/color] Robux here"));
+        assert!(blocklist.matches(None, None, "free robux"));
+        assert!(!blocklist.matches(None, None, "Not free robux"));
+    }
+
+    #[test]
+    fn server_ids_match_exactly() {
+        let blocklist = Blocklist::new(&[entry(BlockKind::ServerId, " spam-1 ")]);
+        assert!(blocklist.matches(Some(&ServerId("spam-1".to_string())), None, "Base"));
+        assert!(!blocklist.matches(Some(&ServerId("spam-10".to_string())), None, "Base"));
+    }
+
+    #[test]
+    fn bad_patterns_are_rejected_and_skipped() {
+        assert!(normalize_pattern(BlockKind::ServerId, "  ").is_err());
+        assert!(normalize_pattern(BlockKind::Name, &"a".repeat(MAX_PATTERN_LEN + 1)).is_err());
+        assert!(normalize_pattern(BlockKind::Name, &"a".repeat(MAX_PATTERN_LEN)).is_ok());
+        let invalid = normalize_pattern(BlockKind::Name, "(unclosed").unwrap_err();
+        assert!(invalid.starts_with("invalid regular expression"), "{}", invalid);
+        assert!(Blocklist::new(&[entry(BlockKind::Name, "(unclosed")]).is_empty());
+    }
+}
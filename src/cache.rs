@@ -0,0 +1,60 @@
+use crate::ids::{GameId, ServerId};
+use crate::view::ServerView;
+use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// One immutable generation of the in-memory server list
+#[derive(Debug, Default)]
+pub struct CacheSnapshot {
+    /// Increases by one with every refresh, starting at 0 for the empty startup snapshot
+    pub version: u64,
+    pub servers: Vec<ServerView>,
+    /// When this snapshot was published (None before the first refresh)
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl CacheSnapshot {
+    /// Find a server by its current game_id
+    pub fn find(&self, game_id: GameId) -> Option<&ServerView> {
+        self.servers.iter().find(|s| s.server.game_id == game_id)
+    }
+
+    /// Find a server by its server_id, which survives restarts
+    pub fn find_server_id(&self, server_id: &ServerId) -> Option<&ServerView> {
+        self.servers.iter().find(|s| s.server.server_id.as_ref() == Some(server_id))
+    }
+}
+
+/// In-memory server cache, updated read-copy-update style
+/// Readers take the current snapshot with a single atomic load and never wait on the
+/// refresh; the refresh builds a complete new snapshot and swaps it in. A request
+/// keeps seeing the snapshot it loaded even if a newer one is published meanwhile.
+#[derive(Debug, Default)]
+pub struct ServerCache {
+    current: ArcSwap<CacheSnapshot>,
+    next_version: AtomicU64,
+}
+
+impl ServerCache {
+    pub fn new_shared() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// The current snapshot
+    pub fn load(&self) -> Arc<CacheSnapshot> {
+        self.current.load_full()
+    }
+
+    /// Publish a new server list, returning the version it was given
+    pub fn publish(&self, servers: Vec<ServerView>) -> u64 {
+        let version = self.next_version.fetch_add(1, Ordering::Relaxed) + 1;
+        self.current.store(Arc::new(CacheSnapshot {
+            version,
+            servers,
+            updated_at: Some(Utc::now()),
+        }));
+        version
+    }
+}
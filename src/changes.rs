@@ -0,0 +1,81 @@
+use crate::ids::{GameId, ServerId};
+use crate::view::ServerView;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+
+/// Changes kept in memory; older ones are forgotten
+pub const MAX_LISTING_CHANGES: usize = 1000;
+
+/// Changes listed on /changes, and returned by /api/changes without `limit`
+pub const DEFAULT_CHANGES_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Listed now, not in the previous refresh
+    Appeared,
+    /// Listed in the previous refresh, gone now
+    Disappeared,
+}
+
+/// A server joining or leaving the listing between two refreshes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListingChange {
+    pub kind: ChangeKind,
+    pub server_id: ServerId,
+    /// The game it was listed under (the last one, for a server that disappeared)
+    pub game_id: GameId,
+    pub name: String,
+    pub game_version: String,
+    /// The refresh that noticed the change (RFC 3339)
+    pub at: String,
+}
+
+/// Servers that appeared in or disappeared from the listing, by server_id, fed from each cache refresh
+/// A restart only changes the game_id, so it isn't a change; servers without a server_id are left out
+/// Kept in memory only, so the feed starts over when the browser restarts
+#[derive(Debug, Default)]
+pub struct ListingChanges {
+    /// Oldest first
+    changes: VecDeque<ListingChange>,
+}
+
+impl ListingChanges {
+    /// Record the difference between two consecutive listings
+    /// The first listing after startup is skipped, since everything in it would look new
+    pub fn record(&mut self, previous: &[ServerView], current: &[ServerView], now: DateTime<Utc>) {
+        if previous.is_empty() {
+            return;
+        }
+        let before: HashSet<&ServerId> = previous.iter().filter_map(|s| s.server.server_id.as_ref()).collect();
+        let after: HashSet<&ServerId> = current.iter().filter_map(|s| s.server.server_id.as_ref()).collect();
+        let at = now.to_rfc3339();
+
+        let change = |kind, view: &ServerView, server_id: &ServerId| ListingChange {
+            kind,
+            server_id: server_id.clone(),
+            game_id: view.server.game_id,
+            name: view.server.name.clone(),
+            game_version: view.server.game_version.clone(),
+            at: at.clone(),
+        };
+        let disappeared = previous.iter().filter_map(|view| {
+            let id = view.server.server_id.as_ref().filter(|id| !after.contains(id))?;
+            Some(change(ChangeKind::Disappeared, view, id))
+        });
+        let appeared = current.iter().filter_map(|view| {
+            let id = view.server.server_id.as_ref().filter(|id| !before.contains(id))?;
+            Some(change(ChangeKind::Appeared, view, id))
+        });
+        self.changes.extend(disappeared.chain(appeared));
+
+        let excess = self.changes.len().saturating_sub(MAX_LISTING_CHANGES);
+        self.changes.drain(..excess);
+    }
+
+    /// The last `limit` changes, newest first
+    pub fn latest(&self, limit: usize) -> Vec<ListingChange> {
+        self.changes.iter().rev().take(limit).cloned().collect()
+    }
+}
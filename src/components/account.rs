@@ -0,0 +1,107 @@
+use crate::accounts::{FilterPreset, MAX_PASSWORD_LEN, MIN_PASSWORD_LEN};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct AccountPageProps {
+    /// Signed-in email (None shows the sign-in and register forms)
+    #[prop_or_default]
+    pub email: Option<String>,
+    #[prop_or_default]
+    pub presets: Vec<FilterPreset>,
+    /// Outcome of the last form post
+    #[prop_or_default]
+    pub message: Option<String>,
+}
+
+const INPUT_CLASS: &str = "py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary";
+const BUTTON_CLASS: &str = "py-2 px-6 bg-btn-green border border-btn-green-dark rounded-sm text-bg-dark font-display text-[0.95rem] font-semibold cursor-pointer transition-all duration-200 hover:bg-btn-green-hover active:bg-btn-green-dark";
+
+/// Email + password form posting to `action`
+fn credentials_form(title: &str, action: &str, button: &str, new_password: bool) -> Html {
+    let autocomplete = if new_password { "new-password" } else { "current-password" };
+    html! {
+        <form method="post" action={action.to_string()} class="flex flex-col gap-3 flex-1 min-w-[240px]">
+            <h3 class="text-sm uppercase tracking-wider text-text-secondary">{title}</h3>
+            <input type="email" name="email" placeholder="Email" required=true autocomplete="email" class={INPUT_CLASS} />
+            <input type="password" name="password" placeholder="Password" required=true
+                minlength={MIN_PASSWORD_LEN.to_string()} maxlength={MAX_PASSWORD_LEN.to_string()}
+                autocomplete={autocomplete} class={INPUT_CLASS} />
+            <button type="submit" class={BUTTON_CLASS}>{button}</button>
+        </form>
+    }
+}
+
+fn preset_row(preset: &FilterPreset) -> Html {
+    let mut summary = Vec::new();
+    if !preset.search.is_empty() {
+        summary.push(format!("\"{}\"", preset.search));
+    }
+    summary.push(match preset.version.as_str() {
+        "" => "latest version".to_string(),
+        "all" => "all versions".to_string(),
+        version => format!("version {}", version),
+    });
+    if !preset.tags.is_empty() {
+        summary.push(preset.tags.join(", "));
+    }
+
+    html! {
+        <div class="flex items-center justify-between gap-4 py-3 px-4 bg-bg-dark rounded-sm">
+            <a href={preset.url()} class="flex flex-col min-w-0 no-underline text-inherit hover:text-accent-primary">
+                <span class="overflow-hidden text-ellipsis whitespace-nowrap">{&preset.name}</span>
+                <span class="text-text-muted text-sm overflow-hidden text-ellipsis whitespace-nowrap">{summary.join(" · ")}</span>
+            </a>
+            <form method="post" action={format!("/presets/{}/delete", preset.id)}>
+                <button type="submit" class="text-sm text-text-muted bg-transparent border-none cursor-pointer transition-colors duration-200 hover:text-status-full">{"Delete"}</button>
+            </form>
+        </div>
+    }
+}
+
+/// Sign in / register, or manage saved filter presets when signed in (SSR-compatible)
+#[function_component(AccountPage)]
+pub fn account_page(props: &AccountPageProps) -> Html {
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[800px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <h2 class="text-2xl">{"Account"}</h2>
+                    {if let Some(ref message) = props.message {
+                        html! { <p class="mt-4 text-accent-secondary">{message}</p> }
+                    } else {
+                        html! {}
+                    }}
+                </header>
+
+                {match props.email {
+                    None => html! {
+                        <div class="p-8 flex flex-wrap gap-8">
+                            {credentials_form("Sign in", "/account/login", "Sign in", false)}
+                            {credentials_form("Create an account", "/account/register", "Register", true)}
+                        </div>
+                    },
+                    Some(ref email) => html! {
+                        <div class="p-8 flex flex-col gap-6">
+                            <div class="flex items-center justify-between gap-4">
+                                <span class="text-text-secondary">{"Signed in as "}<span class="text-text-primary">{email}</span></span>
+                                <form method="post" action="/account/logout">
+                                    <button type="submit" class="text-sm text-accent-primary bg-transparent border-none cursor-pointer transition-colors duration-200 hover:text-accent-secondary">{"Sign out"}</button>
+                                </form>
+                            </div>
+                            <section class="flex flex-col gap-2">
+                                <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"Saved filter presets"}</h3>
+                                {if props.presets.is_empty() {
+                                    html! { <p class="text-text-muted">{"No presets yet. Set up filters on the server list and use \"Save filters\" above them."}</p> }
+                                } else {
+                                    html! { {for props.presets.iter().map(preset_row)} }
+                                }}
+                            </section>
+                        </div>
+                    },
+                }}
+            </div>
+        </div>
+    }
+}
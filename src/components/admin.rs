@@ -0,0 +1,559 @@
+use crate::db::models::{ApiKeyRecord, BlocklistEntry, HiddenServer, ServerReport};
+use crate::drift::UnknownField;
+use crate::metrics::{Subsystem, SubsystemHealth};
+use crate::trace::CycleTrace;
+use crate::utils::strip_all_tags;
+use std::collections::HashMap;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct AdminPageProps {
+    /// get-games fields GameServer doesn't model yet
+    #[prop_or_default]
+    pub unknown_fields: Vec<UnknownField>,
+    /// get-games responses checked for unknown fields since startup
+    #[prop_or_default]
+    pub responses_checked: u64,
+    /// Outcomes of each subsystem since startup, in matrix order
+    #[prop_or_default]
+    pub subsystems: Vec<(Subsystem, SubsystemHealth)>,
+    /// Recent refresh cycles, newest first
+    #[prop_or_default]
+    pub cycles: Vec<CycleTrace>,
+    /// Cycle picked with ?cycle=, shown stage by stage
+    #[prop_or_default]
+    pub selected_cycle: Option<CycleTrace>,
+    /// Servers ever hidden, hidden ones first
+    #[prop_or_default]
+    pub hidden: Vec<HiddenServer>,
+    /// Open visitor reports, newest first
+    #[prop_or_default]
+    pub reports: Vec<ServerReport>,
+    /// Blocklist entries, newest first
+    #[prop_or_default]
+    pub blocklist: Vec<BlocklistEntry>,
+    /// API keys ever issued, active ones first
+    #[prop_or_default]
+    pub api_keys: Vec<ApiKeyRecord>,
+    /// Requests per active key since startup, by key record id
+    #[prop_or_default]
+    pub api_key_usage: HashMap<String, u64>,
+    /// Requests without a key are refused (API_KEYS_REQUIRED)
+    #[prop_or_default]
+    pub api_keys_required: bool,
+    /// Key just issued, shown once
+    #[prop_or_default]
+    pub new_api_key: Option<String>,
+    /// Hiding, restoring and key changes are available (not in read-only mode)
+    #[prop_or_default]
+    pub writable: bool,
+    /// Outcome of the last change
+    #[prop_or_default]
+    pub message: Option<String>,
+}
+
+const INPUT_CLASS: &str = "py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary";
+const BUTTON_CLASS: &str = "py-2 px-6 bg-btn-green border border-btn-green-dark rounded-sm text-bg-dark font-display text-[0.95rem] font-semibold cursor-pointer transition-all duration-200 hover:bg-btn-green-hover active:bg-btn-green-dark";
+
+fn millis(duration: std::time::Duration) -> String {
+    format!("{}ms", duration.as_millis())
+}
+
+fn cycle_row(trace: &CycleTrace) -> Html {
+    let slowest = trace
+        .slowest()
+        .map(|s| format!("{} ({})", s.stage, millis(s.elapsed)))
+        .unwrap_or_default();
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 font-mono">
+                <a href={format!("/admin?cycle={}#cycle", trace.cycle)} class="text-accent-primary no-underline hover:text-accent-secondary">{trace.cycle}</a>
+            </td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{trace.started_at.format("%H:%M:%S UTC").to_string()}</td>
+            <td class="py-2 pr-4 font-mono text-right">{millis(trace.total)}</td>
+            <td class="py-2 pr-4 font-mono text-text-muted">{slowest}</td>
+            <td class="py-2 text-text-muted break-all">{&trace.outcome}</td>
+        </tr>
+    }
+}
+
+/// One cycle's stages as a waterfall, each bar placed by its start offset within the cycle
+fn cycle_detail(trace: &CycleTrace) -> Html {
+    let total = trace.total.as_secs_f64().max(f64::EPSILON);
+    html! {
+        <div id="cycle" class="flex flex-col gap-2">
+            <p class="text-text-muted text-sm">
+                {format!("Cycle {}, started {}, took {}: {}", trace.cycle, trace.started_at.format("%Y-%m-%d %H:%M:%S UTC"), millis(trace.total), trace.outcome)}
+            </p>
+            <div class="overflow-x-auto">
+                <table class="w-full text-sm text-left">
+                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                        <tr>
+                            <th class="pb-2 pr-4">{"Stage"}</th>
+                            <th class="pb-2 pr-4 text-right">{"Start"}</th>
+                            <th class="pb-2 pr-4 text-right">{"Duration"}</th>
+                            <th class="pb-2 pr-4 w-1/3"></th>
+                            <th class="pb-2">{"Note"}</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {for trace.spans.iter().map(|span| {
+                            let left = span.started.as_secs_f64() / total * 100.0;
+                            let width = (span.elapsed.as_secs_f64() / total * 100.0).max(0.5);
+                            html! {
+                                <tr class="border-t border-border-subtle">
+                                    <td class="py-2 pr-4 font-mono text-text-primary">{span.stage}</td>
+                                    <td class="py-2 pr-4 font-mono text-right text-text-muted">{millis(span.started)}</td>
+                                    <td class="py-2 pr-4 font-mono text-right">{millis(span.elapsed)}</td>
+                                    <td class="py-2 pr-4">
+                                        <div class="relative h-2 bg-bg-inset rounded-sm">
+                                            <div class="absolute h-2 bg-accent-primary rounded-sm" style={format!("left: {:.1}%; width: {:.1}%;", left, width)}></div>
+                                        </div>
+                                    </td>
+                                    <td class="py-2 text-text-muted break-all">{span.note.clone().unwrap_or_default()}</td>
+                                </tr>
+                            }
+                        })}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
+fn format_time(time: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    time.map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string()).unwrap_or_else(|| "—".to_string())
+}
+
+fn subsystem_row((subsystem, health): &(Subsystem, SubsystemHealth)) -> Html {
+    let (dot, status) = if health.failing() {
+        ("bg-status-full", "Failing")
+    } else if health.last_success.is_some() {
+        ("bg-status-low", "OK")
+    } else {
+        ("bg-status-empty", "Not run")
+    };
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 text-text-primary whitespace-nowrap">
+                <span class={classes!("inline-block", "w-2", "h-2", "rounded-full", "mr-2", dot)} title={status}></span>
+                {subsystem.label()}
+            </td>
+            <td class="py-2 pr-4 font-mono text-right">{health.successes}</td>
+            <td class="py-2 pr-4 font-mono text-right">{health.failures}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{format_time(health.last_success)}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{format_time(health.last_failure)}</td>
+            <td class="py-2 font-mono text-text-muted break-all">{health.last_error.clone().unwrap_or_default()}</td>
+        </tr>
+    }
+}
+
+/// RFC 3339 timestamp as "2024-05-01 12:00 UTC"
+fn format_timestamp(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+fn hidden_row(server: &HiddenServer, writable: bool) -> Html {
+    let status = if server.hidden {
+        "Hidden".to_string()
+    } else {
+        format!("Restored {}", server.restored_at.as_deref().map(format_timestamp).unwrap_or_default())
+    };
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 text-text-primary">
+                {strip_all_tags(&server.name)}
+                <div class="font-mono text-xs text-text-muted">{server.server_id.to_string()}</div>
+            </td>
+            <td class="py-2 pr-4 text-text-muted">
+                {&server.reason}
+                {if let Some(ref original) = server.reappeared_from {
+                    html! { <div class="text-xs">{format!("Relaunch of {}", original)}</div> }
+                } else {
+                    html! {}
+                }}
+            </td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{format_timestamp(&server.hidden_at)}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{status}</td>
+            <td class="py-2 text-right">
+                {if server.hidden && writable {
+                    html! {
+                        <form method="post" action={format!("/admin/hidden/{}/restore", server.server_id)}>
+                            <button type="submit" class="text-sm text-accent-primary bg-transparent border-none cursor-pointer transition-colors duration-200 hover:text-accent-secondary">{"Restore"}</button>
+                        </form>
+                    }
+                } else {
+                    html! {}
+                }}
+            </td>
+        </tr>
+    }
+}
+
+fn report_row(report: &ServerReport, writable: bool) -> Html {
+    let id = report.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default();
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 text-text-primary">
+                <a href={format!("/server/{}", report.game_id)} class="text-accent-primary no-underline hover:text-accent-secondary">
+                    {strip_all_tags(&report.name)}
+                </a>
+                <div class="font-mono text-xs text-text-muted">{report.game_id.to_string()}</div>
+            </td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{report.reason.label()}</td>
+            <td class="py-2 pr-4 text-text-muted break-words">{&report.details}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{format_timestamp(&report.reported_at)}</td>
+            <td class="py-2 text-right">
+                {if writable {
+                    html! {
+                        <form method="post" action={format!("/admin/reports/{}/dismiss", id)}>
+                            <button type="submit" class="text-sm text-accent-primary bg-transparent border-none cursor-pointer transition-colors duration-200 hover:text-accent-secondary">{"Dismiss"}</button>
+                        </form>
+                    }
+                } else {
+                    html! {}
+                }}
+            </td>
+        </tr>
+    }
+}
+
+fn blocklist_row(entry: &BlocklistEntry, writable: bool) -> Html {
+    let id = entry.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default();
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{entry.kind.label()}</td>
+            <td class="py-2 pr-4 font-mono text-text-primary break-all">{&entry.pattern}</td>
+            <td class="py-2 pr-4 text-text-muted">{&entry.reason}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{format_timestamp(&entry.added_at)}</td>
+            <td class="py-2 text-right">
+                {if writable {
+                    html! {
+                        <form method="post" action={format!("/admin/blocklist/{}/remove", id)}>
+                            <button type="submit" class="text-sm text-accent-primary bg-transparent border-none cursor-pointer transition-colors duration-200 hover:text-accent-secondary">{"Remove"}</button>
+                        </form>
+                    }
+                } else {
+                    html! {}
+                }}
+            </td>
+        </tr>
+    }
+}
+
+fn api_key_row(key: &ApiKeyRecord, usage: &HashMap<String, u64>, writable: bool) -> Html {
+    let id = key.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default();
+    let requests = usage.get(&id).copied().unwrap_or_default();
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 text-text-primary">{&key.name}</td>
+            <td class="py-2 pr-4 font-mono text-right">{key.per_minute}</td>
+            <td class="py-2 pr-4 font-mono text-right">{if key.revoked { "—".to_string() } else { requests.to_string() }}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{format_timestamp(&key.created_at)}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{if key.revoked { "Revoked" } else { "Active" }}</td>
+            <td class="py-2 text-right">
+                {if !key.revoked && writable {
+                    html! {
+                        <form method="post" action={format!("/admin/api-keys/{}/revoke", id)}>
+                            <button type="submit" class="text-sm text-accent-primary bg-transparent border-none cursor-pointer transition-colors duration-200 hover:text-accent-secondary">{"Revoke"}</button>
+                        </form>
+                    }
+                } else {
+                    html! {}
+                }}
+            </td>
+        </tr>
+    }
+}
+
+fn unknown_field_row(field: &UnknownField) -> Html {
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 font-mono text-text-primary">{&field.name}</td>
+            <td class="py-2 pr-4 font-mono text-right">{field.servers}</td>
+            <td class="py-2 pr-4 font-mono text-right">{field.total}</td>
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap">{field.first_seen.format("%Y-%m-%d %H:%M UTC").to_string()}</td>
+            <td class="py-2 font-mono text-text-muted break-all">{&field.example}</td>
+        </tr>
+    }
+}
+
+/// Operator page: visitor reports, hidden servers, blocklist, API keys, subsystem health, refresh cycle timings and schema drift (SSR-compatible)
+#[function_component(AdminPage)]
+pub fn admin_page(props: &AdminPageProps) -> Html {
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[1000px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <h2 class="text-2xl">{"Admin"}</h2>
+                    {if let Some(ref message) = props.message {
+                        html! { <p class="mt-4 text-accent-secondary">{message}</p> }
+                    } else {
+                        html! {}
+                    }}
+                </header>
+
+                <section class="p-8 flex flex-col gap-4 border-b border-border-subtle">
+                    <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"Reports"}</h3>
+                    <p class="text-text-muted text-sm">
+                        {"Servers flagged by visitors from their details page. Hide or block the server if the report holds up, then dismiss it either way."}
+                    </p>
+                    {if props.reports.is_empty() {
+                        html! { <p class="text-text-secondary">{"No open reports."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"Server"}</th>
+                                            <th class="pb-2 pr-4">{"Reason"}</th>
+                                            <th class="pb-2 pr-4">{"Details"}</th>
+                                            <th class="pb-2 pr-4">{"Reported"}</th>
+                                            <th class="pb-2"></th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.reports.iter().map(|r| report_row(r, props.writable))}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+
+                <section class="p-8 flex flex-col gap-4 border-b border-border-subtle">
+                    <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"Hidden servers"}</h3>
+                    <p class="text-text-muted text-sm">
+                        {"Hidden servers stay in the database but leave the listing, search and archive. A hidden server relaunched under a new server ID from the same host is hidden again automatically."}
+                    </p>
+                    {if props.writable {
+                        html! {
+                            <form method="post" action="/admin/hidden" class="flex flex-wrap gap-3">
+                                <input type="text" name="game_id" placeholder="Game ID" required=true inputmode="numeric" class={classes!(INPUT_CLASS, "w-40")} />
+                                <input type="text" name="reason" placeholder="Reason" required=true maxlength="200" class={classes!(INPUT_CLASS, "flex-1", "min-w-[240px]")} />
+                                <button type="submit" class={BUTTON_CLASS}>{"Hide"}</button>
+                            </form>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if props.hidden.is_empty() {
+                        html! { <p class="text-text-secondary">{"No servers have been hidden."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"Server"}</th>
+                                            <th class="pb-2 pr-4">{"Reason"}</th>
+                                            <th class="pb-2 pr-4">{"Hidden"}</th>
+                                            <th class="pb-2 pr-4">{"Status"}</th>
+                                            <th class="pb-2"></th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.hidden.iter().map(|s| hidden_row(s, props.writable))}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+
+                <section class="p-8 flex flex-col gap-4 border-b border-border-subtle">
+                    <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"Blocklist"}</h3>
+                    <p class="text-text-muted text-sm">
+                        {"Spam and advertisement listings matching an entry are dropped at every refresh, before they're stored, so they never show up anywhere. Block by server ID, by host (an IP blocks every port on it, IP:port just one), or by a regular expression on the name without rich text, e.g. (?i)discord\\.gg. Unlike hiding, blocking isn't listed on the transparency page."}
+                    </p>
+                    {if props.writable {
+                        html! {
+                            <form method="post" action="/admin/blocklist" class="flex flex-wrap gap-3">
+                                <select name="kind" class={INPUT_CLASS}>
+                                    <option value="server_id">{"Server ID"}</option>
+                                    <option value="host">{"Host"}</option>
+                                    <option value="name">{"Name pattern"}</option>
+                                </select>
+                                <input type="text" name="pattern" placeholder="Pattern" required=true maxlength="200" class={classes!(INPUT_CLASS, "flex-1", "min-w-[200px]", "font-mono")} />
+                                <input type="text" name="reason" placeholder="Reason" required=true maxlength="200" class={classes!(INPUT_CLASS, "flex-1", "min-w-[200px]")} />
+                                <button type="submit" class={BUTTON_CLASS}>{"Block"}</button>
+                            </form>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if props.blocklist.is_empty() {
+                        html! { <p class="text-text-secondary">{"Nothing is blocked."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"By"}</th>
+                                            <th class="pb-2 pr-4">{"Pattern"}</th>
+                                            <th class="pb-2 pr-4">{"Reason"}</th>
+                                            <th class="pb-2 pr-4">{"Added"}</th>
+                                            <th class="pb-2"></th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.blocklist.iter().map(|e| blocklist_row(e, props.writable))}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+
+                <section class="p-8 flex flex-col gap-4 border-b border-border-subtle">
+                    <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"API keys"}</h3>
+                    <p class="text-text-muted text-sm">
+                        {"Keys identify JSON API consumers (X-Api-Key header or Bearer token) and rate-limit each one separately; request counts are since startup. "}
+                        {if props.api_keys_required {
+                            "Requests without a key are refused."
+                        } else {
+                            "Requests without a key are served too; HTML pages never need one."
+                        }}
+                    </p>
+                    {if let Some(ref key) = props.new_api_key {
+                        html! {
+                            <p class="p-4 bg-bg-inset border border-border-subtle rounded-sm font-mono text-sm text-text-primary break-all select-all">{key}</p>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if props.writable {
+                        html! {
+                            <form method="post" action="/admin/api-keys" class="flex flex-wrap gap-3">
+                                <input type="text" name="name" placeholder="Name" required=true maxlength="100" class={classes!(INPUT_CLASS, "flex-1", "min-w-[240px]")} />
+                                <input type="text" name="per_minute" placeholder="Requests/min (120)" inputmode="numeric" class={classes!(INPUT_CLASS, "w-48")} />
+                                <button type="submit" class={BUTTON_CLASS}>{"Create key"}</button>
+                            </form>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if props.api_keys.is_empty() {
+                        html! { <p class="text-text-secondary">{"No API keys have been issued."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"Name"}</th>
+                                            <th class="pb-2 pr-4 text-right">{"Per minute"}</th>
+                                            <th class="pb-2 pr-4 text-right">{"Requests"}</th>
+                                            <th class="pb-2 pr-4">{"Created"}</th>
+                                            <th class="pb-2 pr-4">{"Status"}</th>
+                                            <th class="pb-2"></th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.api_keys.iter().map(|k| api_key_row(k, &props.api_key_usage, props.writable))}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+
+                <section class="p-8 flex flex-col gap-4 border-b border-border-subtle">
+                    <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"Subsystem health"}</h3>
+                    <p class="text-text-muted text-sm">
+                        {"Runs, lookups or deliveries of each part of the browser since startup. A subsystem is failing when its latest outcome was a failure."}
+                    </p>
+                    <div class="overflow-x-auto">
+                        <table class="w-full text-sm text-left">
+                            <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                <tr>
+                                    <th class="pb-2 pr-4">{"Subsystem"}</th>
+                                    <th class="pb-2 pr-4 text-right">{"OK"}</th>
+                                    <th class="pb-2 pr-4 text-right">{"Failed"}</th>
+                                    <th class="pb-2 pr-4">{"Last success"}</th>
+                                    <th class="pb-2 pr-4">{"Last failure"}</th>
+                                    <th class="pb-2">{"Last error"}</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {for props.subsystems.iter().map(subsystem_row)}
+                            </tbody>
+                        </table>
+                    </div>
+                </section>
+
+                <section class="p-8 flex flex-col gap-4 border-b border-border-subtle">
+                    <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"Refresh cycles"}</h3>
+                    <p class="text-text-muted text-sm">
+                        {"Recent listing refreshes with per-stage timings; pick a cycle to see its stages. Cycles slower than 3 seconds are also logged."}
+                    </p>
+                    {if let Some(ref trace) = props.selected_cycle {
+                        cycle_detail(trace)
+                    } else {
+                        html! {}
+                    }}
+                    {if props.cycles.is_empty() {
+                        html! { <p class="text-text-secondary">{"No refresh has finished yet."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"Cycle"}</th>
+                                            <th class="pb-2 pr-4">{"Started"}</th>
+                                            <th class="pb-2 pr-4 text-right">{"Total"}</th>
+                                            <th class="pb-2 pr-4">{"Slowest stage"}</th>
+                                            <th class="pb-2">{"Outcome"}</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.cycles.iter().map(cycle_row)}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+
+                <section class="p-8 flex flex-col gap-4">
+                    <h3 class="text-sm uppercase tracking-wider text-text-secondary">{"API schema drift"}</h3>
+                    <p class="text-text-muted text-sm">
+                        {format!(
+                            "Fields in get-games responses that the browser doesn't store yet ({} responses checked since startup).",
+                            props.responses_checked
+                        )}
+                    </p>
+                    {if props.unknown_fields.is_empty() {
+                        html! { <p class="text-text-secondary">{"No unknown fields seen."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"Field"}</th>
+                                            <th class="pb-2 pr-4 text-right">{"Servers now"}</th>
+                                            <th class="pb-2 pr-4 text-right">{"Total"}</th>
+                                            <th class="pb-2 pr-4">{"First seen"}</th>
+                                            <th class="pb-2">{"Example"}</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.unknown_fields.iter().map(unknown_field_row)}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+            </div>
+        </div>
+    }
+}
@@ -1,73 +1,138 @@
+use crate::accounts::AccountNav;
 use crate::components::footer::Footer;
-use crate::components::server_list::ServerList;
-use crate::db::models::CachedServer;
+use crate::components::picture::{Picture, ResponsiveImage};
+use crate::components::widgets::{customize_panel, render_widget};
+use crate::db::models::ServerProfile;
+use crate::filter::FilterSpec;
+use crate::ids::ServerId;
+use crate::prefs::{ListColumn, ListState, Widget};
+use crate::utils::format::Locale;
+use crate::view::ServerView;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq, Clone, Default)]
 pub struct AppProps {
     #[prop_or_default]
-    pub servers: Vec<CachedServer>,
+    pub servers: Vec<ServerView>,
+    /// Archived servers matching the search, listed after the live results
+    #[prop_or_default]
+    pub archived: Vec<ServerProfile>,
     #[prop_or_default]
     pub error: Option<String>,
+    /// Filters parsed from the request
+    #[prop_or_default]
+    pub filters: FilterSpec,
+    /// Whether inactive servers are hidden when the request doesn't say (HIDE_INACTIVE_SERVERS)
+    #[prop_or_default]
+    pub inactive_hidden_by_default: bool,
+    #[prop_or_default]
+    pub group_by: String,
+    /// Showing bundled demo data because no credentials are configured
+    #[prop_or_default]
+    pub demo_mode: bool,
+    /// Locale for header numbers, from Accept-Language
+    #[prop_or_default]
+    pub locale: Locale,
+    /// Visible list-view columns from the preferences cookie
+    #[prop_or_default]
+    pub columns: Vec<ListColumn>,
+    /// Sort and layout from the URL
+    #[prop_or_default]
+    pub list_state: ListState,
+    /// The request's query string without `page`, for the list's pager links
     #[prop_or_default]
-    pub search: String,
+    pub page_query: String,
+    /// server_ids starred by the visitor, in the order they were starred
     #[prop_or_default]
-    pub version: String,
+    pub favorites: Vec<ServerId>,
+    /// Sign-in state and saved presets (None when accounts are disabled)
     #[prop_or_default]
-    pub has_players: bool,
+    pub account: Option<AccountNav>,
+    /// VAPID public key for Web Push subscriptions (None when push is disabled)
     #[prop_or_default]
-    pub no_password: bool,
+    pub push_public_key: Option<String>,
+    /// Homepage widgets to show, in order (the default layout when empty)
     #[prop_or_default]
-    pub is_dedicated: bool,
+    pub widgets: Vec<Widget>,
+    /// Player names the visitor follows, from the friends cookie
     #[prop_or_default]
-    pub tags: String, // Comma-separated list of selected tags
+    pub friends: Vec<String>,
+    /// The WASM bundle is built, so the server list hydrates (sort.js leaves the list to it)
+    #[prop_or_default]
+    pub hydrate: bool,
+    /// Header logo, served through the image proxy
+    #[prop_or_default]
+    pub logo: ResponsiveImage,
+}
+
+/// Opt-in for browser notifications about favorites coming online and friends joining
+/// Hidden until sort.js confirms the browser supports push
+fn push_panel(public_key: &str, friends: &[String]) -> Html {
+    let input_class = "py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-sm transition-colors duration-200 focus:outline-none focus:border-accent-primary";
+    let button_class = "py-1 px-3 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-sm cursor-pointer transition-colors duration-200 hover:border-accent-primary";
+    html! {
+        <details id="push-panel" class="hidden mb-8 bg-bg-card border border-border-subtle rounded-md" data-public-key={public_key.to_string()}>
+            <summary class="cursor-pointer py-3 px-4 text-text-secondary hover:text-text-bright">{"🔔 Notifications"}</summary>
+            <div class="flex flex-col gap-3 px-4 pb-4">
+                <p class="text-sm text-text-muted">
+                    {"Get a browser notification when one of your favorites comes online or a friend joins a server."}
+                </p>
+                <label class="flex flex-col gap-1 text-sm text-text-secondary">
+                    {"Friends (player names, comma-separated)"}
+                    <input id="push-friends" type="text" maxlength="1000" placeholder="Name1, Name2" value={friends.join(", ")} class={input_class} />
+                </label>
+                <div class="flex items-center gap-3">
+                    <button id="push-enable" type="button" class={button_class}>{"Enable notifications"}</button>
+                    <button id="push-disable" type="button" class={classes!(button_class, "hidden")}>{"Turn off"}</button>
+                    <span id="push-status" class="text-sm text-text-muted"></span>
+                </div>
+            </div>
+        </details>
+    }
 }
 
-/// Root application component
+/// Root application component: a header, then the homepage widgets in the configured order
 #[function_component(App)]
 pub fn app(props: &AppProps) -> Html {
-    let total_players: usize = props.servers.iter().map(|s| s.player_count).sum();
-    let servers_with_players = props.servers.iter().filter(|s| s.player_count > 0).count();
+    let widgets = if props.widgets.is_empty() { Widget::DEFAULT.to_vec() } else { props.widgets.clone() };
 
     html! {
         <div class="min-h-screen flex flex-col">
             <header class="bg-bg-card/65 backdrop-blur-[10px] border-b border-border-subtle py-8 px-6">
-                <div class="max-w-[1400px] mx-auto text-center mb-6">
+                <div class="max-w-[1400px] mx-auto text-center">
                     <a href="/" class="inline-block" title="Home">
-                        <img src="https://lambs.cafe/wp-content/uploads/2025/12/factorio-logo.png" alt="Factorio" class="h-16 mx-auto" />
+                        <Picture image={props.logo.clone()} alt="Factorio" class="h-16 mx-auto" />
                     </a>
                     <h1 class="text-3xl font-bold text-text-bright mt-2">{"Server Browser"}</h1>
                     <p class="text-text-secondary text-lg mt-2">{"Find and explore public Factorio multiplayer servers"}</p>
                     <p class="text-text-muted text-sm mt-1">{"Not affiliated with Wube Software"}</p>
                 </div>
-                
-                <div class="flex justify-center gap-8 flex-wrap">
-                    <div class="text-center py-4 px-6 bg-bg-card border border-border-subtle rounded-sm min-w-[140px]">
-                        <span class="block text-[2rem] font-semibold text-accent-primary font-mono">{props.servers.len()}</span>
-                        <span class="block text-[0.85rem] text-text-secondary uppercase tracking-wider">{"Total Servers"}</span>
-                    </div>
-                    <div class="text-center py-4 px-6 bg-bg-card border border-border-subtle rounded-sm min-w-[140px]">
-                        <span class="block text-[2rem] font-semibold text-accent-primary font-mono">{servers_with_players}</span>
-                        <span class="block text-[0.85rem] text-text-secondary uppercase tracking-wider">{"Active Servers"}</span>
-                    </div>
-                    <div class="text-center py-4 px-6 bg-bg-card border border-border-subtle rounded-sm min-w-[140px]">
-                        <span class="block text-[2rem] font-semibold text-accent-primary font-mono">{total_players}</span>
-                        <span class="block text-[0.85rem] text-text-secondary uppercase tracking-wider">{"Players Online"}</span>
-                    </div>
-                </div>
             </header>
             
             <main class="flex-1 max-w-[1400px] mx-auto py-8 px-6 w-full">
-                <ServerList 
-                    servers={props.servers.clone()}
-                    error={props.error.clone()}
-                    current_search={props.search.clone()}
-                    current_version={props.version.clone()}
-                    has_players={props.has_players}
-                    no_password={props.no_password}
-                    is_dedicated={props.is_dedicated}
-                    selected_tags={props.tags.clone()}
-                />
+                {if props.demo_mode {
+                    html! {
+                        <div class="text-center py-4 px-4 mb-4 bg-accent-primary/10 border border-accent-primary/30 rounded-md text-accent-secondary">
+                            <p>
+                                {"ℹ You're viewing a bundled demo snapshot. Set "}
+                                <code class="font-mono">{"FACTORIO_USERNAME"}</code>{" and "}
+                                <code class="font-mono">{"FACTORIO_TOKEN"}</code>
+                                {" in your .env file and restart to load live servers — see the "}
+                                <a href="https://github.com/Psaltor/factorio-browser#configuration" target="_blank" rel="noopener" class="text-accent-primary hover:text-accent-secondary transition-colors">{"README"}</a>
+                                {"."}
+                            </p>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if let Some(ref key) = props.push_public_key {
+                    push_panel(key, &props.friends)
+                } else {
+                    html! {}
+                }}
+                {for widgets.iter().map(|widget| render_widget(*widget, props))}
+                {customize_panel(&widgets)}
             </main>
             
             <Footer />
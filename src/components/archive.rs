@@ -0,0 +1,109 @@
+use crate::db::models::{CachedServer, ServerProfile};
+use crate::utils::parse_rich_text;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ArchivePageProps {
+    pub server: ServerProfile,
+    /// Unlisted for ARCHIVE_AFTER_HOURS, rather than just offline for now
+    pub archived: bool,
+    /// Listed server now going by this one's name (see impostor::find_successor)
+    #[prop_or_default]
+    pub successor: Option<CachedServer>,
+}
+
+fn format_date(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&chrono::Utc).format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+/// Tombstone page for a server that isn't on the listing (SSR-compatible)
+/// Shows what it was last listed as, the stats collected while it was around and where it went
+#[function_component(ArchivePage)]
+pub fn archive_page(props: &ArchivePageProps) -> Html {
+    let server = &props.server;
+    let section_title = "text-sm uppercase tracking-wider text-text-secondary";
+    let listed_days = chrono::DateTime::parse_from_rfc3339(&server.first_seen)
+        .ok()
+        .zip(chrono::DateTime::parse_from_rfc3339(&server.last_seen).ok())
+        .map(|(first, last)| (last - first).num_days() + 1)
+        .unwrap_or(1);
+    let tile = |value: String, label: &'static str| html! {
+        <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+            <span class="block text-2xl font-semibold font-mono text-accent-primary">{value}</span>
+            <span class="text-xs text-text-secondary uppercase tracking-wider">{label}</span>
+        </div>
+    };
+
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[1000px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <div class="flex flex-wrap items-center gap-3 mb-1">
+                        <h2 class="text-2xl break-words">{parse_rich_text(&server.name)}</h2>
+                        <span class="py-1 px-2 rounded-sm bg-bg-inset text-text-muted text-[0.85rem]">{if props.archived { "🪦 Archived" } else { "💤 Offline" }}</span>
+                    </div>
+                    <p class="text-text-muted text-sm mb-4">
+                        {format!(
+                            "Listed from {} to {}; no longer on the server list",
+                            format_date(&server.first_seen),
+                            format_date(&server.last_seen)
+                        )}
+                    </p>
+                    {if let Some(ref successor) = props.successor {
+                        html! {
+                            <p class="mb-4 text-sm">
+                                {"Now listed as "}
+                                <a href={format!("/server/{}", successor.game_id)} class="text-accent-primary no-underline hover:text-accent-secondary">
+                                    {parse_rich_text(&successor.name)}
+                                </a>
+                            </p>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    <div class="flex gap-6 max-md:flex-col">
+                        {tile(format!("{:.0}", server.player_hours), "Player-hours")}
+                        {tile(server.peak_players.to_string(), "Peak players")}
+                        {tile(listed_days.to_string(), if listed_days == 1 { "Day listed" } else { "Days listed" })}
+                    </div>
+                </header>
+
+                <section class="p-8 border-b border-border-subtle flex flex-col gap-4">
+                    <h3 class={section_title}>{"Final description"}</h3>
+                    {if server.description.is_empty() {
+                        html! { <p class="text-text-muted">{"No description."}</p> }
+                    } else {
+                        html! { <p class="text-text-primary leading-relaxed whitespace-pre-line break-words">{parse_rich_text(&server.description)}</p> }
+                    }}
+                </section>
+
+                <section class="p-8 flex flex-col gap-4">
+                    <h3 class={section_title}>{"Last listing"}</h3>
+                    <dl class="grid grid-cols-[max-content_1fr] gap-x-6 gap-y-2 text-sm">
+                        <dt class="text-text-secondary">{"Version"}</dt>
+                        <dd class="font-mono text-text-primary">{&server.game_version}</dd>
+                        <dt class="text-text-secondary">{"Max players"}</dt>
+                        <dd class="font-mono text-text-primary">{if server.max_players == 0 { "∞".to_string() } else { server.max_players.to_string() }}</dd>
+                        <dt class="text-text-secondary">{"Mods"}</dt>
+                        <dd class="font-mono text-text-primary">{if server.mod_count == 0 { "Vanilla".to_string() } else { server.mod_count.to_string() }}</dd>
+                    </dl>
+                    {if !server.tags.is_empty() {
+                        html! {
+                            <div class="flex flex-wrap gap-2">
+                                {for server.tags.iter().map(|tag| html! {
+                                    <span class="py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary">{tag}</span>
+                                })}
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </section>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,50 @@
+use crate::view::PlayerStatus;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct CapacityBarProps {
+    /// Percentage of slots taken, clamped to 100
+    pub ratio: u32,
+    pub status: PlayerStatus,
+    /// Tooltip, usually the "current/max" player count
+    #[prop_or_default]
+    pub label: String,
+    /// Bar height class
+    #[prop_or(classes!("h-1.5"))]
+    pub height: Classes,
+    /// No player limit: drawn as a faint full bar instead of a fill level
+    #[prop_or_default]
+    pub unlimited: bool,
+}
+
+/// Horizontal bar showing how full a server is, colored by player status
+#[function_component(CapacityBar)]
+pub fn capacity_bar(props: &CapacityBarProps) -> Html {
+    if props.unlimited {
+        return html! {
+            <div
+                class={classes!("w-full", "bg-bg-dark", "rounded-sm", "overflow-hidden", props.height.clone())}
+                title={format!("{} (unlimited slots)", props.label)}
+            >
+                <div class={classes!("h-full", "w-full", "rounded-sm", "opacity-30", props.status.bg_class())}></div>
+            </div>
+        };
+    }
+
+    let ratio = props.ratio.min(100);
+    // Keep a sliver visible for servers with any players
+    let width = if ratio == 0 && props.status != PlayerStatus::Empty { 2 } else { ratio };
+
+    html! {
+        <div
+            class={classes!("w-full", "bg-bg-dark", "rounded-sm", "overflow-hidden", props.height.clone())}
+            title={props.label.clone()}
+            role="meter"
+            aria-valuemin="0"
+            aria-valuemax="100"
+            aria-valuenow={ratio.to_string()}
+        >
+            <div class={classes!("h-full", "rounded-sm", props.status.bg_class())} style={format!("width: {}%", width)}></div>
+        </div>
+    }
+}
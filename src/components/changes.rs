@@ -0,0 +1,83 @@
+use crate::changes::{ChangeKind, ListingChange};
+use crate::utils::parse_rich_text;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct ChangesPageProps {
+    /// Most recent first
+    #[prop_or_default]
+    pub changes: Vec<ListingChange>,
+}
+
+fn change_label(kind: ChangeKind) -> (&'static str, &'static str) {
+    match kind {
+        ChangeKind::Appeared => ("Appeared", "bg-status-low"),
+        ChangeKind::Disappeared => ("Disappeared", "bg-status-full"),
+    }
+}
+
+fn change_row(change: &ListingChange) -> Html {
+    let (label, color) = change_label(change.kind);
+    let at = chrono::DateTime::parse_from_rfc3339(&change.at)
+        .map(|t| t.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| change.at.clone());
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap font-mono">{at}</td>
+            <td class="py-2 pr-4 whitespace-nowrap">
+                <span class={classes!("inline-block", "py-0.5", "px-2", "rounded-sm", "text-xs", "text-bg-dark", "font-semibold", color)}>{label}</span>
+            </td>
+            <td class="py-2 pr-4 break-words">
+                <a href={format!("/server/{}", change.game_id)} class="text-accent-primary no-underline hover:text-accent-secondary">
+                    {parse_rich_text(&change.name)}
+                </a>
+            </td>
+            <td class="py-2 text-text-secondary whitespace-nowrap">{&change.game_version}</td>
+        </tr>
+    }
+}
+
+/// Servers that recently joined or left the listing, newest first (SSR-compatible)
+#[function_component(ChangesPage)]
+pub fn changes_page(props: &ChangesPageProps) -> Html {
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[1000px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <h2 class="text-2xl mb-1">{"Listing changes"}</h2>
+                    <p class="text-text-muted text-sm">
+                        {"Servers that showed up in or dropped out of the listing between refreshes. A restart isn't a change: servers are followed by their server ID, which stays the same when their game ID changes. Also available as JSON at "}
+                        <a href="/api/changes" class="text-accent-primary no-underline hover:text-accent-secondary">{"/api/changes"}</a>
+                        {"."}
+                    </p>
+                </header>
+
+                <section class="p-8">
+                    {if props.changes.is_empty() {
+                        html! { <p class="text-text-secondary">{"No changes since the browser started."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"Time (UTC)"}</th>
+                                            <th class="pb-2 pr-4">{"Change"}</th>
+                                            <th class="pb-2 pr-4">{"Server"}</th>
+                                            <th class="pb-2">{"Version"}</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.changes.iter().map(change_row)}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+            </div>
+        </div>
+    }
+}
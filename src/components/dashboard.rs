@@ -0,0 +1,70 @@
+use crate::components::capacity_bar::CapacityBar;
+use crate::ids::GameId;
+use crate::utils::parse_rich_text;
+use crate::view::ServerView;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct DashboardProps {
+    /// Selected servers, in the order they were requested
+    pub servers: Vec<ServerView>,
+    /// Requested game_ids that aren't in the current listing
+    #[prop_or_default]
+    pub missing: Vec<GameId>,
+    /// Seconds between automatic page reloads
+    pub refresh_secs: u64,
+}
+
+/// Dense wall-display board of selected servers (SSR-compatible)
+#[function_component(Dashboard)]
+pub fn dashboard(props: &DashboardProps) -> Html {
+    let total_players: usize = props.servers.iter().map(|s| s.server.player_count).sum();
+
+    html! {
+        <div class="min-h-screen flex flex-col p-6 gap-6">
+            <header class="flex items-baseline justify-between gap-4 flex-wrap">
+                <h1 class="text-2xl font-bold text-text-bright">{"Factorio Servers"}</h1>
+                <span class="text-text-secondary font-mono">
+                    {format!("{} players · refreshes every {}s", total_players, props.refresh_secs)}
+                </span>
+            </header>
+
+            {if props.servers.is_empty() && props.missing.is_empty() {
+                html! {
+                    <div class="text-center py-12 text-text-muted">
+                        <p>{"Pick servers with "}<code class="font-mono">{"/dashboard?ids=123,456"}</code>{" using game IDs from the server pages."}</p>
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+
+            <div class="grid grid-cols-[repeat(auto-fill,minmax(260px,1fr))] gap-4">
+                {for props.servers.iter().map(|view| {
+                    let server = &view.server;
+                    html! {
+                        <a href={format!("/server/{}", server.game_id)} class="flex flex-col gap-3 p-5 bg-bg-card border border-border-subtle rounded-md no-underline text-inherit">
+                            <h2 class="text-lg font-semibold leading-tight overflow-hidden text-ellipsis whitespace-nowrap">{parse_rich_text(&server.name)}</h2>
+                            <div class="flex items-baseline gap-2">
+                                <span class={classes!("text-[3rem]", "font-semibold", "font-mono", "leading-none", view.status.text_class())}>{server.player_count}</span>
+                                <span class="text-text-secondary font-mono">{if view.unlimited { "/ ∞".to_string() } else { format!("/ {}", server.max_players) }}</span>
+                            </div>
+                            <CapacityBar ratio={view.player_ratio} status={view.status} unlimited={view.unlimited} label={view.players_display.clone()} height={classes!("h-2")} />
+                            <div class="flex justify-between text-[0.85rem] text-text-muted font-mono">
+                                <span>{&server.game_version}</span>
+                                <span>{&view.game_time}</span>
+                            </div>
+                        </a>
+                    }
+                })}
+
+                {for props.missing.iter().map(|game_id| html! {
+                    <div class="flex flex-col gap-3 p-5 bg-bg-card border border-border-subtle rounded-md text-text-muted">
+                        <h2 class="text-lg font-semibold">{format!("Server {}", game_id)}</h2>
+                        <p class="text-sm">{"Offline or no longer listed"}</p>
+                    </div>
+                })}
+            </div>
+        </div>
+    }
+}
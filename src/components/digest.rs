@@ -0,0 +1,102 @@
+use crate::digest::{DigestServer, WeeklyDigest};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct DigestPageProps {
+    pub digest: WeeklyDigest,
+    /// The same digest as Markdown, ready to paste into Reddit or Discord
+    pub markdown: String,
+    /// When the digest was generated (None when computed on the spot, before the first one was published)
+    #[prop_or_default]
+    pub generated_at: Option<String>,
+}
+
+fn server_list(servers: &[DigestServer]) -> Html {
+    html! {
+        <ol class="flex flex-col gap-2">
+            {for servers.iter().enumerate().map(|(rank, server)| html! {
+                <li class="flex items-baseline gap-3">
+                    <span class="w-6 text-right font-mono text-text-muted">{rank + 1}</span>
+                    <a href={format!("/server/{}", server.game_id)} class="flex-1 min-w-0 overflow-hidden text-ellipsis whitespace-nowrap text-accent-primary no-underline hover:text-accent-secondary">{&server.name}</a>
+                    <span class="font-mono text-sm text-text-secondary whitespace-nowrap">
+                        {format!("peak {} · {}{}", server.peak_players, server.game_version, if server.modded { " · modded" } else { "" })}
+                    </span>
+                </li>
+            })}
+        </ol>
+    }
+}
+
+/// Weekly summary page with a copyable Markdown version (SSR-compatible)
+#[function_component(DigestPage)]
+pub fn digest_page(props: &DigestPageProps) -> Html {
+    let digest = &props.digest;
+    let section_title = "text-sm uppercase tracking-wider text-text-secondary";
+    let tile = |value: String, label: &'static str| html! {
+        <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+            <span class="block text-2xl font-semibold font-mono text-accent-primary">{value}</span>
+            <span class="text-xs text-text-secondary uppercase tracking-wider">{label}</span>
+        </div>
+    };
+
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[1000px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <h2 class="text-2xl mb-1">{format!("Weekly digest: week of {}", digest.week_of)}</h2>
+                    <p class="text-text-muted text-sm mb-4">
+                        {match props.generated_at {
+                            Some(ref at) => format!(
+                                "Generated {}",
+                                chrono::DateTime::parse_from_rfc3339(at)
+                                    .map(|t| t.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M UTC").to_string())
+                                    .unwrap_or_else(|_| at.clone())
+                            ),
+                            None => "Preview; no digest has been published yet".to_string(),
+                        }}
+                    </p>
+                    <div class="flex gap-6 max-md:flex-col">
+                        {tile(format!("{:.0}", digest.average_players), "Players on average")}
+                        {tile(format!("{:.0}", digest.peak_players), "Peak players")}
+                        {tile(format!("{:.0}", digest.average_servers), "Servers on average")}
+                    </div>
+                    {if let Some(ref hour) = digest.busiest_hour {
+                        html! { <p class="mt-4 text-text-secondary text-sm">{format!("Busiest hour: {}", hour)}</p> }
+                    } else {
+                        html! {}
+                    }}
+                </header>
+
+                <section class="p-8 border-b border-border-subtle flex flex-col gap-4">
+                    <h3 class={section_title}>{"Top servers"}</h3>
+                    {if digest.top_servers.is_empty() {
+                        html! { <p class="text-text-muted">{"No servers had players this week."}</p> }
+                    } else {
+                        server_list(&digest.top_servers)
+                    }}
+                </section>
+
+                {if !digest.new_servers.is_empty() {
+                    html! {
+                        <section class="p-8 border-b border-border-subtle flex flex-col gap-4">
+                            <h3 class={section_title}>{"New this week"}</h3>
+                            {server_list(&digest.new_servers)}
+                        </section>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                <section class="p-8 flex flex-col gap-4">
+                    <div class="flex justify-between items-baseline">
+                        <h3 class={section_title}>{"Markdown"}</h3>
+                        <a href="/digest/latest.md" class="text-sm text-accent-primary no-underline hover:text-accent-secondary">{"Raw .md"}</a>
+                    </div>
+                    <textarea readonly=true rows="16" class="w-full p-4 bg-bg-inset border border-border-subtle rounded-sm font-mono text-sm text-text-primary">{&props.markdown}</textarea>
+                </section>
+            </div>
+        </div>
+    }
+}
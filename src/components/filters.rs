@@ -1,7 +1,12 @@
+use crate::accounts::{AccountNav, MAX_PRESET_NAME_LEN};
+use crate::filter::VersionFamily;
+use crate::geo::continent_name;
+use crate::utils::format::{format_game_time, format_number};
 use crate::utils::strip_all_tags;
+use crate::view::INACTIVE_DAYS;
 use yew::prelude::*;
 
-#[derive(Properties, PartialEq)]
+#[derive(Properties, PartialEq, Clone)]
 pub struct FiltersProps {
     #[prop_or_default]
     pub current_search: String,
@@ -14,21 +19,106 @@ pub struct FiltersProps {
     #[prop_or_default]
     pub is_dedicated: bool,
     #[prop_or_default]
-    pub versions: Vec<String>,
+    pub has_free_slots: bool,
+    /// Selected continent code (empty = any)
+    #[prop_or_default]
+    pub current_region: String,
+    /// Selected map age limit in minutes of game time (None = any)
+    #[prop_or_default]
+    pub current_max_game_time: Option<u64>,
+    /// Selected "first listed within" window in days (None = any)
+    #[prop_or_default]
+    pub current_new_days: Option<u32>,
+    /// Continent codes to offer (empty hides the Region filter)
+    #[prop_or_default]
+    pub regions: Vec<String>,
+    /// Inactive (ghost) servers are in the list
+    #[prop_or_default]
+    pub show_inactive: bool,
+    /// Inactive servers are hidden unless the URL says otherwise
+    #[prop_or_default]
+    pub inactive_hidden_by_default: bool,
+    /// Inactive servers matching the other filters
+    #[prop_or_default]
+    pub inactive_count: usize,
+    /// The search also covers servers no longer listed, not only archived ones
+    #[prop_or_default]
+    pub include_offline: bool,
+    /// Modpack fingerprint the list is narrowed to (from a server page link)
+    #[prop_or_default]
+    pub modpack: Option<String>,
+    /// Versions in the listing by release family, newest first
+    #[prop_or_default]
+    pub version_families: Vec<VersionFamily>,
     #[prop_or_default]
     pub latest_version: String,
     #[prop_or_default]
     pub available_tags: Vec<String>,
     #[prop_or_default]
     pub selected_tags: Vec<String>,
+    /// Require every selected tag instead of any
+    #[prop_or_default]
+    pub tag_mode_all: bool,
+    #[prop_or_default]
+    pub current_group_by: String,
+    /// Sort and layout parameters (see ListState::params), kept by every filter link and the form
+    #[prop_or_default]
+    pub list_params: Vec<(String, String)>,
+    /// Sign-in state and saved presets (None when accounts are disabled)
+    #[prop_or_default]
+    pub account: Option<AccountNav>,
+}
+
+/// Map age choices for the "Started" filter: game time limit in minutes and label
+const GAME_TIME_CHOICES: &[(u64, &str)] = &[
+    (60, "< 1 hour"),
+    (6 * 60, "< 6 hours"),
+    (24 * 60, "< 1 day"),
+    (7 * 24 * 60, "< 1 week"),
+];
+
+/// "First listed within" choices, in days
+const NEW_DAYS_CHOICES: &[(u32, &str)] = &[(1, "Today"), (7, "This week"), (30, "This month")];
+
+/// "1 server", "812 servers"
+fn server_count(servers: usize) -> String {
+    match servers {
+        1 => "1 server".to_string(),
+        n => format!("{} servers", format_number(n as u64)),
+    }
+}
+
+/// show_inactive parameter for the current choice, when it differs from the default
+fn inactive_param(props: &FiltersProps) -> Option<String> {
+    if props.show_inactive == props.inactive_hidden_by_default {
+        Some(format!("show_inactive={}", props.show_inactive))
+    } else {
+        None
+    }
+}
+
+/// The list's sort and layout as query string parts
+fn list_params(props: &FiltersProps) -> impl Iterator<Item = String> + '_ {
+    props
+        .list_params
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, urlencoding::encode(value)))
 }
 
 /// Build URL with current filters, optionally toggling a tag
-fn build_filter_url(props: &FiltersProps, toggle_tag: Option<&str>, clear_tags: bool) -> String {
+fn build_filter_url(
+    props: &FiltersProps,
+    toggle_tag: Option<&str>,
+    clear_tags: bool,
+    tag_mode_all: bool,
+) -> String {
     let mut params = Vec::new();
     
     if !props.current_search.is_empty() {
         params.push(format!("search={}", urlencoding::encode(&props.current_search)));
+        if props.include_offline {
+            params.push("include_offline=true".to_string());
+        }
     }
     if !props.current_version.is_empty() {
         params.push(format!("version={}", urlencoding::encode(&props.current_version)));
@@ -42,6 +132,25 @@ fn build_filter_url(props: &FiltersProps, toggle_tag: Option<&str>, clear_tags:
     if props.is_dedicated {
         params.push("is_dedicated=true".to_string());
     }
+    if props.has_free_slots {
+        params.push("has_free_slots=true".to_string());
+    }
+    if !props.current_region.is_empty() {
+        params.push(format!("region={}", urlencoding::encode(&props.current_region)));
+    }
+    if let Some(minutes) = props.current_max_game_time {
+        params.push(format!("max_game_time={}", minutes));
+    }
+    if let Some(days) = props.current_new_days {
+        params.push(format!("new_days={}", days));
+    }
+    params.extend(inactive_param(props));
+    if let Some(ref modpack) = props.modpack {
+        params.push(format!("modpack={}", modpack));
+    }
+    if !props.current_group_by.is_empty() {
+        params.push(format!("group_by={}", urlencoding::encode(&props.current_group_by)));
+    }
     
     // Handle tags
     if !clear_tags {
@@ -57,8 +166,12 @@ fn build_filter_url(props: &FiltersProps, toggle_tag: Option<&str>, clear_tags:
         }
         if !new_tags.is_empty() {
             params.push(format!("tags={}", urlencoding::encode(&new_tags.join(","))));
+            if tag_mode_all {
+                params.push("tag_mode=all".to_string());
+            }
         }
     }
+    params.extend(list_params(props));
     
     if params.is_empty() {
         "/".to_string()
@@ -67,6 +180,53 @@ fn build_filter_url(props: &FiltersProps, toggle_tag: Option<&str>, clear_tags:
     }
 }
 
+/// Saved presets dropdown and "save current filters" form, shown above the filter form
+/// These are separate forms since HTML forms can't nest
+fn presets_bar(props: &FiltersProps) -> Html {
+    let Some(ref account) = props.account else {
+        return html! {};
+    };
+    let link_class = "text-accent-primary no-underline transition-colors duration-200 hover:text-accent-secondary";
+    let input_class = "py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-sm transition-colors duration-200 focus:outline-none focus:border-accent-primary";
+    let button_class = "py-1 px-3 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-sm cursor-pointer transition-colors duration-200 hover:border-accent-primary";
+
+    if account.email.is_none() {
+        return html! {
+            <div class="flex justify-end mb-2 text-sm">
+                <a href="/account" class={link_class}>{"Sign in to save filter presets"}</a>
+            </div>
+        };
+    }
+
+    html! {
+        <div class="flex flex-wrap items-center justify-end gap-4 mb-2 text-sm">
+            {if !account.presets.is_empty() {
+                html! {
+                    <form method="get" action="/presets/load" class="flex items-center gap-2">
+                        <label for="preset" class="text-xs text-text-secondary uppercase tracking-wider">{"Presets"}</label>
+                        <select id="preset" name="id" class={input_class}>
+                            {for account.presets.iter().map(|preset| html! {
+                                <option value={preset.id.clone()}>{&preset.name}</option>
+                            })}
+                        </select>
+                        <button type="submit" class={button_class}>{"Load"}</button>
+                    </form>
+                }
+            } else {
+                html! {}
+            }}
+            <form method="post" action="/presets" class="flex items-center gap-2">
+                <input type="hidden" name="search" value={props.current_search.clone()} />
+                <input type="hidden" name="version" value={props.current_version.clone()} />
+                <input type="hidden" name="tags" value={props.selected_tags.join(",")} />
+                <input type="text" name="name" placeholder="Preset name" required=true maxlength={MAX_PRESET_NAME_LEN.to_string()} class={input_class} />
+                <button type="submit" class={button_class}>{"Save filters"}</button>
+            </form>
+            <a href="/account" class={link_class}>{"Account"}</a>
+        </div>
+    }
+}
+
 /// Filter controls component - renders as a form for SSR
 /// In SSR mode, filters work via form submission / URL parameters
 #[function_component(Filters)]
@@ -79,7 +239,7 @@ pub fn filters(props: &FiltersProps) -> Html {
     let has_selected_tags = !props.selected_tags.is_empty();
     
     // Build URL for clearing all tags
-    let clear_tags_url = build_filter_url(props, None, true);
+    let clear_tags_url = build_filter_url(props, None, true, props.tag_mode_all);
     
     // Build URL for clearing search (preserves other filters)
     let clear_search_url = {
@@ -97,9 +257,32 @@ pub fn filters(props: &FiltersProps) -> Html {
         if props.is_dedicated {
             params.push("is_dedicated=true".to_string());
         }
+        if props.has_free_slots {
+            params.push("has_free_slots=true".to_string());
+        }
+        if !props.current_region.is_empty() {
+            params.push(format!("region={}", urlencoding::encode(&props.current_region)));
+        }
+        if let Some(minutes) = props.current_max_game_time {
+            params.push(format!("max_game_time={}", minutes));
+        }
+        if let Some(days) = props.current_new_days {
+            params.push(format!("new_days={}", days));
+        }
+        params.extend(inactive_param(props));
+        if let Some(ref modpack) = props.modpack {
+            params.push(format!("modpack={}", modpack));
+        }
+        if !props.current_group_by.is_empty() {
+            params.push(format!("group_by={}", urlencoding::encode(&props.current_group_by)));
+        }
         if !props.selected_tags.is_empty() {
             params.push(format!("tags={}", urlencoding::encode(&props.selected_tags.join(","))));
+            if props.tag_mode_all {
+                params.push("tag_mode=all".to_string());
+            }
         }
+        params.extend(list_params(props));
         if params.is_empty() {
             "/".to_string()
         } else {
@@ -108,7 +291,13 @@ pub fn filters(props: &FiltersProps) -> Html {
     };
     let has_search = !props.current_search.is_empty();
 
+    // Same filters as an Atom feed (sorting and layout don't apply there)
+    let feed_props = FiltersProps { list_params: Vec::new(), ..props.clone() };
+    let feed_url = build_filter_url(&feed_props, None, false, props.tag_mode_all).replacen('/', "/feed.xml", 1);
+
     html! {
+        <>
+        {presets_bar(props)}
         <form id="filter-form" class="flex flex-col gap-4 mb-8 p-6 bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-md" method="get" action="/">
             // Main filter controls row
             <div class="flex flex-wrap items-end gap-4">
@@ -138,6 +327,10 @@ pub fn filters(props: &FiltersProps) -> Html {
                             html! {}
                         }}
                     </div>
+                    <label class="flex items-center gap-2 cursor-pointer text-xs text-text-secondary" title="Also find servers that are no longer on the server list">
+                        <input type="checkbox" name="include_offline" value="true" checked={props.include_offline} class="accent-accent-primary w-3 h-3" />
+                        {"Include offline servers"}
+                    </label>
                 </div>
                 
                 <div class="flex flex-col gap-1">
@@ -147,16 +340,84 @@ pub fn filters(props: &FiltersProps) -> Html {
                             {format!("Latest ({})", props.latest_version)}
                         </option>
                         <option value="all" selected={is_all_selected}>{"All Versions"}</option>
-                        {for props.versions.iter().filter(|v| *v != &props.latest_version).map(|v| {
-                            html! {
-                                <option value={v.clone()} selected={&props.current_version == v}>
-                                    {v}
+                        {for props.version_families.iter().map(|family| html! {
+                            <optgroup label={format!("{}.x", family.series)}>
+                                <option value={family.series.clone()} selected={props.current_version == family.series}>
+                                    {format!("{}.x — {}", family.series, server_count(family.servers))}
                                 </option>
-                            }
+                                {for family.versions.iter().filter(|(v, _)| *v != props.latest_version).map(|(v, count)| html! {
+                                    <option value={v.clone()} selected={&props.current_version == v}>
+                                        {format!("{} — {}", v, server_count(*count))}
+                                    </option>
+                                })}
+                            </optgroup>
                         })}
                     </select>
                 </div>
                 
+                {if !props.regions.is_empty() {
+                    html! {
+                        <div class="flex flex-col gap-1">
+                            <label for="region" class="text-xs text-text-secondary uppercase tracking-wider">{"Region"}</label>
+                            <select id="region" name="region" class="py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary">
+                                <option value="" selected={props.current_region.is_empty()}>{"Any"}</option>
+                                {for props.regions.iter().map(|code| html! {
+                                    <option value={code.clone()} selected={&props.current_region == code}>
+                                        {continent_name(code).unwrap_or(code)}
+                                    </option>
+                                })}
+                            </select>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+                
+                <div class="flex flex-col gap-1">
+                    <label for="max_game_time" class="text-xs text-text-secondary uppercase tracking-wider" title="In-game time since the map started">{"Map Age"}</label>
+                    <select id="max_game_time" name="max_game_time" class="py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary">
+                        <option value="" selected={props.current_max_game_time.is_none()}>{"Any"}</option>
+                        {for GAME_TIME_CHOICES.iter().map(|(minutes, label)| html! {
+                            <option value={minutes.to_string()} selected={props.current_max_game_time == Some(*minutes)}>{*label}</option>
+                        })}
+                        // A limit from a hand-edited URL stays selected
+                        {match props.current_max_game_time {
+                            Some(minutes) if !GAME_TIME_CHOICES.iter().any(|(m, _)| *m == minutes) => html! {
+                                <option value={minutes.to_string()} selected=true>{format!("< {}", format_game_time(minutes))}</option>
+                            },
+                            _ => html! {},
+                        }}
+                    </select>
+                </div>
+
+                <div class="flex flex-col gap-1">
+                    <label for="new_days" class="text-xs text-text-secondary uppercase tracking-wider" title="When the server first showed up in the listing">{"New Servers"}</label>
+                    <select id="new_days" name="new_days" class="py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary">
+                        <option value="" selected={props.current_new_days.is_none()}>{"Any"}</option>
+                        {for NEW_DAYS_CHOICES.iter().map(|(days, label)| html! {
+                            <option value={days.to_string()} selected={props.current_new_days == Some(*days)}>{*label}</option>
+                        })}
+                        // A window from a hand-edited URL stays selected
+                        {match props.current_new_days {
+                            Some(days) if !NEW_DAYS_CHOICES.iter().any(|(d, _)| *d == days) => html! {
+                                <option value={days.to_string()} selected=true>{format!("Last {} days", days)}</option>
+                            },
+                            _ => html! {},
+                        }}
+                    </select>
+                </div>
+
+                <div class="flex flex-col gap-1">
+                    <label for="group_by" class="text-xs text-text-secondary uppercase tracking-wider">{"Group By"}</label>
+                    <select id="group_by" name="group_by" class="py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary">
+                        <option value="" selected={props.current_group_by.is_empty()}>{"None"}</option>
+                        <option value="version" selected={props.current_group_by == "version"}>{"Version"}</option>
+                        <option value="mods" selected={props.current_group_by == "mods"}>{"Vanilla / Modded"}</option>
+                        <option value="region" selected={props.current_group_by == "region"}>{"Region"}</option>
+                        <option value="modpack" selected={props.current_group_by == "modpack"}>{"Modpack"}</option>
+                    </select>
+                </div>
+                
                 <div class="flex flex-col gap-1 justify-end">
                     <label class="flex items-center gap-2 cursor-pointer py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm transition-colors duration-200 hover:border-accent-primary">
                         <input 
@@ -196,11 +457,55 @@ pub fn filters(props: &FiltersProps) -> Html {
                     </label>
                 </div>
                 
+                <div class="flex flex-col gap-1 justify-end">
+                    <label
+                        class="flex items-center gap-2 cursor-pointer py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm transition-colors duration-200 hover:border-accent-primary"
+                        title="Servers without a player limit always count as having free slots"
+                    >
+                        <input 
+                            type="checkbox" 
+                            name="has_free_slots"
+                            value="true"
+                            checked={props.has_free_slots}
+                            class="accent-accent-primary w-4 h-4"
+                        />
+                        <span class="text-sm text-text-primary">{"Free Slots"}</span>
+                    </label>
+                </div>
+
+                // Checking the box always means "not the default", so an unchecked box needs no parameter
+                <div class="flex flex-col gap-1 justify-end">
+                    <label
+                        class="flex items-center gap-2 cursor-pointer py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm transition-colors duration-200 hover:border-accent-primary"
+                        title={format!("Servers that have been empty for the last {} days", INACTIVE_DAYS)}
+                    >
+                        {if props.inactive_hidden_by_default {
+                            html! {
+                                <>
+                                    <input type="checkbox" name="show_inactive" value="true" checked={props.show_inactive} class="accent-accent-primary w-4 h-4" />
+                                    <span class="text-sm text-text-primary">{format!("Show Inactive ({})", props.inactive_count)}</span>
+                                </>
+                            }
+                        } else {
+                            html! {
+                                <>
+                                    <input type="checkbox" name="show_inactive" value="false" checked={!props.show_inactive} class="accent-accent-primary w-4 h-4" />
+                                    <span class="text-sm text-text-primary">{format!("Hide Inactive ({})", props.inactive_count)}</span>
+                                </>
+                            }
+                        }}
+                    </label>
+                </div>
+                
                 <div class="flex flex-col gap-1 justify-end">
                     <button type="submit" class="py-2 px-6 bg-btn-green border border-btn-green-dark rounded-sm text-bg-dark font-display text-[0.95rem] font-semibold cursor-pointer transition-all duration-200 hover:bg-btn-green-hover active:bg-btn-green-dark">
                         {"Apply Filters"}
                     </button>
                 </div>
+
+                <a href={feed_url} class="self-center text-xs text-accent-primary hover:text-accent-secondary transition-colors no-underline" title="Atom feed of servers matching these filters">
+                    {"Feed"}
+                </a>
             </div>
             
             // Tag pills row
@@ -221,12 +526,29 @@ pub fn filters(props: &FiltersProps) -> Html {
                             } else {
                                 html! {}
                             }}
+                            {if props.selected_tags.len() > 1 {
+                                let mode_class = |active: bool| if active {
+                                    "text-xs text-accent-primary font-semibold no-underline"
+                                } else {
+                                    "text-xs text-text-muted hover:text-accent-secondary transition-colors no-underline"
+                                };
+                                html! {
+                                    <span class="flex items-center gap-1 text-xs text-text-muted">
+                                        {"Match"}
+                                        <a href={build_filter_url(props, None, false, false)} class={mode_class(!props.tag_mode_all)}>{"any"}</a>
+                                        {"/"}
+                                        <a href={build_filter_url(props, None, false, true)} class={mode_class(props.tag_mode_all)}>{"all"}</a>
+                                    </span>
+                                }
+                            } else {
+                                html! {}
+                            }}
                         </div>
                         <div class="flex flex-wrap gap-1 overflow-x-auto pb-1">
                             {for props.available_tags.iter().map(|tag| {
                                 let is_selected = props.selected_tags.contains(tag);
                                 let tag_escaped = strip_all_tags(tag);
-                                let toggle_url = build_filter_url(props, Some(tag), false);
+                                let toggle_url = build_filter_url(props, Some(tag), false, props.tag_mode_all);
                                 
                                 // Match server card tag styling: py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary
                                 let class = if is_selected {
@@ -251,8 +573,31 @@ pub fn filters(props: &FiltersProps) -> Html {
                 html! {}
             }}
             
+            {if let Some(ref modpack) = props.modpack {
+                let clear_modpack_url = build_filter_url(&FiltersProps { modpack: None, ..props.clone() }, None, false, props.tag_mode_all);
+                html! {
+                    <div class="flex items-center gap-2 text-sm text-text-secondary">
+                        <input type="hidden" name="modpack" value={modpack.clone()} />
+                        {"Servers running one modpack "}
+                        <code class="font-mono text-xs text-text-muted">{modpack}</code>
+                        <a href={clear_modpack_url} class="text-xs text-accent-primary hover:text-accent-secondary transition-colors no-underline">{"Show all"}</a>
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+
             // Hidden input for tags (used when form is submitted via Apply button)
             <input type="hidden" id="tags-input" name="tags" value={selected_tags_value} />
+            {if props.tag_mode_all {
+                html! { <input type="hidden" name="tag_mode" value="all" /> }
+            } else {
+                html! {}
+            }}
+            {for props.list_params.iter().map(|(name, value)| html! {
+                <input type="hidden" class="list-param" name={name.clone()} value={value.clone()} />
+            })}
         </form>
+        </>
     }
 }
@@ -9,7 +9,7 @@ pub fn footer() -> Html {
     html! {
         <footer class="text-center p-6 text-text-muted text-sm">
             <p>{format!("© {} • Source code available at ", current_year)}<a href="https://github.com/Psaltor/factorio-browser" target="_blank" class="text-accent-primary hover:text-accent-secondary transition-colors" target="_blank" rel="noopener">{"Github.com"}</a></p>
-            <p class="mt-1">{"Data from Factorio Matchmaking API • Not affiliated with Wube Software"}</p>
+            <p class="mt-1">{"Data from Factorio Matchmaking API • Not affiliated with Wube Software • "}<a href="/transparency" class="text-accent-primary hover:text-accent-secondary transition-colors">{"Moderation log"}</a>{" • "}<a href="/changes" class="text-accent-primary hover:text-accent-secondary transition-colors">{"Listing changes"}</a></p>
         </footer>
     }
 }
@@ -0,0 +1,136 @@
+use crate::filter::FilterSpec;
+use crate::prefs::SortKey;
+use crate::utils::strip_all_tags;
+use crate::view::ServerView;
+use yew::prelude::*;
+
+/// Inline stylesheet of the lite pages: readable without it, and small enough to not matter on a slow link
+pub const LITE_CSS: &str = "body{font-family:sans-serif;max-width:60em;margin:0 auto;padding:.5em;background:#fff;color:#111}\
+table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:.2em .4em;border-bottom:1px solid #ccc}\
+td.n{text-align:right;white-space:nowrap}a{color:#05c}";
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct LiteListProps {
+    /// This page of matching servers, in list order
+    pub servers: Vec<ServerView>,
+    pub filters: FilterSpec,
+    pub sort: SortKey,
+    /// Servers matching the filters, over all pages
+    pub matching: usize,
+    /// Zero-based
+    pub page: usize,
+    pub pages: usize,
+    /// Query string without `page`, for the pager links
+    #[prop_or_default]
+    pub page_query: String,
+    #[prop_or_default]
+    pub error: Option<String>,
+}
+
+fn page_link(query: &str, page: usize) -> String {
+    if query.is_empty() {
+        format!("/lite?page={}", page + 1)
+    } else {
+        format!("/lite?{}&page={}", query, page + 1)
+    }
+}
+
+/// Text-only server list for old browsers, text-mode browsers and slow connections (SSR only)
+#[function_component(LiteList)]
+pub fn lite_list(props: &LiteListProps) -> Html {
+    let filters = &props.filters;
+    html! {
+        <>
+            <h1>{"Factorio servers"}</h1>
+            <p><a href="/">{"Full version"}</a></p>
+            {if let Some(ref error) = props.error {
+                html! { <p><strong>{error}</strong></p> }
+            } else {
+                html! {}
+            }}
+            <form method="get" action="/lite">
+                <input type="text" name="search" value={filters.search.clone()} placeholder="Search" />
+                {" "}
+                <input type="text" name="version" value={filters.version.as_param().to_string()} placeholder="Version (latest)" size="12" />
+                {" "}
+                <select name="sort">
+                    {for SortKey::ALL.iter().map(|key| html! {
+                        <option value={key.key()} selected={*key == props.sort}>{key.label()}</option>
+                    })}
+                </select>
+                {" "}
+                <label><input type="checkbox" name="has_players" value="true" checked={filters.has_players} />{" With players"}</label>
+                {" "}
+                <label><input type="checkbox" name="no_password" value="true" checked={filters.no_password} />{" No password"}</label>
+                {" "}
+                <button type="submit">{"Filter"}</button>
+            </form>
+            <p>{format!("{} servers, page {} of {}", props.matching, props.page + 1, props.pages)}</p>
+            <table>
+                <tr>
+                    <th>{"Name"}</th>
+                    <th>{"Players"}</th>
+                    <th>{"Version"}</th>
+                    <th>{"Played"}</th>
+                </tr>
+                {for props.servers.iter().map(|server| html! {
+                    <tr>
+                        <td>
+                            <a href={format!("/lite/server/{}", server.server.game_id)}>{strip_all_tags(&server.server.name)}</a>
+                            {if server.server.has_password { " (password)" } else { "" }}
+                        </td>
+                        <td class="n">{&server.players_display}</td>
+                        <td class="n">{&server.server.game_version}</td>
+                        <td class="n">{&server.game_time}</td>
+                    </tr>
+                })}
+            </table>
+            <p>
+                {if props.page > 0 {
+                    html! { <a href={page_link(&props.page_query, props.page - 1)}>{"« Previous"}</a> }
+                } else {
+                    html! {}
+                }}
+                {" "}
+                {if props.page + 1 < props.pages {
+                    html! { <a href={page_link(&props.page_query, props.page + 1)}>{"Next »"}</a> }
+                } else {
+                    html! {}
+                }}
+            </p>
+        </>
+    }
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct LiteServerProps {
+    pub server: ServerView,
+}
+
+/// Text-only page of one server: what's needed to pick it and join (SSR only)
+#[function_component(LiteServer)]
+pub fn lite_server(props: &LiteServerProps) -> Html {
+    let server = &props.server.server;
+    html! {
+        <>
+            <p><a href="/lite">{"« All servers"}</a>{" · "}<a href={format!("/server/{}", server.game_id)}>{"Full version"}</a></p>
+            <h1>{strip_all_tags(&server.name)}</h1>
+            <p>{strip_all_tags(&server.description)}</p>
+            <table>
+                <tr><th>{"Players"}</th><td>{&props.server.players_display}</td></tr>
+                <tr><th>{"Version"}</th><td>{&server.game_version}</td></tr>
+                <tr><th>{"Played"}</th><td>{&props.server.game_time}</td></tr>
+                <tr><th>{"Password"}</th><td>{if server.has_password { "Yes" } else { "No" }}</td></tr>
+                <tr><th>{"Mods"}</th><td>{&props.server.mods_display}</td></tr>
+                <tr><th>{"Address"}</th><td>{server.host_address.clone().unwrap_or_else(|| "Hidden".to_string())}</td></tr>
+                <tr><th>{"Tags"}</th><td>{server.tags.join(", ")}</td></tr>
+            </table>
+            <h2>{"Online players"}</h2>
+            {if server.players.is_empty() {
+                html! { <p>{"Nobody is playing right now."}</p> }
+            } else {
+                html! { <p>{server.players.join(", ")}</p> }
+            }}
+        </>
+    }
+}
@@ -0,0 +1,259 @@
+use crate::components::server_list::{ServerList, ServerListProps};
+use crate::filter::IndexFilters;
+use crate::prefs::{parse_columns, ListColumn, ListState, SortKey, COLUMNS_COOKIE};
+use crate::tags::TagAliases;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{Element, FormData, HtmlDocument, HtmlFormElement, UrlSearchParams};
+use yew::prelude::*;
+
+/// localStorage keys shared with sort.js, so a visitor's saved sort and view carry over
+const STORAGE_KEY_SORT: &str = "factorio-browser-sort-v2";
+const STORAGE_KEY_VIEW: &str = "factorio-browser-view";
+
+/// The list as the page route would render it for `query`, from the servers the page was rendered with
+/// Tags aren't mapped through TAG_ALIASES here: links on the page always carry the canonical tag
+fn list_for_query(base: &ServerListProps, query: &str, columns_cookie: Option<&str>) -> ServerListProps {
+    let filters = IndexFilters::from_query(query);
+    let mut list_state = ListState::parse(
+        filters.sort.as_deref(),
+        filters.dir.as_deref(),
+        filters.view.as_deref(),
+        filters.columns.as_deref(),
+    )
+    .with_page(filters.page);
+    // The search only changes with a page load, so the server's search results still apply
+    let spec = filters
+        .spec(base.inactive_hidden_by_default, &TagAliases::default())
+        .with_search_from(&base.filters);
+    let mut servers = base.servers.clone();
+    list_state.sort_servers(&mut servers);
+    if filters.sort.is_none() {
+        spec.sort_by_relevance(&mut servers);
+    }
+    let columns = list_state
+        .columns
+        .clone()
+        .or_else(|| columns_cookie.map(parse_columns))
+        .unwrap_or_else(|| ListColumn::DEFAULT.to_vec());
+    list_state.columns = (columns != ListColumn::DEFAULT).then(|| columns.clone());
+
+    ServerListProps {
+        servers,
+        archived: base.archived.clone(),
+        error: base.error.clone(),
+        filters: spec,
+        inactive_hidden_by_default: base.inactive_hidden_by_default,
+        columns,
+        list_state,
+        page_query: with_param(query, "page", None),
+        group_by: filters.group_by.unwrap_or_default(),
+        favorites: base.favorites.clone(),
+        account: base.account.clone(),
+    }
+}
+
+/// `query` with every `name` parameter replaced by `value` (or just dropped)
+fn with_param(query: &str, name: &str, value: Option<&str>) -> String {
+    let mut pairs: Vec<String> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some(name))
+        .map(str::to_string)
+        .collect();
+    if let Some(value) = value {
+        pairs.push(format!("{}={}", name, urlencoding::encode(value)));
+    }
+    pairs.join("&")
+}
+
+/// Query string of a link back to the list ("/" or "/?..."), None for links to anywhere else
+fn list_link_query(href: &str) -> Option<&str> {
+    match href {
+        "/" => Some(""),
+        _ => href.strip_prefix("/?"),
+    }
+}
+
+fn current_query() -> String {
+    web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .map(|search| search.trim_start_matches('?').to_string())
+        .unwrap_or_default()
+}
+
+fn load_pref(key: &str) -> Option<String> {
+    web_sys::window()?.local_storage().ok()??.get_item(key).ok()?
+}
+
+fn save_pref(key: &str, value: &str) {
+    if let Some(storage) = web_sys::window().and_then(|w| w.local_storage().ok().flatten()) {
+        let _ = storage.set_item(key, value);
+    }
+}
+
+fn html_document() -> Option<HtmlDocument> {
+    web_sys::window()?.document()?.dyn_into::<HtmlDocument>().ok()
+}
+
+fn columns_cookie() -> Option<String> {
+    let cookies = html_document()?.cookie().ok()?;
+    cookies
+        .split(';')
+        .filter_map(|c| c.trim().split_once('='))
+        .find(|(name, _)| *name == COLUMNS_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+/// The filter form as the query a GET submit would send, leaving out empty fields
+fn form_query(form: &Element) -> Option<String> {
+    let form = form.clone().dyn_into::<HtmlFormElement>().ok()?;
+    let data = FormData::new_with_form(&form).ok()?;
+    let params = UrlSearchParams::new_with_str_sequence_sequence(&data).ok()?;
+    let query = String::from(params.to_string());
+    Some(query.split('&').filter(|pair| !pair.is_empty() && !pair.ends_with('=')).collect::<Vec<_>>().join("&"))
+}
+
+/// The server list after hydration: filter changes, sort and tag links, the pager, view and column
+/// toggles re-render in place and push the URL instead of loading the page again
+/// Server-rendered exactly like the plain list, so without the WASM bundle every link still works
+#[function_component(LiveList)]
+pub fn live_list(props: &ServerListProps) -> Html {
+    let list = use_state(|| props.clone());
+
+    let go = {
+        let props = props.clone();
+        let list = list.clone();
+        Callback::from(move |(query, push): (String, bool)| {
+            let Some(window) = web_sys::window() else { return };
+            let url = if query.is_empty() { "/".to_string() } else { format!("/?{}", query) };
+            let next = list_for_query(&props, &query, columns_cookie().as_deref());
+            // Archived servers matching a new search come from the database, so that takes a page load
+            let searched_offline = next.filters.include_offline != props.filters.include_offline && !next.filters.search.is_empty();
+            if next.filters.search != props.filters.search || searched_offline {
+                let _ = window.location().assign(&url);
+                return;
+            }
+            if push && let Ok(history) = window.history() {
+                let _ = history.push_state_with_url(&JsValue::NULL, "", Some(&url));
+            }
+            list.set(next);
+        })
+    };
+
+    {
+        let go = go.clone();
+        use_effect_with((), move |_| {
+            // Same preferences as sort.js: the URL wins, then what the visitor picked last
+            let query = current_query();
+            let filters = IndexFilters::from_query(&query);
+            let mut saved = query.clone();
+            if filters.sort.is_none()
+                && filters.dir.is_none()
+                && let Some((sort, dir)) = load_pref(STORAGE_KEY_SORT).as_deref().and_then(|s| s.split_once(':'))
+            {
+                let sort = SortKey::from_key(sort).filter(|s| *s != SortKey::default());
+                saved = with_param(&saved, "sort", sort.map(|s| s.key()));
+                saved = with_param(&saved, "dir", (dir == "asc").then_some("asc"));
+            }
+            if filters.view.is_none() && load_pref(STORAGE_KEY_VIEW).as_deref() == Some("list") {
+                saved = with_param(&saved, "view", Some("list"));
+            }
+            if saved != query {
+                let saved = with_param(&saved, "page", None);
+                if let Some(history) = web_sys::window().and_then(|w| w.history().ok()) {
+                    let url = if saved.is_empty() { "/".to_string() } else { format!("/?{}", saved) };
+                    let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+                }
+                go.emit((saved, false));
+            }
+
+            // Back and forward between list states
+            let window = web_sys::window();
+            let listener = Closure::<dyn Fn()>::new(move || go.emit((current_query(), false)));
+            if let Some(ref window) = window {
+                let _ = window.add_event_listener_with_callback("popstate", listener.as_ref().unchecked_ref());
+            }
+            move || {
+                if let Some(window) = window {
+                    let _ = window.remove_event_listener_with_callback("popstate", listener.as_ref().unchecked_ref());
+                }
+            }
+        });
+    }
+
+    let onclick = {
+        let go = go.clone();
+        Callback::from(move |e: MouseEvent| {
+            if e.button() != 0 || e.ctrl_key() || e.meta_key() || e.shift_key() || e.alt_key() {
+                return;
+            }
+            let Some(target) = e.target_dyn_into::<Element>() else { return };
+            if let Ok(Some(button)) = target.closest(".view-btn") {
+                let list_view = button.get_attribute("data-view").as_deref() == Some("list");
+                save_pref(STORAGE_KEY_VIEW, if list_view { "list" } else { "grid" });
+                go.emit((with_param(&current_query(), "view", list_view.then_some("list")), true));
+                return;
+            }
+            let Ok(Some(link)) = target.closest("a") else { return };
+            let Some(query) = link.get_attribute("href").and_then(|href| list_link_query(&href).map(str::to_string)) else {
+                return;
+            };
+            e.prevent_default();
+            if link.matches(".sort-button").unwrap_or(false) {
+                let filters = IndexFilters::from_query(&query);
+                save_pref(
+                    STORAGE_KEY_SORT,
+                    &format!("{}:{}", filters.sort.as_deref().unwrap_or(SortKey::default().key()), filters.dir.as_deref().unwrap_or("desc")),
+                );
+            }
+            go.emit((query, true));
+            if let Ok(Some(_)) = link.closest(".pager")
+                && let Some(root) = web_sys::window().and_then(|w| w.document()).and_then(|d| d.get_element_by_id("server-list"))
+            {
+                root.scroll_into_view();
+            }
+        })
+    };
+
+    let onchange = {
+        let go = go.clone();
+        Callback::from(move |e: Event| {
+            let Some(target) = e.target_dyn_into::<Element>() else { return };
+            if target.matches(".column-toggle").unwrap_or(false) {
+                let Some(document) = html_document() else { return };
+                let Ok(toggles) = document.query_selector_all(".column-toggle:checked") else { return };
+                let keys: Vec<String> = (0..toggles.length())
+                    .filter_map(|i| toggles.item(i)?.dyn_into::<Element>().ok()?.get_attribute("value"))
+                    .collect();
+                let keys = keys.join(",");
+                let _ = document.set_cookie(&format!("{}={}; path=/; max-age=31536000; SameSite=Lax", COLUMNS_COOKIE, keys));
+                go.emit((with_param(&current_query(), "columns", Some(&keys)), true));
+                return;
+            }
+            // The search box applies on submit, like without JavaScript
+            if target.get_attribute("type").as_deref() == Some("text") {
+                return;
+            }
+            if let Some(query) = target.closest("#filter-form").ok().flatten().and_then(|form| form_query(&form)) {
+                go.emit((query, true));
+            }
+        })
+    };
+
+    let onsubmit = Callback::from(move |e: SubmitEvent| {
+        let Some(form) = e.target_dyn_into::<Element>() else { return };
+        if form.id() != "filter-form" {
+            return;
+        }
+        if let Some(query) = form_query(&form) {
+            e.prevent_default();
+            go.emit((query, true));
+        }
+    });
+
+    html! {
+        <div class="live-list" {onclick} {onchange} {onsubmit}>
+            <ServerList ..(*list).clone() />
+        </div>
+    }
+}
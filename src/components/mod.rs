@@ -1,7 +1,25 @@
+pub mod account;
+#[cfg(feature = "server")]
+pub mod admin;
+pub mod archive;
 pub mod app;
+pub mod capacity_bar;
+pub mod changes;
+pub mod dashboard;
+pub mod digest;
 pub mod filters;
 pub mod footer;
+#[cfg(feature = "server")]
+pub mod lite;
+pub mod live_list;
+pub mod picture;
+pub mod player_search;
 pub mod server_card;
+#[cfg(feature = "server")]
 pub mod server_details;
 pub mod server_list;
+pub mod stats;
+pub mod summary_card;
+pub mod transparency;
+pub mod widgets;
 
@@ -0,0 +1,43 @@
+use yew::prelude::*;
+
+/// An image in several sizes and formats, as served by the image proxy
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResponsiveImage {
+    /// PNG at the display size, for browsers without srcset
+    pub src: String,
+    /// PNG for 1x and 2x screens
+    pub srcset: String,
+    pub avif_srcset: String,
+    pub webp_srcset: String,
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PictureProps {
+    pub image: ResponsiveImage,
+    #[prop_or_default]
+    pub alt: String,
+    /// Classes of the img element
+    #[prop_or_default]
+    pub class: Classes,
+    #[prop_or_default]
+    pub lazy: bool,
+}
+
+/// An image as AVIF or WebP where the browser supports them, PNG otherwise, at the screen's density
+#[function_component(Picture)]
+pub fn picture(props: &PictureProps) -> Html {
+    let image = &props.image;
+    html! {
+        <picture>
+            <source type="image/avif" srcset={image.avif_srcset.clone()} />
+            <source type="image/webp" srcset={image.webp_srcset.clone()} />
+            <img
+                src={image.src.clone()}
+                srcset={image.srcset.clone()}
+                alt={props.alt.clone()}
+                loading={if props.lazy { "lazy" } else { "eager" }}
+                class={props.class.clone()}
+            />
+        </picture>
+    }
+}
@@ -0,0 +1,90 @@
+use crate::players::{PlayerSearch, PlayerSighting, RECENT_PLAYER_HOURS};
+use crate::utils::format::format_age;
+use crate::utils::parse_rich_text;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct PlayerSearchProps {
+    /// None before a name has been entered
+    #[prop_or_default]
+    pub search: Option<PlayerSearch>,
+}
+
+/// "5 minutes ago" from an RFC 3339 timestamp
+fn seen_ago(last_seen: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(last_seen)
+        .map(|t| {
+            let minutes = (chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_minutes().max(0);
+            format!("{} ago", format_age(minutes as u64))
+        })
+        .unwrap_or_default()
+}
+
+fn sighting_row(sighting: &PlayerSighting, online: bool) -> Html {
+    html! {
+        <a href={format!("/server/{}", sighting.game_id)} class="flex items-center justify-between gap-4 py-3 px-4 bg-bg-dark rounded-sm no-underline text-inherit transition-colors duration-200 hover:bg-bg-elevated">
+            <span class="overflow-hidden text-ellipsis whitespace-nowrap">{parse_rich_text(&sighting.server_name)}</span>
+            {if online {
+                html! { <span class="flex-shrink-0 text-status-low text-sm font-mono">{"● online"}</span> }
+            } else {
+                html! { <span class="flex-shrink-0 text-text-muted text-sm font-mono">{seen_ago(&sighting.last_seen)}</span> }
+            }}
+        </a>
+    }
+}
+
+/// Player lookup page: which servers a player is on now or was on recently (SSR-compatible)
+#[function_component(PlayerSearchPage)]
+pub fn player_search_page(props: &PlayerSearchProps) -> Html {
+    let query = props.search.as_ref().map(|s| s.query.clone()).unwrap_or_default();
+
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[800px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <h2 class="text-2xl mb-4">{"Find a Player"}</h2>
+                    <form action="/players" method="get" class="flex gap-2">
+                        <input type="text" name="name" value={query} placeholder="Player name" maxlength="64" required=true
+                            class="flex-1 py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary" />
+                        <button type="submit" class="py-2 px-6 bg-btn-green border border-btn-green-dark rounded-sm text-bg-dark font-display text-[0.95rem] font-semibold cursor-pointer transition-all duration-200 hover:bg-btn-green-hover active:bg-btn-green-dark">{"Search"}</button>
+                    </form>
+                </header>
+
+                {match props.search {
+                    None => html! {},
+                    Some(ref search) if search.is_empty() => html! {
+                        <div class="p-8 text-center text-text-muted">
+                            <p>{format!("{} isn't on any listed server and hasn't been seen in the last {} hours.", search.query, RECENT_PLAYER_HOURS)}</p>
+                        </div>
+                    },
+                    Some(ref search) => html! {
+                        <div class="p-8 flex flex-col gap-6">
+                            {if !search.online.is_empty() {
+                                html! {
+                                    <section class="flex flex-col gap-2">
+                                        <h3 class="text-sm uppercase tracking-wider text-text-secondary">{format!("{} is playing on", search.query)}</h3>
+                                        {for search.online.iter().map(|s| sighting_row(s, true))}
+                                    </section>
+                                }
+                            } else {
+                                html! {}
+                            }}
+                            {if !search.recent.is_empty() {
+                                html! {
+                                    <section class="flex flex-col gap-2">
+                                        <h3 class="text-sm uppercase tracking-wider text-text-secondary">{format!("Recently seen (last {} hours)", RECENT_PLAYER_HOURS)}</h3>
+                                        {for search.recent.iter().map(|s| sighting_row(s, false))}
+                                    </section>
+                                }
+                            } else {
+                                html! {}
+                            }}
+                        </div>
+                    },
+                }}
+            </div>
+        </div>
+    }
+}
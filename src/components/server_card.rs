@@ -1,129 +1,226 @@
+use crate::components::capacity_bar::CapacityBar;
 use crate::db::models::CachedServer;
+use crate::geo::{continent_name, country_flag};
+use crate::prefs::ListColumn;
 use crate::utils::parse_rich_text;
+use crate::view::{ServerBadge, ServerView};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct ServerCardProps {
-    pub server: CachedServer,
+    pub server: ServerView,
+    /// Columns shown in the list row
+    #[prop_or_else(|| ListColumn::DEFAULT.to_vec())]
+    pub columns: Vec<ListColumn>,
+    /// Starred by the visitor
+    #[prop_or_default]
+    pub favorite: bool,
 }
 
-/// Individual server card component (SSR-compatible)
-#[function_component(ServerCard)]
-pub fn server_card(props: &ServerCardProps) -> Html {
-    let server = &props.server;
-    let player_ratio = if server.max_players > 0 {
-        (server.player_count as f32 / server.max_players as f32 * 100.0) as u32
-    } else {
-        0
+/// Star/unstar button (servers without a stable server_id can't be starred)
+fn favorite_toggle(view: &ServerView, favorite: bool) -> Html {
+    let Some(ref server_id) = view.server.server_id else {
+        // Keep list rows aligned with the header
+        return html! { <span class="w-8 flex-shrink-0"></span> };
     };
+    let (icon, title) = if favorite { ("⭐", "Remove from favorites") } else { ("☆", "Add to favorites") };
+
+    html! {
+        <form method="post" action={format!("/favorites/{}", urlencoding::encode(server_id))} class="w-8 flex-shrink-0 text-center">
+            <button type="submit" title={title} aria-label={title} aria-pressed={favorite.to_string()}
+                class="p-1 bg-transparent border-0 text-lg leading-none text-accent-primary cursor-pointer transition-transform duration-200 hover:scale-110">
+                {icon}
+            </button>
+        </form>
+    }
+}
 
-    let player_color_class = if player_ratio >= 80 {
-        "text-status-full"
-    } else if player_ratio >= 50 {
-        "text-status-medium"
-    } else if server.player_count > 0 {
-        "text-status-low"
-    } else {
-        "text-status-empty"
+/// Country flag from GeoIP, with the country and continent on hover
+fn country_badge(server: &CachedServer, class: &'static str) -> Html {
+    let Some(ref country) = server.country else {
+        return html! {};
     };
+    let Some(flag) = country_flag(country) else {
+        return html! {};
+    };
+    let title = match server.continent.as_deref().and_then(continent_name) {
+        Some(continent) => format!("{}, {}", country, continent),
+        None => country.clone(),
+    };
+
+    html! { <span class={class} title={title}>{flag}</span> }
+}
 
-    // Format game time (API returns minutes)
-    let hours = server.game_time_elapsed / 60;
-    let minutes = server.game_time_elapsed % 60;
-    let game_time = format!("{}h {}m", hours, minutes);
+/// Caution sign for servers whose host IP has many abuse reports or whose name imitates a verified server
+fn reported_badge(view: &ServerView, class: &'static str) -> Html {
+    let title = match (view.abuse_reports, &view.impostor_of) {
+        (_, Some(imitated)) => format!("{}: named like the verified server \"{}\"", ServerBadge::Impostor.label(), imitated.name),
+        (Some(reports), None) => format!("{}: {} abuse reports against this host", ServerBadge::Reported.label(), reports),
+        (None, None) => return html! {},
+    };
+
+    html! { <span class={classes!(class, "text-status-medium")} title={title}>{"⚠"}</span> }
+}
+
+/// Render one list-row cell for a column
+fn list_cell(view: &ServerView, column: ListColumn) -> Html {
+    let server = &view.server;
+    match column {
+        ListColumn::Players => html! {
+            <span class={classes!(column.cell_class(), "flex", "flex-col", "items-center", "gap-1")}>
+                <span class="text-accent-secondary font-medium">{&view.players_display}</span>
+                <CapacityBar ratio={view.player_ratio} status={view.status} unlimited={view.unlimited} label={view.players_display.clone()} height={classes!("h-1")} />
+            </span>
+        },
+        ListColumn::Version => html! {
+            <span class={classes!(column.cell_class(), "text-text-secondary", "text-sm")}>{&server.game_version}</span>
+        },
+        ListColumn::GameTime => html! {
+            <span class={classes!(column.cell_class(), "text-text-muted", "text-sm")}>{&view.game_time}</span>
+        },
+        ListColumn::Mods => html! {
+            <span class={classes!(column.cell_class(), "text-text-muted", "text-[0.85rem]")}>{&view.mods_display}</span>
+        },
+        ListColumn::Tags => html! {
+            <span class={classes!(column.cell_class(), "text-text-muted", "text-xs", "overflow-hidden", "text-ellipsis", "whitespace-nowrap")} title={view.display_tags.join(", ")}>
+                {view.display_tags.join(", ")}
+            </span>
+        },
+        ListColumn::Peak => html! {
+            <span class={classes!(column.cell_class(), "text-text-secondary", "text-sm", "font-mono")}>
+                {view.peak_players.map(|p| p.to_string()).unwrap_or_else(|| "—".to_string())}
+            </span>
+        },
+        ListColumn::Uptime => html! {
+            <span class={classes!(column.cell_class(), "text-text-secondary", "text-sm", "font-mono")}>
+                {view.uptime.map(|u| format!("{:.0}%", u)).unwrap_or_else(|| "—".to_string())}
+            </span>
+        },
+        ListColumn::Region => {
+            let continent = server.continent.as_deref().and_then(continent_name);
+            let flag = server.country.as_deref().and_then(country_flag);
+            let text = match (&flag, server.continent.as_deref()) {
+                (Some(flag), Some(code)) => format!("{} {}", flag, code.to_uppercase()),
+                (Some(flag), None) => flag.clone(),
+                (None, Some(code)) => code.to_uppercase(),
+                (None, None) => "—".to_string(),
+            };
+            html! {
+                <span class={classes!(column.cell_class(), "text-text-secondary", "text-sm")} title={continent}>{text}</span>
+            }
+        }
+        ListColumn::Latency => html! {
+            <span class={classes!(column.cell_class(), "latency-cell", "text-text-secondary", "text-sm", "font-mono")}
+                data-continent={server.continent.as_deref().map(str::to_uppercase)}>
+                {"—"}
+            </span>
+        },
+    }
+}
+
+/// Individual server card component (SSR-compatible)
+#[function_component(ServerCard)]
+pub fn server_card(props: &ServerCardProps) -> Html {
+    let view = &props.server;
+    let server = &view.server;
 
     // Link to server details page
     let details_url = format!("/server/{}", server.game_id);
 
-    let mods_display = if server.mod_count > 0 {
-        format!("{} mods", server.mod_count)
-    } else {
-        "Vanilla".to_string()
-    };
-
     html! {
-        <div class="server-item contents" data-players={server.player_count.to_string()} data-time={server.game_time_elapsed.to_string()} data-name={server.name.to_lowercase()}>
+        <div class="server-item contents" data-score={view.score.to_string()} data-players={server.player_count.to_string()} data-time={server.game_time_elapsed.to_string()} data-name={server.name.to_lowercase()}>
             // Card view
-            <a href={details_url.clone()} class="server-card block no-underline text-inherit bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-md p-6 cursor-pointer transition-all duration-200 hover:border-accent-primary hover:bg-bg-elevated">
-                <div class="flex items-start justify-between gap-2 mb-4">
-                    <h3 class="text-lg font-normal leading-tight break-words break-all">{parse_rich_text(&server.name)}</h3>
-                    {if server.has_password {
-                        html! { <span class="flex-shrink-0 text-base" title="Password Protected">{"🔒"}</span> }
-                    } else {
-                        html! {}
-                    }}
-                </div>
-                
-                <div class="flex flex-wrap gap-2 mb-4">
-                    <div class={classes!("flex", "items-center", "gap-1", "py-1", "px-2", "bg-bg-dark", "rounded-sm", "text-[0.85rem]", "font-mono", player_color_class)}>
-                        <span>{"👥"}</span>
-                        <span>{format!("{}/{}", server.player_count, server.max_players)}</span>
+            <div class="server-card relative">
+                <a href={details_url.clone()} class="block h-full no-underline text-inherit bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-md p-6 cursor-pointer transition-all duration-200 hover:border-accent-primary hover:bg-bg-elevated">
+                    <div class={classes!("flex", "items-start", "justify-between", "gap-2", "mb-4", server.server_id.is_some().then_some("pr-8"))}>
+                        <h3 class="text-lg font-normal leading-tight break-words break-all">{parse_rich_text(&server.name)}</h3>
+                        {country_badge(server, "flex-shrink-0 text-base")}
+                        {if server.has_password {
+                            html! { <span class="flex-shrink-0 text-base" title="Password Protected">{"🔒"}</span> }
+                        } else {
+                            html! {}
+                        }}
+                        {reported_badge(view, "flex-shrink-0 text-base")}
                     </div>
+                
+                    <div class="flex flex-wrap gap-2 mb-4">
+                        <div class={classes!("flex", "items-center", "gap-1", "py-1", "px-2", "bg-bg-dark", "rounded-sm", "text-[0.85rem]", "font-mono", view.status.text_class())}>
+                            <span>{"👥"}</span>
+                            <span>{&view.players_display}</span>
+                        </div>
                     
-                    <div class="flex items-center gap-1 py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono">
-                        <span>{"🎮"}</span>
-                        <span>{&server.game_version}</span>
-                    </div>
+                        <div class="flex items-center gap-1 py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono">
+                            <span>{"🎮"}</span>
+                            <span>{&server.game_version}</span>
+                        </div>
                     
-                    <div class="flex items-center gap-1 py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono">
-                        <span>{"⏱️"}</span>
-                        <span>{&game_time}</span>
-                    </div>
+                        <div class="flex items-center gap-1 py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono">
+                            <span>{"⏱️"}</span>
+                            <span>{&view.game_time}</span>
+                        </div>
                     
-                    {if server.mod_count > 0 {
+                        {if server.mod_count > 0 {
+                            html! {
+                                <div class="flex items-center gap-1 py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono">
+                                    <span>{"📦"}</span>
+                                    <span>{&view.mods_display}</span>
+                                </div>
+                            }
+                        } else {
+                            html! {
+                                <div class="py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono text-text-muted italic">
+                                    <span>{"Vanilla"}</span>
+                                </div>
+                            }
+                        }}
+                    </div>
+
+                    <div class="mb-4">
+                        <CapacityBar ratio={view.player_ratio} status={view.status} unlimited={view.unlimited} label={view.players_display.clone()} />
+                    </div>
+                
+                    {if !server.description.is_empty() {
                         html! {
-                            <div class="flex items-center gap-1 py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono">
-                                <span>{"📦"}</span>
-                                <span>{format!("{} mods", server.mod_count)}</span>
-                            </div>
+                            <p class="text-sm text-text-secondary mb-4 line-clamp-2">{parse_rich_text(&server.description)}</p>
                         }
                     } else {
+                        html! {}
+                    }}
+                
+                    {if !view.display_tags.is_empty() {
                         html! {
-                            <div class="py-1 px-2 bg-bg-dark rounded-sm text-[0.85rem] font-mono text-text-muted italic">
-                                <span>{"Vanilla"}</span>
+                            <div class="flex flex-wrap gap-1">
+                                {for view.display_tags.iter().take(5).map(|tag| {
+                                    html! { <span class="py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary">{tag}</span> }
+                                })}
                             </div>
                         }
-                    }}
-                </div>
-                
-                {if !server.description.is_empty() {
-                    html! {
-                        <p class="text-sm text-text-secondary mb-4 line-clamp-2">{parse_rich_text(&server.description)}</p>
-                    }
-                } else {
-                    html! {}
-                }}
-                
-                {if !server.tags.is_empty() {
-                    html! {
-                        <div class="flex flex-wrap gap-1">
-                            {for server.tags.iter().take(5).map(|tag| {
-                                html! { <span class="py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary">{parse_rich_text(tag)}</span> }
-                            })}
-                        </div>
-                    }
-                } else {
-                    html! {}
-                }}
-            </a>
-            
-            // List row view
-            <a href={details_url} class="server-row hidden flex-col sm:flex-row sm:items-center gap-2 sm:gap-4 py-2 px-4 bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-sm no-underline text-text-primary transition-all duration-200 hover:border-accent-primary hover:bg-bg-elevated">
-                <span class="flex-1 min-w-0 text-center sm:text-left overflow-hidden text-ellipsis whitespace-nowrap font-medium">
-                    {parse_rich_text(&server.name)}
-                    {if server.has_password {
-                        html! { <span class="ml-1 text-[0.85em]">{"🔒"}</span> }
                     } else {
                         html! {}
                     }}
-                </span>
-                <div class="flex sm:contents gap-4">
-                    <span class="w-[60px] text-center text-accent-secondary font-medium">{format!("{}/{}", server.player_count, server.max_players)}</span>
-                    <span class="w-[70px] text-center text-text-secondary text-sm">{&server.game_version}</span>
-                    <span class="w-[80px] text-center text-text-muted text-sm">{&game_time}</span>
-                    <span class="w-[80px] text-right text-text-muted text-[0.85rem]">{&mods_display}</span>
-                </div>
-            </a>
+                </a>
+                <div class="absolute top-5 right-5">{favorite_toggle(view, props.favorite)}</div>
+            </div>
+            
+            // List row view
+            <div class="server-row hidden items-center gap-2">
+                <a href={details_url} class="flex flex-1 min-w-0 flex-col sm:flex-row sm:items-center gap-2 sm:gap-4 py-2 px-4 bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-sm no-underline text-text-primary transition-all duration-200 hover:border-accent-primary hover:bg-bg-elevated">
+                    <span class="flex-1 min-w-0 text-center sm:text-left overflow-hidden text-ellipsis whitespace-nowrap font-medium">
+                        {parse_rich_text(&server.name)}
+                        {country_badge(server, "ml-1 text-[0.85em]")}
+                        {if server.has_password {
+                            html! { <span class="ml-1 text-[0.85em]">{"🔒"}</span> }
+                        } else {
+                            html! {}
+                        }}
+                        {reported_badge(view, "ml-1 text-[0.85em]")}
+                    </span>
+                    <div class="flex sm:contents gap-4">
+                        {for props.columns.iter().map(|col| list_cell(view, *col))}
+                    </div>
+                </a>
+                {favorite_toggle(view, props.favorite)}
+            </div>
         </div>
     }
 }
@@ -1,6 +1,10 @@
+use crate::api::mod_portal::PortalMod;
 use crate::components::footer::Footer;
-use crate::db::models::CachedServer;
-use crate::utils::parse_rich_text;
+use crate::components::picture::{Picture, ResponsiveImage};
+use crate::db::models::ReportReason;
+use crate::utils::format::{format_age, format_compact};
+use crate::utils::{extract_rules, parse_rich_text, steam_join_url};
+use crate::view::{ServerBadge, ServerView};
 use yew::prelude::*;
 
 /// Player count history entry for display
@@ -15,224 +19,632 @@ pub struct HistoryEntry {
 pub struct ModEntry {
     pub name: String,
     pub version: String,
+    /// Mod Portal details, when the portal knows the mod
+    pub portal: Option<PortalMod>,
+    /// The portal thumbnail through the image proxy
+    pub thumbnail: Option<ResponsiveImage>,
+}
+
+/// Sections of the server page; only the selected one is rendered
+/// Selected with `?tab=<key>`, and `#<key>` links are turned into that by sort.js
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DetailsTab {
+    /// Description, rules, headline numbers, tags and the join button
+    #[default]
+    Overview,
+    /// 24h player chart and the "good time to join" hint
+    Activity,
+    Mods,
+    Players,
+    /// Password, dedicated, slots, version and region
+    Settings,
+    /// First and last seen, the current map, downtime alerts and reporting
+    Events,
+}
+
+impl DetailsTab {
+    /// All tabs, in the order the tab bar shows them
+    pub const ALL: &'static [DetailsTab] = &[
+        DetailsTab::Overview,
+        DetailsTab::Activity,
+        DetailsTab::Mods,
+        DetailsTab::Players,
+        DetailsTab::Settings,
+        DetailsTab::Events,
+    ];
+
+    /// Identifier used in `?tab=` and as the panel's fragment
+    pub fn key(&self) -> &'static str {
+        match self {
+            DetailsTab::Overview => "overview",
+            DetailsTab::Activity => "activity",
+            DetailsTab::Mods => "mods",
+            DetailsTab::Players => "players",
+            DetailsTab::Settings => "settings",
+            DetailsTab::Events => "events",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|t| t.key() == key)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DetailsTab::Overview => "Overview",
+            DetailsTab::Activity => "Activity",
+            DetailsTab::Mods => "Mods",
+            DetailsTab::Players => "Players",
+            DetailsTab::Settings => "Settings",
+            DetailsTab::Events => "Events",
+        }
+    }
 }
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct ServerDetailsProps {
-    pub server: CachedServer,
+    pub server: ServerView,
+    /// Section to render; the others are only links in the tab bar
+    #[prop_or_default]
+    pub tab: DetailsTab,
     #[prop_or_default]
     pub history: Vec<HistoryEntry>,
     #[prop_or_default]
     pub players: Vec<String>,
     #[prop_or_default]
     pub mods: Vec<ModEntry>,
+    /// Machine-translated description, shown instead of the original when set
+    #[prop_or_default]
+    pub translated_description: Option<String>,
+    /// Whether a translation backend is configured
+    #[prop_or_default]
+    pub can_translate: bool,
+    /// "Good time to join" hint based on weekly activity
+    #[prop_or_default]
+    pub join_hint: Option<String>,
+    /// Whether operators can register downtime alerts
+    #[prop_or_default]
+    pub alerts_enabled: bool,
+    /// Result of the last alert registration ("added", "invalid", ...)
+    #[prop_or_default]
+    pub alert_status: Option<String>,
+    /// Whether visitors can report the server to the admins
+    #[prop_or_default]
+    pub reports_enabled: bool,
+    /// Result of the last report ("sent", "duplicate", ...)
+    #[prop_or_default]
+    pub report_status: Option<String>,
+    /// Set when live details couldn't be fetched and cached data is shown instead
+    #[prop_or_default]
+    pub details_error: Option<String>,
+    /// Fingerprint of the server's exact mod set
+    #[prop_or_default]
+    pub modpack: Option<String>,
+    /// Other listed servers running the same modpack
+    #[prop_or_default]
+    pub modpack_servers: usize,
 }
 
+const SECTION: &str = "p-6 px-8 border-b border-border-subtle";
+const SECTION_TITLE: &str = "text-[0.85rem] text-text-secondary uppercase tracking-wider mb-4";
+const EMPTY: &str = "p-6 px-8 border-b border-border-subtle text-sm text-text-muted";
+
 /// Detailed server view component (SSR-compatible, standalone page)
 #[function_component(ServerDetails)]
 pub fn server_details(props: &ServerDetailsProps) -> Html {
-    let server = &props.server;
-
-    // Format game time (API returns minutes)
-    let total_minutes = server.game_time_elapsed;
-    let days = total_minutes / (60 * 24);
-    let hours = (total_minutes % (60 * 24)) / 60;
-    let minutes = total_minutes % 60;
-
-    let game_time = if days > 0 {
-        format!("{}d {}h {}m", days, hours, minutes)
-    } else {
-        format!("{}h {}m", hours, minutes)
-    };
+    let view = &props.server;
+    let server = &view.server;
 
-    // Calculate history stats and aggregate into 24 hourly buckets
-    let (history_stats, hourly_data) = if !props.history.is_empty() {
-        let counts: Vec<usize> = props.history.iter().map(|h| h.player_count).collect();
-        let max = *counts.iter().max().unwrap_or(&0);
-        let min = *counts.iter().min().unwrap_or(&0);
-        let avg = counts.iter().sum::<usize>() / counts.len();
-        
-        // Aggregate into 24 hourly buckets (newest first in history)
-        // Each bucket represents ~60 data points (1 per minute for 1 hour)
-        let bucket_size = (props.history.len() / 24).max(1);
-        let hourly: Vec<usize> = props.history
-            .chunks(bucket_size)
-            .take(24)
-            .map(|chunk| {
-                // Average of the chunk
-                chunk.iter().map(|h| h.player_count).sum::<usize>() / chunk.len().max(1)
-            })
-            .collect();
-        
-        (Some((min, max, avg)), hourly)
-    } else {
-        (None, Vec::new())
+    let panel = match props.tab {
+        DetailsTab::Overview => overview(props),
+        DetailsTab::Activity => activity(props),
+        DetailsTab::Mods => mods(props),
+        DetailsTab::Players => players(props),
+        DetailsTab::Settings => settings(view),
+        DetailsTab::Events => events(props),
     };
 
     html! {
         <div class="min-h-screen py-8 px-6 max-w-[800px] mx-auto">
             <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
-            
+
             <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg max-w-[700px] w-full max-h-[90vh] overflow-y-auto relative animate-slide-up">
                 <header class="p-8 pb-6 border-b border-border-subtle">
                     <h2 class="text-2xl mb-2 pr-12 break-words break-all">{parse_rich_text(&server.name)}</h2>
-                    {if server.has_password {
-                        html! { <span class="inline-block py-1 px-2 rounded-sm text-[0.85rem] bg-status-full/15 text-status-full">{"🔒 Password Protected"}</span> }
+                    {if view.badges.contains(&ServerBadge::Password) {
+                        html! { <span class="inline-block py-1 px-2 rounded-sm text-[0.85rem] bg-status-full/15 text-status-full">{"🔒 "}{ServerBadge::Password.label()}</span> }
                     } else {
                         html! { <span class="inline-block py-1 px-2 rounded-sm text-[0.85rem] bg-status-low/15 text-status-low">{"🌐 Public"}</span> }
                     }}
+                    {if view.badges.contains(&ServerBadge::Dedicated) {
+                        html! { <span class="inline-block ml-2 py-1 px-2 rounded-sm text-[0.85rem] bg-accent-glow text-accent-secondary">{"🖥 "}{ServerBadge::Dedicated.label()}</span> }
+                    } else {
+                        html! {}
+                    }}
+                    {if let Some(ref imitated) = view.impostor_of {
+                        html! {
+                            <span class="inline-block ml-2 py-1 px-2 rounded-sm text-[0.85rem] bg-status-full/15 text-status-full" title="A new listing named like a long-established verified server, on a different host">
+                                {"⚠ "}{ServerBadge::Impostor.label()}{" of "}
+                                <a href={format!("/server/{}", imitated.game_id)} class="text-status-full underline">{&imitated.name}</a>
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if let Some(reports) = view.abuse_reports {
+                        html! {
+                            <span class="inline-block ml-2 py-1 px-2 rounded-sm text-[0.85rem] bg-status-medium/15 text-status-medium" title={format!("{} abuse reports against this server's IP in the last 90 days; it may be an impostor using a popular name", reports)}>
+                                {"⚠ "}{ServerBadge::Reported.label()}
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if let Some(ref error) = props.details_error {
+                        html! { <span class="inline-block ml-2 py-1 px-2 rounded-sm text-[0.85rem] bg-status-medium/15 text-status-medium" role="status">{"⚠ "}{error}</span> }
+                    } else {
+                        html! {}
+                    }}
                 </header>
-                
-                {if !server.description.is_empty() {
-                    html! {
-                        <section class="p-6 px-8 border-b border-border-subtle">
-                            <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider mb-4">{"Description"}</h3>
-                            <p class="text-text-primary leading-relaxed">{parse_rich_text(&server.description)}</p>
-                        </section>
-                    }
+
+                {tab_bar(props)}
+
+                <div id={props.tab.key()} role="tabpanel" aria-label={props.tab.label()}>
+                    {panel}
+                </div>
+
+                <div class="p-4 px-8 bg-bg-dark rounded-b-lg">
+                    <Footer />
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Links to the other sections; each one is a full page load rendering just that section
+fn tab_bar(props: &ServerDetailsProps) -> Html {
+    let server = &props.server.server;
+    let count = |tab: DetailsTab| match tab {
+        DetailsTab::Mods if server.mod_count > 0 => Some(server.mod_count as usize),
+        DetailsTab::Players if server.player_count > 0 => Some(server.player_count),
+        _ => None,
+    };
+
+    html! {
+        <nav class="details-tabs flex gap-1 px-6 border-b border-border-subtle overflow-x-auto" role="tablist" aria-label="Server sections">
+            {for DetailsTab::ALL.iter().map(|&tab| {
+                let href = match tab {
+                    DetailsTab::Overview => format!("/server/{}", server.game_id),
+                    _ => format!("/server/{}?tab={}", server.game_id, tab.key()),
+                };
+                let selected = tab == props.tab;
+                let class = if selected {
+                    "py-3 px-3 -mb-px border-b-2 border-accent-primary text-text-bright text-sm no-underline whitespace-nowrap"
                 } else {
-                    html! {}
-                }}
-                
-                <section class="p-6 px-8 border-b border-border-subtle grid grid-cols-2 gap-4 max-md:grid-cols-1">
-                    <div class="flex items-center gap-4 p-4 bg-bg-inset border border-border-subtle rounded-sm">
-                        <span class="text-2xl">{"👥"}</span>
-                        <div class="flex flex-col">
-                            <span class="text-lg font-semibold font-mono text-accent-primary">{format!("{}/{}", server.player_count, server.max_players)}</span>
-                            <span class="text-xs text-text-secondary">{"Players"}</span>
-                        </div>
-                    </div>
-                    
-                    <div class="flex items-center gap-4 p-4 bg-bg-inset border border-border-subtle rounded-sm">
-                        <span class="text-2xl">{"🎮"}</span>
-                        <div class="flex flex-col">
-                            <span class="text-lg font-semibold font-mono text-accent-primary">{&server.game_version}</span>
-                            <span class="text-xs text-text-secondary">{"Version"}</span>
-                        </div>
-                    </div>
-                    
-                    <div class="flex items-center gap-4 p-4 bg-bg-inset border border-border-subtle rounded-sm">
-                        <span class="text-2xl">{"⏱️"}</span>
-                        <div class="flex flex-col">
-                            <span class="text-lg font-semibold font-mono text-accent-primary">{game_time}</span>
-                            <span class="text-xs text-text-secondary">{"Game Time"}</span>
-                        </div>
-                    </div>
-                    
-                    <div class="flex items-center gap-4 p-4 bg-bg-inset border border-border-subtle rounded-sm">
-                        <span class="text-2xl">{"📦"}</span>
-                        <div class="flex flex-col">
-                            <span class="text-lg font-semibold font-mono text-accent-primary">{if server.mod_count > 0 { server.mod_count.to_string() } else { "Vanilla".to_string() }}</span>
-                            <span class="text-xs text-text-secondary">{"Mods"}</span>
+                    "py-3 px-3 -mb-px border-b-2 border-transparent text-text-secondary text-sm no-underline whitespace-nowrap transition-colors duration-200 hover:text-accent-primary"
+                };
+                html! {
+                    <a href={href} role="tab" aria-selected={selected.to_string()} class={class}>
+                        {tab.label()}
+                        {count(tab).map(|n| html! { <span class="ml-1 text-xs font-mono text-text-muted">{n}</span> }).unwrap_or_default()}
+                    </a>
+                }
+            })}
+        </nav>
+    }
+}
+
+fn overview(props: &ServerDetailsProps) -> Html {
+    let view = &props.server;
+    let server = &view.server;
+
+    let rules = extract_rules(&server.description);
+
+    html! {
+        <>
+            {if !server.description.is_empty() {
+                let details_url = format!("/server/{}", server.game_id);
+                html! {
+                    <section class={SECTION}>
+                        <div class="flex justify-between items-center mb-4">
+                            <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider">{"Description"}</h3>
+                            {if props.translated_description.is_some() {
+                                html! { <a href={details_url} class="text-xs text-accent-primary hover:text-accent-secondary transition-colors no-underline">{"Show original"}</a> }
+                            } else if props.can_translate {
+                                html! { <a href={format!("{}?translate=true", details_url)} class="text-xs text-accent-primary hover:text-accent-secondary transition-colors no-underline" rel="nofollow">{"Translate"}</a> }
+                            } else {
+                                html! {}
+                            }}
                         </div>
-                    </div>
-                </section>
-                
-                {if let Some((min, max, avg)) = history_stats {
-                    let chart_max = hourly_data.iter().max().copied().unwrap_or(1).max(1);
-                    html! {
-                        <section class="p-6 px-8 border-b border-border-subtle">
-                            <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider mb-4">{"Player Activity (Last 24h)"}</h3>
-                            <div class="flex gap-6 mb-6">
-                                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
-                                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{min}</span>
-                                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Min"}</span>
-                                </div>
-                                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
-                                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{avg}</span>
-                                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Avg"}</span>
-                                </div>
-                                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
-                                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{max}</span>
-                                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Max"}</span>
-                                </div>
-                            </div>
-                            <div class="flex items-end gap-0.5 h-20 p-2 bg-bg-inset rounded-md">
-                                {for hourly_data.iter().rev().map(|&count| {
-                                    let height = (count as f32 / chart_max as f32 * 100.0) as u32;
-                                    let height_style = format!("height: {}%", height.max(2));
-                                    html! {
-                                        <div class="history-bar" style={height_style} title={format!("{} players (avg)", count)}></div>
-                                    }
-                                })}
-                            </div>
-                        </section>
-                    }
+                        {if let Some(ref translated) = props.translated_description {
+                            html! {
+                                <>
+                                    <p class="text-text-primary leading-relaxed">{parse_rich_text(translated)}</p>
+                                    <p class="text-xs text-text-muted mt-2 italic">{"Machine translated"}</p>
+                                </>
+                            }
+                        } else {
+                            html! { <p class="text-text-primary leading-relaxed">{parse_rich_text(&server.description)}</p> }
+                        }}
+                    </section>
+                }
+            } else {
+                html! {}
+            }}
+
+            {if !rules.is_empty() {
+                html! {
+                    <section class={SECTION}>
+                        <h3 class={SECTION_TITLE}>{"Rules"}</h3>
+                        <ul class="list-disc pl-5 flex flex-col gap-1 text-text-primary leading-relaxed">
+                            {for rules.iter().map(|rule| {
+                                html! { <li>{parse_rich_text(rule)}</li> }
+                            })}
+                        </ul>
+                    </section>
+                }
+            } else {
+                html! {}
+            }}
+
+            <section class="p-6 px-8 border-b border-border-subtle grid grid-cols-2 gap-4 max-md:grid-cols-1">
+                {stat_tile("👥", view.players_display.clone(), if view.unlimited { "Players (unlimited slots)" } else { "Players" }, None)}
+                {stat_tile("🎮", server.game_version.clone(), "Version", None)}
+                {stat_tile("⏱️", view.game_time.clone(), "Game Time", None)}
+                {stat_tile("📦", if server.mod_count > 0 { server.mod_count.to_string() } else { "Vanilla".to_string() }, "Mods", None)}
+                {if let Some(ref listed_for) = view.listed_for {
+                    stat_tile("📅", listed_for.clone(), "Listed For", server.first_seen.clone())
                 } else {
                     html! {}
                 }}
-                
-                {if !props.players.is_empty() {
-                    html! {
-                        <section class="p-6 px-8 border-b border-border-subtle">
-                            <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider mb-4">{"Online Players"}</h3>
-                            <div class="flex flex-wrap gap-2">
-                                {for props.players.iter().map(|player| {
-                                    html! { <span class="py-1 px-2 bg-bg-dark border border-border-accent rounded-sm text-sm font-mono">{player}</span> }
-                                })}
+            </section>
+
+            {if !view.display_tags.is_empty() {
+                html! {
+                    <section class={SECTION}>
+                        <h3 class={SECTION_TITLE}>{"Tags"}</h3>
+                        <div class="flex flex-wrap gap-2">
+                            {for view.display_tags.iter().map(|tag| {
+                                html! { <span class="py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary">{tag}</span> }
+                            })}
+                        </div>
+                    </section>
+                }
+            } else {
+                html! {}
+            }}
+
+            {if let Some(ref addr) = server.host_address {
+                html! {
+                    <section class={SECTION}>
+                        <h3 class={SECTION_TITLE}>{"Connection"}</h3>
+                        <div class="flex items-center gap-4">
+                            <code class="flex-1 p-4 bg-bg-dark rounded-sm font-mono text-sm text-accent-primary break-all">{addr}</code>
+                            <a href={steam_join_url(addr)} class="py-2 px-6 bg-btn-green border border-btn-green-dark rounded-sm text-bg-dark font-display text-[0.95rem] font-semibold cursor-pointer transition-all duration-200 hover:bg-btn-green-hover active:bg-btn-green-dark no-underline">
+                                {"Join"}
+                            </a>
+                        </div>
+                        <details class="mt-4">
+                            <summary class="cursor-pointer text-sm text-text-secondary hover:text-text-bright">{"QR code"}</summary>
+                            <div class="flex items-center gap-4 mt-3">
+                                <img src={format!("/server/{}/qr.svg", server.game_id)} width="160" height="160" loading="lazy"
+                                    alt="QR code of the Steam join link" class="rounded-sm" />
+                                <p class="text-sm text-text-muted">{"Scan to open the Steam join link on another device, or type the address into Multiplayer → Connect to address."}</p>
                             </div>
-                        </section>
-                    }
-                } else {
-                    html! {}
-                }}
-                
-                {if !props.mods.is_empty() {
+                        </details>
+                    </section>
+                }
+            } else {
+                html! {}
+            }}
+
+            <p class="p-4 px-8 border-b border-border-subtle text-sm">
+                <a href={format!("/server/{}/card", server.game_id)} class="text-accent-primary no-underline transition-colors duration-200 hover:text-accent-secondary">{"🖨 Summary card"}</a>
+                <span class="text-text-muted">{" for screenshots, printing and announcements"}</span>
+            </p>
+        </>
+    }
+}
+
+fn stat_tile(icon: &'static str, value: String, label: &'static str, title: Option<String>) -> Html {
+    html! {
+        <div class="flex items-center gap-4 p-4 bg-bg-inset border border-border-subtle rounded-sm" title={title}>
+            <span class="text-2xl">{icon}</span>
+            <div class="flex flex-col">
+                <span class="text-lg font-semibold font-mono text-accent-primary">{value}</span>
+                <span class="text-xs text-text-secondary">{label}</span>
+            </div>
+        </div>
+    }
+}
+
+fn activity(props: &ServerDetailsProps) -> Html {
+    // History arrives pre-aggregated into chart buckets, newest first
+    if props.history.is_empty() {
+        return html! { <p class={EMPTY}>{"No player activity recorded in the last 24 hours."}</p> };
+    }
+    let counts: Vec<usize> = props.history.iter().map(|h| h.player_count).collect();
+    let max = *counts.iter().max().unwrap_or(&0);
+    let min = *counts.iter().min().unwrap_or(&0);
+    let avg = counts.iter().sum::<usize>() / counts.len();
+    let chart_max = max.max(1);
+
+    html! {
+        <section class={SECTION}>
+            <h3 class={SECTION_TITLE}>{"Player Activity (Last 24h)"}</h3>
+            {if let Some(ref hint) = props.join_hint {
+                html! { <p class="text-sm text-accent-secondary mb-4">{"🕒 "}{hint}</p> }
+            } else {
+                html! {}
+            }}
+            <div class="flex gap-6 mb-6">
+                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{min}</span>
+                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Min"}</span>
+                </div>
+                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{avg}</span>
+                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Avg"}</span>
+                </div>
+                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{max}</span>
+                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Max"}</span>
+                </div>
+            </div>
+            <div class="flex items-end gap-0.5 h-20 p-2 bg-bg-inset rounded-md">
+                {for counts.iter().rev().map(|&count| {
+                    let height = (count as f32 / chart_max as f32 * 100.0) as u32;
+                    let height_style = format!("height: {}%", height.max(2));
                     html! {
-                        <section class="p-6 px-8 border-b border-border-subtle">
-                            <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider mb-4">{"Mods"}</h3>
-                            <div class="mods-list grid grid-cols-[repeat(auto-fill,minmax(250px,1fr))] gap-2 max-h-[400px] overflow-y-auto">
-                                {for props.mods.iter().map(|m| {
-                                    let mod_url = format!("https://mods.factorio.com/mod/{}", m.name);
-                                    html! { 
-                                        <a href={mod_url} class="flex justify-between items-center py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-[0.85rem] no-underline transition-all duration-200 hover:border-accent-primary hover:bg-bg-card" target="_blank" rel="noopener noreferrer">
-                                            <span class="text-accent-primary overflow-hidden text-ellipsis whitespace-nowrap hover:text-accent-secondary">{&m.name}</span>
-                                            <span class="text-text-muted font-mono text-xs ml-2 flex-shrink-0">{&m.version}</span>
-                                        </a>
-                                    }
-                                })}
-                            </div>
-                        </section>
+                        <div class="history-bar" style={height_style} title={format!("{} players (avg)", count)}></div>
                     }
-                } else {
-                    html! {}
-                }}
-                
-                {if !server.tags.is_empty() {
-                    html! {
-                        <section class="p-6 px-8 border-b border-border-subtle">
-                            <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider mb-4">{"Tags"}</h3>
-                            <div class="flex flex-wrap gap-2">
-                                {for server.tags.iter().map(|tag| {
-                                    html! { <span class="py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary">{parse_rich_text(tag)}</span> }
-                                })}
-                            </div>
-                        </section>
+                })}
+            </div>
+        </section>
+    }
+}
+
+fn players(props: &ServerDetailsProps) -> Html {
+    if props.players.is_empty() {
+        return html! { <p class={EMPTY}>{"Nobody is playing right now."}</p> };
+    }
+
+    html! {
+        <section class={SECTION}>
+            <h3 class={SECTION_TITLE}>{"Online Players"}</h3>
+            <div class="flex flex-wrap gap-2">
+                {for props.players.iter().map(|player| {
+                    html! { <span class="py-1 px-2 bg-bg-dark border border-border-accent rounded-sm text-sm font-mono">{player}</span> }
+                })}
+            </div>
+        </section>
+    }
+}
+
+fn mods(props: &ServerDetailsProps) -> Html {
+    if props.mods.is_empty() {
+        let message = if props.server.server.mod_count > 0 {
+            "The mod list couldn't be loaded right now."
+        } else {
+            "This server runs vanilla Factorio."
+        };
+        return html! { <p class={EMPTY}>{message}</p> };
+    }
+
+    html! {
+        <section class={SECTION}>
+            <div class="flex justify-between items-baseline gap-4 mb-4">
+                <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider">{"Mods"}</h3>
+                {match props.modpack {
+                    Some(ref modpack) if props.modpack_servers > 0 => {
+                        let noun = if props.modpack_servers == 1 { "server runs" } else { "servers run" };
+                        html! {
+                            <a href={format!("/?version=all&show_inactive=true&modpack={}", modpack)} class="text-sm text-accent-primary no-underline transition-colors duration-200 hover:text-accent-secondary">
+                                {format!("{} other {} this exact modpack", props.modpack_servers, noun)}
+                            </a>
+                        }
                     }
-                } else {
-                    html! {}
+                    _ => html! {},
                 }}
-                
-                {if let Some(ref addr) = server.host_address {
-                    let join_url = format!("steam://run/427520//--mp-connect%20{}", addr);
-                    html! {
-                        <section class="p-6 px-8 border-b border-border-subtle">
-                            <h3 class="text-[0.85rem] text-text-secondary uppercase tracking-wider mb-4">{"Connection"}</h3>
-                            <div class="flex items-center gap-4">
-                                <code class="flex-1 p-4 bg-bg-dark rounded-sm font-mono text-sm text-accent-primary break-all">{addr}</code>
-                                <a href={join_url} class="py-2 px-6 bg-btn-green border border-btn-green-dark rounded-sm text-bg-dark font-display text-[0.95rem] font-semibold cursor-pointer transition-all duration-200 hover:bg-btn-green-hover active:bg-btn-green-dark no-underline">
-                                    {"Join"}
-                                </a>
-                            </div>
-                        </section>
+            </div>
+            <div class="mods-list grid grid-cols-[repeat(auto-fill,minmax(250px,1fr))] gap-2 max-h-[400px] overflow-y-auto">
+                {for props.mods.iter().map(|m| {
+                    let mod_url = format!("https://mods.factorio.com/mod/{}", urlencoding::encode(&m.name));
+                    match m.portal {
+                        Some(ref portal) => html! {
+                            <a href={mod_url} class="flex items-center gap-2 py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-[0.85rem] no-underline transition-all duration-200 hover:border-accent-primary hover:bg-bg-card" target="_blank" rel="noopener noreferrer" title={m.name.clone()}>
+                                {if let Some(ref thumbnail) = m.thumbnail {
+                                    html! { <Picture image={thumbnail.clone()} lazy=true class="w-8 h-8 rounded-sm object-cover flex-shrink-0" /> }
+                                } else {
+                                    html! { <span class="w-8 h-8 rounded-sm bg-bg-card flex-shrink-0"></span> }
+                                }}
+                                <span class="flex flex-col min-w-0 flex-1">
+                                    <span class="text-accent-primary overflow-hidden text-ellipsis whitespace-nowrap hover:text-accent-secondary">{&portal.title}</span>
+                                    <span class="text-text-muted text-xs overflow-hidden text-ellipsis whitespace-nowrap">
+                                        {format!("{} downloads", format_compact(portal.downloads))}
+                                        {portal.category.as_ref().map(|c| format!(" · {}", c.replace('-', " "))).unwrap_or_default()}
+                                    </span>
+                                </span>
+                                <span class="text-text-muted font-mono text-xs ml-2 flex-shrink-0">{&m.version}</span>
+                            </a>
+                        },
+                        None => html! {
+                            <a href={mod_url} class="flex justify-between items-center py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-[0.85rem] no-underline transition-all duration-200 hover:border-accent-primary hover:bg-bg-card" target="_blank" rel="noopener noreferrer">
+                                <span class="text-accent-primary overflow-hidden text-ellipsis whitespace-nowrap hover:text-accent-secondary">{&m.name}</span>
+                                <span class="text-text-muted font-mono text-xs ml-2 flex-shrink-0">{&m.version}</span>
+                            </a>
+                        },
                     }
-                } else {
-                    html! {}
-                }}
-                <div class="p-4 px-8 bg-bg-dark rounded-b-lg">
-                    <Footer />
-                </div>
+                })}
             </div>
-        </div>
+        </section>
+    }
+}
+
+/// How the server is configured, as reported by the listing
+fn settings(view: &ServerView) -> Html {
+    let server = &view.server;
+    let yes_no = |value: bool| if value { "Yes" } else { "No" }.to_string();
+    let mut rows = vec![
+        ("Password", yes_no(server.has_password)),
+        ("Dedicated server", yes_no(server.headless_server)),
+        ("Player slots", if view.unlimited { "Unlimited".to_string() } else { server.max_players.to_string() }),
+        ("Game version", format!("{} (build {})", server.game_version, server.build_version)),
+        ("Mods", if server.mod_count > 0 { server.mod_count.to_string() } else { "None (vanilla)".to_string() }),
+    ];
+    if let Some(ref build_mode) = server.build_mode {
+        rows.push(("Build mode", build_mode.clone()));
+    }
+    if let Some(ref platform) = server.platform {
+        rows.push(("Platform", platform.clone()));
+    }
+    if let Some(ref country) = server.country {
+        let region = match server.continent {
+            Some(ref continent) => format!("{} ({})", country, continent),
+            None => country.clone(),
+        };
+        rows.push(("Region", region));
+    }
+
+    html! {
+        <section class={SECTION}>
+            <h3 class={SECTION_TITLE}>{"Server Settings"}</h3>
+            <dl class="grid grid-cols-[auto_1fr] gap-x-6 gap-y-2 text-sm">
+                {for rows.into_iter().map(|(label, value)| html! {
+                    <>
+                        <dt class="text-text-secondary">{label}</dt>
+                        <dd class="text-text-primary font-mono">{value}</dd>
+                    </>
+                })}
+            </dl>
+        </section>
+    }
+}
+
+/// Minutes since an RFC 3339 timestamp, as "3 hours ago"
+fn ago(timestamp: &str) -> Option<String> {
+    let then = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    let minutes = (chrono::Utc::now() - then.with_timezone(&chrono::Utc)).num_minutes();
+    Some(format!("{} ago", format_age(minutes.max(0) as u64)))
+}
+
+/// What's known about the server's lifetime, downtime alert registration and reporting
+fn events(props: &ServerDetailsProps) -> Html {
+    let server = &props.server.server;
+    let mut timeline: Vec<(&'static str, &'static str, String, Option<String>)> = Vec::new();
+    if let Some(ref first_seen) = server.first_seen
+        && let Some(when) = ago(first_seen)
+    {
+        timeline.push(("📅", "First listed", when, Some(first_seen.clone())));
+    }
+    timeline.push(("🗺", "Current map running for", props.server.game_time.clone(), None));
+    if let Some(ref last_seen) = server.last_seen
+        && let Some(when) = ago(last_seen)
+    {
+        timeline.push(("👁", "Last seen in the listing", when, Some(last_seen.clone())));
+    }
+
+    html! {
+        <>
+            <section class={SECTION}>
+                <h3 class={SECTION_TITLE}>{"Events"}</h3>
+                <ul class="flex flex-col gap-2 text-sm">
+                    {for timeline.into_iter().map(|(icon, label, when, title)| html! {
+                        <li class="flex items-center gap-3" title={title}>
+                            <span>{icon}</span>
+                            <span class="text-text-secondary">{label}</span>
+                            <span class="text-text-primary font-mono">{when}</span>
+                        </li>
+                    })}
+                </ul>
+            </section>
+            {if props.alerts_enabled {
+                alerts_form(props)
+            } else {
+                html! {}
+            }}
+            {if props.reports_enabled {
+                report_form(props)
+            } else {
+                html! {}
+            }}
+        </>
+    }
+}
+
+fn report_form(props: &ServerDetailsProps) -> Html {
+    let action = format!("/server/{}/report", props.server.server.game_id);
+    let status = match props.report_status.as_deref() {
+        Some("sent") => Some(("text-status-low", "Thanks, the report was sent to the admins of this site.")),
+        Some("duplicate") => Some(("text-status-medium", "You've already reported this server; the admins will look at it.")),
+        Some("invalid") => Some(("text-status-full", "Please pick a reason for the report.")),
+        Some("too_many") => Some(("text-status-full", "You've sent several reports in the last hour. Please try again later.")),
+        Some("busy") => Some(("text-status-full", "Lots of reports are coming in right now. Please try again in a minute.")),
+        Some("disabled") => Some(("text-status-full", "Reporting is disabled on this instance.")),
+        Some("error") => Some(("text-status-full", "Failed to send the report. Please try again later.")),
+        _ => None,
+    };
+    let input = "py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary";
+
+    html! {
+        <section class={SECTION}>
+            <h3 class={SECTION_TITLE}>{"Report This Server"}</h3>
+            <p class="text-sm text-text-secondary mb-4">{"Spam, offensive content, or pretending to be another server? Let the admins of this site know."}</p>
+            <form method="post" action={action} class="flex flex-wrap items-center gap-4">
+                <select name="reason" required=true class={input}>
+                    {for ReportReason::ALL.iter().map(|reason| html! {
+                        <option value={reason.key()}>{reason.label()}</option>
+                    })}
+                </select>
+                <input type="text" name="details" maxlength="500" placeholder="Details (optional)" class={classes!(input, "flex-1", "min-w-[240px]")} />
+                <button type="submit" class="py-2 px-6 bg-transparent border border-border-subtle rounded-sm text-text-secondary font-display text-[0.95rem] cursor-pointer transition-colors duration-200 hover:text-text-primary hover:border-accent-primary">
+                    {"Report"}
+                </button>
+            </form>
+            {if let Some((class, message)) = status {
+                html! { <p class={classes!("text-sm", "mt-2", class)}>{message}</p> }
+            } else {
+                html! {}
+            }}
+        </section>
+    }
+}
+
+fn alerts_form(props: &ServerDetailsProps) -> Html {
+    let action = format!("/server/{}/alerts", props.server.server.game_id);
+    let status = match props.alert_status.as_deref() {
+        Some("pending") => Some(("text-status-medium", "Almost there: the webhook's channel got a code to add to this server's description. Alerts start once the listing shows it.")),
+        Some("added") => Some(("text-status-low", "This webhook already gets alerts for this server.")),
+        Some("invalid") => Some(("text-status-full", "Please enter a valid Discord webhook URL.")),
+        Some("unreachable") => Some(("text-status-full", "Couldn't post to that webhook. Please check the URL.")),
+        Some("unidentified") => Some(("text-status-full", "This server doesn't report a stable ID, so alerts can't follow it across restarts.")),
+        Some("busy") => Some(("text-status-full", "Lots of alerts are being registered right now. Please try again in a minute.")),
+        Some("disabled") => Some(("text-status-full", "Downtime alerts are disabled on this instance.")),
+        Some("error") => Some(("text-status-full", "Failed to register alert. Please try again later.")),
+        _ => None,
+    };
+
+    html! {
+        <section class={SECTION}>
+            <h3 class={SECTION_TITLE}>{"Downtime Alerts"}</h3>
+            <p class="text-sm text-text-secondary mb-4">{"Run this server? Get a Discord message if it disappears from the listing or has 0 players for more than 30 minutes. You'll be sent a code to put in the server's description, to show it's yours."}</p>
+            <form method="post" action={action} class="flex items-center gap-4">
+                <input
+                    type="url"
+                    name="webhook_url"
+                    required=true
+                    placeholder="https://discord.com/api/webhooks/..."
+                    class="flex-1 py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-[0.95rem] transition-colors duration-200 focus:outline-none focus:border-accent-primary"
+                />
+                <button type="submit" class="py-2 px-6 bg-btn-green border border-btn-green-dark rounded-sm text-bg-dark font-display text-[0.95rem] font-semibold cursor-pointer transition-all duration-200 hover:bg-btn-green-hover active:bg-btn-green-dark">
+                    {"Notify Me"}
+                </button>
+            </form>
+            {if let Some((class, message)) = status {
+                html! { <p class={classes!("text-sm", "mt-2", class)}>{message}</p> }
+            } else {
+                html! {}
+            }}
+        </section>
     }
 }
@@ -1,116 +1,322 @@
+use crate::accounts::AccountNav;
 use crate::components::filters::Filters;
 use crate::components::server_card::ServerCard;
-use crate::db::models::CachedServer;
-use semver::Version;
+use crate::db::models::ServerProfile;
+use crate::filter::{version_families, versions_newest_first, FilterSpec, TagMode};
+use crate::geo::{continent_name, rtt_table, CONTINENTS};
+use crate::impostor::find_successor;
+use crate::ids::ServerId;
+use crate::prefs::{ListColumn, ListState, SortKey, PAGE_SIZE};
+use crate::utils::parse_rich_text;
+use crate::utils::version::GameVersion;
+use crate::view::ServerView;
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use yew::prelude::*;
 
-#[derive(Properties, PartialEq, Clone)]
+/// Also the hydration payload: the page embeds these props as JSON for the WASM bundle
+#[derive(Properties, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ServerListProps {
-    pub servers: Vec<CachedServer>,
+    pub servers: Vec<ServerView>,
+    /// Archived servers matching the search (no longer listed, kept for reference)
+    #[prop_or_default]
+    pub archived: Vec<ServerProfile>,
     #[prop_or_default]
     pub error: Option<String>,
+    /// Filters parsed from the request
+    #[prop_or_default]
+    pub filters: FilterSpec,
+    /// Whether inactive servers are hidden when the request doesn't say
     #[prop_or_default]
-    pub current_search: String,
+    pub inactive_hidden_by_default: bool,
+    /// Visible list-view columns (empty = defaults)
     #[prop_or_default]
-    pub current_version: String,
+    pub columns: Vec<ListColumn>,
+    /// Sort and layout from the URL (servers arrive already sorted)
     #[prop_or_default]
-    pub has_players: bool,
+    pub list_state: ListState,
+    /// The request's query string without `page`, which the pager links extend
     #[prop_or_default]
-    pub no_password: bool,
+    pub page_query: String,
+    /// Section the grid by this key ("version", "mods", "region" or "modpack"; empty = no grouping)
     #[prop_or_default]
-    pub is_dedicated: bool,
+    pub group_by: String,
+    /// server_ids the visitor has starred
     #[prop_or_default]
-    pub selected_tags: String, // Comma-separated list of selected tags
+    pub favorites: Vec<ServerId>,
+    /// Sign-in state and saved presets (None when accounts are disabled)
+    #[prop_or_default]
+    pub account: Option<AccountNav>,
 }
 
-/// Server list component with filtering (SSR-compatible)
-#[function_component(ServerList)]
-pub fn server_list(props: &ServerListProps) -> Html {
-    // Extract unique versions from servers, sorted by semver (descending)
-    let mut versions: Vec<String> = props
-        .servers
-        .iter()
-        .map(|s| s.game_version.clone())
-        .collect();
-    versions.sort_by(|a, b| {
-        let va = Version::parse(a).ok();
-        let vb = Version::parse(b).ok();
-        vb.cmp(&va) // Descending order
-    });
-    versions.dedup();
+/// Ways to split the server grid into collapsible sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// Major.minor game version, newest first
+    Version,
+    /// Vanilla vs modded
+    Mods,
+    /// Continent of the host (needs GEOIP_DB), biggest first
+    Region,
+    /// Servers running the exact same mod set, biggest first; modpacks only one server runs share a section
+    Modpack,
+}
 
-    // Latest version is first after sorting
-    let latest_version = versions.first().cloned().unwrap_or_default();
+/// Section for servers whose host couldn't be placed
+const UNKNOWN_REGION: &str = "Unknown region";
 
-    // Determine effective version filter (empty = latest, "all" = no filter)
-    let effective_version = if props.current_version.is_empty() {
-        &latest_version
-    } else if props.current_version == "all" {
-        ""
-    } else {
-        &props.current_version
-    };
+/// Section for modded servers whose mods haven't been looked up yet
+const MODS_NOT_LOOKED_UP: &str = "Modded (mods not looked up yet)";
 
-    // Parse selected tags from comma-separated string
-    let selected_tags: Vec<String> = if props.selected_tags.is_empty() {
-        Vec::new()
-    } else {
-        props.selected_tags
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect()
-    };
+/// Section for modpacks no other listed server runs
+const OTHER_MODPACKS: &str = "Other modpacks";
 
-    // Helper closure to check if a server passes non-tag filters
-    let passes_non_tag_filters = |s: &CachedServer| {
-        // Search filter
-        if !props.current_search.is_empty() {
-            let search_lower = props.current_search.to_lowercase();
-            let name_matches = s.name.to_lowercase().contains(&search_lower);
-            let desc_matches = s.description.to_lowercase().contains(&search_lower);
-            let tags_match = s.tags.iter().any(|t| t.to_lowercase().contains(&search_lower));
-            if !name_matches && !desc_matches && !tags_match {
-                return false;
-            }
+impl GroupBy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "version" => Some(GroupBy::Version),
+            "mods" => Some(GroupBy::Mods),
+            "region" => Some(GroupBy::Region),
+            "modpack" => Some(GroupBy::Modpack),
+            _ => None,
         }
+    }
 
-        // Version filter
-        if !effective_version.is_empty() && !s.game_version.starts_with(effective_version) {
-            return false;
+    /// Section label for a server
+    fn group_key(&self, view: &ServerView) -> String {
+        match self {
+            GroupBy::Version => GameVersion::parse(&view.server.game_version).series(),
+            GroupBy::Mods => {
+                if view.server.mod_count > 0 {
+                    "Modded".to_string()
+                } else {
+                    "Vanilla".to_string()
+                }
+            }
+            GroupBy::Region => view
+                .server
+                .continent
+                .as_deref()
+                .and_then(continent_name)
+                .unwrap_or(UNKNOWN_REGION)
+                .to_string(),
+            GroupBy::Modpack => match view.modpack {
+                _ if view.server.mod_count == 0 => "Vanilla".to_string(),
+                Some(ref fingerprint) => format!(
+                    "Modpack {} ({} mods)",
+                    fingerprint.get(..8).unwrap_or(fingerprint),
+                    view.server.mod_count
+                ),
+                None => MODS_NOT_LOOKED_UP.to_string(),
+            },
         }
+    }
+}
 
-        // Has players filter
-        if props.has_players && s.player_count == 0 {
-            return false;
+/// Split servers into labelled groups, keeping each group's input order
+fn group_servers<'a>(servers: &[&'a ServerView], group_by: GroupBy) -> Vec<(String, Vec<&'a ServerView>)> {
+    let mut groups: Vec<(String, Vec<&ServerView>)> = Vec::new();
+    for server in servers {
+        let key = group_by.group_key(server);
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push(server),
+            None => groups.push((key, vec![server])),
         }
+    }
 
-        // No password filter
-        if props.no_password && s.has_password {
-            return false;
+    if group_by == GroupBy::Modpack {
+        let (unique, shared): (Vec<_>, Vec<_>) = groups
+            .into_iter()
+            .partition(|(key, members)| members.len() == 1 && key.starts_with("Modpack "));
+        groups = shared;
+        // Groups are in order of their first server, so these stay in input order
+        let others: Vec<&ServerView> = unique.into_iter().flat_map(|(_, members)| members).collect();
+        if !others.is_empty() {
+            groups.push((OTHER_MODPACKS.to_string(), others));
         }
+    }
 
-        // Dedicated server filter
-        if props.is_dedicated && !s.headless_server {
-            return false;
-        }
+    match group_by {
+        GroupBy::Version => groups.sort_by_cached_key(|(series, _)| std::cmp::Reverse(GameVersion::parse(series))),
+        GroupBy::Mods => groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0))),
+        // Catch-all sections go last
+        GroupBy::Region | GroupBy::Modpack => groups.sort_by(|a, b| {
+            let catch_all = |key: &str| [UNKNOWN_REGION, MODS_NOT_LOOKED_UP, OTHER_MODPACKS].contains(&key);
+            catch_all(&a.0)
+                .cmp(&catch_all(&b.0))
+                .then_with(|| b.1.len().cmp(&a.1.len()))
+                .then_with(|| a.0.cmp(&b.0))
+        }),
+    }
+    groups
+}
+
+/// Grid of server cards with the list-view header
+pub(crate) fn server_grid(servers: &[&ServerView], columns: &[ListColumn], favorites: &[ServerId], list_view: bool) -> Html {
+    html! {
+        <div class={classes!("server-grid", "grid", "grid-cols-[repeat(auto-fill,minmax(320px,1fr))]", "gap-6", list_view.then_some("list-view"))}>
+            <div class="list-header hidden items-center gap-4 py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm sticky top-0 z-10 text-xs font-semibold uppercase tracking-widest text-text-secondary">
+                <span class="hidden sm:flex sm:flex-1 min-w-0">{"Name"}</span>
+                {for columns.iter().map(|col| html! {
+                    // The latency estimate is computed in the browser from this table
+                    <span class={col.cell_class()} data-rtt={(*col == ListColumn::Latency).then(rtt_table)}>{col.label()}</span>
+                })}
+                <span class="w-8 flex-shrink-0"></span>
+            </div>
+            {for servers.iter().map(|server| {
+                let favorite = server.server.server_id.as_ref().is_some_and(|id| favorites.contains(id));
+                html! {
+                    <ServerCard 
+                        server={(*server).clone()} 
+                        columns={columns.to_vec()}
+                        favorite={favorite}
+                    />
+                }
+            })}
+        </div>
+    }
+}
+
+/// Link to one page of the list (`page` counts from 1 in the URL)
+fn page_url(page_query: &str, page: usize) -> String {
+    match (page_query.is_empty(), page) {
+        (true, 0) => "/".to_string(),
+        (false, 0) => format!("/?{}", page_query),
+        (true, _) => format!("/?page={}", page + 1),
+        (false, _) => format!("/?{}&page={}", page_query, page + 1),
+    }
+}
 
-        true
+/// Link to the first page in another order, keeping the filters and layout
+/// Without JavaScript this is how sorting works; sort.js takes over the click when it runs
+fn sort_url(page_query: &str, sort: SortKey, ascending: bool) -> String {
+    let mut params: Vec<String> = page_query
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !matches!(pair.split('=').next(), Some("sort" | "dir")))
+        .map(str::to_string)
+        .collect();
+    if sort != SortKey::default() {
+        params.push(format!("sort={}", sort.key()));
+    }
+    if ascending {
+        params.push("dir=asc".to_string());
+    }
+    if params.is_empty() {
+        "/".to_string()
+    } else {
+        format!("/?{}", params.join("&"))
+    }
+}
+
+/// Previous/next links and page numbers: the first, the last, and the two either side of the current one
+fn pager(page: usize, pages: usize, page_query: &str) -> Html {
+    if pages <= 1 {
+        return html! {};
+    }
+    let link_class = "py-1 px-3 bg-bg-inset border border-border-subtle rounded-sm text-text-secondary text-sm no-underline transition-colors duration-200 hover:border-accent-primary hover:text-accent-primary";
+    let step = |target: Option<usize>, label: &'static str| match target {
+        Some(target) => html! { <a href={page_url(page_query, target)} class={link_class}>{label}</a> },
+        None => html! { <span class="py-1 px-3 text-text-muted text-sm">{label}</span> },
     };
+    let shown: Vec<usize> = (0..pages)
+        .filter(|&p| p == 0 || p == pages - 1 || p.abs_diff(page) <= 2)
+        .collect();
+
+    html! {
+        <nav class="pager flex justify-center items-center flex-wrap gap-2 mt-8" aria-label="Pages">
+            {step(page.checked_sub(1), "← Previous")}
+            {for shown.iter().enumerate().map(|(i, &p)| {
+                let gap = i > 0 && p - shown[i - 1] > 1;
+                html! {
+                    <>
+                        {if gap { html! { <span class="text-text-muted">{"…"}</span> } } else { html! {} }}
+                        {if p == page {
+                            html! { <span class="py-1 px-3 border border-accent-primary rounded-sm text-text-bright text-sm" aria-current="page">{p + 1}</span> }
+                        } else {
+                            html! { <a href={page_url(page_query, p)} class={link_class}>{p + 1}</a> }
+                        }}
+                    </>
+                }
+            })}
+            {step((page + 1 < pages).then_some(page + 1), "Next →")}
+        </nav>
+    }
+}
+
+/// Links to archive pages of servers that matched the search but are no longer listed, and to the
+/// listed server now using the name, if any
+fn archived_results(archived: &[ServerProfile], servers: &[ServerView], include_offline: bool) -> Html {
+    let heading = if include_offline { "💤 Offline and archived servers" } else { "🪦 Archived servers" };
+    html! {
+        <section class="mt-8 p-6 bg-bg-card/65 border border-border-subtle rounded-md">
+            <h3 class="text-sm uppercase tracking-wider text-text-secondary mb-3">{heading}</h3>
+            <ul class="flex flex-col gap-2">
+                {for archived.iter().map(|server| {
+                    let last_seen = chrono::DateTime::parse_from_rfc3339(&server.last_seen)
+                        .map(|t| t.format("%Y-%m-%d").to_string())
+                        .unwrap_or_default();
+                    let successor = find_successor(&server.name, &server.server_id, servers.iter().map(|s| &s.server));
+                    html! {
+                        <li class="flex items-baseline gap-3">
+                            <a href={format!("/archive/{}", server.server_id)} class="flex-1 min-w-0 overflow-hidden text-ellipsis whitespace-nowrap text-accent-primary no-underline hover:text-accent-secondary">
+                                {parse_rich_text(&server.name)}
+                            </a>
+                            {if let Some(successor) = successor {
+                                html! {
+                                    <a href={format!("/server/{}", successor.game_id)} class="text-sm text-accent-secondary no-underline whitespace-nowrap hover:text-accent-primary" title="Listed now under another server ID">
+                                        {"→ now listed"}
+                                    </a>
+                                }
+                            } else {
+                                html! {}
+                            }}
+                            <span class="font-mono text-sm text-text-muted whitespace-nowrap">{format!("last seen {}", last_seen)}</span>
+                        </li>
+                    }
+                })}
+            </ul>
+        </section>
+    }
+}
+
+/// Server list component with filtering (SSR-compatible)
+#[function_component(ServerList)]
+pub fn server_list(props: &ServerListProps) -> Html {
+    // Unique versions, newest first
+    let versions = versions_newest_first(props.servers.iter().map(|s| &s.server));
+
+    // Continents with at least one server, in dropdown order (empty without a GeoIP database)
+    let regions: Vec<String> = CONTINENTS
+        .iter()
+        .map(|(code, _)| code.to_string())
+        .filter(|code| props.servers.iter().any(|s| s.server.continent.as_ref() == Some(code)))
+        .collect();
+
+    // Latest version is first after sorting
+    let latest_version = versions.first().cloned().unwrap_or_default();
+    let filters = &props.filters;
+
+    // Inactive servers the other filters would show, for the toggle's count
+    let mut with_inactive = filters.clone();
+    with_inactive.show_inactive = true;
+    let inactive_count = props
+        .servers
+        .iter()
+        .filter(|s| s.inactive && with_inactive.matches(&s.server, &latest_version) && with_inactive.matches_view(s))
+        .count();
 
     // Pre-filter servers (all filters except tags) for accurate tag counting
-    let pre_filtered_servers: Vec<&CachedServer> = props
+    let pre_filtered_servers: Vec<&ServerView> = props
         .servers
         .iter()
-        .filter(|s| passes_non_tag_filters(s))
+        .filter(|s| filters.matches_ignoring_tags(&s.server, &latest_version) && filters.matches_view(s))
         .collect();
 
     // Extract unique tags from pre-filtered servers with frequency count
     // Each tag is counted once per server (deduplicated within each server)
     let mut tag_counts: HashMap<String, usize> = HashMap::new();
     for server in &pre_filtered_servers {
-        let unique_tags: HashSet<&String> = server.tags.iter().collect();
+        let unique_tags: HashSet<&String> = server.server.tags.iter().collect();
         for tag in unique_tags {
             *tag_counts.entry(tag.clone()).or_insert(0) += 1;
         }
@@ -134,35 +340,52 @@ pub fn server_list(props: &ServerListProps) -> Html {
         .collect();
 
     // Apply tag filter on top of pre-filtered servers
-    let filtered_servers: Vec<&CachedServer> = pre_filtered_servers
+    let filtered_servers: Vec<&ServerView> = pre_filtered_servers
         .into_iter()
-        .filter(|s| {
-            // Tag filter (OR logic - server must have at least one selected tag)
-            if !selected_tags.is_empty() {
-                if !selected_tags.iter().any(|t| s.tags.contains(t)) {
-                    return false;
-                }
-            }
-            true
-        })
+        .filter(|s| filters.matches_tags(&s.server))
         .collect();
 
+    let columns = if props.columns.is_empty() {
+        ListColumn::DEFAULT.to_vec()
+    } else {
+        props.columns.clone()
+    };
+
     // Calculate total players in filtered servers
-    let filtered_player_count: usize = filtered_servers.iter().map(|s| s.player_count).sum();
-    let total_player_count: usize = props.servers.iter().map(|s| s.player_count).sum();
+    let filtered_player_count: usize = filtered_servers.iter().map(|s| s.server.player_count).sum();
+    let total_player_count: usize = props.servers.iter().map(|s| s.server.player_count).sum();
+
+    // Only one page of cards is rendered; past the last page shows the last one
+    let pages = filtered_servers.len().div_ceil(PAGE_SIZE).max(1);
+    let page = props.list_state.page.min(pages - 1);
+    let page_servers: Vec<&ServerView> = filtered_servers.iter().skip(page * PAGE_SIZE).take(PAGE_SIZE).copied().collect();
 
     html! {
         <div>
             <Filters 
-                current_search={props.current_search.clone()}
-                current_version={props.current_version.clone()}
-                has_players={props.has_players}
-                no_password={props.no_password}
-                is_dedicated={props.is_dedicated}
-                versions={versions}
+                current_search={filters.search.clone()}
+                current_version={filters.version.as_param().to_string()}
+                has_players={filters.has_players}
+                no_password={filters.no_password}
+                is_dedicated={filters.dedicated}
+                has_free_slots={filters.free_slots}
+                current_region={filters.region.clone().unwrap_or_default()}
+                current_max_game_time={filters.max_game_time}
+                current_new_days={filters.new_days}
+                regions={regions}
+                show_inactive={filters.show_inactive}
+                inactive_hidden_by_default={props.inactive_hidden_by_default}
+                inactive_count={inactive_count}
+                include_offline={filters.include_offline}
+                modpack={filters.modpack.clone()}
+                version_families={version_families(props.servers.iter().map(|s| &s.server))}
                 latest_version={latest_version}
                 available_tags={available_tags}
-                selected_tags={selected_tags}
+                selected_tags={filters.tags.clone()}
+                tag_mode_all={filters.tag_mode == TagMode::All}
+                current_group_by={props.group_by.clone()}
+                list_params={props.list_state.params()}
+                account={props.account.clone()}
             />
             
             // Show error banner if there's an error (but still show cached servers below)
@@ -191,39 +414,74 @@ pub fn server_list(props: &ServerListProps) -> Html {
                 
                 <div class="flex items-center gap-2">
                     <span class="text-text-muted text-[0.85rem]">{"Sort by:"}</span>
-                    <button type="button" class="sort-button py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-text-secondary font-display text-[0.85rem] cursor-pointer transition-all duration-200 hover:border-accent-primary hover:text-accent-primary" data-sort="name">
-                        {"Name "}<span class="sort-arrow text-xs ml-0.5">{""}</span>
-                    </button>
-                    <button type="button" class="sort-button active py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-text-secondary font-display text-[0.85rem] cursor-pointer transition-all duration-200 hover:border-accent-primary hover:text-accent-primary" data-sort="players" data-dir="desc">
-                        {"Players "}<span class="sort-arrow text-xs ml-0.5">{"▼"}</span>
-                    </button>
-                    <button type="button" class="sort-button py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-text-secondary font-display text-[0.85rem] cursor-pointer transition-all duration-200 hover:border-accent-primary hover:text-accent-primary" data-sort="time">
-                        {"Game Time "}<span class="sort-arrow text-xs ml-0.5">{""}</span>
-                    </button>
+                    {for SortKey::ALL.iter().map(|key| {
+                        let active = *key == props.list_state.sort;
+                        let dir = if props.list_state.ascending { "asc" } else { "desc" };
+                        let arrow = match (active, props.list_state.ascending) {
+                            (false, _) => "",
+                            (true, false) => "▼",
+                            (true, true) => "▲",
+                        };
+                        let title = (*key == SortKey::Score).then_some("Players, recent activity and uptime, minus spammy listings");
+                        // Clicking the active sort flips its direction; any other sort starts descending
+                        let href = sort_url(&props.page_query, *key, active && !props.list_state.ascending);
+                        html! {
+                            <a
+                                href={href}
+                                class={classes!("sort-button", active.then_some("active"), "py-1", "px-2", "bg-bg-inset", "border", "border-border-subtle", "rounded-sm", "text-text-secondary", "font-display", "text-[0.85rem]", "no-underline", "cursor-pointer", "transition-all", "duration-200", "hover:border-accent-primary", "hover:text-accent-primary")}
+                                data-sort={key.key()}
+                                data-dir={active.then_some(dir)}
+                                title={title}
+                            >
+                                {key.label()}{" "}<span class="sort-arrow text-xs ml-0.5">{arrow}</span>
+                            </a>
+                        }
+                    })}
                     
+                    <details class="column-picker relative ml-4 pl-4 border-l border-border-subtle">
+                        <summary class="py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-text-secondary text-[0.85rem] cursor-pointer list-none hover:border-accent-primary hover:text-accent-primary" title="List view columns">{"Columns"}</summary>
+                        <div class="absolute right-0 mt-1 z-20 flex flex-col gap-1 p-3 bg-bg-card border border-border-subtle rounded-sm min-w-[140px]">
+                            {for ListColumn::ALL.iter().map(|col| {
+                                html! {
+                                    <label class="flex items-center gap-2 text-[0.85rem] text-text-secondary cursor-pointer whitespace-nowrap">
+                                        <input type="checkbox" class="column-toggle" value={col.key()} checked={columns.contains(col)} />
+                                        {col.label()}
+                                    </label>
+                                }
+                            })}
+                        </div>
+                    </details>
+
                     <div class="flex gap-0.5 ml-4 pl-4 border-l border-border-subtle">
-                        <button type="button" class="view-btn active py-1 px-2 bg-bg-inset border border-border-subtle text-text-secondary text-base cursor-pointer transition-all duration-200 leading-none rounded-l-sm hover:border-accent-primary hover:text-accent-primary" data-view="grid" title="Grid view">{"▦"}</button>
-                        <button type="button" class="view-btn py-1 px-2 bg-bg-inset border border-border-subtle border-l-0 text-text-secondary text-base cursor-pointer transition-all duration-200 leading-none rounded-r-sm hover:border-accent-primary hover:text-accent-primary" data-view="list" title="List view">{"☰"}</button>
+                        <button type="button" class={classes!("view-btn", (!props.list_state.list_view).then_some("active"), "py-1", "px-2", "bg-bg-inset", "border", "border-border-subtle", "text-text-secondary", "text-base", "cursor-pointer", "transition-all", "duration-200", "leading-none", "rounded-l-sm", "hover:border-accent-primary", "hover:text-accent-primary")} data-view="grid" title="Grid view">{"▦"}</button>
+                        <button type="button" class={classes!("view-btn", props.list_state.list_view.then_some("active"), "py-1", "px-2", "bg-bg-inset", "border", "border-border-subtle", "border-l-0", "text-text-secondary", "text-base", "cursor-pointer", "transition-all", "duration-200", "leading-none", "rounded-r-sm", "hover:border-accent-primary", "hover:text-accent-primary")} data-view="list" title="List view">{"☰"}</button>
                     </div>
                 </div>
             </div>
             
-            <div class="server-grid grid grid-cols-[repeat(auto-fill,minmax(320px,1fr))] gap-6">
-                <div class="list-header hidden items-center gap-4 py-2 px-4 bg-bg-inset border border-border-subtle rounded-sm sticky top-0 z-10 text-xs font-semibold uppercase tracking-widest text-text-secondary">
-                    <span class="hidden sm:flex sm:flex-1 min-w-0">{"Name"}</span>
-                    <span class="w-[60px] text-center">{"Players"}</span>
-                    <span class="w-[70px] text-center">{"Version"}</span>
-                    <span class="w-[80px] text-center">{"Time"}</span>
-                    <span class="w-[80px] text-right">{"Mods"}</span>
-                </div>
-                {for filtered_servers.iter().map(|server| {
-                    html! {
-                        <ServerCard 
-                            server={(*server).clone()} 
-                        />
-                    }
-                })}
-            </div>
+            {match GroupBy::parse(&props.group_by) {
+                Some(group_by) => html! {
+                    <div class="flex flex-col gap-4">
+                        {for group_servers(&page_servers, group_by).into_iter().map(|(label, members)| {
+                            let players: usize = members.iter().map(|s| s.server.player_count).sum();
+                            html! {
+                                <details class="server-group" open=true>
+                                    <summary class="flex items-baseline gap-3 py-2 px-4 mb-4 bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-sm cursor-pointer">
+                                        <span class="text-lg font-semibold text-text-bright">{&label}</span>
+                                        <span class="text-[0.85rem] text-text-secondary">
+                                            {format!("{} servers · {} players", members.len(), players)}
+                                        </span>
+                                    </summary>
+                                    {server_grid(&members, &columns, &props.favorites, props.list_state.list_view)}
+                                </details>
+                            }
+                        })}
+                    </div>
+                },
+                None => server_grid(&page_servers, &columns, &props.favorites, props.list_state.list_view),
+            }}
+
+            {pager(page, pages, &props.page_query)}
             
             {if filtered_servers.is_empty() {
                 html! {
@@ -234,6 +492,85 @@ pub fn server_list(props: &ServerListProps) -> Html {
             } else {
                 html! {}
             }}
+
+            {if !props.archived.is_empty() {
+                archived_results(&props.archived, &props.servers, filters.include_offline)
+            } else {
+                html! {}
+            }}
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::models::CachedServer;
+
+    fn view(game_id: u64, continent: Option<&str>, mod_count: u32, modpack: Option<&str>) -> ServerView {
+        let server: CachedServer = serde_json::from_value(serde_json::json!({
+            "game_id": game_id,
+            "name": format!("Server {}", game_id),
+            "max_players": 10,
+            "player_count": 1,
+            "game_time_elapsed": 0,
+            "has_password": false,
+            "mod_count": mod_count,
+            "game_version": "2.0.28",
+            "build_version": 0,
+            "cached_at": "2026-01-01T00:00:00Z",
+            "continent": continent,
+        }))
+        .unwrap();
+        let mut view = ServerView::from(server);
+        view.modpack = modpack.map(str::to_string);
+        view
+    }
+
+    fn sections(servers: &[ServerView], group_by: GroupBy) -> Vec<(String, Vec<u64>)> {
+        let servers: Vec<&ServerView> = servers.iter().collect();
+        group_servers(&servers, group_by)
+            .into_iter()
+            .map(|(label, members)| (label, members.iter().map(|s| s.server.game_id.0).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn regions_biggest_first_with_unknown_last() {
+        let servers = [
+            view(1, None, 0, None),
+            view(2, Some("EU"), 0, None),
+            view(3, Some("NA"), 0, None),
+            view(4, Some("EU"), 0, None),
+        ];
+        assert_eq!(
+            sections(&servers, GroupBy::Region),
+            vec![
+                ("Europe".to_string(), vec![2, 4]),
+                ("North America".to_string(), vec![3]),
+                (UNKNOWN_REGION.to_string(), vec![1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn modpacks_only_one_server_runs_share_a_section() {
+        let servers = [
+            view(1, None, 3, Some("aaaaaaaaaaaa")),
+            view(2, None, 0, None),
+            view(3, None, 5, Some("bbbbbbbbbbbb")),
+            view(4, None, 3, Some("aaaaaaaaaaaa")),
+            view(5, None, 2, None),
+            view(6, None, 7, Some("cccccccccccc")),
+        ];
+        assert_eq!(
+            sections(&servers, GroupBy::Modpack),
+            vec![
+                ("Modpack aaaaaaaa (3 mods)".to_string(), vec![1, 4]),
+                ("Vanilla".to_string(), vec![2]),
+                (OTHER_MODPACKS.to_string(), vec![3, 6]),
+                (MODS_NOT_LOOKED_UP.to_string(), vec![5]),
+            ]
+        );
+    }
+}
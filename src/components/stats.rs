@@ -0,0 +1,213 @@
+use crate::db::models::HourlyTotals;
+use crate::stats::{GlobalStats, VersionAdoption};
+use crate::utils::strip_all_tags;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct StatsPageProps {
+    /// Listing-wide totals per hour, oldest first
+    #[prop_or_default]
+    pub totals: Vec<HourlyTotals>,
+    /// Latest materialized aggregates (None until the first computation)
+    #[prop_or_default]
+    pub stats: Option<GlobalStats>,
+    /// When `stats` was computed
+    #[prop_or_default]
+    pub generated_at: Option<String>,
+    /// Days covered by `totals`
+    pub days: i64,
+    /// Share of players per version, day by day
+    #[prop_or_default]
+    pub adoption: VersionAdoption,
+    /// Days covered by `adoption`
+    #[prop_or_default]
+    pub adoption_days: i64,
+}
+
+/// Colors of the charted versions, newest first; "Other" is grey
+const VERSION_COLORS: &[&str] = &["#f4a200", "#5cb3c1", "#7fcd33", "#c26fd1", "#ff6b6b", "#f1be64"];
+const OTHER_COLOR: &str = "#555555";
+
+fn version_color(index: usize) -> &'static str {
+    VERSION_COLORS.get(index).copied().unwrap_or(OTHER_COLOR)
+}
+
+/// 100% stacked daily bars of each version's share of players, oldest day left
+fn adoption_chart(adoption: &VersionAdoption) -> Html {
+    let labels: Vec<&str> = adoption.versions.iter().map(String::as_str).chain(std::iter::once("Other")).collect();
+    html! {
+        <>
+            <div class="flex flex-wrap gap-4 text-xs text-text-secondary">
+                {for labels.iter().enumerate().map(|(i, label)| html! {
+                    <span class="flex items-center gap-1 font-mono">
+                        <span class="inline-block w-3 h-3 rounded-sm" style={format!("background-color: {}", version_color(i))}></span>
+                        {*label}
+                    </span>
+                })}
+            </div>
+            <div class="flex items-stretch gap-px h-40 p-2 bg-bg-inset rounded-md">
+                {for adoption.days.iter().map(|day| {
+                    let title = labels
+                        .iter()
+                        .zip(&day.shares)
+                        .filter(|(_, share)| **share >= 0.05)
+                        .map(|(label, share)| format!("{}: {:.1}%", label, share))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    html! {
+                        <div class="flex-1 flex flex-col-reverse" title={format!("{} ({} players on average)\n{}", day.day, day.players.round(), title)}>
+                            {for day.shares.iter().enumerate().map(|(i, share)| html! {
+                                <div style={format!("height: {:.2}%; background-color: {}", share, version_color(i))}></div>
+                            })}
+                        </div>
+                    }
+                })}
+            </div>
+        </>
+    }
+}
+
+/// Bar chart of one series, scaled to its own maximum
+fn bar_chart(totals: &[HourlyTotals], value: fn(&HourlyTotals) -> f64, unit: &str) -> Html {
+    let max = totals.iter().map(value).fold(1.0, f64::max);
+    html! {
+        <div class="flex items-end gap-px h-32 p-2 bg-bg-inset rounded-md">
+            {for totals.iter().map(|point| {
+                let v = value(point);
+                let height = (v / max * 100.0) as u32;
+                let hour = chrono::DateTime::parse_from_rfc3339(&point.hour)
+                    .map(|t| t.format("%a %H:00 UTC").to_string())
+                    .unwrap_or_default();
+                html! {
+                    <div class="history-bar" style={format!("height: {}%", height.max(2))} title={format!("{}: {} {}", hour, v.round(), unit)}></div>
+                }
+            })}
+        </div>
+    }
+}
+
+/// Share of the listing as a percentage, for adoption bars
+fn share(part: usize, total: usize) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 / total as f64 * 100.0 }
+}
+
+fn version_row(version: &crate::stats::VersionCount, stats: &GlobalStats) -> Html {
+    let servers_share = share(version.servers, stats.total_servers);
+    let players_share = share(version.players, stats.total_players);
+    html! {
+        <div class="flex flex-col gap-1">
+            <div class="flex justify-between text-sm">
+                <a href={format!("/?version={}", urlencoding::encode(&version.version))} class="font-mono text-accent-primary no-underline hover:text-accent-secondary">{&version.version}</a>
+                <span class="text-text-secondary font-mono">
+                    {format!("{} servers ({:.1}%) · {} players ({:.1}%)", version.servers, servers_share, version.players, players_share)}
+                </span>
+            </div>
+            <div class="h-2 bg-bg-dark rounded-sm overflow-hidden" title={format!("{:.1}% of servers", servers_share)}>
+                <div class="h-full bg-accent-primary rounded-sm" style={format!("width: {:.1}%", servers_share)}></div>
+            </div>
+        </div>
+    }
+}
+
+/// Global statistics page: totals over time, version adoption and popular tags (SSR-compatible)
+#[function_component(StatsPage)]
+pub fn stats_page(props: &StatsPageProps) -> Html {
+    let section_title = "text-sm uppercase tracking-wider text-text-secondary";
+
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[1000px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <h2 class="text-2xl mb-4">{"Statistics"}</h2>
+                    {if let Some(ref stats) = props.stats {
+                        html! {
+                            <div class="flex gap-6">
+                                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+                                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{stats.total_servers}</span>
+                                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Servers"}</span>
+                                </div>
+                                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+                                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{stats.active_servers}</span>
+                                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"With Players"}</span>
+                                </div>
+                                <div class="text-center p-4 bg-bg-dark rounded-md flex-1">
+                                    <span class="block text-2xl font-semibold font-mono text-accent-primary">{stats.total_players}</span>
+                                    <span class="text-xs text-text-secondary uppercase tracking-wider">{"Players Online"}</span>
+                                </div>
+                            </div>
+                        }
+                    } else {
+                        html! { <p class="text-text-muted">{"Statistics are computed a few minutes after startup."}</p> }
+                    }}
+                </header>
+
+                {if props.totals.is_empty() {
+                    html! {
+                        <section class="p-8 border-b border-border-subtle">
+                            <p class="text-text-muted">{"No history recorded yet."}</p>
+                        </section>
+                    }
+                } else {
+                    html! {
+                        <>
+                            <section class="p-8 border-b border-border-subtle flex flex-col gap-4">
+                                <h3 class={section_title}>{format!("Players online (last {} days, hourly average)", props.days)}</h3>
+                                {bar_chart(&props.totals, |t| t.players, "players")}
+                            </section>
+                            <section class="p-8 border-b border-border-subtle flex flex-col gap-4">
+                                <h3 class={section_title}>{format!("Servers listed (last {} days, hourly average)", props.days)}</h3>
+                                {bar_chart(&props.totals, |t| t.servers, "servers")}
+                                <h3 class={section_title}>{"Servers with players"}</h3>
+                                {bar_chart(&props.totals, |t| t.active_servers, "servers")}
+                            </section>
+                        </>
+                    }
+                }}
+
+                {if let Some(ref stats) = props.stats {
+                    html! {
+                        <>
+                            {if props.adoption.days.is_empty() {
+                                html! {}
+                            } else {
+                                html! {
+                                    <section class="p-8 border-b border-border-subtle flex flex-col gap-4">
+                                        <h3 class={section_title}>{format!("Players by version (last {} days, daily share)", props.adoption_days)}</h3>
+                                        {adoption_chart(&props.adoption)}
+                                    </section>
+                                }
+                            }}
+                            <section class="p-8 border-b border-border-subtle flex flex-col gap-4">
+                                <h3 class={section_title}>{"Version adoption"}</h3>
+                                {for stats.versions.iter().map(|v| version_row(v, stats))}
+                            </section>
+                            <section class="p-8 flex flex-col gap-4">
+                                <h3 class={section_title}>{"Top tags"}</h3>
+                                <div class="flex flex-wrap gap-2">
+                                    {for stats.top_tags.iter().map(|tag| html! {
+                                        <a
+                                            href={format!("/?version=all&tags={}", urlencoding::encode(&tag.tag))}
+                                            class="py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary no-underline transition-all duration-200 hover:bg-accent-primary hover:text-bg-dark"
+                                        >
+                                            {strip_all_tags(&tag.tag)}{format!(" · {}", tag.servers)}
+                                        </a>
+                                    })}
+                                </div>
+                            </section>
+                        </>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                {if let Some(ref generated_at) = props.generated_at {
+                    html! { <p class="px-8 pb-6 text-xs text-text-muted">{format!("Breakdowns computed at {}", generated_at)}</p> }
+                } else {
+                    html! {}
+                }}
+            </div>
+        </div>
+    }
+}
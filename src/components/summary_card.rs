@@ -0,0 +1,98 @@
+use crate::utils::parse_rich_text;
+use crate::view::{ServerBadge, ServerView};
+use yew::prelude::*;
+
+/// Tags shown on the card; the rest are left to the server page
+const CARD_MAX_TAGS: usize = 8;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct SummaryCardProps {
+    pub server: ServerView,
+    /// Absolute URL of the server page, printed on the card
+    pub page_url: String,
+}
+
+/// Single self-contained server summary for screenshots and printing (SSR-compatible)
+/// No video, navigation or footer, just the card on a plain background
+#[function_component(SummaryCard)]
+pub fn summary_card(props: &SummaryCardProps) -> Html {
+    let view = &props.server;
+    let server = &view.server;
+    let as_of = chrono::DateTime::parse_from_rfc3339(&server.cached_at)
+        .map(|t| t.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let fact = |label: &'static str, value: String| html! {
+        <div class="flex flex-col p-3 bg-bg-inset border border-border-subtle rounded-sm">
+            <span class="text-lg font-semibold font-mono text-accent-primary">{value}</span>
+            <span class="text-xs text-text-secondary uppercase tracking-wider">{label}</span>
+        </div>
+    };
+
+    html! {
+        <div class="min-h-screen flex items-center justify-center p-6">
+            <article class="summary-card w-full max-w-[640px] flex flex-col gap-5 p-8 bg-bg-card border border-border-subtle rounded-lg">
+                <header class="flex flex-col gap-2">
+                    <h1 class="text-2xl font-bold text-text-bright break-words">{parse_rich_text(&server.name)}</h1>
+                    <div class="flex flex-wrap gap-2 text-[0.85rem]">
+                        {if view.badges.contains(&ServerBadge::Password) {
+                            html! { <span class="py-1 px-2 rounded-sm bg-status-full/15 text-status-full">{"🔒 "}{ServerBadge::Password.label()}</span> }
+                        } else {
+                            html! { <span class="py-1 px-2 rounded-sm bg-status-low/15 text-status-low">{"🌐 Public"}</span> }
+                        }}
+                        {if view.badges.contains(&ServerBadge::Dedicated) {
+                            html! { <span class="py-1 px-2 rounded-sm bg-accent-glow text-accent-secondary">{"🖥 "}{ServerBadge::Dedicated.label()}</span> }
+                        } else {
+                            html! {}
+                        }}
+                        {if view.badges.contains(&ServerBadge::Impostor) {
+                            html! { <span class="py-1 px-2 rounded-sm bg-status-full/15 text-status-full">{"⚠ "}{ServerBadge::Impostor.label()}</span> }
+                        } else {
+                            html! {}
+                        }}
+                        {if view.badges.contains(&ServerBadge::Reported) {
+                            html! { <span class="py-1 px-2 rounded-sm bg-status-medium/15 text-status-medium">{"⚠ "}{ServerBadge::Reported.label()}</span> }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                </header>
+
+                {if !server.description.is_empty() {
+                    html! { <p class="text-text-primary leading-relaxed line-clamp-4">{parse_rich_text(&server.description)}</p> }
+                } else {
+                    html! {}
+                }}
+
+                <div class="grid grid-cols-4 gap-3 max-md:grid-cols-2">
+                    {fact("Players", view.players_display.clone())}
+                    {fact("Version", server.game_version.clone())}
+                    {fact("Mods", view.mods_display.clone())}
+                    {fact("Game Time", view.game_time.clone())}
+                </div>
+
+                {if !view.display_tags.is_empty() {
+                    html! {
+                        <div class="flex flex-wrap gap-2">
+                            {for view.display_tags.iter().take(CARD_MAX_TAGS).map(|tag| html! {
+                                <span class="py-1 px-2 bg-accent-glow border border-accent-primary rounded-sm text-xs text-accent-primary">{tag}</span>
+                            })}
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                {if let Some(ref addr) = server.host_address {
+                    html! { <code class="p-3 bg-bg-dark rounded-sm font-mono text-sm text-accent-primary break-all">{addr}</code> }
+                } else {
+                    html! {}
+                }}
+
+                <footer class="flex justify-between items-baseline gap-4 flex-wrap pt-4 border-t border-border-subtle text-xs text-text-muted font-mono">
+                    <a href={props.page_url.clone()} class="text-text-muted no-underline break-all">{&props.page_url}</a>
+                    <span>{format!("as of {}", as_of)}</span>
+                </footer>
+            </article>
+        </div>
+    }
+}
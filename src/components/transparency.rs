@@ -0,0 +1,88 @@
+use crate::db::models::ModerationAction;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct TransparencyPageProps {
+    /// Most recent first
+    #[prop_or_default]
+    pub actions: Vec<ModerationAction>,
+    /// Servers hidden right now
+    #[prop_or_default]
+    pub hidden_now: usize,
+}
+
+fn action_label(action: &str) -> (&'static str, &'static str) {
+    match action {
+        "hidden" => ("Hidden", "bg-status-full"),
+        "rehidden" => ("Hidden again (relaunch)", "bg-status-full"),
+        "restored" => ("Restored", "bg-status-low"),
+        _ => ("Changed", "bg-status-empty"),
+    }
+}
+
+fn action_row(action: &ModerationAction) -> Html {
+    let (label, color) = action_label(&action.action);
+    let at = chrono::DateTime::parse_from_rfc3339(&action.at)
+        .map(|t| t.with_timezone(&chrono::Utc).format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| action.at.clone());
+    html! {
+        <tr class="border-t border-border-subtle">
+            <td class="py-2 pr-4 text-text-muted whitespace-nowrap font-mono">{at}</td>
+            <td class="py-2 pr-4 whitespace-nowrap">
+                <span class={classes!("inline-block", "py-0.5", "px-2", "rounded-sm", "text-xs", "text-bg-dark", "font-semibold", color)}>{label}</span>
+            </td>
+            <td class="py-2 pr-4 text-text-primary">{&action.name}</td>
+            <td class="py-2 text-text-secondary">{&action.reason}</td>
+        </tr>
+    }
+}
+
+/// Public log of servers hidden from and restored to the listing, with reasons (SSR-compatible)
+#[function_component(TransparencyPage)]
+pub fn transparency_page(props: &TransparencyPageProps) -> Html {
+    html! {
+        <div class="min-h-screen py-8 px-6 max-w-[1000px] mx-auto">
+            <a href="/" class="inline-block text-accent-primary no-underline mb-6 text-[0.95rem] transition-colors duration-200 hover:text-accent-secondary">{"← Back to Server List"}</a>
+
+            <div class="bg-bg-card/65 backdrop-blur-[10px] border border-border-subtle rounded-lg w-full">
+                <header class="p-8 pb-6 border-b border-border-subtle">
+                    <h2 class="text-2xl mb-1">{"Moderation log"}</h2>
+                    <p class="text-text-muted text-sm">
+                        {"Every server this browser has hidden from its listing, and every one it put back, with the reason given. Servers are never hidden silently: each action is logged here as it happens."}
+                    </p>
+                    <p class="mt-4 text-text-secondary">
+                        {match props.hidden_now {
+                            0 => "No servers are hidden right now.".to_string(),
+                            1 => "1 server is hidden right now.".to_string(),
+                            n => format!("{} servers are hidden right now.", n),
+                        }}
+                    </p>
+                </header>
+
+                <section class="p-8">
+                    {if props.actions.is_empty() {
+                        html! { <p class="text-text-secondary">{"No moderation actions yet."}</p> }
+                    } else {
+                        html! {
+                            <div class="overflow-x-auto">
+                                <table class="w-full text-sm text-left">
+                                    <thead class="text-xs uppercase tracking-wider text-text-secondary">
+                                        <tr>
+                                            <th class="pb-2 pr-4">{"Date"}</th>
+                                            <th class="pb-2 pr-4">{"Action"}</th>
+                                            <th class="pb-2 pr-4">{"Server"}</th>
+                                            <th class="pb-2">{"Reason"}</th>
+                                        </tr>
+                                    </thead>
+                                    <tbody>
+                                        {for props.actions.iter().map(action_row)}
+                                    </tbody>
+                                </table>
+                            </div>
+                        }
+                    }}
+                </section>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,279 @@
+use crate::components::app::AppProps;
+use crate::components::live_list::LiveList;
+use crate::components::server_list::{server_grid, ServerListProps};
+use crate::filter::versions_newest_first;
+use crate::prefs::{ListColumn, Widget, MAX_FRIENDS};
+use crate::utils::format::{format_compact_in, format_number_in};
+use crate::utils::parse_rich_text;
+use crate::view::ServerView;
+use yew::prelude::*;
+
+/// Fewest players gained over the last hour's average for a server to count as trending
+const TRENDING_MIN_GAIN: i64 = 2;
+
+/// Servers shown in the trending widget
+const TRENDING_LIMIT: usize = 5;
+
+/// Top-ranked servers the spotlight rotates through, one per hour
+const SPOTLIGHT_POOL: usize = 10;
+
+const SECTION_TITLE: &str = "flex items-baseline gap-3 mb-4 text-lg font-semibold text-text-bright";
+const INPUT_CLASS: &str = "py-1 px-2 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-sm transition-colors duration-200 focus:outline-none focus:border-accent-primary";
+const BUTTON_CLASS: &str = "py-1 px-3 bg-bg-inset border border-border-subtle rounded-sm text-text-primary font-display text-sm cursor-pointer transition-colors duration-200 hover:border-accent-primary";
+
+/// Render one homepage widget; this match is the registry of available widgets
+pub fn render_widget(widget: Widget, props: &AppProps) -> Html {
+    match widget {
+        Widget::Stats => stats(props),
+        Widget::Trending => trending(props),
+        Widget::Spotlight => spotlight(props),
+        Widget::Friends => friends(props),
+        Widget::Favorites => favorites(props),
+        Widget::List => list(props),
+    }
+}
+
+/// Header figures: they follow the current filters, with the whole listing as the secondary figure
+fn stats(props: &AppProps) -> Html {
+    let latest_version = versions_newest_first(props.servers.iter().map(|s| &s.server))
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let filtered: Vec<&ServerView> = props
+        .servers
+        .iter()
+        .filter(|s| props.filters.matches(&s.server, &latest_version) && props.filters.matches_view(s))
+        .collect();
+    let aggregates = |servers: &[&ServerView]| {
+        let active = servers.iter().filter(|s| s.server.player_count > 0).count();
+        let players: usize = servers.iter().map(|s| s.server.player_count).sum();
+        (servers.len(), active, players)
+    };
+    let everything: Vec<&ServerView> = props.servers.iter().collect();
+    let (servers, active_servers, players) = aggregates(&filtered);
+    let (total_servers, total_active_servers, total_players) = aggregates(&everything);
+
+    // Compact figures, exact count on hover, the unfiltered total underneath
+    let stat = |n: usize, total: usize, label: &'static str| html! {
+        <div class="text-center py-4 px-6 bg-bg-card border border-border-subtle rounded-sm min-w-[140px]">
+            <span class="block text-[2rem] font-semibold text-accent-primary font-mono" title={format_number_in(n as u64, props.locale)}>
+                {format_compact_in(n as u64, props.locale)}
+            </span>
+            <span class="block text-[0.85rem] text-text-secondary uppercase tracking-wider">{label}</span>
+            {if n != total {
+                html! {
+                    <span class="block text-xs text-text-muted mt-1" title={format_number_in(total as u64, props.locale)}>
+                        {format!("of {} overall", format_compact_in(total as u64, props.locale))}
+                    </span>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    };
+
+    html! {
+        <section class="widget-stats flex justify-center gap-8 flex-wrap mb-8">
+            {stat(servers, total_servers, "Servers")}
+            {stat(active_servers, total_active_servers, "Active Servers")}
+            {stat(players, total_players, "Players Online")}
+        </section>
+    }
+}
+
+/// Servers gaining players fastest; left out when nothing is picking up
+fn trending(props: &AppProps) -> Html {
+    let mut rising: Vec<&ServerView> = props.servers.iter().filter(|s| s.trend >= TRENDING_MIN_GAIN).collect();
+    if rising.is_empty() {
+        return html! {};
+    }
+    rising.sort_by_key(|s| std::cmp::Reverse(s.trend));
+
+    html! {
+        <section class="widget-trending mb-8">
+            <h2 class={SECTION_TITLE}>{"📈 Trending"}</h2>
+            <ul class="flex flex-col gap-2">
+                {for rising.iter().take(TRENDING_LIMIT).map(|view| html! {
+                    <li class="flex items-center justify-between gap-4 py-2 px-4 bg-bg-card border border-border-subtle rounded-sm">
+                        <a href={format!("/server/{}", view.server.game_id)} class="text-text-primary no-underline break-words hover:text-accent-primary">
+                            {parse_rich_text(&view.server.name)}
+                        </a>
+                        <span class="flex-shrink-0 text-sm font-mono text-text-secondary">
+                            <span class="text-status-low">{format!("+{}", view.trend)}</span>
+                            {format!(" in the last hour · {}", view.players_display)}
+                        </span>
+                    </li>
+                })}
+            </ul>
+        </section>
+    }
+}
+
+/// One of the best-ranked open servers with players, a different one each hour
+fn spotlight(props: &AppProps) -> Html {
+    let mut pool: Vec<&ServerView> = props
+        .servers
+        .iter()
+        .filter(|s| s.server.player_count > 0 && !s.server.has_password && !s.inactive)
+        .collect();
+    if pool.is_empty() {
+        return html! {};
+    }
+    pool.sort_by_key(|s| std::cmp::Reverse(s.score));
+    pool.truncate(SPOTLIGHT_POOL);
+    let hour = chrono::Utc::now().timestamp() / 3600;
+    let view = pool[hour as usize % pool.len()];
+
+    html! {
+        <section class="widget-spotlight mb-8">
+            <h2 class={SECTION_TITLE}>{"🔦 Spotlight"}</h2>
+            <a href={format!("/server/{}", view.server.game_id)} class="block p-6 bg-bg-card border border-accent-primary/40 rounded-md no-underline text-text-primary transition-colors duration-200 hover:border-accent-primary">
+                <h3 class="text-xl mb-2 break-words">{parse_rich_text(&view.server.name)}</h3>
+                <p class="text-sm text-text-secondary mb-4 line-clamp-2">{parse_rich_text(&view.server.description)}</p>
+                <div class="flex flex-wrap gap-4 text-sm font-mono text-text-muted">
+                    <span class="text-accent-secondary">{format!("{} players", view.players_display)}</span>
+                    <span>{&view.server.game_version}</span>
+                    <span>{&view.mods_display}</span>
+                    <span>{&view.game_time}</span>
+                </div>
+            </a>
+        </section>
+    }
+}
+
+/// Which followed players are on a server right now, with a form to edit the names
+fn friends(props: &AppProps) -> Html {
+    let playing = |name: &str| {
+        props
+            .servers
+            .iter()
+            .find(|s| s.server.players.iter().any(|p| p.eq_ignore_ascii_case(name)))
+    };
+
+    html! {
+        <section class="widget-friends mb-8">
+            <h2 class={SECTION_TITLE}>{"👥 Friends online"}</h2>
+            {if props.friends.is_empty() {
+                html! { <p class="text-sm text-text-muted mb-3">{"Add player names to see when your friends are playing."}</p> }
+            } else {
+                html! {
+                    <ul class="flex flex-col gap-1 mb-3">
+                        {for props.friends.iter().map(|name| match playing(name) {
+                            Some(view) => html! {
+                                <li class="text-sm">
+                                    <a href={format!("/players/{}", urlencoding::encode(name))} class="text-accent-primary no-underline hover:text-accent-secondary">{name}</a>
+                                    {" is on "}
+                                    <a href={format!("/server/{}", view.server.game_id)} class="text-text-primary no-underline hover:text-accent-primary">{parse_rich_text(&view.server.name)}</a>
+                                </li>
+                            },
+                            None => html! {
+                                <li class="text-sm text-text-muted">{format!("{} is offline", name)}</li>
+                            },
+                        })}
+                    </ul>
+                }
+            }}
+            <form method="post" action="/friends" class="flex flex-wrap items-center gap-2">
+                <input type="text" name="names" value={props.friends.join(", ")} placeholder="Name1, Name2"
+                    aria-label="Friends (player names, comma-separated)" title={format!("Up to {} names", MAX_FRIENDS)} class={INPUT_CLASS} />
+                <button type="submit" class={BUTTON_CLASS}>{"Save friends"}</button>
+            </form>
+        </section>
+    }
+}
+
+/// Starred servers: they ignore the filters, and starred servers that aren't listed right now are counted
+fn favorites(props: &AppProps) -> Html {
+    if props.favorites.is_empty() {
+        return html! {};
+    }
+    let starred: Vec<&ServerView> = props
+        .favorites
+        .iter()
+        .filter_map(|id| props.servers.iter().find(|s| s.server.server_id.as_ref() == Some(id)))
+        .collect();
+    let offline = props.favorites.len() - starred.len();
+    let columns = if props.columns.is_empty() { ListColumn::DEFAULT.to_vec() } else { props.columns.clone() };
+
+    html! {
+        <section class="favorites mb-8">
+            <h2 class={SECTION_TITLE}>
+                {"⭐ Favorites"}
+                {if offline > 0 {
+                    html! { <span class="text-[0.85rem] font-normal text-text-muted">{format!("{} not listed right now", offline)}</span> }
+                } else {
+                    html! {}
+                }}
+            </h2>
+            {server_grid(&starred, &columns, &props.favorites, props.list_state.list_view)}
+        </section>
+    }
+}
+
+fn list(props: &AppProps) -> Html {
+    html! {
+        <div id="server-list" data-hydrate={props.hydrate.then_some("")}>
+            <LiveList ..list_props(props) />
+        </div>
+    }
+}
+
+/// Props of the server list widget; the page also embeds them as JSON for the hydration bundle
+pub fn list_props(props: &AppProps) -> ServerListProps {
+    ServerListProps {
+        servers: props.servers.clone(),
+        archived: props.archived.clone(),
+        error: props.error.clone(),
+        filters: props.filters.clone(),
+        inactive_hidden_by_default: props.inactive_hidden_by_default,
+        columns: props.columns.clone(),
+        list_state: props.list_state.clone(),
+        page_query: props.page_query.clone(),
+        group_by: props.group_by.clone(),
+        favorites: props.favorites.clone(),
+        account: props.account.clone(),
+    }
+}
+
+/// Panel for choosing which widgets the homepage shows and in what order
+/// Shown widgets come first in their current order, then the hidden ones
+pub fn customize_panel(layout: &[Widget]) -> Html {
+    let hidden = Widget::ALL.iter().copied().filter(|w| !layout.contains(w));
+    let rows: Vec<(Widget, bool)> = layout.iter().map(|w| (*w, true)).chain(hidden.map(|w| (w, false))).collect();
+    let move_button = |widget: Widget, up: bool, label: &'static str, title: &'static str| html! {
+        <button type="submit" name="move" value={format!("{}:{}", widget.key(), if up { "up" } else { "down" })}
+            title={title} aria-label={format!("{} {}", title, widget.label())}
+            class="px-2 bg-transparent border-0 text-text-muted cursor-pointer hover:text-accent-primary">{label}</button>
+    };
+
+    html! {
+        <details class="customize-widgets mt-8 bg-bg-card border border-border-subtle rounded-md">
+            <summary class="cursor-pointer py-3 px-4 text-text-secondary hover:text-text-bright">{"⚙ Customize homepage"}</summary>
+            <form method="post" action="/widgets" class="flex flex-col gap-2 px-4 pb-4">
+                {for rows.iter().map(|&(widget, shown)| html! {
+                    <div class="flex items-center gap-2 text-sm">
+                        <input type="hidden" name="order" value={widget.key()} />
+                        <label class="flex items-center gap-2 flex-1 cursor-pointer">
+                            <input type="checkbox" name="show" value={widget.key()} checked={shown} />
+                            {widget.label()}
+                        </label>
+                        {if shown {
+                            html! {
+                                <>
+                                    {move_button(widget, true, "▲", "Move up")}
+                                    {move_button(widget, false, "▼", "Move down")}
+                                </>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                })}
+                <div class="flex gap-2 mt-2">
+                    <button type="submit" class={BUTTON_CLASS}>{"Save layout"}</button>
+                    <button type="submit" name="reset" value="true" class={BUTTON_CLASS}>{"Reset"}</button>
+                </div>
+            </form>
+        </details>
+    }
+}
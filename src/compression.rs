@@ -0,0 +1,167 @@
+use flate2::write::GzEncoder;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{ContentType, Header, Status};
+use rocket::{Request, Response};
+use std::io::{Cursor, Write};
+
+/// Bodies smaller than this aren't worth the CPU (or the encoding overhead)
+pub(crate) const MIN_COMPRESS_BYTES: usize = 1024;
+
+/// Brotli quality, 0-11; 5 compresses the HTML nearly as well as 11 at a fraction of the time
+const BROTLI_QUALITY: u32 = 5;
+
+/// Brotli window size as a power of two (the usual 4 MB)
+const BROTLI_WINDOW: u32 = 22;
+
+/// Content encodings we produce, in order of preference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    /// The best encoding the client accepts, from its Accept-Encoding header
+    /// A "*" entry accepts every encoding except the ones refused by name
+    pub(crate) fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let mut accepted: Vec<&str> = Vec::new();
+        let mut refused: Vec<&str> = Vec::new();
+        for entry in accept_encoding.split(',') {
+            let mut parts = entry.split(';').map(str::trim);
+            let Some(name) = parts.next().filter(|name| !name.is_empty()) else {
+                continue;
+            };
+            // "gzip;q=0" means the client refuses it
+            if parts.any(|p| p.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0)) {
+                refused.push(name);
+            } else {
+                accepted.push(name);
+            }
+        }
+        let named = |names: &[&str], e: Encoding| names.iter().any(|name| name.eq_ignore_ascii_case(e.name()));
+        [Encoding::Brotli, Encoding::Gzip]
+            .into_iter()
+            .filter(|&e| !named(&refused, e))
+            .find(|&e| named(&accepted, e) || accepted.contains(&"*"))
+    }
+
+    pub(crate) fn compress(&self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Encoding::Brotli => {
+                let mut output = Vec::with_capacity(body.len() / 4);
+                let mut encoder = brotli::CompressorWriter::new(&mut output, 4096, BROTLI_QUALITY, BROTLI_WINDOW);
+                encoder.write_all(body)?;
+                drop(encoder);
+                Ok(output)
+            }
+            Encoding::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 4), flate2::Compression::default());
+                encoder.write_all(body)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Text formats that shrink well: HTML, JSON, feeds, sitemaps, SVG, CSS and scripts
+fn is_compressible(content_type: &ContentType) -> bool {
+    content_type.top() == "text"
+        || content_type.is_json()
+        || content_type.is_xml()
+        || content_type.sub().as_str().ends_with("+xml")
+        || content_type.sub() == "javascript"
+}
+
+/// Compresses text responses with brotli or gzip, whichever the client prefers
+/// The server-rendered list is hundreds of KB of repetitive HTML and shrinks about tenfold
+pub struct ResponseCompression;
+
+#[rocket::async_trait]
+impl Fairing for ResponseCompression {
+    fn info(&self) -> Info {
+        Info {
+            name: "Response compression",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        if res.status() != Status::Ok || res.headers().contains("Content-Encoding") {
+            return;
+        }
+        if !res.content_type().is_some_and(|ct| is_compressible(&ct)) {
+            return;
+        }
+        // Caches must keep the encodings apart, whichever one this client gets
+        res.adjoin_header(Header::new("Vary", "Accept-Encoding"));
+
+        let Some(encoding) = req.headers().get_one("Accept-Encoding").and_then(Encoding::negotiate) else {
+            return;
+        };
+        if res.body().preset_size().is_some_and(|size| size < MIN_COMPRESS_BYTES) {
+            return;
+        }
+
+        let body = match res.body_mut().to_bytes().await {
+            Ok(body) => body,
+            Err(e) => {
+                eprintln!("Failed to read response body for compression: {}", e);
+                return;
+            }
+        };
+        if body.len() < MIN_COMPRESS_BYTES {
+            res.set_sized_body(body.len(), Cursor::new(body));
+            return;
+        }
+        match encoding.compress(&body) {
+            Ok(compressed) => {
+                res.set_header(Header::new("Content-Encoding", encoding.name()));
+                res.set_sized_body(compressed.len(), Cursor::new(compressed));
+            }
+            Err(e) => {
+                eprintln!("Failed to compress response: {}", e);
+                res.set_sized_body(body.len(), Cursor::new(body));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_prefers_brotli() {
+        assert_eq!(Encoding::negotiate("gzip, deflate, br, zstd"), Some(Encoding::Brotli));
+        assert_eq!(Encoding::negotiate("GZIP"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::negotiate("gzip;q=0.5, br;q=0.1"), Some(Encoding::Brotli));
+        assert_eq!(Encoding::negotiate("*"), Some(Encoding::Brotli));
+        assert_eq!(Encoding::negotiate("deflate, identity"), None);
+        assert_eq!(Encoding::negotiate(""), None);
+    }
+
+    #[test]
+    fn negotiate_skips_refused_encodings() {
+        assert_eq!(Encoding::negotiate("br;q=0, gzip"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::negotiate("br;q=0, *"), Some(Encoding::Gzip));
+        assert_eq!(Encoding::negotiate("*, br;q=0, gzip; q=0"), None);
+        assert_eq!(Encoding::negotiate("br, *;q=0"), Some(Encoding::Brotli));
+        assert_eq!(Encoding::negotiate("gzip, *;q=0"), Some(Encoding::Gzip));
+    }
+
+    #[test]
+    fn only_text_formats_are_compressed() {
+        assert!(is_compressible(&ContentType::HTML));
+        assert!(is_compressible(&ContentType::JSON));
+        assert!(is_compressible(&ContentType::SVG));
+        assert!(is_compressible(&ContentType::new("application", "atom+xml")));
+        assert!(!is_compressible(&ContentType::PNG));
+    }
+}
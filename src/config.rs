@@ -0,0 +1,172 @@
+use rocket::figment::providers::{Env, Format, Serialized, Toml};
+use rocket::figment::Figment;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::Path;
+
+/// Config file read when CONFIG_FILE isn't set, relative to the working directory
+pub const DEFAULT_CONFIG_FILE: &str = "factorio-browser.toml";
+
+/// Environment variables that override config file keys, e.g. SURREAL_URL for `database.url`
+const ENV_KEYS: &[(&str, &str)] = &[
+    ("REFRESH_INTERVAL_SECS", "refresh_interval_secs"),
+    ("REFRESH_JITTER_SECS", "refresh_jitter_secs"),
+    ("REFRESH_MAX_BACKOFF_SECS", "refresh_max_backoff_secs"),
+    ("HEALTH_MAX_AGE_SECS", "health_max_age_secs"),
+    ("SURREAL_URL", "database.url"),
+    ("SURREAL_NS", "database.namespace"),
+    ("SURREAL_DB", "database.name"),
+    ("SURREAL_USER", "database.username"),
+    ("SURREAL_PASS", "database.password"),
+    ("SURREAL_REPLICAS", "database.replicas"),
+    ("EXCLUDED_TAGS", "excluded_tags"),
+    ("SITE_NAME", "site_name"),
+    ("DISABLED_FEATURES", "disabled_features"),
+];
+
+/// Settings from the config file, with environment variables (and `.env`) taking precedence
+/// Everything has a default, so neither the file nor any variable is required
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Seconds between listing refreshes
+    pub refresh_interval_secs: u64,
+    /// Up to this many seconds are added to each wait at random
+    pub refresh_jitter_secs: u64,
+    /// Longest wait between refreshes while the Factorio API keeps failing
+    pub refresh_max_backoff_secs: u64,
+    /// /health answers 503 once the cached listing is older than this
+    pub health_max_age_secs: u64,
+    pub database: DatabaseConfig,
+    /// Tags dropped from every server when it's cached, e.g. spam or meaningless tags
+    #[serde(deserialize_with = "list")]
+    pub excluded_tags: Vec<String>,
+    /// Name in page titles and link previews
+    #[serde(deserialize_with = "text")]
+    pub site_name: String,
+    /// Route groups to leave unmounted (see `feature_routes` in main.rs)
+    #[serde(deserialize_with = "list")]
+    pub disabled_features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    /// SurrealDB connection URL, e.g. rocksdb:///var/lib/factorio-browser/database
+    #[serde(deserialize_with = "text")]
+    pub url: String,
+    #[serde(deserialize_with = "text")]
+    pub namespace: String,
+    #[serde(deserialize_with = "text")]
+    pub name: String,
+    /// Credentials, only for remote servers
+    #[serde(deserialize_with = "optional_text")]
+    pub username: Option<String>,
+    #[serde(deserialize_with = "optional_text")]
+    pub password: Option<String>,
+    /// Read replicas of the same database, read from while the primary is down (same credentials)
+    #[serde(deserialize_with = "list")]
+    pub replicas: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            refresh_interval_secs: 60,
+            refresh_jitter_secs: 5,
+            refresh_max_backoff_secs: 900,
+            health_max_age_secs: 300,
+            database: DatabaseConfig::default(),
+            excluded_tags: Vec::new(),
+            site_name: "Factorio Server Browser".to_string(),
+            disabled_features: Vec::new(),
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: "mem://".to_string(),
+            namespace: "factorio".to_string(),
+            name: "browser".to_string(),
+            username: None,
+            password: None,
+            replicas: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load CONFIG_FILE (or factorio-browser.toml when present), then apply the environment
+    pub fn load() -> Result<Self, String> {
+        let path = match std::env::var("CONFIG_FILE") {
+            Ok(path) if !path.is_empty() => {
+                if !Path::new(&path).exists() {
+                    return Err(format!("config file {} not found", path));
+                }
+                path
+            }
+            _ => DEFAULT_CONFIG_FILE.to_string(),
+        };
+        let env = Env::raw()
+            .only(&ENV_KEYS.iter().map(|(var, _)| *var).collect::<Vec<_>>())
+            .map(|var| {
+                ENV_KEYS
+                    .iter()
+                    .find(|(name, _)| var == *name)
+                    .map(|(_, key)| (*key).into())
+                    .unwrap_or_else(|| var.into())
+            });
+
+        Figment::from(Serialized::defaults(Config::default()))
+            .merge(Toml::file(path))
+            .merge(env)
+            .extract()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A plain value; figment parses environment variables, so SURREAL_PASS=1234 arrives as a number
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl From<Scalar> for String {
+    fn from(value: Scalar) -> Self {
+        match value {
+            Scalar::Text(text) => text,
+            Scalar::Int(n) => n.to_string(),
+            Scalar::Float(n) => n.to_string(),
+            Scalar::Bool(b) => b.to_string(),
+        }
+    }
+}
+
+fn text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Scalar::deserialize(deserializer).map(String::from)
+}
+
+fn optional_text<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(Option::<Scalar>::deserialize(deserializer)?.map(String::from))
+}
+
+/// A list given as an array in the file or comma-separated in the environment
+fn list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Items(Vec<Scalar>),
+        Joined(Scalar),
+    }
+
+    let items: Vec<String> = match List::deserialize(deserializer)? {
+        List::Items(items) => items.into_iter().map(String::from).collect(),
+        List::Joined(joined) => String::from(joined).split(',').map(str::to_string).collect(),
+    };
+    Ok(items.into_iter().map(|item| item.trim().to_string()).filter(|item| !item.is_empty()).collect())
+}
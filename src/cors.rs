@@ -0,0 +1,107 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method, Status};
+use rocket::{Request, Response};
+use std::io::Cursor;
+
+/// Methods browsers may use cross-origin unless CORS_ALLOWED_METHODS says otherwise
+pub const DEFAULT_METHODS: &str = "GET, HEAD, POST, OPTIONS";
+
+/// Request headers browsers may send cross-origin unless CORS_ALLOWED_HEADERS says otherwise
+pub const DEFAULT_HEADERS: &str = "X-Api-Key, Authorization, If-None-Match, Content-Type";
+
+/// Response headers scripts may read besides the safelisted ones
+const EXPOSED_HEADERS: &str = "ETag, Age, Retry-After";
+
+/// How long browsers may reuse a preflight answer, in seconds
+const PREFLIGHT_MAX_AGE: u32 = 86400;
+
+/// Only the JSON and GraphQL APIs are shared with other sites; pages and forms stay same-origin
+const API_PREFIX: &str = "/api/";
+const GRAPHQL_PATH: &str = "/graphql";
+
+#[derive(Debug, Clone, PartialEq)]
+enum AllowedOrigins {
+    Any,
+    /// Lowercased, without a trailing slash
+    List(Vec<String>),
+}
+
+/// Lets pages on other sites call the JSON API from the browser
+/// Answers preflight requests itself and adds the CORS headers to API responses for allowed origins;
+/// requests from other origins get no CORS headers, so browsers keep blocking them
+#[derive(Debug, Clone)]
+pub struct Cors {
+    origins: AllowedOrigins,
+    methods: String,
+    headers: String,
+}
+
+impl Cors {
+    /// From comma-separated origins ("*" for any); None when no origin is given, leaving CORS off
+    pub fn new(origins: &str, methods: &str, headers: &str) -> Option<Self> {
+        let list: Vec<String> = origins
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/').to_ascii_lowercase())
+            .filter(|origin| !origin.is_empty())
+            .collect();
+        let origins = if list.iter().any(|origin| origin == "*") {
+            AllowedOrigins::Any
+        } else if list.is_empty() {
+            return None;
+        } else {
+            AllowedOrigins::List(list)
+        };
+        Some(Self {
+            origins,
+            methods: methods.trim().to_string(),
+            headers: headers.trim().to_string(),
+        })
+    }
+
+    /// Value for Access-Control-Allow-Origin, if the origin may call the API
+    fn allow_origin(&self, origin: &str) -> Option<String> {
+        match self.origins {
+            AllowedOrigins::Any => Some("*".to_string()),
+            AllowedOrigins::List(ref list) => {
+                let origin = origin.trim_end_matches('/');
+                list.iter().any(|o| o.eq_ignore_ascii_case(origin)).then(|| origin.to_string())
+            }
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS for the JSON API",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+        let path = req.uri().path().as_str();
+        if !path.starts_with(API_PREFIX) && path != GRAPHQL_PATH {
+            return;
+        }
+        // The answer depends on Origin unless every origin gets the same one
+        if self.origins != AllowedOrigins::Any {
+            res.adjoin_header(Header::new("Vary", "Origin"));
+        }
+        let Some(allowed) = req.headers().get_one("Origin").and_then(|origin| self.allow_origin(origin)) else {
+            return;
+        };
+        res.set_header(Header::new("Access-Control-Allow-Origin", allowed));
+
+        // No API route handles OPTIONS, so a preflight would be a 404; answer it here instead
+        if req.method() == Method::Options && req.headers().contains("Access-Control-Request-Method") {
+            res.set_status(Status::NoContent);
+            res.set_sized_body(0, Cursor::new(Vec::new()));
+            res.set_header(Header::new("Access-Control-Allow-Methods", self.methods.clone()));
+            res.set_header(Header::new("Access-Control-Allow-Headers", self.headers.clone()));
+            res.set_header(Header::new("Access-Control-Max-Age", PREFLIGHT_MAX_AGE.to_string()));
+        } else {
+            res.set_header(Header::new("Access-Control-Expose-Headers", EXPOSED_HEADERS));
+        }
+    }
+}
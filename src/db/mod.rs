@@ -1,3 +1,7 @@
 pub mod models;
+#[cfg(feature = "server")]
 pub mod queries;
-
+#[cfg(feature = "server")]
+pub mod sampling;
+#[cfg(feature = "server")]
+pub mod writer;
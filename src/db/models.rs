@@ -1,12 +1,21 @@
+use crate::ids::{GameId, ServerId};
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
 use surrealdb::sql::Thing;
 
+/// Record ids only mean something to the database; the hydration bundle just carries them along
+#[cfg(not(feature = "server"))]
+type Thing = serde_json::Value;
+
 /// Cached server record stored in SurrealDB
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CachedServer {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<Thing>,
-    pub game_id: u64,
+    pub game_id: GameId,
+    /// Stable server identity across restarts (game_id changes on every restart)
+    #[serde(default)]
+    pub server_id: Option<ServerId>,
     pub name: String,
     #[serde(default)]
     pub description: String,
@@ -22,11 +31,30 @@ pub struct CachedServer {
     pub mod_count: u32,
     pub game_version: String,
     pub build_version: u32,
+    /// Kind of game build, e.g. "headless" for the dedicated server build (None until the next refresh
+    /// for servers cached before it was stored)
+    #[serde(default)]
+    pub build_mode: Option<String>,
+    /// OS the server runs on, e.g. "linux64"
+    #[serde(default)]
+    pub platform: Option<String>,
     #[serde(default)]
     pub host_address: Option<String>,
     #[serde(default)]
     pub headless_server: bool,
     pub cached_at: String,
+    /// When this server was first listed (kept across restarts and short absences)
+    #[serde(default)]
+    pub first_seen: Option<String>,
+    /// When this server was last listed
+    #[serde(default)]
+    pub last_seen: Option<String>,
+    /// ISO country code of host_address, when a GeoIP database is configured
+    #[serde(default)]
+    pub country: Option<String>,
+    /// Continent code of host_address, e.g. "EU"
+    #[serde(default)]
+    pub continent: Option<String>,
 }
 
 /// Server history record for tracking player counts over time
@@ -34,7 +62,9 @@ pub struct CachedServer {
 pub struct ServerHistory {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<Thing>,
-    pub game_id: u64,
+    pub game_id: GameId,
+    #[serde(default)]
+    pub server_id: Option<ServerId>,
     pub player_count: usize,
     pub recorded_at: String,
 }
@@ -42,7 +72,8 @@ pub struct ServerHistory {
 /// Input type for creating a new cached server (without id)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewCachedServer {
-    pub game_id: u64,
+    pub game_id: GameId,
+    pub server_id: Option<ServerId>,
     pub name: String,
     pub description: String,
     pub max_players: u32,
@@ -54,23 +85,110 @@ pub struct NewCachedServer {
     pub mod_count: u32,
     pub game_version: String,
     pub build_version: u32,
+    pub build_mode: Option<String>,
+    pub platform: Option<String>,
     pub host_address: Option<String>,
     pub headless_server: bool,
     pub cached_at: String,
+    pub country: Option<String>,
+    pub continent: Option<String>,
 }
 
 /// Input type for creating a new history record
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewServerHistory {
-    pub game_id: u64,
+    pub game_id: GameId,
+    pub server_id: Option<ServerId>,
     pub player_count: usize,
     pub recorded_at: String,
 }
 
+#[cfg(feature = "server")]
+impl NewServerHistory {
+    /// History record of a server's current player count
+    pub fn from_server(server: &crate::api::factorio::GameServer, recorded_at: &str) -> Self {
+        Self {
+            game_id: server.game_id,
+            server_id: server.server_id.clone(),
+            player_count: server.players.len(),
+            recorded_at: recorded_at.to_string(),
+        }
+    }
+}
+
+/// Input type for recording listing-wide totals after a refresh
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewListingTotals {
+    pub recorded_at: String,
+    /// recorded_at truncated to the hour, for grouping
+    pub hour: String,
+    pub servers: usize,
+    pub active_servers: usize,
+    pub players: usize,
+}
+
+/// Listing-wide totals averaged over one hour
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HourlyTotals {
+    pub hour: String,
+    pub servers: f64,
+    pub active_servers: f64,
+    pub players: f64,
+}
+
+/// Input type for recording one version's totals for an hour (the last refresh of the hour wins)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewVersionTotals {
+    pub hour: String,
+    pub version: String,
+    pub servers: usize,
+    pub players: usize,
+}
+
+/// Servers and players on one game version during one hour
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VersionTotals {
+    pub hour: String,
+    pub version: String,
+    pub servers: usize,
+    pub players: usize,
+}
+
+/// Hours a server was listed over a time window, from presence records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresenceHours {
+    pub server_id: ServerId,
+    pub hours: usize,
+}
+
+/// Highest recorded player count for a server over a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeakPlayers {
+    pub game_id: GameId,
+    pub peak: usize,
+}
+
+/// Mean player count of one game over a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AveragePlayers {
+    pub game_id: GameId,
+    pub average: f64,
+}
+
+/// A server that had players at some point in a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveServer {
+    pub game_id: GameId,
+    #[serde(default)]
+    pub server_id: Option<ServerId>,
+}
+
+#[cfg(feature = "server")]
 impl From<crate::api::factorio::GameServer> for NewCachedServer {
     fn from(server: crate::api::factorio::GameServer) -> Self {
         Self {
             game_id: server.game_id,
+            server_id: server.server_id,
             name: server.name,
             description: server.description,
             max_players: server.max_players,
@@ -82,10 +200,520 @@ impl From<crate::api::factorio::GameServer> for NewCachedServer {
             mod_count: server.mod_count,
             game_version: server.application_version.game_version,
             build_version: server.application_version.build_version,
+            build_mode: Some(server.application_version.build_mode),
+            platform: Some(server.application_version.platform),
             host_address: server.host_address,
             headless_server: server.headless_server,
             cached_at: chrono::Utc::now().to_rfc3339(),
+            country: None,
+            continent: None,
+        }
+    }
+}
+
+
+/// Cached machine translation of a server description
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Translation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub hash: String,
+    pub target: String,
+    pub text: String,
+    pub created_at: String,
+}
+
+/// Input type for creating a new translation record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewTranslation {
+    pub hash: String,
+    pub target: String,
+    pub text: String,
+    pub created_at: String,
+}
+
+/// Cached Mod Portal details for a mod
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub name: String,
+    /// None when the portal doesn't know the mod (private or removed), so it isn't looked up again
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+    pub downloads: u64,
+    #[serde(default)]
+    pub category: Option<String>,
+    pub fetched_at: String,
+}
+
+/// Input type for storing Mod Portal details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewModMetadata {
+    pub name: String,
+    pub title: Option<String>,
+    pub thumbnail: Option<String>,
+    pub downloads: u64,
+    pub category: Option<String>,
+    pub fetched_at: String,
+}
+
+/// Cached abuse reports for a server's host IP
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpReputation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub ip: String,
+    pub reports: u32,
+    pub confidence: u32,
+    pub fetched_at: String,
+}
+
+/// Input type for storing abuse reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewIpReputation {
+    pub ip: String,
+    pub reports: u32,
+    pub confidence: u32,
+    pub fetched_at: String,
+}
+
+/// Modpack fingerprint of a running game (mods only change across restarts, which change game_id)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Modpack {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub game_id: GameId,
+    pub fingerprint: String,
+    pub mod_count: u32,
+    pub recorded_at: String,
+}
+
+/// Input type for recording a modpack fingerprint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewModpack {
+    pub game_id: GameId,
+    pub fingerprint: String,
+    pub mod_count: u32,
+    pub recorded_at: String,
+}
+
+/// Downtime alert registered by a server operator, following the server across restarts by server_id
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DowntimeAlert {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub server_id: ServerId,
+    pub server_name: String,
+    pub webhook_url: String,
+    /// Code sent to the webhook that the operator puts in the server's description to claim the alert
+    pub claim_code: String,
+    /// Whether the code has shown up in the description; unclaimed alerts send nothing else
+    #[serde(default)]
+    pub claimed: bool,
+    /// When the server was first seen with 0 players (None while it has players)
+    #[serde(default)]
+    pub empty_since: Option<String>,
+    /// Whether an alert has been sent for the current outage
+    #[serde(default)]
+    pub alerted: bool,
+    pub created_at: String,
+}
+
+/// Input type for creating a new downtime alert
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewDowntimeAlert {
+    pub server_id: ServerId,
+    pub server_name: String,
+    pub webhook_url: String,
+    pub claim_code: String,
+    pub claimed: bool,
+    pub empty_since: Option<String>,
+    pub alerted: bool,
+    pub created_at: String,
+}
+
+/// A server an admin took off the listing, keyed by server_id
+/// Restoring clears `hidden` and keeps the record, so the history of a server stays visible
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HiddenServer {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub server_id: ServerId,
+    /// Listing as it was when hidden
+    pub game_id: GameId,
+    pub name: String,
+    #[serde(default)]
+    pub host_address: Option<String>,
+    pub reason: String,
+    pub hidden: bool,
+    pub hidden_at: String,
+    #[serde(default)]
+    pub restored_at: Option<String>,
+    /// Hidden automatically as a relaunch of this hidden server
+    #[serde(default)]
+    pub reappeared_from: Option<ServerId>,
+}
+
+/// Input type for hiding a server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewHiddenServer {
+    pub server_id: ServerId,
+    pub game_id: GameId,
+    pub name: String,
+    pub host_address: Option<String>,
+    pub reason: String,
+    pub hidden: bool,
+    pub hidden_at: String,
+    pub restored_at: Option<String>,
+    pub reappeared_from: Option<ServerId>,
+}
+
+/// One hide or restore, as listed on the public transparency page
+/// Holds no server_id, address or admin, only what the listing showed publicly
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModerationAction {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// "hidden", "rehidden" (a relaunch of a hidden server) or "restored"
+    pub action: String,
+    /// Server name, tags stripped
+    pub name: String,
+    pub reason: String,
+    pub at: String,
+}
+
+/// Input type for logging a moderation action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewModerationAction {
+    pub action: String,
+    pub name: String,
+    pub reason: String,
+    pub at: String,
+}
+
+/// What a blocklist entry matches a listing on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    ServerId,
+    /// A bare IP blocks every port on it, "ip:port" just that one
+    Host,
+    /// Regular expression matched against the name without rich text markup
+    Name,
+}
+
+impl BlockKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            BlockKind::ServerId => "Server ID",
+            BlockKind::Host => "Host",
+            BlockKind::Name => "Name pattern",
         }
     }
 }
 
+/// Spam or advertisement listing an admin blocked; matching servers are dropped at refresh
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BlocklistEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub kind: BlockKind,
+    pub pattern: String,
+    pub reason: String,
+    pub added_at: String,
+}
+
+/// Input type for adding a blocklist entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewBlocklistEntry {
+    pub kind: BlockKind,
+    pub pattern: String,
+    pub reason: String,
+    pub added_at: String,
+}
+
+/// Why a visitor reported a server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    Spam,
+    Offensive,
+    /// Pretending to be another, usually well-known, server
+    Impersonation,
+    Other,
+}
+
+impl ReportReason {
+    /// All reasons, in the order the report form lists them
+    pub const ALL: [ReportReason; 4] = [
+        ReportReason::Spam,
+        ReportReason::Offensive,
+        ReportReason::Impersonation,
+        ReportReason::Other,
+    ];
+
+    /// Form value
+    pub fn key(self) -> &'static str {
+        match self {
+            ReportReason::Spam => "spam",
+            ReportReason::Offensive => "offensive",
+            ReportReason::Impersonation => "impersonation",
+            ReportReason::Other => "other",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.key() == key)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportReason::Spam => "Spam or advertising",
+            ReportReason::Offensive => "Offensive content",
+            ReportReason::Impersonation => "Impersonating another server",
+            ReportReason::Other => "Something else",
+        }
+    }
+}
+
+/// A visitor's report of a listed server, open until an admin dismisses it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServerReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// Listing as it was when reported
+    pub game_id: GameId,
+    #[serde(default)]
+    pub server_id: Option<ServerId>,
+    pub name: String,
+    pub reason: ReportReason,
+    #[serde(default)]
+    pub details: String,
+    /// Keyed hash of the reporter's IP, so one visitor can't pile up reports of one server
+    pub reporter: String,
+    pub reported_at: String,
+    pub dismissed: bool,
+}
+
+/// Input type for filing a report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewServerReport {
+    pub game_id: GameId,
+    pub server_id: Option<ServerId>,
+    pub name: String,
+    pub reason: ReportReason,
+    pub details: String,
+    pub reporter: String,
+    pub reported_at: String,
+    pub dismissed: bool,
+}
+
+/// An API key for heavy API consumers; only the key's hash is kept
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// Who the key was issued to, shown on the admin page
+    pub name: String,
+    /// SHA-256 of the key, hex
+    pub key_hash: String,
+    pub per_minute: u32,
+    pub created_at: String,
+    pub revoked: bool,
+}
+
+/// Input type for issuing an API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewApiKeyRecord {
+    pub name: String,
+    pub key_hash: String,
+    pub per_minute: u32,
+    pub created_at: String,
+    pub revoked: bool,
+}
+
+/// Web Push subscription from a visitor's browser
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PushSubscription {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    /// Push service URL messages are POSTed to
+    pub endpoint: String,
+    /// Browser's P-256 public key, base64url
+    pub p256dh: String,
+    /// Browser's auth secret, base64url
+    pub auth: String,
+    /// Favorited servers, notified when they come online
+    #[serde(default)]
+    pub server_ids: Vec<ServerId>,
+    /// Player names, notified when they join any server
+    #[serde(default)]
+    pub friends: Vec<String>,
+    pub created_at: String,
+}
+
+/// Input type for storing a push subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPushSubscription {
+    pub endpoint: String,
+    pub p256dh: String,
+    pub auth: String,
+    pub server_ids: Vec<ServerId>,
+    pub friends: Vec<String>,
+    pub created_at: String,
+}
+
+/// Hourly presence record: the server was listed at some point during `hour`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerPresence {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub server_id: ServerId,
+    pub hour: String,
+}
+
+/// Input type for creating a new presence record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewServerPresence {
+    pub server_id: ServerId,
+    pub hour: String,
+}
+
+/// Lifetime record of a server_id, updated on every refresh it is listed in
+/// Outlives the server's cache row, so a server that shut down keeps an archive page
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServerProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub server_id: ServerId,
+    /// game_id of the last listing
+    pub game_id: GameId,
+    /// Name, description and settings as last listed
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub game_version: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub max_players: u32,
+    #[serde(default)]
+    pub mod_count: u32,
+    /// Most players seen online at once
+    pub peak_players: u32,
+    /// Players online summed over time listed
+    pub player_hours: f64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// A server's identity as exchanged between federated instances: its profile without player hours,
+/// which only mean something to the instance that counted them
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FederatedProfile {
+    pub server_id: ServerId,
+    pub game_id: GameId,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub game_version: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub max_players: u32,
+    #[serde(default)]
+    pub mod_count: u32,
+    pub peak_players: u32,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+/// A player count record as exchanged between federated instances (only ones with a server_id,
+/// since a bare game_id can't be matched up across instances for long)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FederatedHistory {
+    pub server_id: ServerId,
+    pub game_id: GameId,
+    pub player_count: usize,
+    pub recorded_at: String,
+}
+
+/// Precomputed aggregate stored by the scheduler (payload is JSON-encoded)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaterializedResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub key: String,
+    pub payload: String,
+    pub generated_at: String,
+}
+
+/// Input type for storing a materialized result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewMaterializedResult {
+    pub key: String,
+    pub payload: String,
+    pub generated_at: String,
+}
+
+/// Decoded materialized result along with when it was generated
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Materialized<T> {
+    pub data: T,
+    pub generated_at: String,
+}
+
+/// Browser account (the password is stored as an argon2 hash, generated in SurrealDB)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Account {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub email: String,
+    pub created_at: String,
+}
+
+/// Signed-in session; only a hash of the cookie token is stored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub token_hash: String,
+    pub email: String,
+    pub expires_at: String,
+}
+
+/// Input type for creating a new session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewSession {
+    pub token_hash: String,
+    pub email: String,
+    pub expires_at: String,
+}
+
+/// Named filter preset saved by an account
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SavedPreset {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub email: String,
+    pub name: String,
+    #[serde(default)]
+    pub search: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: String,
+}
+
+/// Input type for saving a filter preset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewSavedPreset {
+    pub email: String,
+    pub name: String,
+    pub search: String,
+    pub version: String,
+    pub tags: Vec<String>,
+    pub created_at: String,
+}
@@ -1,13 +1,95 @@
 use crate::api::factorio::GameServer;
-use crate::db::models::{CachedServer, NewCachedServer, NewServerHistory, ServerHistory};
+use crate::filter::FilterSpec;
+use crate::geo::GeoIp;
+use crate::tags::TagAliases;
+use crate::ids::{GameId, ServerId};
+use crate::db::models::{
+    Account, ActiveServer, ApiKeyRecord, AveragePlayers, BlocklistEntry, CachedServer, DowntimeAlert, FederatedHistory, FederatedProfile, HiddenServer, HourlyTotals, Materialized, MaterializedResult, ModMetadata,
+    ModerationAction,
+    IpReputation, Modpack, NewApiKeyRecord, NewBlocklistEntry, NewCachedServer, NewDowntimeAlert, NewHiddenServer, NewIpReputation, NewListingTotals, NewMaterializedResult,
+    NewModMetadata, NewModerationAction, NewModpack, NewPushSubscription, NewSavedPreset, NewServerReport, NewServerHistory, NewServerPresence, NewSession, NewTranslation,
+    NewVersionTotals, PeakPlayers, PresenceHours, PushSubscription, SavedPreset, ServerHistory, ServerPresence, ServerProfile, ServerReport, Session,
+    Translation, VersionTotals,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use surrealdb::engine::any::{connect, Any};
 use surrealdb::opt::auth::Root;
+use surrealdb::sql::Thing;
 use surrealdb::Surreal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// How long player count history is kept (7 days, enough for the weekly activity heatmap)
+pub const HISTORY_RETENTION_HOURS: i64 = 24 * 7;
+
+/// How long hourly presence records are kept (7 days, used for uptime badges)
+pub const PRESENCE_RETENTION_HOURS: i64 = 24 * 7;
+
+/// How long a server's first/last seen times are kept after it was last listed (90 days)
+pub const SIGHTING_RETENTION_HOURS: i64 = 24 * 90;
+
+/// How long a server_id must go unlisted before its profile is shown as an archive page (30 days)
+pub const ARCHIVE_AFTER_HOURS: i64 = 24 * 30;
+
+/// Words of an archive search beyond this are ignored
+const MAX_SEARCH_WORDS: usize = 8;
+
+/// Shortest word the server_search analyzer indexes (its edgengram minimum); shorter words never match
+const MIN_SEARCH_WORD_CHARS: usize = 2;
+
+/// Longest gap between two listings still counted towards a server's player-hours
+/// Longer gaps (the server or this browser was down) count as nobody playing
+const PLAYER_HOURS_MAX_GAP_SECS: i64 = 15 * 60;
+
+/// How long per-version totals are kept (90 days, long enough to follow a release being adopted)
+pub const VERSION_HISTORY_RETENTION_HOURS: i64 = 24 * 90;
+
+/// How long Mod Portal metadata is reused before it is fetched again (download counts drift)
+pub const MOD_METADATA_TTL_HOURS: i64 = 24;
+
+/// How long an address's abuse reports are reused before it is checked again (lookups are rate limited)
+pub const IP_REPUTATION_TTL_HOURS: i64 = 24 * 3;
+
+/// Version of the schema init_schema sets up; bump it with every table or field change
+/// so `factorio-browser check` can tell a database set up by another release
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Record ID for a cached server: the stable server_id, or the game_id when the API omits it
+fn server_record_key(server: &GameServer) -> String {
+    match server.server_id {
+        Some(ref server_id) => server_id.to_string(),
+        None => format!("game-{}", server.game_id),
+    }
+}
+
+/// One row of a cache refresh, bound into the upsert query
+#[derive(Serialize)]
+struct CacheRecord {
+    key: String,
+    data: NewCachedServer,
+}
+
+/// How long a health check waits for a database node before counting it as down
+const HEALTH_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// A read-only copy of the database, read from while the primary is down
+#[derive(Clone)]
+struct Replica {
+    url: String,
+    db: Surreal<Any>,
+    healthy: Arc<AtomicBool>,
+}
 
 /// Database client wrapper for SurrealDB operations
+/// Writes always go to the primary; with replicas, reads move to the first healthy replica while
+/// the primary fails its health checks (see check_health) and back once it answers again
 #[derive(Clone)]
 pub struct DbClient {
     db: Surreal<Any>,
+    /// In order of preference (SURREAL_REPLICAS)
+    replicas: Vec<Replica>,
+    primary_healthy: Arc<AtomicBool>,
 }
 
 /// Error type for database operations
@@ -34,6 +116,29 @@ impl From<surrealdb::Error> for DbError {
     }
 }
 
+/// Whether a node answers a trivial query in time
+async fn ping_node(db: &Surreal<Any>) -> Result<(), DbError> {
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, db.query("RETURN true")).await {
+        Ok(response) => {
+            response?.check()?;
+            Ok(())
+        }
+        Err(_) => Err(DbError::Connection("timed out".to_string())),
+    }
+}
+
+/// Record a node's health check, logging when it goes down or comes back
+fn update_health(name: &str, healthy: &AtomicBool, result: Result<(), DbError>) {
+    let up = result.is_ok();
+    if healthy.swap(up, Ordering::Relaxed) == up {
+        return;
+    }
+    match result {
+        Ok(()) => println!("The {} is reachable again", name),
+        Err(e) => eprintln!("The {} is unreachable: {}", name, e),
+    }
+}
+
 impl DbClient {
     /// Connect to SurrealDB and initialize the database
     pub async fn connect(
@@ -43,6 +148,62 @@ impl DbClient {
         username: Option<&str>,
         password: Option<&str>,
     ) -> Result<Self, DbError> {
+        let client = Self::new(Self::open(url, namespace, database, username, password).await?);
+        client.init_schema().await?;
+
+        Ok(client)
+    }
+
+    /// Connect to SurrealDB without touching the schema (for read-only mode)
+    pub async fn connect_read_only(
+        url: &str,
+        namespace: &str,
+        database: &str,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<Self, DbError> {
+        Ok(Self::new(Self::open(url, namespace, database, username, password).await?))
+    }
+
+    fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db,
+            replicas: Vec::new(),
+            primary_healthy: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// Add read replicas of the same namespace/database, used in the order given
+    /// A replica that can't be reached now is left out with a warning (it's picked up on restart)
+    pub async fn with_replicas(
+        mut self,
+        urls: &[String],
+        namespace: &str,
+        database: &str,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Self {
+        for url in urls {
+            match Self::open(url, namespace, database, username, password).await {
+                Ok(db) => self.replicas.push(Replica {
+                    url: url.clone(),
+                    db,
+                    healthy: Arc::new(AtomicBool::new(true)),
+                }),
+                Err(e) => eprintln!("Warning: leaving out database replica {}: {}", url, e),
+            }
+        }
+        self
+    }
+
+    /// Open a connection and select the namespace/database
+    async fn open(
+        url: &str,
+        namespace: &str,
+        database: &str,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<Surreal<Any>, DbError> {
         let db = connect(url)
             .await
             .map_err(|e| DbError::Connection(e.to_string()))?;
@@ -64,10 +225,36 @@ impl DbClient {
             .await
             .map_err(|e| DbError::Connection(e.to_string()))?;
 
-        let client = Self { db };
-        client.init_schema().await?;
+        Ok(db)
+    }
 
-        Ok(client)
+    /// Where reads go: the primary while it's healthy, otherwise the first healthy replica
+    /// (the primary again when no replica is up either)
+    fn reader(&self) -> &Surreal<Any> {
+        if self.primary_healthy.load(Ordering::Relaxed) {
+            return &self.db;
+        }
+        self.replicas
+            .iter()
+            .find(|replica| replica.healthy.load(Ordering::Relaxed))
+            .map_or(&self.db, |replica| &replica.db)
+    }
+
+    pub fn has_replicas(&self) -> bool {
+        !self.replicas.is_empty()
+    }
+
+    /// Whether reads are being served by a replica because the primary is down
+    pub fn reading_from_replica(&self) -> bool {
+        !std::ptr::eq(self.reader(), &self.db)
+    }
+
+    /// Ping the primary and every replica, moving reads over or back; run every few seconds
+    pub async fn check_health(&self) {
+        update_health("primary database", &self.primary_healthy, ping_node(&self.db).await);
+        for replica in &self.replicas {
+            update_health(&format!("database replica {}", replica.url), &replica.healthy, ping_node(&replica.db).await);
+        }
     }
 
     /// Initialize database schema
@@ -78,6 +265,7 @@ impl DbClient {
                 r#"
                 DEFINE TABLE IF NOT EXISTS servers SCHEMAFULL;
                 DEFINE FIELD IF NOT EXISTS game_id ON servers TYPE int;
+                DEFINE FIELD IF NOT EXISTS server_id ON servers TYPE option<string>;
                 DEFINE FIELD IF NOT EXISTS name ON servers TYPE string;
                 DEFINE FIELD IF NOT EXISTS description ON servers TYPE string;
                 DEFINE FIELD IF NOT EXISTS max_players ON servers TYPE int;
@@ -89,167 +277,1773 @@ impl DbClient {
                 DEFINE FIELD IF NOT EXISTS mod_count ON servers TYPE int;
                 DEFINE FIELD IF NOT EXISTS game_version ON servers TYPE string;
                 DEFINE FIELD IF NOT EXISTS build_version ON servers TYPE int;
+                DEFINE FIELD IF NOT EXISTS build_mode ON servers TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS platform ON servers TYPE option<string>;
                 DEFINE FIELD IF NOT EXISTS host_address ON servers TYPE option<string>;
                 DEFINE FIELD IF NOT EXISTS headless_server ON servers TYPE bool;
                 DEFINE FIELD IF NOT EXISTS cached_at ON servers TYPE string;
+                DEFINE FIELD IF NOT EXISTS first_seen ON servers TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS last_seen ON servers TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS country ON servers TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS continent ON servers TYPE option<string>;
                 DEFINE INDEX IF NOT EXISTS game_id_idx ON servers FIELDS game_id UNIQUE;
+                DEFINE INDEX IF NOT EXISTS server_id_idx ON servers FIELDS server_id;
                 "#,
             )
             .await?;
 
+        // Full-text indexes for search_servers; edge n-grams let a word match the start of a longer one
+        // ("space" finds "SpaceExploration"), up to 15 characters
+        self.db
+            .query(
+                r#"
+                DEFINE ANALYZER IF NOT EXISTS server_search TOKENIZERS blank, class FILTERS lowercase, ascii, edgengram(2, 15);
+                DEFINE INDEX IF NOT EXISTS servers_name_search ON servers FIELDS name SEARCH ANALYZER server_search BM25;
+                DEFINE INDEX IF NOT EXISTS servers_description_search ON servers FIELDS description SEARCH ANALYZER server_search BM25;
+                DEFINE INDEX IF NOT EXISTS servers_tags_search ON servers FIELDS tags SEARCH ANALYZER server_search BM25;
+                "#,
+            )
+            .await?
+            .check()?;
+
         // Create server_history table
         self.db
             .query(
                 r#"
                 DEFINE TABLE IF NOT EXISTS server_history SCHEMAFULL;
                 DEFINE FIELD IF NOT EXISTS game_id ON server_history TYPE int;
+                DEFINE FIELD IF NOT EXISTS server_id ON server_history TYPE option<string>;
                 DEFINE FIELD IF NOT EXISTS player_count ON server_history TYPE int;
                 DEFINE FIELD IF NOT EXISTS recorded_at ON server_history TYPE string;
                 DEFINE INDEX IF NOT EXISTS history_game_idx ON server_history FIELDS game_id;
+                DEFINE INDEX IF NOT EXISTS history_server_idx ON server_history FIELDS server_id;
                 DEFINE INDEX IF NOT EXISTS history_time_idx ON server_history FIELDS recorded_at;
                 "#,
             )
             .await?;
 
-        Ok(())
-    }
-
-    /// Cache a list of servers from the API (batch operation)
-    /// Uses a transaction to ensure atomicity - either all servers are updated or none are
-    pub async fn cache_servers(&self, servers: Vec<GameServer>) -> Result<usize, DbError> {
-        let start = std::time::Instant::now();
-        let count = servers.len();
-        
-        // Use native insert_many for better performance
-        let new_servers: Vec<NewCachedServer> = servers.into_iter().map(|s| s.into()).collect();
-        
-        // Begin transaction for atomic delete + insert
-        self.db.query("BEGIN TRANSACTION").await?;
-        
-        // Delete all existing servers
-        if let Err(e) = self.db.query("DELETE FROM servers").await {
-            self.db.query("CANCEL TRANSACTION").await.ok();
-            return Err(e.into());
-        }
-        
-        // Insert in batches for better performance
-        const BATCH_SIZE: usize = 500;
-        for chunk in new_servers.chunks(BATCH_SIZE) {
-            if let Err(e) = self.db
-                .insert::<Vec<CachedServer>>("servers")
-                .content(chunk.to_vec())
-                .await
-            {
-                self.db.query("CANCEL TRANSACTION").await.ok();
-                return Err(e.into());
-            }
-        }
-        
-        // Commit transaction
-        self.db.query("COMMIT TRANSACTION").await?;
+        // Create translations table (cached per description hash and target language)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS translations SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS hash ON translations TYPE string;
+                DEFINE FIELD IF NOT EXISTS target ON translations TYPE string;
+                DEFINE FIELD IF NOT EXISTS text ON translations TYPE string;
+                DEFINE FIELD IF NOT EXISTS created_at ON translations TYPE string;
+                DEFINE INDEX IF NOT EXISTS translation_key_idx ON translations FIELDS hash, target UNIQUE;
+                "#,
+            )
+            .await?;
 
-        let elapsed = start.elapsed();
-        if elapsed.as_millis() > 500 {
-            eprintln!("[DB SLOW] cache_servers took {:?} for {} servers", elapsed, count);
-        }
+        // Create mod_metadata table (Mod Portal details, one row per mod name)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS mod_metadata SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS name ON mod_metadata TYPE string;
+                DEFINE FIELD IF NOT EXISTS title ON mod_metadata TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS thumbnail ON mod_metadata TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS downloads ON mod_metadata TYPE int;
+                DEFINE FIELD IF NOT EXISTS category ON mod_metadata TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS fetched_at ON mod_metadata TYPE string;
+                DEFINE INDEX IF NOT EXISTS mod_metadata_name_idx ON mod_metadata FIELDS name UNIQUE;
+                "#,
+            )
+            .await?;
 
-        Ok(count)
-    }
+        // Create ip_reputation table (abuse reports per host IP, one row per address)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS ip_reputation SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS ip ON ip_reputation TYPE string;
+                DEFINE FIELD IF NOT EXISTS reports ON ip_reputation TYPE int;
+                DEFINE FIELD IF NOT EXISTS confidence ON ip_reputation TYPE int;
+                DEFINE FIELD IF NOT EXISTS fetched_at ON ip_reputation TYPE string;
+                DEFINE INDEX IF NOT EXISTS ip_reputation_ip_idx ON ip_reputation FIELDS ip UNIQUE;
+                "#,
+            )
+            .await?;
 
-    /// Record player count for history tracking (batch operation)
-    pub async fn record_player_counts(&self, servers: &[GameServer]) -> Result<(), DbError> {
-        let start = std::time::Instant::now();
-        let now = chrono::Utc::now().to_rfc3339();
+        // Create modpacks table (one fingerprint per game_id, indexed to find servers sharing one)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS modpacks SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS game_id ON modpacks TYPE int;
+                DEFINE FIELD IF NOT EXISTS fingerprint ON modpacks TYPE string;
+                DEFINE FIELD IF NOT EXISTS mod_count ON modpacks TYPE int;
+                DEFINE FIELD IF NOT EXISTS recorded_at ON modpacks TYPE string;
+                DEFINE INDEX IF NOT EXISTS modpack_game_idx ON modpacks FIELDS game_id UNIQUE;
+                DEFINE INDEX IF NOT EXISTS modpack_fingerprint_idx ON modpacks FIELDS fingerprint;
+                "#,
+            )
+            .await?;
 
-        // Only record history for servers with players (significant data reduction)
-        let history_records: Vec<NewServerHistory> = servers
-            .iter()
-            .filter(|server| !server.players.is_empty())
-            .map(|server| NewServerHistory {
-                game_id: server.game_id,
-                player_count: server.players.len(),
-                recorded_at: now.clone(),
-            })
-            .collect();
-        
-        if history_records.is_empty() {
-            return Ok(());
-        }
-        
-        let record_count = history_records.len();
-        
-        // Use native insert for better performance
-        let _: Vec<ServerHistory> = self.db
-            .insert("server_history")
-            .content(history_records)
+        // Create downtime_alerts table (one row per server_id + webhook)
+        // Alerts from before they were keyed on server_id and claimed are dropped; operators register again
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS downtime_alerts SCHEMAFULL;
+                DELETE downtime_alerts WHERE server_id = NONE;
+                REMOVE INDEX IF EXISTS alert_key_idx ON downtime_alerts;
+                REMOVE FIELD IF EXISTS game_id ON downtime_alerts;
+                DEFINE FIELD IF NOT EXISTS server_id ON downtime_alerts TYPE string;
+                DEFINE FIELD IF NOT EXISTS server_name ON downtime_alerts TYPE string;
+                DEFINE FIELD IF NOT EXISTS webhook_url ON downtime_alerts TYPE string;
+                DEFINE FIELD IF NOT EXISTS claim_code ON downtime_alerts TYPE string;
+                DEFINE FIELD IF NOT EXISTS claimed ON downtime_alerts TYPE bool;
+                DEFINE FIELD IF NOT EXISTS empty_since ON downtime_alerts TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS alerted ON downtime_alerts TYPE bool;
+                DEFINE FIELD IF NOT EXISTS created_at ON downtime_alerts TYPE string;
+                DEFINE INDEX IF NOT EXISTS alert_server_idx ON downtime_alerts FIELDS server_id, webhook_url UNIQUE;
+                "#,
+            )
             .await?;
 
-        let elapsed = start.elapsed();
-        if elapsed.as_millis() > 500 {
-            eprintln!("[DB SLOW] record_player_counts took {:?} for {} records", elapsed, record_count);
-        }
+        // Create push_subscriptions table (one row per browser subscription, keyed by endpoint hash)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS push_subscriptions SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS endpoint ON push_subscriptions TYPE string;
+                DEFINE FIELD IF NOT EXISTS p256dh ON push_subscriptions TYPE string;
+                DEFINE FIELD IF NOT EXISTS auth ON push_subscriptions TYPE string;
+                DEFINE FIELD IF NOT EXISTS server_ids ON push_subscriptions TYPE array<string>;
+                DEFINE FIELD IF NOT EXISTS friends ON push_subscriptions TYPE array<string>;
+                DEFINE FIELD IF NOT EXISTS created_at ON push_subscriptions TYPE string;
+                "#,
+            )
+            .await?;
 
-        Ok(())
-    }
+        // Create server_sightings table (first/last seen per server, outlives the servers table)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS server_sightings SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS first_seen ON server_sightings TYPE string;
+                DEFINE FIELD IF NOT EXISTS last_seen ON server_sightings TYPE string;
+                DEFINE INDEX IF NOT EXISTS sightings_last_seen_idx ON server_sightings FIELDS last_seen;
+                "#,
+            )
+            .await?;
 
-    /// Get all cached servers
-    pub async fn get_all_servers(&self) -> Result<Vec<CachedServer>, DbError> {
-        let servers: Vec<CachedServer> = self
-            .db
-            .query("SELECT * FROM servers ORDER BY player_count DESC")
-            .await?
-            .take(0)?;
+        // Create server_profiles table (lifetime stats per server_id, kept after the server is delisted)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS server_profiles SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS server_id ON server_profiles TYPE string;
+                DEFINE FIELD IF NOT EXISTS game_id ON server_profiles TYPE int;
+                DEFINE FIELD IF NOT EXISTS name ON server_profiles TYPE string;
+                DEFINE FIELD IF NOT EXISTS description ON server_profiles TYPE string;
+                DEFINE FIELD IF NOT EXISTS game_version ON server_profiles TYPE string;
+                DEFINE FIELD IF NOT EXISTS tags ON server_profiles TYPE array<string>;
+                DEFINE FIELD IF NOT EXISTS max_players ON server_profiles TYPE int;
+                DEFINE FIELD IF NOT EXISTS mod_count ON server_profiles TYPE int;
+                DEFINE FIELD IF NOT EXISTS peak_players ON server_profiles TYPE int;
+                DEFINE FIELD IF NOT EXISTS player_hours ON server_profiles TYPE float;
+                DEFINE FIELD IF NOT EXISTS first_seen ON server_profiles TYPE string;
+                DEFINE FIELD IF NOT EXISTS last_seen ON server_profiles TYPE string;
+                DEFINE INDEX IF NOT EXISTS profiles_server_idx ON server_profiles FIELDS server_id UNIQUE;
+                DEFINE INDEX IF NOT EXISTS profiles_game_idx ON server_profiles FIELDS game_id;
+                DEFINE INDEX IF NOT EXISTS profiles_last_seen_idx ON server_profiles FIELDS last_seen;
+                "#,
+            )
+            .await?;
 
-        Ok(servers)
-    }
+        // Create server_presence table (one row per server_id per hour it was listed)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS server_presence SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS server_id ON server_presence TYPE string;
+                DEFINE FIELD IF NOT EXISTS hour ON server_presence TYPE string;
+                DEFINE INDEX IF NOT EXISTS presence_key_idx ON server_presence FIELDS server_id, hour UNIQUE;
+                DEFINE INDEX IF NOT EXISTS presence_hour_idx ON server_presence FIELDS hour;
+                "#,
+            )
+            .await?;
 
-    /// Get a specific server by game_id
-    pub async fn get_server(&self, game_id: u64) -> Result<Option<CachedServer>, DbError> {
-        let mut result: Vec<CachedServer> = self
-            .db
-            .query("SELECT * FROM servers WHERE game_id = $game_id")
-            .bind(("game_id", game_id))
-            .await?
-            .take(0)?;
+        // Create listing_totals table (listing-wide counts, one row per refresh, for the stats page)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS listing_totals SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS recorded_at ON listing_totals TYPE string;
+                DEFINE FIELD IF NOT EXISTS hour ON listing_totals TYPE string;
+                DEFINE FIELD IF NOT EXISTS servers ON listing_totals TYPE int;
+                DEFINE FIELD IF NOT EXISTS active_servers ON listing_totals TYPE int;
+                DEFINE FIELD IF NOT EXISTS players ON listing_totals TYPE int;
+                DEFINE INDEX IF NOT EXISTS listing_totals_hour_idx ON listing_totals FIELDS hour;
+                "#,
+            )
+            .await?;
 
-        Ok(result.pop())
-    }
+        // Create version_totals table (servers and players per game version per hour, for adoption history)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS version_totals SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS hour ON version_totals TYPE string;
+                DEFINE FIELD IF NOT EXISTS version ON version_totals TYPE string;
+                DEFINE FIELD IF NOT EXISTS servers ON version_totals TYPE int;
+                DEFINE FIELD IF NOT EXISTS players ON version_totals TYPE int;
+                DEFINE INDEX IF NOT EXISTS version_totals_hour_idx ON version_totals FIELDS hour;
+                "#,
+            )
+            .await?;
 
-    /// Get player count history for a server
-    pub async fn get_server_history(
-        &self,
-        game_id: u64,
-        hours: u32,
-    ) -> Result<Vec<ServerHistory>, DbError> {
-        let history: Vec<ServerHistory> = self
-            .db
+        // Create materialized_results table (precomputed aggregates keyed by name)
+        self.db
             .query(
                 r#"
-                SELECT * FROM server_history 
-                WHERE game_id = $game_id 
-                ORDER BY recorded_at DESC 
-                LIMIT $limit
+                DEFINE TABLE IF NOT EXISTS materialized_results SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS key ON materialized_results TYPE string;
+                DEFINE FIELD IF NOT EXISTS payload ON materialized_results TYPE string;
+                DEFINE FIELD IF NOT EXISTS generated_at ON materialized_results TYPE string;
+                DEFINE INDEX IF NOT EXISTS materialized_key_idx ON materialized_results FIELDS key UNIQUE;
                 "#,
             )
-            .bind(("game_id", game_id))
-            .bind(("limit", hours * 60)) // Assuming ~1 record per minute
-            .await?
-            .take(0)?;
+            .await?;
 
-        Ok(history)
-    }
+        // Create accounts, sessions and filter_presets tables (saved filters for signed-in users)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS accounts SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS email ON accounts TYPE string;
+                DEFINE FIELD IF NOT EXISTS password_hash ON accounts TYPE string;
+                DEFINE FIELD IF NOT EXISTS created_at ON accounts TYPE string;
+                DEFINE INDEX IF NOT EXISTS account_email_idx ON accounts FIELDS email UNIQUE;
 
-    /// Clean up old history records (keep last 24 hours)
-    pub async fn cleanup_old_history(&self) -> Result<(), DbError> {
-        let cutoff = chrono::Utc::now() - chrono::Duration::hours(24);
+                DEFINE TABLE IF NOT EXISTS sessions SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS token_hash ON sessions TYPE string;
+                DEFINE FIELD IF NOT EXISTS email ON sessions TYPE string;
+                DEFINE FIELD IF NOT EXISTS expires_at ON sessions TYPE string;
+                DEFINE INDEX IF NOT EXISTS session_token_idx ON sessions FIELDS token_hash UNIQUE;
+                DEFINE INDEX IF NOT EXISTS session_expires_idx ON sessions FIELDS expires_at;
 
+                DEFINE TABLE IF NOT EXISTS filter_presets SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS email ON filter_presets TYPE string;
+                DEFINE FIELD IF NOT EXISTS name ON filter_presets TYPE string;
+                DEFINE FIELD IF NOT EXISTS search ON filter_presets TYPE string;
+                DEFINE FIELD IF NOT EXISTS version ON filter_presets TYPE string;
+                DEFINE FIELD IF NOT EXISTS tags ON filter_presets TYPE array<string>;
+                DEFINE FIELD IF NOT EXISTS created_at ON filter_presets TYPE string;
+                DEFINE INDEX IF NOT EXISTS preset_key_idx ON filter_presets FIELDS email, name UNIQUE;
+                "#,
+            )
+            .await?;
+
+        // Create hidden_servers table (one row per server_id an admin hid, kept after a restore)
         self.db
-            .query("DELETE FROM server_history WHERE recorded_at < $cutoff")
-            .bind(("cutoff", cutoff.to_rfc3339()))
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS hidden_servers SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS server_id ON hidden_servers TYPE string;
+                DEFINE FIELD IF NOT EXISTS game_id ON hidden_servers TYPE int;
+                DEFINE FIELD IF NOT EXISTS name ON hidden_servers TYPE string;
+                DEFINE FIELD IF NOT EXISTS host_address ON hidden_servers TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS reason ON hidden_servers TYPE string;
+                DEFINE FIELD IF NOT EXISTS hidden ON hidden_servers TYPE bool;
+                DEFINE FIELD IF NOT EXISTS hidden_at ON hidden_servers TYPE string;
+                DEFINE FIELD IF NOT EXISTS restored_at ON hidden_servers TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS reappeared_from ON hidden_servers TYPE option<string>;
+                "#,
+            )
             .await?;
 
-        Ok(())
-    }
+        // Create moderation_log table (append-only record of hides and restores, shown on /transparency)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS moderation_log SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS action ON moderation_log TYPE string;
+                DEFINE FIELD IF NOT EXISTS name ON moderation_log TYPE string;
+                DEFINE FIELD IF NOT EXISTS reason ON moderation_log TYPE string;
+                DEFINE FIELD IF NOT EXISTS at ON moderation_log TYPE string;
+                DEFINE INDEX IF NOT EXISTS moderation_at_idx ON moderation_log FIELDS at;
+                "#,
+            )
+            .await?;
 
-}
+        // Create api_keys table (keys for the JSON API, stored as hashes; revoked keys are kept)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS api_keys SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS name ON api_keys TYPE string;
+                DEFINE FIELD IF NOT EXISTS key_hash ON api_keys TYPE string;
+                DEFINE FIELD IF NOT EXISTS per_minute ON api_keys TYPE int;
+                DEFINE FIELD IF NOT EXISTS created_at ON api_keys TYPE string;
+                DEFINE FIELD IF NOT EXISTS revoked ON api_keys TYPE bool;
+                DEFINE INDEX IF NOT EXISTS api_key_hash_idx ON api_keys FIELDS key_hash UNIQUE;
+                "#,
+            )
+            .await?;
+
+        // Create server_reports table (visitors flagging listed servers, reviewed on the admin page)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS server_reports SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS game_id ON server_reports TYPE int;
+                DEFINE FIELD IF NOT EXISTS server_id ON server_reports TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS name ON server_reports TYPE string;
+                DEFINE FIELD IF NOT EXISTS reason ON server_reports TYPE string;
+                DEFINE FIELD IF NOT EXISTS details ON server_reports TYPE string;
+                DEFINE FIELD IF NOT EXISTS reporter ON server_reports TYPE string;
+                DEFINE FIELD IF NOT EXISTS reported_at ON server_reports TYPE string;
+                DEFINE FIELD IF NOT EXISTS dismissed ON server_reports TYPE bool;
+                DEFINE INDEX IF NOT EXISTS reports_reporter_idx ON server_reports FIELDS reporter, game_id;
+                DEFINE INDEX IF NOT EXISTS reports_reporter_server_idx ON server_reports FIELDS reporter, server_id;
+                "#,
+            )
+            .await?;
 
+        // Create blocklist table (spam listings an admin blocked by server_id, host or name pattern)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS blocklist SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS kind ON blocklist TYPE string;
+                DEFINE FIELD IF NOT EXISTS pattern ON blocklist TYPE string;
+                DEFINE FIELD IF NOT EXISTS reason ON blocklist TYPE string;
+                DEFINE FIELD IF NOT EXISTS added_at ON blocklist TYPE string;
+                "#,
+            )
+            .await?;
+
+        // Record the schema version last, so it's only there once everything above is set up
+        // (never lowered, so an older release started by mistake doesn't hide the newer schema)
+        self.db
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS schema_meta SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS version ON schema_meta TYPE int;
+                DEFINE FIELD IF NOT EXISTS demo_since ON schema_meta TYPE option<string>;
+                UPSERT schema_meta:current SET version = IF version > $version THEN version ELSE $version END;
+                "#,
+            )
+            .bind(("version", SCHEMA_VERSION))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Schema version recorded in the primary database, None when it was never set up
+    /// (a new database, or one set up before versions were recorded)
+    pub async fn get_schema_version(&self) -> Result<Option<u32>, DbError> {
+        let version: Option<u32> = self
+            .db
+            .query("SELECT VALUE version FROM ONLY schema_meta:current")
+            .await?
+            .take(0)?;
+
+        Ok(version)
+    }
+
+    /// Note that the demo snapshot is being stored, from now unless it already was
+    pub async fn mark_demo_data(&self) -> Result<(), DbError> {
+        self.db
+            .query("UPSERT schema_meta:current SET demo_since = demo_since ?? $now")
+            .bind(("now", chrono::Utc::now().to_rfc3339()))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Remove what the demo snapshot left behind, once real credentials are configured: rows of
+    /// servers whose server_id has `prefix`, and the statistics recorded since the demo started.
+    /// Returns whether there was demo data to clear
+    pub async fn clear_demo_data(&self, prefix: &str) -> Result<bool, DbError> {
+        let since: Option<String> = self
+            .db
+            .query("SELECT VALUE demo_since FROM ONLY schema_meta:current")
+            .await?
+            .take(0)?;
+        let Some(since) = since.as_deref().and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok()) else {
+            return Ok(false);
+        };
+        let since_hour = crate::stats::hour_bucket(since.with_timezone(&chrono::Utc));
+
+        self.db
+            .query(
+                r#"
+                BEGIN TRANSACTION;
+                LET $game_ids = array::union(
+                    (SELECT VALUE game_id FROM servers WHERE string::starts_with(server_id ?? '', $prefix)),
+                    (SELECT VALUE game_id FROM server_history WHERE string::starts_with(server_id ?? '', $prefix))
+                );
+                DELETE servers WHERE string::starts_with(server_id ?? '', $prefix);
+                DELETE server_history WHERE string::starts_with(server_id ?? '', $prefix);
+                DELETE server_sightings WHERE string::starts_with(<string>record::id(id), $prefix);
+                DELETE server_profiles WHERE string::starts_with(server_id, $prefix);
+                DELETE server_presence WHERE string::starts_with(server_id, $prefix);
+                DELETE server_reports WHERE string::starts_with(server_id ?? '', $prefix);
+                DELETE downtime_alerts WHERE string::starts_with(server_id, $prefix);
+                DELETE modpacks WHERE game_id INSIDE $game_ids;
+                DELETE listing_totals WHERE hour >= $since_hour;
+                DELETE version_totals WHERE hour >= $since_hour;
+                DELETE materialized_results;
+                UPDATE schema_meta:current SET demo_since = NONE;
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("prefix", prefix.to_string()))
+            .bind(("since_hour", since_hour))
+            .await?
+            .check()?;
+
+        Ok(true)
+    }
+
+    /// Cache a list of servers from the API (batch operation)
+    /// Upserts each server keyed on server_id (game_id when missing) so fields not in the
+    /// listing survive refreshes, and removes servers that are no longer listed.
+    /// First/last seen times are tracked in server_sightings, which keeps them across
+    /// short absences, and copied onto each cached server.
+    /// Runs in a single transaction - either all servers are updated or none are
+    /// With a GeoIP database, each server's country and continent are resolved from host_address
+    /// Tags are normalized (markup stripped, case folded, aliases resolved) before they're stored
+    pub async fn cache_servers(
+        &self,
+        servers: Vec<GameServer>,
+        geoip: Option<&GeoIp>,
+        tag_aliases: &TagAliases,
+    ) -> Result<usize, DbError> {
+        self.cache_servers_at(servers, chrono::Utc::now(), geoip, tag_aliases).await
+    }
+
+    /// cache_servers as of a given time, for replaying recorded responses
+    pub async fn cache_servers_at(
+        &self,
+        servers: Vec<GameServer>,
+        at: chrono::DateTime<chrono::Utc>,
+        geoip: Option<&GeoIp>,
+        tag_aliases: &TagAliases,
+    ) -> Result<usize, DbError> {
+        let start = std::time::Instant::now();
+        let count = servers.len();
+        let now = at.to_rfc3339();
+
+        let records: Vec<CacheRecord> = servers
+            .into_iter()
+            .map(|server| {
+                let key = server_record_key(&server);
+                let region = geoip.zip(server.host_address.as_deref()).and_then(|(g, host)| g.lookup(host));
+                let mut data = NewCachedServer::from(server);
+                data.cached_at = now.clone();
+                data.tags = tag_aliases.normalize_all(&data.tags);
+                if let Some(region) = region {
+                    data.country = region.country;
+                    data.continent = region.continent;
+                }
+                CacheRecord { key, data }
+            })
+            .collect();
+        let keys: Vec<String> = records.iter().map(|r| r.key.clone()).collect();
+
+        // Delete stale rows first so a server changing key can't collide on the game_id index
+        self.db
+            .query(
+                r#"
+                BEGIN TRANSACTION;
+                DELETE FROM servers WHERE record::id(id) NOTINSIDE $keys;
+                FOR $record IN $records {
+                    LET $seen = (UPSERT type::thing('server_sightings', $record.key)
+                        SET first_seen = first_seen ?? $now, last_seen = $now)[0];
+                    UPSERT type::thing('servers', $record.key) MERGE object::from_entries(array::concat(
+                        object::entries($record.data),
+                        [['first_seen', $seen.first_seen], ['last_seen', $seen.last_seen]]
+                    ));
+                    IF $record.data.server_id != NONE {
+                        UPSERT type::thing('server_profiles', $record.key) SET
+                            player_hours = (player_hours ?? 0f) + (
+                                IF last_seen != NONE AND <datetime>$now > <datetime>last_seen
+                                    AND duration::secs(<datetime>$now - <datetime>last_seen) <= $max_gap
+                                THEN $record.data.player_count * duration::secs(<datetime>$now - <datetime>last_seen) / 3600f
+                                ELSE 0f END
+                            ),
+                            peak_players = math::max([peak_players ?? 0, $record.data.player_count]),
+                            server_id = $record.data.server_id,
+                            game_id = $record.data.game_id,
+                            name = $record.data.name,
+                            description = $record.data.description,
+                            game_version = $record.data.game_version,
+                            tags = $record.data.tags,
+                            max_players = $record.data.max_players,
+                            mod_count = $record.data.mod_count,
+                            first_seen = first_seen ?? $now,
+                            last_seen = $now;
+                    };
+                };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("keys", keys))
+            .bind(("records", records))
+            .bind(("now", now))
+            .bind(("max_gap", PLAYER_HOURS_MAX_GAP_SECS))
+            .await?
+            .check()?;
+
+        let elapsed = start.elapsed();
+        if elapsed.as_millis() > 500 {
+            eprintln!("[DB SLOW] cache_servers took {:?} for {} servers", elapsed, count);
+        }
+
+        Ok(count)
+    }
+
+    /// Record player count for history tracking (batch operation)
+    /// Which servers to record is decided by the caller's HistorySampler
+    pub async fn record_player_counts(&self, servers: &[&GameServer]) -> Result<(), DbError> {
+        let now = chrono::Utc::now().to_rfc3339();
+        let history_records = servers
+            .iter()
+            .map(|server| NewServerHistory::from_server(server, &now))
+            .collect();
+
+        self.insert_history(history_records).await
+    }
+
+    /// Insert a batch of history records
+    pub async fn insert_history(&self, history_records: Vec<NewServerHistory>) -> Result<(), DbError> {
+        if history_records.is_empty() {
+            return Ok(());
+        }
+
+        let start = std::time::Instant::now();
+        let record_count = history_records.len();
+        
+        // Use native insert for better performance
+        let _: Vec<ServerHistory> = self.db
+            .insert("server_history")
+            .content(history_records)
+            .await?;
+
+        let elapsed = start.elapsed();
+        if elapsed.as_millis() > 500 {
+            eprintln!("[DB SLOW] insert_history took {:?} for {} records", elapsed, record_count);
+        }
+
+        Ok(())
+    }
+
+    /// Get all cached servers
+    pub async fn get_all_servers(&self) -> Result<Vec<CachedServer>, DbError> {
+        let servers: Vec<CachedServer> = self
+            .reader()
+            .query("SELECT * FROM servers ORDER BY player_count DESC")
+            .await?
+            .take(0)?;
+
+        Ok(servers)
+    }
+
+    /// Listed servers whose name, description or tags contain every word of `query` (as a word or the
+    /// start of one), most relevant first; name matches count double
+    pub async fn search_servers(&self, query: &str) -> Result<Vec<GameId>, DbError> {
+        let query = query.split_whitespace().take(MAX_SEARCH_WORDS).collect::<Vec<_>>().join(" ");
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let game_ids: Vec<GameId> = self
+            .reader()
+            .query(
+                "SELECT VALUE game_id FROM (
+                    SELECT game_id, search::score(0) * 2 + search::score(1) + search::score(2) AS relevance FROM servers
+                    WHERE name @0@ $query OR description @1@ $query OR tags @2@ $query
+                    ORDER BY relevance DESC
+                )",
+            )
+            .bind(("query", query))
+            .await?
+            .take(0)?;
+
+        Ok(game_ids)
+    }
+
+    /// `spec` with its search answered by search_servers. The search falls back to matching substrings
+    /// in memory when the database can't answer it: a word is too short for the index ("a", "2"),
+    /// nothing matched word starts (a search inside a word like "exploration"), or the query failed
+    pub async fn with_search(&self, spec: FilterSpec) -> FilterSpec {
+        if spec.search.is_empty() {
+            return spec;
+        }
+        if spec.search.split_whitespace().any(|word| word.chars().count() < MIN_SEARCH_WORD_CHARS) {
+            return spec;
+        }
+        match self.search_servers(&spec.search).await {
+            Ok(hits) if hits.is_empty() => spec,
+            Ok(hits) => spec.with_search_hits(&hits),
+            Err(e) => {
+                eprintln!("Failed to search servers: {}", e);
+                spec
+            }
+        }
+    }
+
+    /// Get a specific server by game_id
+    pub async fn get_server(&self, game_id: GameId) -> Result<Option<CachedServer>, DbError> {
+        let mut result: Vec<CachedServer> = self
+            .reader()
+            .query("SELECT * FROM servers WHERE game_id = $game_id")
+            .bind(("game_id", game_id))
+            .await?
+            .take(0)?;
+
+        Ok(result.pop())
+    }
+
+    /// Get a cached server by its stable server_id
+    pub async fn get_server_by_server_id(&self, server_id: &ServerId) -> Result<Option<CachedServer>, DbError> {
+        let mut result: Vec<CachedServer> = self
+            .reader()
+            .query("SELECT * FROM servers WHERE server_id = $server_id")
+            .bind(("server_id", server_id.clone()))
+            .await?
+            .take(0)?;
+
+        Ok(result.pop())
+    }
+
+    /// Get player count history for a server
+    /// With a server_id, history follows the server across restarts (which change game_id)
+    pub async fn get_server_history(
+        &self,
+        game_id: GameId,
+        server_id: Option<&ServerId>,
+        hours: u32,
+    ) -> Result<Vec<ServerHistory>, DbError> {
+        let filter = if server_id.is_some() {
+            "server_id = $server_id OR game_id = $game_id"
+        } else {
+            "game_id = $game_id"
+        };
+
+        let history: Vec<ServerHistory> = self
+            .reader()
+            .query(format!(
+                r#"
+                SELECT * FROM server_history 
+                WHERE {filter}
+                ORDER BY recorded_at DESC 
+                LIMIT $limit
+                "#
+            ))
+            .bind(("game_id", game_id))
+            .bind(("server_id", server_id.cloned()))
+            .bind(("limit", hours * 60)) // Assuming ~1 record per minute
+            .await?
+            .take(0)?;
+
+        Ok(history)
+    }
+
+    /// Find the server_id last recorded for a game_id, to follow a server after it restarts
+    pub async fn find_server_id(&self, game_id: GameId) -> Result<Option<ServerId>, DbError> {
+        let mut result: Vec<ServerId> = self
+            .reader()
+            .query("SELECT VALUE server_id FROM server_history WHERE game_id = $game_id AND server_id != NONE LIMIT 1")
+            .bind(("game_id", game_id))
+            .await?
+            .take(0)?;
+
+        Ok(result.pop())
+    }
+
+    /// Get each server's peak player count over the last `hours`
+    pub async fn get_peak_players(&self, hours: i64) -> Result<Vec<PeakPlayers>, DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(hours);
+
+        let peaks: Vec<PeakPlayers> = self
+            .reader()
+            .query(
+                r#"
+                SELECT game_id, math::max(player_count) AS peak FROM server_history
+                WHERE recorded_at >= $cutoff
+                GROUP BY game_id
+                "#,
+            )
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(peaks)
+    }
+
+    /// Get each server's average player count over the last `hours`
+    pub async fn get_average_players(&self, hours: i64) -> Result<Vec<AveragePlayers>, DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(hours);
+
+        let averages: Vec<AveragePlayers> = self
+            .reader()
+            .query(
+                r#"
+                SELECT game_id, math::mean(player_count) AS average FROM server_history
+                WHERE recorded_at >= $cutoff
+                GROUP BY game_id
+                "#,
+            )
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(averages)
+    }
+
+    /// Servers with players recorded in the last `hours`, by game_id and server_id
+    pub async fn get_active_servers(&self, hours: i64) -> Result<Vec<ActiveServer>, DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(hours);
+
+        let active: Vec<ActiveServer> = self
+            .reader()
+            .query(
+                r#"
+                SELECT game_id, server_id FROM server_history
+                WHERE recorded_at >= $cutoff AND player_count > 0
+                GROUP BY game_id, server_id
+                "#,
+            )
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(active)
+    }
+
+    /// Clean up old history records (keep last HISTORY_RETENTION_HOURS)
+    pub async fn cleanup_old_history(&self) -> Result<(), DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(HISTORY_RETENTION_HOURS);
+
+        self.db
+            .query("DELETE FROM server_history WHERE recorded_at < $cutoff")
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Profiles of servers listed since `since`, for federated instances to import
+    pub async fn get_federated_profiles(&self, since: &str) -> Result<Vec<FederatedProfile>, DbError> {
+        let profiles: Vec<FederatedProfile> = self
+            .reader()
+            .query(
+                r#"
+                SELECT server_id, game_id, name, description, game_version, tags, max_players, mod_count,
+                    peak_players, first_seen, last_seen
+                FROM server_profiles
+                WHERE last_seen >= $since
+                "#,
+            )
+            .bind(("since", since.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(profiles)
+    }
+
+    /// History records with a server_id recorded after `after`, oldest first, about `limit` at a time
+    /// A page never ends partway through one refresh's records, so the last recorded_at on it is a
+    /// safe cursor for the next page; the flag says whether there may be more
+    pub async fn get_federated_history(&self, after: &str, limit: usize) -> Result<(Vec<FederatedHistory>, bool), DbError> {
+        let mut history: Vec<FederatedHistory> = self
+            .reader()
+            .query(
+                r#"
+                SELECT server_id, game_id, player_count, recorded_at FROM server_history
+                WHERE server_id != NONE AND recorded_at > $after
+                ORDER BY recorded_at ASC
+                LIMIT $limit
+                "#,
+            )
+            .bind(("after", after.to_string()))
+            .bind(("limit", limit))
+            .await?
+            .take(0)?;
+
+        if history.len() < limit {
+            return Ok((history, false));
+        }
+        let Some(last) = history.last().map(|h| h.recorded_at.clone()) else {
+            return Ok((history, false));
+        };
+        let rest: Vec<FederatedHistory> = self
+            .reader()
+            .query(
+                "SELECT server_id, game_id, player_count, recorded_at FROM server_history \
+                 WHERE server_id != NONE AND recorded_at = $last",
+            )
+            .bind(("last", last.clone()))
+            .await?
+            .take(0)?;
+        history.retain(|h| h.recorded_at != last);
+        history.extend(rest);
+
+        Ok((history, true))
+    }
+
+    /// When this instance refreshed the listing between `from` and `to`, from its listing totals
+    pub async fn get_refresh_times(&self, from: &str, to: &str) -> Result<Vec<String>, DbError> {
+        let times: Vec<String> = self
+            .reader()
+            .query("SELECT VALUE recorded_at FROM listing_totals WHERE recorded_at >= $from AND recorded_at <= $to")
+            .bind(("from", from.to_string()))
+            .bind(("to", to.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(times)
+    }
+
+    /// History already stored for these servers between `from` and `to`
+    pub async fn get_stored_history(
+        &self,
+        server_ids: Vec<ServerId>,
+        from: &str,
+        to: &str,
+    ) -> Result<Vec<FederatedHistory>, DbError> {
+        if server_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let history: Vec<FederatedHistory> = self
+            .reader()
+            .query(
+                r#"
+                SELECT server_id, game_id, player_count, recorded_at FROM server_history
+                WHERE server_id INSIDE $server_ids AND recorded_at >= $from AND recorded_at <= $to
+                "#,
+            )
+            .bind(("server_ids", server_ids))
+            .bind(("from", from.to_string()))
+            .bind(("to", to.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(history)
+    }
+
+    /// Merge profiles from a federated instance into server_profiles and server_sightings
+    /// First seen takes the earlier time and last seen and peak the later/higher one; name, version
+    /// and settings come from whichever instance saw the server last. Player hours stay local
+    /// Times are compared as datetimes, so callers must pass RFC 3339 ones (see federation::clean_profiles)
+    pub async fn merge_federated_profiles(&self, profiles: Vec<FederatedProfile>) -> Result<(), DbError> {
+        if profiles.is_empty() {
+            return Ok(());
+        }
+
+        self.db
+            .query(
+                r#"
+                BEGIN TRANSACTION;
+                FOR $profile IN $profiles {
+                    LET $seen = type::thing('server_profiles', $profile.server_id).last_seen;
+                    LET $newer = IF $seen = NONE THEN true ELSE <datetime>$seen < <datetime>$profile.last_seen END;
+                    UPSERT type::thing('server_profiles', $profile.server_id) SET
+                        server_id = $profile.server_id,
+                        game_id = IF $newer THEN $profile.game_id ELSE game_id END,
+                        name = IF $newer THEN $profile.name ELSE name END,
+                        description = IF $newer THEN $profile.description ELSE description END,
+                        game_version = IF $newer THEN $profile.game_version ELSE game_version END,
+                        tags = IF $newer THEN $profile.tags ELSE tags END,
+                        max_players = IF $newer THEN $profile.max_players ELSE max_players END,
+                        mod_count = IF $newer THEN $profile.mod_count ELSE mod_count END,
+                        peak_players = math::max([peak_players ?? 0, $profile.peak_players]),
+                        player_hours = player_hours ?? 0f,
+                        first_seen = IF first_seen = NONE OR <datetime>first_seen > <datetime>$profile.first_seen THEN $profile.first_seen ELSE first_seen END,
+                        last_seen = IF $newer THEN $profile.last_seen ELSE last_seen END;
+                    UPSERT type::thing('server_sightings', $profile.server_id) SET
+                        first_seen = IF first_seen = NONE OR <datetime>first_seen > <datetime>$profile.first_seen THEN $profile.first_seen ELSE first_seen END,
+                        last_seen = IF last_seen = NONE OR <datetime>last_seen < <datetime>$profile.last_seen THEN $profile.last_seen ELSE last_seen END;
+                };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind(("profiles", profiles))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Record that servers were listed during the given hour
+    /// Callers should only pass server_ids not yet recorded for this hour
+    pub async fn record_presence(&self, server_ids: Vec<ServerId>, hour: &str) -> Result<(), DbError> {
+        if server_ids.is_empty() {
+            return Ok(());
+        }
+
+        let records: Vec<NewServerPresence> = server_ids
+            .into_iter()
+            .map(|server_id| NewServerPresence {
+                server_id,
+                hour: hour.to_string(),
+            })
+            .collect();
+
+        self.db
+            .query("INSERT IGNORE INTO server_presence $records")
+            .bind(("records", records))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Uptime percentage of a server over the last `hours` hours
+    /// Measured from the first hour the server was seen within the window, so newly
+    /// tracked servers aren't penalized. Returns None if the server was never seen.
+    pub async fn get_uptime(&self, server_id: &ServerId, hours: i64) -> Result<Option<f64>, DbError> {
+        let cutoff = crate::stats::hour_bucket(chrono::Utc::now() - chrono::Duration::hours(hours - 1));
+        let presence: Vec<ServerPresence> = self
+            .reader()
+            .query("SELECT * FROM server_presence WHERE server_id = $server_id AND hour >= $cutoff ORDER BY hour ASC")
+            .bind(("server_id", server_id.clone()))
+            .bind(("cutoff", cutoff))
+            .await?
+            .take(0)?;
+
+        let Some(first) = presence
+            .first()
+            .and_then(|p| chrono::DateTime::parse_from_rfc3339(&p.hour).ok())
+        else {
+            return Ok(None);
+        };
+
+        let tracked_hours = (chrono::Utc::now() - first.with_timezone(&chrono::Utc)).num_hours() + 1;
+        let uptime = presence.len() as f64 / tracked_hours.clamp(1, hours) as f64 * 100.0;
+
+        Ok(Some(uptime.min(100.0)))
+    }
+
+    /// Hours each server was listed within the last `hours`, for ranking all servers at once
+    pub async fn get_presence_hours(&self, hours: i64) -> Result<Vec<PresenceHours>, DbError> {
+        let cutoff = crate::stats::hour_bucket(chrono::Utc::now() - chrono::Duration::hours(hours - 1));
+        let presence: Vec<PresenceHours> = self
+            .reader()
+            .query("SELECT server_id, count() AS hours FROM server_presence WHERE hour >= $cutoff GROUP BY server_id")
+            .bind(("cutoff", cutoff))
+            .await?
+            .take(0)?;
+
+        Ok(presence)
+    }
+
+    /// Clean up presence records older than PRESENCE_RETENTION_HOURS
+    pub async fn cleanup_old_presence(&self) -> Result<(), DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(PRESENCE_RETENTION_HOURS);
+
+        self.db
+            .query("DELETE FROM server_presence WHERE hour < $cutoff")
+            .bind(("cutoff", crate::stats::hour_bucket(cutoff)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record listing-wide totals for one refresh
+    pub async fn record_listing_totals(&self, totals: NewListingTotals) -> Result<(), DbError> {
+        self.db
+            .query("CREATE listing_totals CONTENT $totals")
+            .bind(("totals", totals))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Listing-wide totals over the last `hours`, averaged per hour, oldest first
+    pub async fn get_hourly_totals(&self, hours: i64) -> Result<Vec<HourlyTotals>, DbError> {
+        let cutoff = crate::stats::hour_bucket(chrono::Utc::now() - chrono::Duration::hours(hours - 1));
+        let totals: Vec<HourlyTotals> = self
+            .reader()
+            .query(
+                r#"
+                SELECT hour, math::mean(servers) AS servers, math::mean(active_servers) AS active_servers,
+                    math::mean(players) AS players
+                FROM listing_totals
+                WHERE hour >= $cutoff
+                GROUP BY hour
+                ORDER BY hour ASC
+                "#,
+            )
+            .bind(("cutoff", cutoff))
+            .await?
+            .take(0)?;
+
+        Ok(totals)
+    }
+
+    /// Record per-version totals for the current hour, replacing earlier refreshes in the same hour
+    pub async fn record_version_totals(&self, records: Vec<NewVersionTotals>) -> Result<(), DbError> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        self.db
+            .query("FOR $record IN $records { UPSERT type::thing('version_totals', [$record.hour, $record.version]) CONTENT $record; }")
+            .bind(("records", records))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Per-version totals over the last `hours`, oldest first
+    pub async fn get_version_totals(&self, hours: i64) -> Result<Vec<VersionTotals>, DbError> {
+        let cutoff = crate::stats::hour_bucket(chrono::Utc::now() - chrono::Duration::hours(hours - 1));
+        let totals: Vec<VersionTotals> = self
+            .reader()
+            .query("SELECT hour, version, servers, players FROM version_totals WHERE hour >= $cutoff ORDER BY hour ASC")
+            .bind(("cutoff", cutoff))
+            .await?
+            .take(0)?;
+
+        Ok(totals)
+    }
+
+    /// Clean up per-version totals older than VERSION_HISTORY_RETENTION_HOURS
+    pub async fn cleanup_old_version_totals(&self) -> Result<(), DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(VERSION_HISTORY_RETENTION_HOURS);
+
+        self.db
+            .query("DELETE FROM version_totals WHERE hour < $cutoff")
+            .bind(("cutoff", crate::stats::hour_bucket(cutoff)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Clean up listing totals older than HISTORY_RETENTION_HOURS
+    pub async fn cleanup_old_listing_totals(&self) -> Result<(), DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(HISTORY_RETENTION_HOURS);
+
+        self.db
+            .query("DELETE FROM listing_totals WHERE hour < $cutoff")
+            .bind(("cutoff", crate::stats::hour_bucket(cutoff)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Forget first/last seen times of servers not listed for SIGHTING_RETENTION_HOURS
+    pub async fn cleanup_old_sightings(&self) -> Result<(), DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(SIGHTING_RETENTION_HOURS);
+
+        self.db
+            .query("DELETE FROM server_sightings WHERE last_seen < $cutoff")
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Profile of a server that isn't on the listing (the caller checks the current listing)
+    pub async fn get_server_profile(&self, server_id: &ServerId) -> Result<Option<ServerProfile>, DbError> {
+        let mut result: Vec<ServerProfile> = self
+            .reader()
+            .query("SELECT * FROM server_profiles WHERE server_id = $server_id")
+            .bind(("server_id", server_id.clone()))
+            .await?
+            .take(0)?;
+
+        Ok(result.pop())
+    }
+
+    /// server_id of an archived server whose last listing had this game_id
+    pub async fn find_archived_server_id(&self, game_id: GameId) -> Result<Option<ServerId>, DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(ARCHIVE_AFTER_HOURS);
+
+        let mut result: Vec<ServerId> = self
+            .reader()
+            .query("SELECT VALUE server_id FROM server_profiles WHERE game_id = $game_id AND last_seen < $cutoff LIMIT 1")
+            .bind(("game_id", game_id))
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(result.pop())
+    }
+
+    /// Servers off the listing whose last name, description or tags contain every word of `query`
+    /// (case-insensitive), most recently seen first; `unlisted_before` bounds their last sighting and
+    /// `listed` excludes servers on the current listing
+    pub async fn search_unlisted_servers(
+        &self,
+        query: &str,
+        unlisted_before: chrono::DateTime<chrono::Utc>,
+        listed: &[ServerId],
+        limit: usize,
+    ) -> Result<Vec<ServerProfile>, DbError> {
+        let words: Vec<String> = query.to_lowercase().split_whitespace().take(MAX_SEARCH_WORDS).map(str::to_string).collect();
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conditions: Vec<String> = (0..words.len())
+            .map(|i| {
+                format!(
+                    "(string::contains(string::lowercase(name), $word{i}) \
+                     OR string::contains(string::lowercase(description), $word{i}) \
+                     OR string::contains(string::lowercase(array::join(tags, ' ')), $word{i}))"
+                )
+            })
+            .collect();
+        let sql = format!(
+            "SELECT * FROM server_profiles WHERE last_seen < $cutoff AND server_id NOTINSIDE $listed AND {} \
+             ORDER BY last_seen DESC LIMIT $limit",
+            conditions.join(" AND ")
+        );
+
+        let mut request = self
+            .reader()
+            .query(sql)
+            .bind(("cutoff", unlisted_before.to_rfc3339()))
+            .bind(("listed", listed.to_vec()))
+            .bind(("limit", limit));
+        for (i, word) in words.into_iter().enumerate() {
+            request = request.bind((format!("word{}", i), word));
+        }
+        let profiles: Vec<ServerProfile> = request.await?.take(0)?;
+
+        Ok(profiles)
+    }
+
+    /// Store (or replace) a precomputed aggregate under `key`
+    pub async fn store_materialized<T: Serialize>(&self, key: &str, value: &T) -> Result<(), DbError> {
+        let record = NewMaterializedResult {
+            key: key.to_string(),
+            payload: serde_json::to_string(value).map_err(|e| DbError::Query(e.to_string()))?,
+            generated_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        self.db
+            .query("UPSERT type::thing('materialized_results', $key) CONTENT $record")
+            .bind(("key", key.to_string()))
+            .bind(("record", record))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Get a precomputed aggregate by key
+    pub async fn get_materialized<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<Option<Materialized<T>>, DbError> {
+        let mut result: Vec<MaterializedResult> = self
+            .reader()
+            .query("SELECT * FROM materialized_results WHERE key = $key LIMIT 1")
+            .bind(("key", key.to_string()))
+            .await?
+            .take(0)?;
+
+        let Some(record) = result.pop() else {
+            return Ok(None);
+        };
+        let data = serde_json::from_str(&record.payload).map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(Some(Materialized {
+            data,
+            generated_at: record.generated_at,
+        }))
+    }
+
+    /// Get a cached translation by description hash and target language
+    pub async fn get_translation(
+        &self,
+        hash: &str,
+        target: &str,
+    ) -> Result<Option<Translation>, DbError> {
+        let mut result: Vec<Translation> = self
+            .reader()
+            .query("SELECT * FROM translations WHERE hash = $hash AND target = $target LIMIT 1")
+            .bind(("hash", hash.to_string()))
+            .bind(("target", target.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(result.pop())
+    }
+
+    /// Store a translation in the cache
+    pub async fn save_translation(
+        &self,
+        hash: &str,
+        target: &str,
+        text: &str,
+    ) -> Result<(), DbError> {
+        let record = NewTranslation {
+            hash: hash.to_string(),
+            target: target.to_string(),
+            text: text.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let _: Vec<Translation> = self.db.insert("translations").content(vec![record]).await?;
+
+        Ok(())
+    }
+
+    /// Get Mod Portal metadata fetched within MOD_METADATA_TTL_HOURS for these mods
+    pub async fn get_mod_metadata(&self, names: Vec<String>) -> Result<Vec<ModMetadata>, DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(MOD_METADATA_TTL_HOURS);
+
+        let metadata: Vec<ModMetadata> = self
+            .reader()
+            .query("SELECT * FROM mod_metadata WHERE name IN $names AND fetched_at >= $cutoff")
+            .bind(("names", names))
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(metadata)
+    }
+
+    /// Store (or refresh) Mod Portal metadata, keyed by mod name
+    pub async fn save_mod_metadata(&self, records: Vec<NewModMetadata>) -> Result<(), DbError> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        self.db
+            .query("FOR $record IN $records { UPSERT type::thing('mod_metadata', $record.name) CONTENT $record; }")
+            .bind(("records", records))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Drop Mod Portal metadata past its TTL
+    pub async fn cleanup_old_mod_metadata(&self) -> Result<(), DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(MOD_METADATA_TTL_HOURS);
+
+        self.db
+            .query("DELETE FROM mod_metadata WHERE fetched_at < $cutoff")
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get abuse reports checked within IP_REPUTATION_TTL_HOURS for these addresses
+    pub async fn get_ip_reputation(&self, ips: Vec<String>) -> Result<Vec<IpReputation>, DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(IP_REPUTATION_TTL_HOURS);
+
+        let reputation: Vec<IpReputation> = self
+            .reader()
+            .query("SELECT * FROM ip_reputation WHERE ip IN $ips AND fetched_at >= $cutoff")
+            .bind(("ips", ips))
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(reputation)
+    }
+
+    /// Store (or refresh) abuse reports, keyed by address
+    pub async fn save_ip_reputation(&self, records: Vec<NewIpReputation>) -> Result<(), DbError> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        self.db
+            .query("FOR $record IN $records { UPSERT type::thing('ip_reputation', $record.ip) CONTENT $record; }")
+            .bind(("records", records))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Drop abuse reports past their TTL
+    pub async fn cleanup_old_ip_reputation(&self) -> Result<(), DbError> {
+        let cutoff = chrono::Utc::now() - chrono::Duration::hours(IP_REPUTATION_TTL_HOURS);
+
+        self.db
+            .query("DELETE FROM ip_reputation WHERE fetched_at < $cutoff")
+            .bind(("cutoff", cutoff.to_rfc3339()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store a game's modpack fingerprint (replacing any earlier one for the same game_id)
+    pub async fn save_modpack(&self, modpack: NewModpack) -> Result<(), DbError> {
+        self.db
+            .query("UPSERT type::thing('modpacks', $game_id) CONTENT $modpack")
+            .bind(("game_id", modpack.game_id))
+            .bind(("modpack", modpack))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// All recorded modpack fingerprints
+    pub async fn get_modpacks(&self) -> Result<Vec<Modpack>, DbError> {
+        let modpacks: Vec<Modpack> = self.reader().query("SELECT * FROM modpacks").await?.take(0)?;
+        Ok(modpacks)
+    }
+
+    /// game_ids running the modpack with this fingerprint
+    pub async fn get_modpack_game_ids(&self, fingerprint: &str) -> Result<Vec<GameId>, DbError> {
+        let game_ids: Vec<GameId> = self
+            .reader()
+            .query("SELECT VALUE game_id FROM modpacks WHERE fingerprint = $fingerprint")
+            .bind(("fingerprint", fingerprint.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(game_ids)
+    }
+
+    /// Forget fingerprints of games that are no longer listed
+    pub async fn cleanup_unlisted_modpacks(&self) -> Result<(), DbError> {
+        self.db
+            .query("DELETE FROM modpacks WHERE game_id NOTINSIDE (SELECT VALUE game_id FROM servers)")
+            .await?;
+
+        Ok(())
+    }
+
+    /// The alert a webhook has registered for a server, if any
+    pub async fn find_downtime_alert(
+        &self,
+        server_id: &ServerId,
+        webhook_url: &str,
+    ) -> Result<Option<DowntimeAlert>, DbError> {
+        let mut existing: Vec<DowntimeAlert> = self
+            .db
+            .query("SELECT * FROM downtime_alerts WHERE server_id = $server_id AND webhook_url = $url")
+            .bind(("server_id", server_id.clone()))
+            .bind(("url", webhook_url.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(existing.pop())
+    }
+
+    /// Register an unclaimed downtime alert for a server
+    pub async fn add_downtime_alert(
+        &self,
+        server_id: &ServerId,
+        server_name: &str,
+        webhook_url: &str,
+        claim_code: &str,
+    ) -> Result<(), DbError> {
+        let record = NewDowntimeAlert {
+            server_id: server_id.clone(),
+            server_name: server_name.to_string(),
+            webhook_url: webhook_url.to_string(),
+            claim_code: claim_code.to_string(),
+            claimed: false,
+            empty_since: None,
+            alerted: false,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        let _: Vec<DowntimeAlert> = self.db.insert("downtime_alerts").content(vec![record]).await?;
+
+        Ok(())
+    }
+
+    /// Mark a downtime alert as claimed by the server's operator
+    pub async fn claim_downtime_alert(&self, id: &Thing) -> Result<(), DbError> {
+        self.db
+            .query("UPDATE $id SET claimed = true")
+            .bind(("id", id.clone()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Get all registered downtime alerts
+    pub async fn get_downtime_alerts(&self) -> Result<Vec<DowntimeAlert>, DbError> {
+        let alerts: Vec<DowntimeAlert> = self
+            .reader()
+            .query("SELECT * FROM downtime_alerts")
+            .await?
+            .take(0)?;
+
+        Ok(alerts)
+    }
+
+    /// Update the tracking state of a downtime alert
+    pub async fn update_downtime_alert(
+        &self,
+        id: &Thing,
+        empty_since: Option<String>,
+        alerted: bool,
+    ) -> Result<(), DbError> {
+        self.db
+            .query("UPDATE $id SET empty_since = $empty_since, alerted = $alerted")
+            .bind(("id", id.clone()))
+            .bind(("empty_since", empty_since))
+            .bind(("alerted", alerted))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Delete a downtime alert
+    pub async fn delete_downtime_alert(&self, id: &Thing) -> Result<(), DbError> {
+        self.db
+            .query("DELETE $id")
+            .bind(("id", id.clone()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Check the database answers reads (a replica's answer counts while the primary is down)
+    pub async fn ping(&self) -> Result<(), DbError> {
+        self.reader().query("RETURN true").await?.check()?;
+        Ok(())
+    }
+
+    /// Hide a server from the listing, replacing an earlier record for the same server_id
+    pub async fn hide_server(&self, record: NewHiddenServer) -> Result<(), DbError> {
+        self.db
+            .query("UPSERT type::thing('hidden_servers', $server_id) CONTENT $record")
+            .bind(("server_id", record.server_id.clone()))
+            .bind(("record", record))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Put a hidden server back on the listing, keeping the record; None when it wasn't hidden
+    pub async fn restore_hidden_server(&self, server_id: &ServerId) -> Result<Option<HiddenServer>, DbError> {
+        let mut restored: Vec<HiddenServer> = self
+            .db
+            .query("UPDATE type::thing('hidden_servers', $server_id) SET hidden = false, restored_at = $now WHERE hidden = true")
+            .bind(("server_id", server_id.clone()))
+            .bind(("now", chrono::Utc::now().to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(restored.pop())
+    }
+
+    /// Append a hide or restore to the moderation log
+    pub async fn log_moderation_action(&self, action: NewModerationAction) -> Result<(), DbError> {
+        let _: Vec<ModerationAction> = self.db.insert("moderation_log").content(vec![action]).await?;
+        Ok(())
+    }
+
+    /// The most recent moderation actions, newest first
+    pub async fn get_moderation_actions(&self, limit: usize) -> Result<Vec<ModerationAction>, DbError> {
+        let actions: Vec<ModerationAction> = self
+            .reader()
+            .query("SELECT * FROM moderation_log ORDER BY at DESC LIMIT $limit")
+            .bind(("limit", limit))
+            .await?
+            .take(0)?;
+
+        Ok(actions)
+    }
+
+    /// Every server ever hidden, hidden ones first, most recent first
+    pub async fn get_hidden_servers(&self) -> Result<Vec<HiddenServer>, DbError> {
+        let hidden: Vec<HiddenServer> = self
+            .reader()
+            .query("SELECT * FROM hidden_servers ORDER BY hidden DESC, hidden_at DESC")
+            .await?
+            .take(0)?;
+
+        Ok(hidden)
+    }
+
+    /// Store a newly issued API key
+    pub async fn create_api_key(&self, key: NewApiKeyRecord) -> Result<(), DbError> {
+        let _: Vec<ApiKeyRecord> = self.db.insert("api_keys").content(vec![key]).await?;
+        Ok(())
+    }
+
+    /// Every API key ever issued, active ones first, newest first
+    pub async fn get_api_keys(&self) -> Result<Vec<ApiKeyRecord>, DbError> {
+        let keys: Vec<ApiKeyRecord> = self
+            .reader()
+            .query("SELECT * FROM api_keys ORDER BY revoked ASC, created_at DESC")
+            .await?
+            .take(0)?;
+
+        Ok(keys)
+    }
+
+    /// Revoke an API key, keeping the record; false when there was no such active key
+    pub async fn revoke_api_key(&self, id: &str) -> Result<bool, DbError> {
+        let revoked: Vec<ApiKeyRecord> = self
+            .db
+            .query("UPDATE type::thing('api_keys', $id) SET revoked = true WHERE revoked = false")
+            .bind(("id", id.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(!revoked.is_empty())
+    }
+
+    /// File a visitor's report of a server
+    pub async fn add_server_report(&self, report: NewServerReport) -> Result<(), DbError> {
+        let _: Vec<ServerReport> = self.db.insert("server_reports").content(vec![report]).await?;
+        Ok(())
+    }
+
+    /// Whether this reporter already has an open report of the server
+    /// Matched by server_id, which survives restarts; by game_id only for servers without one
+    pub async fn has_open_report(&self, reporter: &str, game_id: GameId, server_id: Option<&ServerId>) -> Result<bool, DbError> {
+        let server = if server_id.is_some() { "server_id = $server_id" } else { "game_id = $game_id" };
+        let open: Vec<ServerReport> = self
+            .reader()
+            .query(format!(
+                "SELECT * FROM server_reports WHERE reporter = $reporter AND {} AND dismissed = false LIMIT 1",
+                server
+            ))
+            .bind(("reporter", reporter.to_string()))
+            .bind(("game_id", game_id))
+            .bind(("server_id", server_id.cloned()))
+            .await?
+            .take(0)?;
+
+        Ok(!open.is_empty())
+    }
+
+    /// Reports this reporter filed since `since` (RFC 3339), dismissed or not
+    pub async fn count_reports_since(&self, reporter: &str, since: &str) -> Result<usize, DbError> {
+        let count: Option<usize> = self
+            .reader()
+            .query("SELECT count() AS reports FROM server_reports WHERE reporter = $reporter AND reported_at >= $since GROUP ALL")
+            .bind(("reporter", reporter.to_string()))
+            .bind(("since", since.to_string()))
+            .await?
+            .take((0, "reports"))?;
+
+        Ok(count.unwrap_or(0))
+    }
+
+    /// Reports not dismissed yet, newest first
+    pub async fn get_open_reports(&self, limit: usize) -> Result<Vec<ServerReport>, DbError> {
+        let reports: Vec<ServerReport> = self
+            .reader()
+            .query("SELECT * FROM server_reports WHERE dismissed = false ORDER BY reported_at DESC LIMIT $limit")
+            .bind(("limit", limit))
+            .await?
+            .take(0)?;
+
+        Ok(reports)
+    }
+
+    /// Dismiss a report, keeping the record; false when there was no such open report
+    pub async fn dismiss_report(&self, id: &str) -> Result<bool, DbError> {
+        let dismissed: Vec<ServerReport> = self
+            .db
+            .query("UPDATE type::thing('server_reports', $id) SET dismissed = true WHERE dismissed = false")
+            .bind(("id", id.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(!dismissed.is_empty())
+    }
+
+    /// Add an entry to the blocklist
+    pub async fn add_blocklist_entry(&self, entry: NewBlocklistEntry) -> Result<(), DbError> {
+        let _: Vec<BlocklistEntry> = self.db.insert("blocklist").content(vec![entry]).await?;
+        Ok(())
+    }
+
+    /// The whole blocklist, newest first
+    pub async fn get_blocklist(&self) -> Result<Vec<BlocklistEntry>, DbError> {
+        let entries: Vec<BlocklistEntry> = self
+            .reader()
+            .query("SELECT * FROM blocklist ORDER BY added_at DESC")
+            .await?
+            .take(0)?;
+
+        Ok(entries)
+    }
+
+    /// Remove a blocklist entry; None when there was no such entry
+    pub async fn remove_blocklist_entry(&self, id: &str) -> Result<Option<BlocklistEntry>, DbError> {
+        let mut removed: Vec<BlocklistEntry> = self
+            .db
+            .query("DELETE type::thing('blocklist', $id) RETURN BEFORE")
+            .bind(("id", id.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(removed.pop())
+    }
+
+    /// Store a push subscription under `id`, replacing an earlier one from the same endpoint
+    pub async fn save_push_subscription(&self, id: &str, subscription: NewPushSubscription) -> Result<(), DbError> {
+        self.db
+            .query("UPSERT type::thing('push_subscriptions', $id) CONTENT $subscription")
+            .bind(("id", id.to_string()))
+            .bind(("subscription", subscription))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Get all push subscriptions
+    pub async fn get_push_subscriptions(&self) -> Result<Vec<PushSubscription>, DbError> {
+        let subscriptions: Vec<PushSubscription> = self
+            .reader()
+            .query("SELECT * FROM push_subscriptions")
+            .await?
+            .take(0)?;
+
+        Ok(subscriptions)
+    }
+
+    /// Replace the servers a push subscription watches (no-op if it doesn't exist)
+    pub async fn update_push_servers(&self, id: &str, server_ids: Vec<ServerId>) -> Result<(), DbError> {
+        self.db
+            .query("UPDATE type::thing('push_subscriptions', $id) SET server_ids = $server_ids")
+            .bind(("id", id.to_string()))
+            .bind(("server_ids", server_ids))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Delete a push subscription
+    pub async fn delete_push_subscription(&self, id: &str) -> Result<(), DbError> {
+        self.db
+            .query("DELETE type::thing('push_subscriptions', $id)")
+            .bind(("id", id.to_string()))
+            .await?
+            .check()?;
+
+        Ok(())
+    }
+
+    /// Create an account, hashing the password with argon2 inside SurrealDB
+    /// Returns false if the email is already registered
+    pub async fn create_account(&self, email: &str, password: &str) -> Result<bool, DbError> {
+        let existing: Vec<Account> = self
+            .db
+            .query("SELECT id, email, created_at FROM accounts WHERE email = $email")
+            .bind(("email", email.to_string()))
+            .await?
+            .take(0)?;
+        if !existing.is_empty() {
+            return Ok(false);
+        }
+
+        self.db
+            .query(
+                "CREATE accounts SET email = $email, password_hash = crypto::argon2::generate($password), created_at = $now",
+            )
+            .bind(("email", email.to_string()))
+            .bind(("password", password.to_string()))
+            .bind(("now", chrono::Utc::now().to_rfc3339()))
+            .await?
+            .check()?;
+
+        Ok(true)
+    }
+
+    /// Check an email and password against the stored hash
+    pub async fn verify_account(&self, email: &str, password: &str) -> Result<bool, DbError> {
+        let matches: Vec<bool> = self
+            .reader()
+            .query("SELECT VALUE crypto::argon2::compare(password_hash, $password) FROM accounts WHERE email = $email")
+            .bind(("email", email.to_string()))
+            .bind(("password", password.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(matches.first().copied().unwrap_or(false))
+    }
+
+    /// Start a session for an account
+    pub async fn create_session(&self, token_hash: &str, email: &str, expires_at: &str) -> Result<(), DbError> {
+        let record = NewSession {
+            token_hash: token_hash.to_string(),
+            email: email.to_string(),
+            expires_at: expires_at.to_string(),
+        };
+        let _: Vec<Session> = self.db.insert("sessions").content(vec![record]).await?;
+
+        Ok(())
+    }
+
+    /// Email of the account a session token hash belongs to, if the session hasn't expired
+    pub async fn get_session_email(&self, token_hash: &str) -> Result<Option<String>, DbError> {
+        let sessions: Vec<Session> = self
+            .reader()
+            .query("SELECT * FROM sessions WHERE token_hash = $token_hash AND expires_at > $now LIMIT 1")
+            .bind(("token_hash", token_hash.to_string()))
+            .bind(("now", chrono::Utc::now().to_rfc3339()))
+            .await?
+            .take(0)?;
+
+        Ok(sessions.into_iter().next().map(|s| s.email))
+    }
+
+    /// End a session (sign out)
+    pub async fn delete_session(&self, token_hash: &str) -> Result<(), DbError> {
+        self.db
+            .query("DELETE FROM sessions WHERE token_hash = $token_hash")
+            .bind(("token_hash", token_hash.to_string()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Delete sessions past their expiry
+    pub async fn cleanup_expired_sessions(&self) -> Result<(), DbError> {
+        self.db
+            .query("DELETE FROM sessions WHERE expires_at < $now")
+            .bind(("now", chrono::Utc::now().to_rfc3339()))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Filter presets saved by an account, oldest first
+    pub async fn get_filter_presets(&self, email: &str) -> Result<Vec<SavedPreset>, DbError> {
+        let presets: Vec<SavedPreset> = self
+            .reader()
+            .query("SELECT * FROM filter_presets WHERE email = $email ORDER BY created_at ASC")
+            .bind(("email", email.to_string()))
+            .await?
+            .take(0)?;
+
+        Ok(presets)
+    }
+
+    /// Save a filter preset, replacing any preset of the same name on the account
+    pub async fn save_filter_preset(&self, preset: NewSavedPreset) -> Result<(), DbError> {
+        self.db
+            .query("DELETE FROM filter_presets WHERE email = $email AND name = $name")
+            .bind(("email", preset.email.clone()))
+            .bind(("name", preset.name.clone()))
+            .await?;
+        let _: Vec<SavedPreset> = self.db.insert("filter_presets").content(vec![preset]).await?;
+
+        Ok(())
+    }
+
+    /// Delete one of an account's filter presets (other accounts' presets are left alone)
+    pub async fn delete_filter_preset(&self, email: &str, id: &str) -> Result<(), DbError> {
+        self.db
+            .query("DELETE type::thing('filter_presets', $id) WHERE email = $email")
+            .bind(("id", id.to_string()))
+            .bind(("email", email.to_string()))
+            .await?;
+
+        Ok(())
+    }
+}
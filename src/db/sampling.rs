@@ -0,0 +1,95 @@
+use crate::api::factorio::GameServer;
+use crate::ids::GameId;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+
+/// In on-change mode, re-record unchanged non-empty servers at least this often
+const HEARTBEAT_MINUTES: i64 = 60;
+
+/// How often player counts are written to server_history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingMode {
+    /// Record every refresh cycle (minute-level data)
+    EveryRefresh,
+    /// Record at most once every N minutes
+    Interval(u32),
+    /// Record only when a server's player count changes (plus an hourly heartbeat)
+    OnChange,
+}
+
+impl SamplingMode {
+    /// Parse from config: "every_refresh", "interval" (with `minutes`), or "on_change"
+    pub fn parse(mode: &str, minutes: u32) -> Option<Self> {
+        match mode.trim().to_lowercase().as_str() {
+            "every_refresh" | "" => Some(SamplingMode::EveryRefresh),
+            "interval" => Some(SamplingMode::Interval(minutes.max(1))),
+            "on_change" => Some(SamplingMode::OnChange),
+            _ => None,
+        }
+    }
+}
+
+/// Decides which servers get a history record on each refresh
+#[derive(Debug)]
+pub struct HistorySampler {
+    mode: SamplingMode,
+    last_sample: Option<DateTime<Utc>>,
+    /// Last recorded (player count, time) per game_id, for on-change mode
+    last_counts: HashMap<GameId, (usize, DateTime<Utc>)>,
+}
+
+impl HistorySampler {
+    pub fn new(mode: SamplingMode) -> Self {
+        Self {
+            mode,
+            last_sample: None,
+            last_counts: HashMap::new(),
+        }
+    }
+
+    pub fn mode(&self) -> SamplingMode {
+        self.mode
+    }
+
+    /// Select the servers to record history for at `now`
+    pub fn select<'a>(&mut self, servers: &'a [GameServer], now: DateTime<Utc>) -> Vec<&'a GameServer> {
+        match self.mode {
+            SamplingMode::EveryRefresh => servers.iter().filter(|s| !s.players.is_empty()).collect(),
+            SamplingMode::Interval(minutes) => {
+                let due = self
+                    .last_sample
+                    .is_none_or(|last| now - last >= Duration::minutes(minutes as i64));
+                if !due {
+                    return Vec::new();
+                }
+                self.last_sample = Some(now);
+                servers.iter().filter(|s| !s.players.is_empty()).collect()
+            }
+            SamplingMode::OnChange => {
+                // Forget servers that left the listing to keep memory bounded
+                let listed: HashMap<GameId, &GameServer> = servers.iter().map(|s| (s.game_id, s)).collect();
+                self.last_counts.retain(|id, _| listed.contains_key(id));
+
+                let mut selected = Vec::new();
+                for server in servers {
+                    let count = server.players.len();
+                    let record = match self.last_counts.get(&server.game_id) {
+                        // First sighting: only record if someone is playing
+                        None => count > 0,
+                        Some(&(last_count, last_time)) => {
+                            last_count != count
+                                || (count > 0 && now - last_time >= Duration::minutes(HEARTBEAT_MINUTES))
+                        }
+                    };
+                    if record || !self.last_counts.contains_key(&server.game_id) {
+                        self.last_counts.insert(server.game_id, (count, now));
+                    }
+                    if record {
+                        selected.push(server);
+                    }
+                }
+                selected
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::db::models::NewServerHistory;
+use crate::db::queries::DbClient;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// History records the queue holds before new ones are dropped
+pub const HISTORY_QUEUE_CAPACITY: usize = 50_000;
+
+/// Most records written in one insert
+const WRITE_BATCH_SIZE: usize = 5_000;
+
+/// Write-behind queue for player history
+/// The refresh loop pushes records without waiting on the DB; a dedicated task drains
+/// the queue and inserts in batches. When the DB falls behind far enough to fill the
+/// queue, new records are dropped (and counted) rather than blocking the refresh.
+pub struct HistoryQueue {
+    sender: mpsc::Sender<NewServerHistory>,
+    dropped: AtomicU64,
+}
+
+impl HistoryQueue {
+    /// Create the queue and spawn its writer task (must be called inside a Tokio runtime)
+    pub fn spawn(db: Arc<DbClient>, capacity: usize) -> Arc<Self> {
+        let (sender, receiver) = mpsc::channel(capacity);
+        tokio::spawn(run_writer(db, receiver));
+
+        Arc::new(Self {
+            sender,
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Queue records for writing, never waiting; returns how many were accepted
+    pub fn push(&self, records: Vec<NewServerHistory>) -> usize {
+        let total = records.len();
+        let mut accepted = 0;
+        for record in records {
+            if self.sender.try_send(record).is_err() {
+                break;
+            }
+            accepted += 1;
+        }
+
+        let dropped = (total - accepted) as u64;
+        if dropped > 0 {
+            self.dropped.fetch_add(dropped, Ordering::Relaxed);
+            eprintln!("History queue full, dropped {} records", dropped);
+        }
+        accepted
+    }
+
+    /// Records waiting to be written
+    pub fn depth(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+
+    /// Records dropped because the queue was full, since startup
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Drain the queue, inserting whatever has accumulated as one batch
+async fn run_writer(db: Arc<DbClient>, mut receiver: mpsc::Receiver<NewServerHistory>) {
+    let mut batch = Vec::with_capacity(WRITE_BATCH_SIZE);
+    while receiver.recv_many(&mut batch, WRITE_BATCH_SIZE).await > 0 {
+        if let Err(e) = db.insert_history(std::mem::take(&mut batch)).await {
+            eprintln!("Failed to write history batch: {}", e);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use crate::db::models::HourlyTotals;
+use crate::ids::GameId;
+use crate::stats::{GlobalStats, TagCount, VersionCount};
+use crate::utils::strip_all_tags;
+use crate::view::ServerView;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Materialized result key for the latest weekly digest
+pub const WEEKLY_DIGEST_KEY: &str = "weekly_digest";
+
+/// Days a digest looks back over
+pub const DIGEST_DAYS: i64 = 7;
+
+/// Servers in the "top servers" list
+const TOP_SERVERS: usize = 10;
+
+/// Servers in the "new this week" list
+const NEW_SERVERS: usize = 5;
+
+/// Versions and tags in the ecosystem section
+const TOP_BREAKDOWN: usize = 5;
+
+/// Discord rejects messages longer than this
+const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// One server in a digest list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigestServer {
+    pub game_id: GameId,
+    /// Name without rich text
+    pub name: String,
+    /// Highest player count over the digest's week
+    pub peak_players: usize,
+    pub game_version: String,
+    pub modded: bool,
+}
+
+/// Weekly summary of the listing, for posting to r/factorio or community Discords
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    /// Monday (UTC) of the week the digest was made in, as YYYY-MM-DD; one digest per week
+    pub week_of: String,
+    /// Busiest servers by their peak over the last DIGEST_DAYS
+    pub top_servers: Vec<DigestServer>,
+    /// Servers first listed in the last DIGEST_DAYS and not already in top_servers, busiest first
+    pub new_servers: Vec<DigestServer>,
+    /// Mean of the hourly player totals
+    pub average_players: f64,
+    /// Highest hourly player total
+    pub peak_players: f64,
+    /// Mean of the hourly server totals
+    pub average_servers: f64,
+    /// Hour with the most players, e.g. "Saturday 19:00 UTC"
+    pub busiest_hour: Option<String>,
+    pub versions: Vec<VersionCount>,
+    pub top_tags: Vec<TagCount>,
+}
+
+/// Monday (UTC) of the week `now` falls in
+pub fn week_of(now: DateTime<Utc>) -> NaiveDate {
+    now.date_naive() - Duration::days(now.weekday().num_days_from_monday() as i64)
+}
+
+impl WeeklyDigest {
+    /// Build the digest from the current listing, each game's peak over the week and the hourly totals
+    /// Servers flagged as reported hosts or impostors are left out of the lists
+    pub fn compute(
+        servers: &[ServerView],
+        weekly_peaks: &HashMap<GameId, usize>,
+        totals: &[HourlyTotals],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let eligible: Vec<&ServerView> = servers
+            .iter()
+            .filter(|v| v.abuse_reports.is_none() && v.impostor_of.is_none())
+            .collect();
+        let entry = |view: &ServerView| DigestServer {
+            game_id: view.server.game_id,
+            name: strip_all_tags(&view.server.name),
+            peak_players: weekly_peaks
+                .get(&view.server.game_id)
+                .copied()
+                .unwrap_or(0)
+                .max(view.server.player_count),
+            game_version: view.server.game_version.clone(),
+            modded: view.server.mod_count > 0,
+        };
+        let busiest_first = |a: &DigestServer, b: &DigestServer| b.peak_players.cmp(&a.peak_players).then_with(|| a.name.cmp(&b.name));
+
+        let mut top_servers: Vec<DigestServer> = eligible.iter().map(|v| entry(v)).filter(|s| s.peak_players > 0).collect();
+        top_servers.sort_by(busiest_first);
+        top_servers.truncate(TOP_SERVERS);
+
+        let week_ago = now - Duration::days(DIGEST_DAYS);
+        let mut new_servers: Vec<DigestServer> = eligible
+            .iter()
+            .filter(|v| {
+                v.server
+                    .first_seen
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .is_some_and(|t| t.with_timezone(&Utc) >= week_ago)
+            })
+            .map(|v| entry(v))
+            .filter(|s| s.peak_players > 0 && !top_servers.iter().any(|t| t.game_id == s.game_id))
+            .collect();
+        new_servers.sort_by(busiest_first);
+        new_servers.truncate(NEW_SERVERS);
+
+        let hours = totals.len().max(1) as f64;
+        let busiest = totals.iter().max_by(|a, b| a.players.total_cmp(&b.players));
+        let busiest_hour = busiest.and_then(|t| DateTime::parse_from_rfc3339(&t.hour).ok()).map(|t| t.format("%A %H:00 UTC").to_string());
+
+        let mut stats = GlobalStats::compute(servers.iter().map(|v| &v.server));
+        stats.versions.truncate(TOP_BREAKDOWN);
+        stats.top_tags.truncate(TOP_BREAKDOWN);
+
+        Self {
+            week_of: week_of(now).to_string(),
+            top_servers,
+            new_servers,
+            average_players: totals.iter().map(|t| t.players).sum::<f64>() / hours,
+            peak_players: busiest.map(|t| t.players).unwrap_or(0.0),
+            average_servers: totals.iter().map(|t| t.servers).sum::<f64>() / hours,
+            busiest_hour,
+            versions: stats.versions,
+            top_tags: stats.top_tags,
+        }
+    }
+
+    /// The digest as Markdown, with server names linking to their pages on `site_url`
+    pub fn to_markdown(&self, site_url: &str) -> String {
+        let server_line = |rank: usize, s: &DigestServer| {
+            format!(
+                "{}. [{}]({}/server/{}) — peak {} players, {}{}\n",
+                rank + 1,
+                escape_markdown(&s.name),
+                site_url,
+                s.game_id,
+                s.peak_players,
+                s.game_version,
+                if s.modded { ", modded" } else { "" }
+            )
+        };
+
+        let mut md = format!("## Factorio multiplayer, week of {}\n\n", self.week_of);
+        md.push_str(&format!(
+            "About **{:.0} players** on **{:.0} servers** at any time this week, peaking at **{:.0}**",
+            self.average_players, self.average_servers, self.peak_players
+        ));
+        match self.busiest_hour {
+            Some(ref hour) => md.push_str(&format!(" on {}.\n\n", hour)),
+            None => md.push_str(".\n\n"),
+        }
+
+        if !self.top_servers.is_empty() {
+            md.push_str("### Top servers\n\n");
+            for (rank, server) in self.top_servers.iter().enumerate() {
+                md.push_str(&server_line(rank, server));
+            }
+            md.push('\n');
+        }
+        if !self.new_servers.is_empty() {
+            md.push_str("### New this week\n\n");
+            for (rank, server) in self.new_servers.iter().enumerate() {
+                md.push_str(&server_line(rank, server));
+            }
+            md.push('\n');
+        }
+        if !self.versions.is_empty() {
+            let versions: Vec<String> = self.versions.iter().map(|v| format!("{} ({} servers)", v.version, v.servers)).collect();
+            md.push_str(&format!("**Versions:** {}\n\n", versions.join(", ")));
+        }
+        if !self.top_tags.is_empty() {
+            let tags: Vec<String> = self.top_tags.iter().map(|t| escape_markdown(&t.tag)).collect();
+            md.push_str(&format!("**Popular tags:** {}\n\n", tags.join(", ")));
+        }
+        md.push_str(&format!("Browse every server at {}\n", site_url));
+        md
+    }
+
+    /// Markdown cut to fit a Discord message, ending with a link to the full digest when shortened
+    pub fn to_discord(&self, site_url: &str) -> String {
+        let full = self.to_markdown(site_url);
+        if full.chars().count() <= DISCORD_MESSAGE_LIMIT {
+            return full;
+        }
+        let more = format!("…\nFull digest: {}/digest/latest", site_url);
+        let budget = DISCORD_MESSAGE_LIMIT - more.chars().count();
+        let mut message = String::new();
+        for line in full.lines() {
+            if message.chars().count() + line.chars().count() + 1 > budget {
+                break;
+            }
+            message.push_str(line);
+            message.push('\n');
+        }
+        message.push_str(&more);
+        message
+    }
+}
+
+/// Backslash-escape characters that Markdown (Reddit and Discord flavours) would interpret in a name
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '[' | ']' | '(' | ')' | '#' | '>' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
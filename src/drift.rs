@@ -0,0 +1,74 @@
+use crate::api::factorio::GameServer;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Longest example value kept per unknown field
+const MAX_EXAMPLE_LEN: usize = 120;
+
+/// A get-games field that GameServer doesn't model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnknownField {
+    pub name: String,
+    /// Servers carrying the field in the latest response
+    pub servers: usize,
+    /// Server entries carrying the field across all responses
+    pub total: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// JSON of one value, truncated to MAX_EXAMPLE_LEN characters
+    pub example: String,
+}
+
+/// Tracks fields the matchmaking API sends that we drop, so new upstream data gets noticed
+/// Kept in memory only; counts start over when the browser restarts
+#[derive(Debug, Default)]
+pub struct SchemaDrift {
+    fields: BTreeMap<String, UnknownField>,
+    /// get-games responses checked so far
+    responses: u64,
+}
+
+impl SchemaDrift {
+    /// Count the unknown fields in one get-games response
+    /// Returns the fields that had never been seen before, for logging
+    pub fn observe(&mut self, servers: &[GameServer], now: DateTime<Utc>) -> Vec<UnknownField> {
+        self.responses += 1;
+        for field in self.fields.values_mut() {
+            field.servers = 0;
+        }
+
+        let mut new_fields = Vec::new();
+        for server in servers {
+            for (name, value) in &server.unknown_fields {
+                let field = self.fields.entry(name.clone()).or_insert_with(|| {
+                    let example: String = value.to_string().chars().take(MAX_EXAMPLE_LEN).collect();
+                    let field = UnknownField {
+                        name: name.clone(),
+                        servers: 0,
+                        total: 0,
+                        first_seen: now,
+                        last_seen: now,
+                        example,
+                    };
+                    new_fields.push(field.clone());
+                    field
+                });
+                field.servers += 1;
+                field.total += 1;
+                field.last_seen = now;
+            }
+        }
+
+        new_fields
+    }
+
+    /// Every unknown field seen since startup, by name
+    pub fn fields(&self) -> Vec<UnknownField> {
+        self.fields.values().cloned().collect()
+    }
+
+    pub fn responses(&self) -> u64 {
+        self.responses
+    }
+}
@@ -0,0 +1,74 @@
+use crate::cache::CacheSnapshot;
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::{Responder, Response};
+use sha2::{Digest, Sha256};
+
+/// Hex characters of the hash kept in the tag
+const ETAG_LEN: usize = 16;
+
+/// Weak ETag for a response built from `snapshot` and `inputs` (query string, cookies, ...)
+/// The snapshot's publish time is part of it, so tags don't repeat across restarts
+pub fn etag(snapshot: &CacheSnapshot, inputs: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(snapshot.version.to_le_bytes());
+    hasher.update(snapshot.updated_at.map(|t| t.timestamp_micros()).unwrap_or_default().to_le_bytes());
+    format!("W/\"{}\"", &hash_inputs(hasher, inputs)[..ETAG_LEN])
+}
+
+/// Key for the same `inputs` across refreshes, e.g. for keeping a page's previous rendering around
+pub fn cache_key(inputs: &[&str]) -> String {
+    hash_inputs(Sha256::new(), inputs)
+}
+
+fn hash_inputs(mut hasher: Sha256, inputs: &[&str]) -> String {
+    for input in inputs {
+        // Length-prefixed so ("ab", "c") and ("a", "bc") differ
+        hasher.update((input.len() as u64).to_le_bytes());
+        hasher.update(input.as_bytes());
+    }
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The request's If-None-Match header, for answering polling clients with 304 Not Modified
+pub struct IfNoneMatch(Option<String>);
+
+impl IfNoneMatch {
+    /// Whether the client already has the response tagged `etag` (weak comparison, `*` matches anything)
+    pub fn matches(&self, etag: &str) -> bool {
+        let Some(ref header) = self.0 else {
+            return false;
+        };
+        let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+        header.split(',').any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfNoneMatch(req.headers().get_one("If-None-Match").map(str::to_string)))
+    }
+}
+
+/// A response carrying an ETag, or a bodiless 304 when the client's copy is still current
+pub enum Tagged<R> {
+    NotModified(String),
+    Fresh(String, R),
+}
+
+impl<'r, 'o: 'r, R: Responder<'r, 'o>> Responder<'r, 'o> for Tagged<R> {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'o> {
+        let (etag, response) = match self {
+            Tagged::NotModified(etag) => (etag, Response::build().status(Status::NotModified).finalize()),
+            Tagged::Fresh(etag, inner) => (etag, inner.respond_to(req)?),
+        };
+        Response::build_from(response)
+            .header(Header::new("ETag", etag))
+            // Pages differ per visitor (cookies), so only the browser keeps a copy, and checks back every time
+            .header(Header::new("Cache-Control", "private, no-cache"))
+            .ok()
+    }
+}
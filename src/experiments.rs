@@ -0,0 +1,72 @@
+use crate::ranking::RankingWeights;
+
+/// Cookie holding the visitor's ranking experiment variant
+pub const EXPERIMENT_COOKIE: &str = "rank_variant";
+
+/// How long a visitor stays in the same variant
+pub const EXPERIMENT_COOKIE_DAYS: i64 = 30;
+
+/// Longest variant name accepted, since it ends up in cookies and metric labels
+const MAX_VARIANT_NAME_LEN: usize = 32;
+
+/// One arm of a ranking experiment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: String,
+    pub weights: RankingWeights,
+}
+
+/// A ranking experiment: visitors are split evenly between variants, each ranking
+/// the "Recommended" sort with its own weights
+#[derive(Debug, Clone, PartialEq)]
+pub struct Experiment {
+    pub variants: Vec<Variant>,
+}
+
+impl Experiment {
+    /// Parse from config: `;`-separated `name:weights` variants, where the weights
+    /// override `base` like RANKING_WEIGHTS does, e.g. "control:;uptime:uptime=1,players=0.5"
+    /// Needs at least two variants with unique names made of letters, digits, `-` and `_`
+    pub fn parse(spec: &str, base: RankingWeights) -> Result<Self, String> {
+        let mut variants: Vec<Variant> = Vec::new();
+        for part in spec.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, weights) = part.split_once(':').unwrap_or((part, ""));
+            let name = name.trim();
+            if name.is_empty()
+                || name.len() > MAX_VARIANT_NAME_LEN
+                || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(format!("invalid variant name '{}'", name));
+            }
+            if variants.iter().any(|v| v.name == name) {
+                return Err(format!("duplicate variant '{}'", name));
+            }
+            let weights = base
+                .with_overrides(weights)
+                .map_err(|e| format!("variant {}: {}", name, e))?;
+            variants.push(Variant {
+                name: name.to_string(),
+                weights,
+            });
+        }
+
+        if variants.len() < 2 {
+            return Err("an experiment needs at least two variants".to_string());
+        }
+        Ok(Self { variants })
+    }
+
+    /// Index of the variant named in a visitor's cookie, if it is still part of the experiment
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.variants.iter().position(|v| v.name == name)
+    }
+
+    /// Pick a variant for a new visitor, uniformly at random
+    pub fn assign(&self) -> usize {
+        let mut bytes = [0u8; 4];
+        if let Err(e) = getrandom::fill(&mut bytes) {
+            eprintln!("Failed to pick an experiment variant: {}", e);
+        }
+        u32::from_le_bytes(bytes) as usize % self.variants.len()
+    }
+}
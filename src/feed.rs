@@ -0,0 +1,79 @@
+use crate::utils::{escape_xml, strip_all_tags};
+use crate::view::ServerView;
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+/// Most entries in one feed
+pub const MAX_FEED_ENTRIES: usize = 50;
+
+/// Longest description excerpt in an entry summary
+const MAX_SUMMARY_LEN: usize = 300;
+
+/// When a server was first listed, falling back to when it was cached
+fn listed_at(view: &ServerView) -> Option<DateTime<Utc>> {
+    let time = view.server.first_seen.as_deref().unwrap_or(&view.server.cached_at);
+    DateTime::parse_from_rfc3339(time).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// Servers for a feed: newest listing first, capped at MAX_FEED_ENTRIES
+/// A server that starts matching shows up as a new entry on the refresh it appears in
+pub fn feed_entries<'a>(servers: impl IntoIterator<Item = &'a ServerView>) -> Vec<&'a ServerView> {
+    let mut entries: Vec<&ServerView> = servers.into_iter().collect();
+    entries.sort_by_key(|view| std::cmp::Reverse(listed_at(view)));
+    entries.truncate(MAX_FEED_ENTRIES);
+    entries
+}
+
+/// Render servers as an Atom feed
+/// `site_url` has no trailing slash; `self_url` is the feed's own absolute URL
+pub fn render_atom(
+    title: &str,
+    site_url: &str,
+    self_url: &str,
+    servers: &[&ServerView],
+    updated: DateTime<Utc>,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    let _ = writeln!(out, r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    let _ = writeln!(out, "  <title>{}</title>", escape_xml(title));
+    let _ = writeln!(out, "  <id>{}</id>", escape_xml(self_url));
+    let _ = writeln!(out, r#"  <link rel="self" href="{}"/>"#, escape_xml(self_url));
+    let _ = writeln!(out, r#"  <link rel="alternate" type="text/html" href="{}/"/>"#, escape_xml(site_url));
+    let _ = writeln!(out, "  <updated>{}</updated>", updated.to_rfc3339());
+    let _ = writeln!(out, "  <author><name>Factorio Server Browser</name></author>");
+
+    for view in servers {
+        let server = &view.server;
+        // server_id survives restarts, so readers don't see a restarted server as new
+        let key = match server.server_id {
+            Some(ref server_id) => server_id.to_string(),
+            None => format!("game-{}", server.game_id),
+        };
+        let entry_updated = listed_at(view).unwrap_or(updated);
+
+        let mut summary = format!(
+            "{} players · {} · {}",
+            view.players_display, server.game_version, view.mods_display
+        );
+        let description: String = strip_all_tags(&server.description).chars().take(MAX_SUMMARY_LEN).collect();
+        if !description.trim().is_empty() {
+            summary.push_str("\n\n");
+            summary.push_str(description.trim());
+        }
+
+        let _ = writeln!(out, "  <entry>");
+        let _ = writeln!(out, "    <title>{}</title>", escape_xml(&strip_all_tags(&server.name)));
+        let _ = writeln!(out, "    <id>urn:factorio-browser:server:{}</id>", escape_xml(&key));
+        let _ = writeln!(out, r#"    <link href="{}/server/{}"/>"#, escape_xml(site_url), server.game_id);
+        let _ = writeln!(out, "    <updated>{}</updated>", entry_updated.to_rfc3339());
+        for tag in &view.display_tags {
+            let _ = writeln!(out, r#"    <category term="{}"/>"#, escape_xml(tag));
+        }
+        let _ = writeln!(out, "    <summary>{}</summary>", escape_xml(&summary));
+        let _ = writeln!(out, "  </entry>");
+    }
+
+    let _ = writeln!(out, "</feed>");
+    out
+}
@@ -0,0 +1,721 @@
+use crate::db::models::CachedServer;
+use crate::geo::continent_name;
+use crate::ids::GameId;
+use crate::modpack::is_fingerprint;
+use crate::tags::{fold_tag, TagAliases};
+use crate::utils::version::GameVersion;
+use crate::view::ServerView;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Largest page size the API will return in one response
+pub const MAX_LIMIT: usize = 1000;
+
+/// Longest version filter accepted; real versions like "1.1.110-1" are far shorter
+const MAX_VERSION_LEN: usize = 32;
+
+/// Longest "new servers" window accepted, in days; first_seen is only known for servers seen since
+/// sightings were tracked, and they're pruned 90 days after a server was last listed
+pub const MAX_NEW_DAYS: u32 = 90;
+
+/// Query parameters for server filtering
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "server", derive(rocket::FromForm))]
+pub struct ServerFilters {
+    /// Search by server name
+    pub search: Option<String>,
+    /// Filter by game version prefix ("all" for every version, defaults to the latest)
+    pub version: Option<String>,
+    /// Only show servers with players
+    pub has_players: Option<bool>,
+    /// Only show servers without password
+    pub no_password: Option<bool>,
+    /// Only show dedicated (headless) servers
+    pub is_dedicated: Option<bool>,
+    /// Only show servers with a free player slot (max_players 0 means unlimited)
+    pub has_free_slots: Option<bool>,
+    /// Continent code from GeoIP, e.g. "EU"
+    pub region: Option<String>,
+    /// Comma-separated tags
+    pub tags: Option<String>,
+    /// How tags combine: "any" (default) or "all"
+    pub tag_mode: Option<String>,
+    /// Filter by mod count (minimum)
+    pub min_mods: Option<u32>,
+    /// Only maps started less than this many minutes of game time ago
+    pub max_game_time: Option<u64>,
+    /// Only servers first listed within this many days
+    pub new_days: Option<u32>,
+    /// Game build mode, e.g. "headless" to leave out servers hosted from a game client
+    pub build_mode: Option<String>,
+    /// Server OS, e.g. "linux64"
+    pub platform: Option<String>,
+    /// Maximum number of results
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for the main page
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "server", derive(rocket::FromForm))]
+pub struct IndexFilters {
+    pub search: Option<String>,
+    pub version: Option<String>,
+    pub has_players: Option<bool>,
+    pub no_password: Option<bool>,
+    pub is_dedicated: Option<bool>,
+    pub has_free_slots: Option<bool>,
+    pub region: Option<String>,
+    /// Only maps younger than this many minutes of game time
+    pub max_game_time: Option<u64>,
+    /// Only servers first listed within this many days
+    pub new_days: Option<u32>,
+    pub tags: Option<String>, // Comma-separated list of tags
+    pub tag_mode: Option<String>, // "any" (default) or "all"
+    pub group_by: Option<String>,
+    pub show_inactive: Option<bool>,
+    pub modpack: Option<String>,
+    /// Also search servers that are off the listing, not just archived ones
+    pub include_offline: Option<bool>,
+    /// List ordering and layout, so the exact view can be bookmarked
+    pub sort: Option<String>,
+    pub dir: Option<String>,
+    pub view: Option<String>,
+    pub columns: Option<String>,
+    /// Page of the server list, from 1
+    pub page: Option<usize>,
+}
+
+impl IndexFilters {
+    /// The filter subset shared with the API, for parsing into a FilterSpec
+    pub fn server_filters(&self) -> ServerFilters {
+        ServerFilters {
+            search: self.search.clone(),
+            version: self.version.clone(),
+            has_players: self.has_players,
+            no_password: self.no_password,
+            is_dedicated: self.is_dedicated,
+            has_free_slots: self.has_free_slots,
+            region: self.region.clone(),
+            tags: self.tags.clone(),
+            tag_mode: self.tag_mode.clone(),
+            min_mods: None,
+            max_game_time: self.max_game_time,
+            new_days: self.new_days,
+            build_mode: None,
+            platform: None,
+            limit: None,
+        }
+    }
+
+    /// Parsed filters, hiding inactive servers unless asked (or configured) to show them
+    pub fn spec(&self, hide_inactive: bool, tag_aliases: &TagAliases) -> FilterSpec {
+        let mut spec = FilterSpec::from(&self.server_filters()).with_tag_aliases(tag_aliases);
+        spec.show_inactive = self.show_inactive.unwrap_or(!hide_inactive);
+        spec.modpack = self.modpack.clone().filter(|m| is_fingerprint(m));
+        spec.include_offline = self.include_offline.unwrap_or(false);
+        spec
+    }
+
+    /// Parse a raw query string the way the page route does, for the hydrated list
+    pub fn from_query(query: &str) -> Self {
+        let mut filters = Self::default();
+        for pair in query.trim_start_matches('?').split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = value.replace('+', " ");
+            let value = urlencoding::decode(&value).map(|v| v.into_owned()).unwrap_or(value);
+            let text = Some(value.clone());
+            match key {
+                "search" => filters.search = text,
+                "version" => filters.version = text,
+                "has_players" => filters.has_players = parse_bool(&value),
+                "no_password" => filters.no_password = parse_bool(&value),
+                "is_dedicated" => filters.is_dedicated = parse_bool(&value),
+                "has_free_slots" => filters.has_free_slots = parse_bool(&value),
+                "region" => filters.region = text,
+                "max_game_time" => filters.max_game_time = value.parse().ok(),
+                "new_days" => filters.new_days = value.parse().ok(),
+                "tags" => filters.tags = text,
+                "tag_mode" => filters.tag_mode = text,
+                "group_by" => filters.group_by = text,
+                "show_inactive" => filters.show_inactive = parse_bool(&value),
+                "modpack" => filters.modpack = text,
+                "include_offline" => filters.include_offline = parse_bool(&value),
+                "sort" => filters.sort = text,
+                "dir" => filters.dir = text,
+                "view" => filters.view = text,
+                "columns" => filters.columns = text,
+                "page" => filters.page = value.parse().ok(),
+                _ => {}
+            }
+        }
+        filters
+    }
+}
+
+/// A trimmed, lowercased text filter (None when empty)
+fn lowercase_value(value: Option<&str>) -> Option<String> {
+    value.map(|v| v.trim().to_lowercase()).filter(|v| !v.is_empty())
+}
+
+/// Form checkbox values, as Rocket reads them ("" and "on" are true)
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "" | "on" | "yes" | "true" => Some(true),
+        "off" | "no" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Which game versions to show
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VersionFilter {
+    /// Newest version in the listing (the default)
+    #[default]
+    Latest,
+    /// No version filter
+    All,
+    /// A release family or exact version, e.g. "2.0" or "1.1.110-1", matched part by part
+    Prefix(String),
+}
+
+impl VersionFilter {
+    /// Parse a version parameter: "" = latest, "all" = no filter, otherwise a version or prefix
+    /// Anything that doesn't start with a number, or looks nothing like a version, falls back to latest
+    pub fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("latest") {
+            return VersionFilter::Latest;
+        }
+        if value.eq_ignore_ascii_case("all") {
+            return VersionFilter::All;
+        }
+
+        let valid = value.len() <= MAX_VERSION_LEN
+            && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'))
+            && GameVersion::parse(value).is_numbered();
+        if valid {
+            VersionFilter::Prefix(value.to_string())
+        } else {
+            VersionFilter::Latest
+        }
+    }
+
+    /// Value for the version form field / query parameter
+    pub fn as_param(&self) -> &str {
+        match self {
+            VersionFilter::Latest => "",
+            VersionFilter::All => "all",
+            VersionFilter::Prefix(prefix) => prefix,
+        }
+    }
+}
+
+/// How multiple selected tags combine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TagMode {
+    /// Server has at least one selected tag
+    #[default]
+    Any,
+    /// Server has every selected tag
+    All,
+}
+
+impl TagMode {
+    /// Parse a tag_mode parameter: "all" (or "and") = All, anything else = Any
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "all" | "and" => TagMode::All,
+            _ => TagMode::Any,
+        }
+    }
+}
+
+/// Normalized server filters, parsed once per request and shared by the SSR list and the API
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct FilterSpec {
+    /// Search text as entered, trimmed
+    pub search: String,
+    search_lower: String,
+    /// Rank of each server the database search found for `search`, 0 being the most relevant
+    /// (see with_search_hits); without it the search matches substrings in memory
+    #[serde(default)]
+    search_ranks: Option<HashMap<GameId, usize>>,
+    pub version: VersionFilter,
+    pub has_players: bool,
+    pub no_password: bool,
+    pub dedicated: bool,
+    pub free_slots: bool,
+    /// Continent code, uppercase (unknown codes are dropped)
+    pub region: Option<String>,
+    pub min_mods: Option<u32>,
+    /// Game time in minutes a map must be under, for finding freshly started ones
+    pub max_game_time: Option<u64>,
+    /// Days since a server was first listed it must be under, for finding new servers (1..=MAX_NEW_DAYS)
+    pub new_days: Option<u32>,
+    /// Build mode and platform, lowercase (API only)
+    pub build_mode: Option<String>,
+    pub platform: Option<String>,
+    /// Selected tags, folded like stored tags (see tags::fold_tag) and deduplicated
+    pub tags: Vec<String>,
+    pub tag_mode: TagMode,
+    /// Result limit, clamped to 1..=MAX_LIMIT
+    pub limit: Option<usize>,
+    /// Include inactive (ghost) servers; the page sets this from the request or HIDE_INACTIVE_SERVERS
+    pub show_inactive: bool,
+    /// Only servers running the modpack with this fingerprint (set by the page)
+    pub modpack: Option<String>,
+    /// Search servers that went offline since the last refresh too, not only archived ones (set by the page)
+    pub include_offline: bool,
+}
+
+impl From<&ServerFilters> for FilterSpec {
+    fn from(filters: &ServerFilters) -> Self {
+        let search = filters.search.as_deref().unwrap_or("").trim().to_string();
+
+        let mut tags: Vec<String> = Vec::new();
+        for tag in filters.tags.as_deref().unwrap_or("").split(',').map(fold_tag) {
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        Self {
+            search_lower: search.to_lowercase(),
+            search_ranks: None,
+            search,
+            version: VersionFilter::parse(filters.version.as_deref().unwrap_or("")),
+            has_players: filters.has_players.unwrap_or(false),
+            no_password: filters.no_password.unwrap_or(false),
+            dedicated: filters.is_dedicated.unwrap_or(false),
+            free_slots: filters.has_free_slots.unwrap_or(false),
+            region: filters
+                .region
+                .as_deref()
+                .map(|r| r.trim().to_uppercase())
+                .filter(|r| continent_name(r).is_some()),
+            min_mods: filters.min_mods,
+            max_game_time: filters.max_game_time.filter(|&minutes| minutes > 0),
+            new_days: filters.new_days.filter(|&days| days > 0).map(|days| days.min(MAX_NEW_DAYS)),
+            build_mode: lowercase_value(filters.build_mode.as_deref()),
+            platform: lowercase_value(filters.platform.as_deref()),
+            tags,
+            tag_mode: TagMode::parse(filters.tag_mode.as_deref().unwrap_or("")),
+            limit: filters.limit.map(|l| l.clamp(1, MAX_LIMIT)),
+            show_inactive: true,
+            modpack: None,
+            include_offline: false,
+        }
+    }
+}
+
+impl FilterSpec {
+    /// Match the search against servers found by DbClient::search_servers, most relevant first,
+    /// instead of scanning names and descriptions in memory
+    pub fn with_search_hits(mut self, hits: &[GameId]) -> Self {
+        if !self.search.is_empty() {
+            self.search_ranks = Some(hits.iter().enumerate().map(|(rank, game_id)| (*game_id, rank)).collect());
+        }
+        self
+    }
+
+    /// Keep the database search results of `previous` while the search is the same
+    pub fn with_search_from(mut self, previous: &FilterSpec) -> Self {
+        if self.search == previous.search {
+            self.search_ranks = previous.search_ranks.clone();
+        }
+        self
+    }
+
+    /// Order search results by relevance, for lists without an explicit sort
+    pub fn sort_by_relevance(&self, servers: &mut [ServerView]) {
+        if let Some(ref ranks) = self.search_ranks {
+            servers.sort_by_key(|s| ranks.get(&s.server.game_id).copied().unwrap_or(usize::MAX));
+        }
+    }
+
+    /// Map the selected tags through TAG_ALIASES, so filtering by an alias finds the canonical tag
+    pub fn with_tag_aliases(mut self, aliases: &TagAliases) -> Self {
+        self.tags = aliases.normalize_all(&self.tags);
+        self
+    }
+
+    /// Check every filter except tags (used for tag counts)
+    /// `latest_version` resolves `VersionFilter::Latest`
+    pub fn matches_ignoring_tags(&self, server: &CachedServer, latest_version: &str) -> bool {
+        if let Some(ref ranks) = self.search_ranks {
+            if !ranks.contains_key(&server.game_id) {
+                return false;
+            }
+        } else if !self.search_lower.is_empty() {
+            let name_matches = server.name.to_lowercase().contains(&self.search_lower);
+            let desc_matches = server.description.to_lowercase().contains(&self.search_lower);
+            let tags_match = server.tags.iter().any(|t| t.to_lowercase().contains(&self.search_lower));
+            if !name_matches && !desc_matches && !tags_match {
+                return false;
+            }
+        }
+
+        let version_prefix = match &self.version {
+            VersionFilter::Latest => latest_version,
+            VersionFilter::All => "",
+            VersionFilter::Prefix(prefix) => prefix,
+        };
+        if !version_prefix.is_empty()
+            && !GameVersion::parse(&server.game_version).starts_with(&GameVersion::parse(version_prefix))
+        {
+            return false;
+        }
+
+        if self.has_players && server.player_count == 0 {
+            return false;
+        }
+
+        if self.no_password && server.has_password {
+            return false;
+        }
+
+        if self.dedicated && !server.headless_server {
+            return false;
+        }
+
+        if let Some(ref region) = self.region
+            && server.continent.as_ref() != Some(region)
+        {
+            return false;
+        }
+
+        // Joining a full server just fails in-game
+        if self.free_slots && server.max_players > 0 && server.player_count >= server.max_players as usize {
+            return false;
+        }
+
+        if let Some(min_mods) = self.min_mods
+            && server.mod_count < min_mods
+        {
+            return false;
+        }
+
+        if let Some(max_game_time) = self.max_game_time
+            && server.game_time_elapsed >= max_game_time
+        {
+            return false;
+        }
+
+        // Servers whose first sighting isn't known yet aren't new
+        if let Some(days) = self.new_days {
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(i64::from(days));
+            let first_seen = server
+                .first_seen
+                .as_deref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok());
+            if first_seen.is_none_or(|t| t < cutoff) {
+                return false;
+            }
+        }
+
+        // Servers cached before build details were stored match neither
+        if let Some(ref build_mode) = self.build_mode
+            && !server.build_mode.as_ref().is_some_and(|m| m.eq_ignore_ascii_case(build_mode))
+        {
+            return false;
+        }
+
+        if let Some(ref platform) = self.platform
+            && !server.platform.as_ref().is_some_and(|p| p.eq_ignore_ascii_case(platform))
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// Check the tag filter (any or all selected tags, per `tag_mode`)
+    pub fn matches_tags(&self, server: &CachedServer) -> bool {
+        if self.tags.is_empty() {
+            return true;
+        }
+        match self.tag_mode {
+            TagMode::Any => self.tags.iter().any(|t| server.tags.contains(t)),
+            TagMode::All => self.tags.iter().all(|t| server.tags.contains(t)),
+        }
+    }
+
+    pub fn matches(&self, server: &CachedServer, latest_version: &str) -> bool {
+        self.matches_ignoring_tags(server, latest_version) && self.matches_tags(server)
+    }
+
+    /// Check the filters that need the view: inactive servers and modpack (both come from the DB, not the listing)
+    pub fn matches_view(&self, view: &ServerView) -> bool {
+        if !self.show_inactive && view.inactive {
+            return false;
+        }
+        match self.modpack {
+            Some(ref modpack) => view.modpack.as_ref() == Some(modpack),
+            None => true,
+        }
+    }
+}
+
+/// A major.minor release family in the listing, e.g. "2.0", with its exact versions
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionFamily {
+    /// e.g. "2.0", which is also the version filter selecting the whole family
+    pub series: String,
+    /// Servers on any version of the family
+    pub servers: usize,
+    /// Exact versions with their server counts, newest first
+    pub versions: Vec<(String, usize)>,
+}
+
+/// Game versions in the listing grouped by release family, newest first, with server counts
+/// Versions that don't start with a number can't be filtered on, so they're left out
+pub fn version_families<'a>(servers: impl IntoIterator<Item = &'a CachedServer>) -> Vec<VersionFamily> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for server in servers {
+        *counts.entry(server.game_version.as_str()).or_default() += 1;
+    }
+    let mut versions: Vec<(GameVersion, &str, usize)> = counts
+        .into_iter()
+        .map(|(version, count)| (GameVersion::parse(version), version, count))
+        .filter(|(parsed, _, _)| parsed.is_numbered())
+        .collect();
+    versions.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+    // Sorted by number, so each family's versions are next to each other
+    let mut families: Vec<VersionFamily> = Vec::new();
+    for (parsed, version, count) in versions {
+        let series = parsed.series();
+        match families.last_mut() {
+            Some(family) if family.series == series => {
+                family.servers += count;
+                family.versions.push((version.to_string(), count));
+            }
+            _ => families.push(VersionFamily {
+                series,
+                servers: count,
+                versions: vec![(version.to_string(), count)],
+            }),
+        }
+    }
+    families
+}
+
+/// All game versions in the listing, newest first (see GameVersion; builds break ties)
+pub fn versions_newest_first<'a>(servers: impl IntoIterator<Item = &'a CachedServer>) -> Vec<String> {
+    let mut versions: Vec<(GameVersion, &str)> = servers
+        .into_iter()
+        .map(|s| (GameVersion::parse(&s.game_version).with_build(s.build_version), s.game_version.as_str()))
+        .collect();
+    versions.sort_by(|a, b| b.0.cmp(&a.0));
+    let mut seen = HashSet::new();
+    versions
+        .into_iter()
+        .filter(|(_, v)| seen.insert(*v))
+        .map(|(_, v)| v.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    const LATEST: &str = "2.0.28";
+
+    /// A listed server, with `fields` replacing the defaults
+    fn server(fields: Value) -> CachedServer {
+        let mut server = json!({
+            "game_id": 1,
+            "server_id": "abc",
+            "name": "Big Base",
+            "description": "Friendly megabase",
+            "max_players": 10,
+            "player_count": 3,
+            "game_time_elapsed": 600,
+            "has_password": false,
+            "tags": ["pvp", "eu"],
+            "mod_count": 0,
+            "game_version": LATEST,
+            "build_version": 0,
+            "build_mode": "headless",
+            "platform": "linux64",
+            "headless_server": true,
+            "continent": "EU",
+            "first_seen": (chrono::Utc::now() - chrono::Duration::days(3)).to_rfc3339(),
+            "cached_at": "2026-01-01T00:00:00Z",
+        });
+        for (key, value) in fields.as_object().unwrap() {
+            server[key] = value.clone();
+        }
+        serde_json::from_value(server).unwrap()
+    }
+
+    /// Whether the page filters in `query` match a server
+    fn matches(query: &str, fields: Value) -> bool {
+        IndexFilters::from_query(query)
+            .spec(false, &TagAliases::default())
+            .matches(&server(fields), LATEST)
+    }
+
+    #[test]
+    fn no_filters_match_the_latest_version() {
+        assert!(matches("", json!({})));
+        assert!(!matches("", json!({"game_version": "1.1.110"})));
+    }
+
+    #[test]
+    fn search_matches_names_descriptions_and_tags() {
+        assert!(matches("search=big+BASE", json!({})));
+        assert!(matches("search=megabase", json!({})));
+        assert!(matches("search=pv", json!({})));
+        assert!(!matches("search=space", json!({})));
+
+        let spec = FilterSpec::from(&ServerFilters { search: Some("space".into()), ..Default::default() });
+        let ranked = spec.with_search_hits(&[GameId(1)]);
+        assert!(ranked.matches(&server(json!({})), LATEST));
+        assert!(!ranked.matches(&server(json!({"game_id": 2})), LATEST));
+    }
+
+    #[test]
+    fn version_filters_match_by_prefix() {
+        let old = json!({"game_version": "1.1.110"});
+        assert!(matches("version=all", old.clone()));
+        assert!(matches("version=1.1", old.clone()));
+        assert!(!matches("version=1.1", json!({})));
+        assert!(!matches("version=1.1.11", old.clone()));
+        // Nonsense falls back to the latest version
+        assert!(!matches("version=<script>", old));
+        assert_eq!(VersionFilter::parse("latest"), VersionFilter::Latest);
+        assert_eq!(VersionFilter::parse(&"1".repeat(MAX_VERSION_LEN + 1)), VersionFilter::Latest);
+    }
+
+    #[test]
+    fn flag_filters() {
+        assert!(matches("has_players=true", json!({})));
+        assert!(!matches("has_players=true", json!({"player_count": 0})));
+        assert!(!matches("no_password=on", json!({"has_password": true})));
+        assert!(matches("no_password=off", json!({"has_password": true})));
+        assert!(!matches("is_dedicated", json!({"headless_server": false})));
+    }
+
+    #[test]
+    fn free_slots_treat_zero_max_players_as_unlimited() {
+        assert!(matches("has_free_slots=true", json!({"player_count": 9})));
+        assert!(!matches("has_free_slots=true", json!({"player_count": 10})));
+        assert!(matches("has_free_slots=true", json!({"player_count": 50, "max_players": 0})));
+    }
+
+    #[test]
+    fn region_must_be_a_known_continent() {
+        assert!(matches("region=eu", json!({})));
+        assert!(!matches("region=NA", json!({})));
+        assert!(!matches("region=EU", json!({"continent": null})));
+        // Unknown codes are dropped rather than matching nothing
+        assert!(matches("region=XX", json!({"continent": "NA"})));
+    }
+
+    #[test]
+    fn mods_and_map_age() {
+        let spec = FilterSpec::from(&ServerFilters { min_mods: Some(5), ..Default::default() });
+        assert!(!spec.matches(&server(json!({"mod_count": 4})), LATEST));
+        assert!(spec.matches(&server(json!({"mod_count": 5})), LATEST));
+
+        assert!(matches("max_game_time=601", json!({})));
+        assert!(!matches("max_game_time=600", json!({})));
+        assert!(matches("max_game_time=0", json!({"game_time_elapsed": 100_000})));
+    }
+
+    #[test]
+    fn new_servers_need_a_recent_first_seen() {
+        let days_ago = |days: i64| (chrono::Utc::now() - chrono::Duration::days(days)).to_rfc3339();
+        assert!(matches("new_days=7", json!({})));
+        assert!(!matches("new_days=1", json!({})));
+        assert!(!matches("new_days=7", json!({"first_seen": null})));
+        assert!(!matches("new_days=7", json!({"first_seen": "yesterday"})));
+        // Windows past MAX_NEW_DAYS are clamped
+        assert!(!matches("new_days=1000", json!({"first_seen": days_ago(i64::from(MAX_NEW_DAYS) + 1)})));
+        assert!(matches("new_days=1000", json!({"first_seen": days_ago(i64::from(MAX_NEW_DAYS) - 1)})));
+    }
+
+    #[test]
+    fn build_mode_and_platform_ignore_case() {
+        let spec = FilterSpec::from(&ServerFilters {
+            build_mode: Some(" Headless ".into()),
+            platform: Some("LINUX64".into()),
+            ..Default::default()
+        });
+        assert!(spec.matches(&server(json!({})), LATEST));
+        assert!(!spec.matches(&server(json!({"build_mode": "client"})), LATEST));
+        assert!(!spec.matches(&server(json!({"platform": null})), LATEST));
+    }
+
+    #[test]
+    fn tags_match_any_or_all() {
+        assert!(matches("tags=PvP,coop", json!({})));
+        assert!(!matches("tags=pvp,coop&tag_mode=all", json!({})));
+        assert!(matches("tags=pvp,EU&tag_mode=and", json!({})));
+
+        let aliases = TagAliases::parse("player vs player=pvp").unwrap();
+        let spec = IndexFilters::from_query("tags=Player+vs+Player").spec(false, &aliases);
+        assert_eq!(spec.tags, ["pvp"]);
+        assert!(spec.matches_ignoring_tags(&server(json!({"tags": []})), LATEST));
+        assert!(!spec.matches(&server(json!({"tags": []})), LATEST));
+    }
+
+    #[test]
+    fn inactive_and_modpack_filters_use_the_view() {
+        let mut view = ServerView::from(server(json!({})));
+        view.inactive = true;
+        view.modpack = Some("0123456789abcdef".to_string());
+        assert!(!IndexFilters::from_query("").spec(true, &TagAliases::default()).matches_view(&view));
+        assert!(IndexFilters::from_query("show_inactive=true").spec(true, &TagAliases::default()).matches_view(&view));
+        let spec = |query: &str| IndexFilters::from_query(query).spec(false, &TagAliases::default());
+        assert!(spec("modpack=0123456789abcdef").matches_view(&view));
+        assert!(!spec("modpack=fedcba9876543210").matches_view(&view));
+        // Not a fingerprint, so no filter
+        assert!(spec("modpack=vanilla").matches_view(&view));
+    }
+
+    #[test]
+    fn limit_is_clamped() {
+        let limit = |limit| FilterSpec::from(&ServerFilters { limit: Some(limit), ..Default::default() }).limit;
+        assert_eq!(limit(0), Some(1));
+        assert_eq!(limit(50), Some(50));
+        assert_eq!(limit(MAX_LIMIT + 1), Some(MAX_LIMIT));
+    }
+
+    #[test]
+    fn page_query_round_trips_through_server_filters() {
+        let query = "search=%20Big%20Base%20&version=2.0&has_players=on&no_password=true&is_dedicated=&has_free_slots=yes\
+            &region=eu&max_game_time=60&new_days=7&tags=PvP,eu,pvp&tag_mode=all";
+        let page = IndexFilters::from_query(query).server_filters();
+        let api = ServerFilters {
+            search: Some(" Big Base ".into()),
+            version: Some("2.0".into()),
+            has_players: Some(true),
+            no_password: Some(true),
+            is_dedicated: Some(true),
+            has_free_slots: Some(true),
+            region: Some("eu".into()),
+            tags: Some("PvP,eu,pvp".into()),
+            tag_mode: Some("all".into()),
+            max_game_time: Some(60),
+            new_days: Some(7),
+            ..Default::default()
+        };
+        let spec = FilterSpec::from(&page);
+        assert_eq!(spec, FilterSpec::from(&api));
+
+        assert_eq!(spec.search, "Big Base");
+        assert_eq!(spec.version, VersionFilter::Prefix("2.0".into()));
+        assert!(spec.has_players && spec.no_password && spec.dedicated && spec.free_slots);
+        assert_eq!(spec.region.as_deref(), Some("EU"));
+        assert_eq!((spec.max_game_time, spec.new_days), (Some(60), Some(7)));
+        assert_eq!(spec.tags, ["pvp", "eu"]);
+        assert_eq!(spec.tag_mode, TagMode::All);
+
+        // The spec is sent to the hydrated list as JSON
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(serde_json::from_str::<FilterSpec>(&json).unwrap(), spec);
+    }
+}
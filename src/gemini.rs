@@ -0,0 +1,198 @@
+use crate::cache::ServerCache;
+use crate::filter::{FilterSpec, ServerFilters};
+use crate::ids::GameId;
+use crate::utils::strip_all_tags;
+use crate::view::ServerView;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{self, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+/// Standard Gemini port
+pub const DEFAULT_GEMINI_ADDR: &str = "0.0.0.0:1965";
+
+/// Servers on the listing page; the rest are reachable through search
+const LISTING_SERVERS: usize = 100;
+
+/// A request is one URL of at most 1024 bytes plus CRLF
+const MAX_REQUEST: usize = 1026;
+
+/// Clients that don't send their request line within this are dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// TLS setup from PEM files; Gemini clients trust on first use, so a self-signed certificate is fine
+pub fn tls_acceptor(cert: &Path, key: &Path) -> Result<TlsAcceptor, String> {
+    let certs = CertificateDer::pem_file_iter(cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("can't read certificate {}: {}", cert.display(), e))?;
+    let key = PrivateKeyDer::from_pem_file(key).map_err(|e| format!("can't read key {}: {}", key.display(), e))?;
+    let config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .and_then(|builder| builder.with_no_client_auth().with_single_cert(certs, key))
+        .map_err(|e| e.to_string())?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// A line of listing text as plain gemtext, so descriptions can't turn into links or headings
+fn text_line(line: &str) -> String {
+    let line = line.trim_end();
+    if ["=>", "#", "*", ">", "```"].iter().any(|marker| line.starts_with(marker)) {
+        format!(" {}", line)
+    } else {
+        line.to_string()
+    }
+}
+
+/// One link line per server: name, players, version
+fn server_link(view: &ServerView) -> String {
+    format!(
+        "=> /server/{} {} · {} · {}{}\n",
+        view.server.game_id,
+        strip_all_tags(&view.server.name).replace('\n', " "),
+        view.players_display,
+        view.server.game_version,
+        if view.server.has_password { " · password" } else { "" }
+    )
+}
+
+/// The listing (or search results) as gemtext, in list order
+pub fn render_listing(site_name: &str, servers: &[&ServerView], search: &str) -> String {
+    let players: usize = servers.iter().map(|s| s.server.player_count).sum();
+    let mut page = format!("# {}\n\n", site_name);
+    if search.is_empty() {
+        page.push_str(&format!("{} servers listed, {} players online.\n", servers.len(), players));
+    } else {
+        let plural = if servers.len() == 1 { "" } else { "s" };
+        page.push_str(&format!("{} server{} matching \"{}\".\n", servers.len(), plural, text_line(search)));
+    }
+    page.push_str("=> /search Search servers\n");
+    if !search.is_empty() {
+        page.push_str("=> / All servers\n");
+    }
+    page.push_str("\n## Servers\n\n");
+    for view in servers.iter().take(LISTING_SERVERS) {
+        page.push_str(&server_link(view));
+    }
+    if servers.len() > LISTING_SERVERS {
+        page.push_str(&format!("\n{} more; search to narrow the list down.\n", servers.len() - LISTING_SERVERS));
+    }
+    page
+}
+
+/// One server as gemtext: what's needed to pick it and join
+pub fn render_server(view: &ServerView) -> String {
+    let server = &view.server;
+    let mut page = format!("# {}\n\n", text_line(&strip_all_tags(&server.name)));
+    for line in strip_all_tags(&server.description).lines() {
+        page.push_str(&text_line(line));
+        page.push('\n');
+    }
+    page.push('\n');
+    page.push_str(&format!("* Players: {}\n", view.players_display));
+    page.push_str(&format!("* Version: {}\n", server.game_version));
+    page.push_str(&format!("* Game time: {}\n", view.game_time));
+    page.push_str(&format!("* Mods: {}\n", view.mods_display));
+    page.push_str(&format!("* Password: {}\n", if server.has_password { "yes" } else { "no" }));
+    if !server.tags.is_empty() {
+        page.push_str(&format!("* Tags: {}\n", server.tags.join(", ")));
+    }
+    if let Some(ref address) = server.host_address {
+        page.push_str(&format!("* Address: {}\n", address));
+    }
+    page.push_str("\n## Online players\n\n");
+    if server.players.is_empty() {
+        page.push_str("Nobody is playing right now.\n");
+    }
+    for player in &server.players {
+        page.push_str(&format!("* {}\n", player));
+    }
+    page.push_str("\n=> / Back to the listing\n");
+    page
+}
+
+/// Response (header line plus body) for one request URL
+fn respond(url: &str, cache: &ServerCache, site_name: &str) -> String {
+    let Some(rest) = url.strip_prefix("gemini://") else {
+        return "59 Only gemini:// URLs are served here\r\n".to_string();
+    };
+    let path_and_query = rest.find('/').map(|i| &rest[i..]).unwrap_or("/");
+    let (path, query) = match path_and_query.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path_and_query, None),
+    };
+    let snapshot = cache.load();
+    let ok = |body: String| format!("20 text/gemini; charset=utf-8\r\n{}", body);
+
+    match path {
+        "" | "/" => ok(render_listing(site_name, &snapshot.servers.iter().collect::<Vec<_>>(), "")),
+        "/search" => {
+            let Some(search) = query.and_then(|q| urlencoding::decode(q).ok()).filter(|q| !q.trim().is_empty()) else {
+                return "10 Server name, description or tag\r\n".to_string();
+            };
+            let spec = FilterSpec::from(&ServerFilters {
+                search: Some(search.into_owned()),
+                version: Some("all".to_string()),
+                ..Default::default()
+            });
+            let matching: Vec<&ServerView> = snapshot.servers.iter().filter(|s| spec.matches(&s.server, "")).collect();
+            ok(render_listing(site_name, &matching, &spec.search))
+        }
+        _ => {
+            let server = path
+                .strip_prefix("/server/")
+                .and_then(|id| id.parse::<GameId>().ok())
+                .and_then(|id| snapshot.find(id));
+            match server {
+                Some(view) => ok(render_server(view)),
+                None => "51 Not found\r\n".to_string(),
+            }
+        }
+    }
+}
+
+/// Serve the in-memory listing over Gemini until the process exits
+pub async fn serve(addr: &str, tls: TlsAcceptor, cache: Arc<ServerCache>, site_name: String) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    println!("Gemini mirror listening on {}", addr);
+    let site_name: Arc<str> = site_name.into();
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                eprintln!("Failed to accept Gemini connection: {}", e);
+                continue;
+            }
+        };
+        let tls = tls.clone();
+        let cache = cache.clone();
+        let site_name = site_name.clone();
+        tokio::spawn(async move {
+            let handled = tokio::time::timeout(REQUEST_TIMEOUT, async {
+                let mut stream = tls.accept(stream).await?;
+                let mut request = Vec::with_capacity(MAX_REQUEST);
+                let mut byte = [0u8; 1];
+                while !request.ends_with(b"\r\n") && request.len() < MAX_REQUEST {
+                    if stream.read(&mut byte).await? == 0 {
+                        break;
+                    }
+                    request.push(byte[0]);
+                }
+                let response = match std::str::from_utf8(&request).ok().and_then(|r| r.strip_suffix("\r\n")) {
+                    Some(url) => respond(url, &cache, &site_name),
+                    None => "59 Bad request\r\n".to_string(),
+                };
+                stream.write_all(response.as_bytes()).await?;
+                stream.shutdown().await
+            })
+            .await;
+            if let Ok(Err(e)) = handled {
+                eprintln!("Gemini request failed: {}", e);
+            }
+        });
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(feature = "server")]
+use maxminddb::{geoip2, Reader};
+use std::net::{IpAddr, SocketAddr};
+#[cfg(feature = "server")]
+use std::path::Path;
+#[cfg(feature = "server")]
+use std::sync::Arc;
+
+/// Continent codes used by MaxMind, with display names, in filter dropdown order
+pub const CONTINENTS: &[(&str, &str)] = &[
+    ("EU", "Europe"),
+    ("NA", "North America"),
+    ("SA", "South America"),
+    ("AS", "Asia"),
+    ("OC", "Oceania"),
+    ("AF", "Africa"),
+    ("AN", "Antarctica"),
+];
+
+/// Rough round-trip times between continents in milliseconds, each pair listed once in either order
+/// Only good for telling a nearby server from one across the world
+pub const CONTINENT_RTT_MS: &[(&str, &str, u32)] = &[
+    ("EU", "EU", 30),
+    ("NA", "NA", 50),
+    ("SA", "SA", 60),
+    ("AS", "AS", 70),
+    ("OC", "OC", 40),
+    ("AF", "AF", 60),
+    ("EU", "NA", 100),
+    ("EU", "SA", 200),
+    ("EU", "AS", 180),
+    ("EU", "OC", 280),
+    ("EU", "AF", 120),
+    ("NA", "SA", 140),
+    ("NA", "AS", 160),
+    ("NA", "OC", 180),
+    ("NA", "AF", 220),
+    ("SA", "AS", 300),
+    ("SA", "OC", 250),
+    ("SA", "AF", 300),
+    ("AS", "OC", 130),
+    ("AS", "AF", 230),
+    ("OC", "AF", 300),
+];
+
+/// CONTINENT_RTT_MS as "EU:NA:100,..." for sort.js
+pub fn rtt_table() -> String {
+    CONTINENT_RTT_MS
+        .iter()
+        .map(|(a, b, ms)| format!("{}:{}:{}", a, b, ms))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Display name of a continent code, e.g. "EU" = "Europe"
+pub fn continent_name(code: &str) -> Option<&'static str> {
+    CONTINENTS
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, name)| *name)
+}
+
+/// Flag emoji for a two-letter ISO country code, built from regional indicator symbols
+pub fn country_flag(iso_code: &str) -> Option<String> {
+    if iso_code.len() != 2 || !iso_code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    iso_code
+        .to_ascii_uppercase()
+        .chars()
+        .map(|c| char::from_u32(0x1F1E6 + (c as u32 - 'A' as u32)))
+        .collect()
+}
+
+/// Where a server's address is located
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// ISO 3166-1 alpha-2 code, e.g. "DE"
+    pub country: Option<String>,
+    /// MaxMind continent code, e.g. "EU"
+    pub continent: Option<String>,
+}
+
+/// IP address of a host_address ("1.2.3.4:34197", "[::1]:34197", or a bare IP)
+/// Hostnames aren't resolved, so refreshes never wait on DNS
+pub fn host_ip(host_address: &str) -> Option<IpAddr> {
+    host_address
+        .parse::<SocketAddr>()
+        .map(|addr| addr.ip())
+        .or_else(|_| host_address.parse::<IpAddr>())
+        .ok()
+}
+
+/// Country lookups against a MaxMind GeoLite2/GeoIP2 Country or City database
+#[cfg(feature = "server")]
+pub struct GeoIp {
+    reader: Reader<Vec<u8>>,
+}
+
+#[cfg(feature = "server")]
+impl GeoIp {
+    /// Load the database file into memory
+    pub fn open_shared(path: &Path) -> Result<Arc<Self>, String> {
+        let reader = Reader::open_readfile(path).map_err(|e| e.to_string())?;
+        Ok(Arc::new(Self { reader }))
+    }
+
+    /// Region of a server's host_address, or None when it isn't an IP or isn't in the database
+    pub fn lookup(&self, host_address: &str) -> Option<Region> {
+        let ip = host_ip(host_address)?;
+        let record: geoip2::Country = self.reader.lookup(ip).ok()?;
+        let country = record.country.and_then(|c| c.iso_code).map(str::to_string);
+        let continent = record.continent.and_then(|c| c.code).map(str::to_string);
+        if country.is_none() && continent.is_none() {
+            return None;
+        }
+        Some(Region { country, continent })
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Matchmaking game ID: identifies one run of a server and changes on every restart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GameId(pub u64);
+
+/// Stable server identity reported by the matchmaking API, kept across restarts
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ServerId(pub String);
+
+impl fmt::Display for GameId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<u64> for GameId {
+    fn from(id: u64) -> Self {
+        GameId(id)
+    }
+}
+
+impl std::str::FromStr for GameId {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(GameId)
+    }
+}
+
+impl ServerId {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ServerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<String> for ServerId {
+    fn from(id: String) -> Self {
+        ServerId(id)
+    }
+}
+
+impl From<&str> for ServerId {
+    fn from(id: &str) -> Self {
+        ServerId(id.to_string())
+    }
+}
+
+impl std::ops::Deref for ServerId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for ServerId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for ServerId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+/// Route parameters and `uri!` arguments, for the web server
+#[cfg(feature = "server")]
+mod rocket_impls {
+    use super::{GameId, ServerId};
+    use rocket::http::impl_from_uri_param_identity;
+    use rocket::http::uri::fmt::{Formatter, Path, UriDisplay};
+    use rocket::request::FromParam;
+    use std::fmt;
+
+    impl UriDisplay<Path> for GameId {
+        fn fmt(&self, f: &mut Formatter<'_, Path>) -> fmt::Result {
+            f.write_value(self.0)
+        }
+    }
+
+    impl_from_uri_param_identity!([Path] GameId);
+
+    impl<'a> FromParam<'a> for GameId {
+        type Error = std::num::ParseIntError;
+
+        fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+            param.parse()
+        }
+    }
+
+    impl UriDisplay<Path> for ServerId {
+        fn fmt(&self, f: &mut Formatter<'_, Path>) -> fmt::Result {
+            f.write_value(&self.0)
+        }
+    }
+
+    impl_from_uri_param_identity!([Path] ServerId);
+
+    impl<'a> FromParam<'a> for ServerId {
+        type Error = &'a str;
+
+        fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+            if param.is_empty() {
+                Err(param)
+            } else {
+                Ok(ServerId(param.to_string()))
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use crate::accounts::hex;
+use crate::components::picture::ResponsiveImage;
+use image::codecs::avif::AvifEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader, Limits};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, Semaphore};
+
+/// Directory resized images are kept in unless IMAGE_CACHE_DIR says otherwise
+pub const DEFAULT_CACHE_DIR: &str = "image-cache";
+
+/// Box (in pixels) images are resized to fit; requested sizes round up to the next one
+pub const SIZES: &[u32] = &[32, 64, 128, 256, 512];
+
+/// Largest upstream image accepted, before decoding
+const MAX_SOURCE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Sources with larger sides aren't decoded, so a small file can't expand into a huge bitmap
+const MAX_SOURCE_SIDE: u32 = 8192;
+
+/// Memory a single decode may allocate
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// Upstream content types the proxy accepts; everything is re-encoded
+const CONTENT_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Cached images older than this are fetched again (and served stale if that fails)
+const CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upstream fetches at once, so a page full of new thumbnails doesn't fan out
+const CONCURRENT_FETCHES: usize = 4;
+
+/// Images waiting for their variants to be generated; more are left to the first request
+const JOB_QUEUE: usize = 256;
+
+/// AVIF encoder speed (1-10) and quality (1-100): thumbnails don't need slow, careful encodes
+const AVIF_SPEED: u8 = 10;
+const AVIF_QUALITY: u8 = 70;
+
+/// Hex characters of the URL digest in proxy paths
+const HASH_LEN: usize = 32;
+
+/// Formats images are served in; pages offer AVIF and WebP with PNG as the fallback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Png,
+    Webp,
+    Avif,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 3] = [OutputFormat::Png, OutputFormat::Webp, OutputFormat::Avif];
+
+    /// Value of the `format` query parameter, and the cache file extension
+    pub fn key(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Webp => "webp",
+            OutputFormat::Avif => "avif",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.key() == key)
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "image/png",
+            OutputFormat::Webp => "image/webp",
+            OutputFormat::Avif => "image/avif",
+        }
+    }
+}
+
+/// Why an image can't be served
+#[derive(Debug)]
+pub enum ProxyError {
+    /// No page links an image with this hash
+    Unknown,
+    /// The upstream host couldn't be reached or didn't answer 2xx
+    Fetch(String),
+    /// Too large, not an accepted content type, or not a decodable image
+    Rejected(String),
+}
+
+impl std::fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyError::Unknown => write!(f, "unknown image"),
+            ProxyError::Fetch(e) => write!(f, "fetch failed: {}", e),
+            ProxyError::Rejected(e) => write!(f, "rejected: {}", e),
+        }
+    }
+}
+
+/// Variants of one image to generate ahead of the first request for them
+struct Job {
+    hash: String,
+    url: String,
+    sizes: Vec<u32>,
+}
+
+/// Serves third-party images (mod thumbnails, the header logo) from this host: fetched once,
+/// checked, resized, re-encoded and cached on disk, so visitors never load them from hosts they
+/// didn't choose
+/// Only URLs a page was rendered with can be fetched, so the proxy can't be pointed elsewhere
+pub struct ImageProxy {
+    client: Client,
+    dir: PathBuf,
+    /// Upstream URL by hash, for every image linked through the proxy since startup
+    urls: RwLock<HashMap<String, String>>,
+    fetches: Semaphore,
+    /// Newly linked images, for the background job that renders their sizes and formats
+    jobs: mpsc::Sender<Job>,
+    /// Names temporary files, so concurrent writes of one image don't interleave
+    writes: AtomicU64,
+}
+
+impl ImageProxy {
+    /// Create a proxy caching into `dir` (created on first write) and start its variant job
+    pub fn new_shared(dir: PathBuf) -> Arc<Self> {
+        let client = Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .expect("default HTTP client settings are valid");
+        let (jobs, receiver) = mpsc::channel(JOB_QUEUE);
+        let proxy = Arc::new(Self {
+            client,
+            dir,
+            urls: RwLock::new(HashMap::new()),
+            fetches: Semaphore::new(CONCURRENT_FETCHES),
+            jobs,
+            writes: AtomicU64::new(0),
+        });
+        tokio::spawn(run_jobs(proxy.clone(), receiver));
+        proxy
+    }
+
+    /// Proxy paths for an image shown at up to `size` CSS pixels, at 1x and 2x in every format;
+    /// allows the proxy to fetch `url`, and queues the variants the first time it's linked
+    pub fn responsive(&self, url: &str, size: u32) -> ResponsiveImage {
+        let hash = url_hash(url);
+        let mut densities = vec![(fit_size(Some(size)), "1x")];
+        let double = fit_size(Some(size.saturating_mul(2)));
+        if double != densities[0].0 {
+            densities.push((double, "2x"));
+        }
+
+        let new = self
+            .urls
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(hash.clone(), url.to_string())
+            .is_none();
+        if new {
+            let job = Job {
+                hash: hash.clone(),
+                url: url.to_string(),
+                sizes: densities.iter().map(|(size, _)| *size).collect(),
+            };
+            // A full queue only means the first request renders its variant itself
+            let _ = self.jobs.try_send(job);
+        }
+
+        let path = |size: u32, format: OutputFormat| format!("/img-proxy/{}?size={}&format={}", hash, size, format.key());
+        let srcset = |format: OutputFormat| {
+            densities
+                .iter()
+                .map(|(size, density)| format!("{} {}", path(*size, format), density))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        ResponsiveImage {
+            src: path(densities[0].0, OutputFormat::Png),
+            srcset: srcset(OutputFormat::Png),
+            avif_srcset: srcset(OutputFormat::Avif),
+            webp_srcset: srcset(OutputFormat::Webp),
+        }
+    }
+
+    /// The image behind a proxy hash fitting a `size` box (the largest size when None)
+    pub async fn get(&self, hash: &str, size: Option<u32>, format: OutputFormat) -> Result<Vec<u8>, ProxyError> {
+        if hash.len() != HASH_LEN || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ProxyError::Unknown);
+        }
+        let size = fit_size(size);
+        let path = self.variant_path(hash, size, format);
+
+        let cached = match tokio::fs::read(&path).await {
+            Ok(bytes) if self.is_fresh(&path).await => return Ok(bytes),
+            Ok(bytes) => Some(bytes),
+            Err(_) => None,
+        };
+
+        let url = self.urls.read().unwrap_or_else(|e| e.into_inner()).get(hash).cloned();
+        let rendered = match url {
+            Some(url) => self.render(hash, &url, &[size], &[format]).await,
+            None => Err(ProxyError::Unknown),
+        };
+        match (rendered, cached) {
+            (Ok(mut variants), _) => variants.pop().map(|(_, _, bytes)| bytes).ok_or(ProxyError::Unknown),
+            // Images only linked before a restart stay available, as does everything when a host is down
+            (Err(ProxyError::Unknown | ProxyError::Fetch(_)), Some(stale)) => Ok(stale),
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    fn variant_path(&self, hash: &str, size: u32, format: OutputFormat) -> PathBuf {
+        self.dir.join(format!("{}-{}.{}", hash, size, format.key()))
+    }
+
+    async fn is_fresh(&self, path: &std::path::Path) -> bool {
+        tokio::fs::metadata(path)
+            .await
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age < CACHE_TTL))
+    }
+
+    /// Fetch an image once and cache it in every given size and format
+    async fn render(
+        &self,
+        hash: &str,
+        url: &str,
+        sizes: &[u32],
+        formats: &[OutputFormat],
+    ) -> Result<Vec<(u32, OutputFormat, Vec<u8>)>, ProxyError> {
+        let source = self.fetch(url).await?;
+        let (sizes, formats) = (sizes.to_vec(), formats.to_vec());
+        let variants = tokio::task::spawn_blocking(move || encode_variants(&source, &sizes, &formats))
+            .await
+            .map_err(|e| ProxyError::Rejected(e.to_string()))??;
+        for (size, format, bytes) in &variants {
+            self.store(&self.variant_path(hash, *size, *format), bytes).await;
+        }
+        Ok(variants)
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Vec<u8>, ProxyError> {
+        let _permit = self.fetches.acquire().await.map_err(|e| ProxyError::Fetch(e.to_string()))?;
+        let mut response = self.client.get(url).send().await.map_err(|e| ProxyError::Fetch(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ProxyError::Fetch(format!("upstream answered {}", response.status())));
+        }
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        if !CONTENT_TYPES.contains(&content_type.as_str()) {
+            return Err(ProxyError::Rejected(format!("content type '{}'", content_type)));
+        }
+        if response.content_length().is_some_and(|len| len > MAX_SOURCE_BYTES as u64) {
+            return Err(ProxyError::Rejected("too large".to_string()));
+        }
+
+        // Content-Length can be missing or wrong, so the limit is enforced while reading too
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| ProxyError::Fetch(e.to_string()))? {
+            if body.len() + chunk.len() > MAX_SOURCE_BYTES {
+                return Err(ProxyError::Rejected("too large".to_string()));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Write through a temporary file, so readers never see half an image
+    async fn store(&self, path: &std::path::Path, bytes: &[u8]) {
+        let temp = path.with_extension(format!("{}.tmp", self.writes.fetch_add(1, Ordering::Relaxed)));
+        let written = async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            tokio::fs::write(&temp, bytes).await?;
+            tokio::fs::rename(&temp, path).await
+        };
+        if let Err(e) = written.await {
+            eprintln!("Failed to cache proxied image {}: {}", path.display(), e);
+            let _ = tokio::fs::remove_file(&temp).await;
+        }
+    }
+}
+
+/// Render every size and format of newly linked images in the background, so the srcset variants a
+/// browser picks are usually cached before it asks for them
+async fn run_jobs(proxy: Arc<ImageProxy>, mut receiver: mpsc::Receiver<Job>) {
+    while let Some(job) = receiver.recv().await {
+        let mut complete = true;
+        for size in &job.sizes {
+            for format in OutputFormat::ALL {
+                complete &= proxy.is_fresh(&proxy.variant_path(&job.hash, *size, format)).await;
+            }
+        }
+        if complete {
+            continue;
+        }
+        if let Err(e) = proxy.render(&job.hash, &job.url, &job.sizes, &OutputFormat::ALL).await {
+            eprintln!("Failed to render image variants for {}: {}", job.url, e);
+        }
+    }
+}
+
+fn url_hash(url: &str) -> String {
+    hex(&Sha256::digest(url.as_bytes())[..HASH_LEN / 2])
+}
+
+/// Smallest supported size at least `size` (the largest when None or larger)
+fn fit_size(size: Option<u32>) -> u32 {
+    let largest = SIZES[SIZES.len() - 1];
+    size.and_then(|size| SIZES.iter().copied().find(|s| *s >= size)).unwrap_or(largest)
+}
+
+/// Decode an image once (sniffing the format rather than trusting the content type) and encode it
+/// within each `size` box in each format; smaller images keep their size
+fn encode_variants(
+    source: &[u8],
+    sizes: &[u32],
+    formats: &[OutputFormat],
+) -> Result<Vec<(u32, OutputFormat, Vec<u8>)>, ProxyError> {
+    let mut reader = ImageReader::new(Cursor::new(source))
+        .with_guessed_format()
+        .map_err(|e| ProxyError::Rejected(e.to_string()))?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_SIDE);
+    limits.max_image_height = Some(MAX_SOURCE_SIDE);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    reader.limits(limits);
+    // Every encoder takes 8-bit RGBA, whatever the source was
+    let image = DynamicImage::ImageRgba8(reader.decode().map_err(|e| ProxyError::Rejected(e.to_string()))?.to_rgba8());
+
+    let mut variants = Vec::new();
+    for &size in sizes {
+        let resized = if image.width() > size || image.height() > size {
+            image.resize(size, size, FilterType::Triangle)
+        } else {
+            image.clone()
+        };
+        for &format in formats {
+            let mut bytes = Vec::new();
+            let encoded = match format {
+                OutputFormat::Png => resized.write_with_encoder(PngEncoder::new(&mut bytes)),
+                OutputFormat::Webp => resized.write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+                OutputFormat::Avif => {
+                    resized.write_with_encoder(AvifEncoder::new_with_speed_quality(&mut bytes, AVIF_SPEED, AVIF_QUALITY))
+                }
+            };
+            encoded.map_err(|e| ProxyError::Rejected(e.to_string()))?;
+            variants.push((size, format, bytes));
+        }
+    }
+    Ok(variants)
+}
@@ -0,0 +1,231 @@
+use crate::db::models::CachedServer;
+use crate::geo::host_ip;
+use crate::ids::{GameId, ServerId};
+use crate::utils::strip_all_tags;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Listings younger than this are checked against the established servers
+const NEW_LISTING_DAYS: i64 = 7;
+
+/// A verified server must have been listed this long before its name is protected
+const ESTABLISHED_DAYS: i64 = 30;
+
+/// Folded names shorter than this are too generic ("Factorio", "Vanilla") to claim
+const MIN_NAME_CHARS: usize = 6;
+
+/// Character edits between two folded names that still count as the same name
+const MAX_EDIT_DISTANCE: usize = 2;
+
+/// The established server a listing seems to be imitating
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Imitated {
+    pub game_id: GameId,
+    pub server_id: ServerId,
+    pub name: String,
+}
+
+/// A name reduced to its lowercase letters and digits, so "[color=red]Big Base[/color]!" and
+/// "bigbase" are equal
+fn fold_name(name: &str) -> String {
+    strip_all_tags(name)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The listed server now going by an unlisted server's name under another server_id, e.g. after a
+/// relaunch; the busiest one when several match, none for names too generic to tell
+pub fn find_successor<'a>(
+    name: &str,
+    server_id: &ServerId,
+    servers: impl IntoIterator<Item = &'a CachedServer>,
+) -> Option<&'a CachedServer> {
+    let folded = fold_name(name);
+    if folded.chars().count() < MIN_NAME_CHARS {
+        return None;
+    }
+    servers
+        .into_iter()
+        .filter(|s| s.server_id.as_ref() != Some(server_id) && fold_name(&s.name) == folded)
+        .max_by_key(|s| s.players.len())
+}
+
+/// Whether a listing looks like another one relaunched under a new server_id: the same name, too
+/// specific to be a coincidence, from the same host IP
+pub fn same_server(server: &CachedServer, name: &str, host_address: Option<&str>) -> bool {
+    let folded = fold_name(name);
+    let host = host_address.and_then(host_ip);
+    folded.chars().count() >= MIN_NAME_CHARS
+        && host.is_some()
+        && host == server.host_address.as_deref().and_then(host_ip)
+        && fold_name(&server.name) == folded
+}
+
+/// Levenshtein distance, in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// How long ago a server was first listed (None when first_seen is missing or unreadable)
+fn listed_for(server: &CachedServer, now: DateTime<Utc>) -> Option<Duration> {
+    let first_seen = DateTime::parse_from_rfc3339(server.first_seen.as_deref()?).ok()?;
+    Some(now - first_seen.with_timezone(&Utc))
+}
+
+/// New listings whose names are near-duplicates of a long-established verified server on another host
+/// Keyed by the new listing's game_id; the operator's own servers (same server_id or host IP) never match
+pub fn find_impostors(
+    servers: &[CachedServer],
+    verified: &HashSet<ServerId>,
+    now: DateTime<Utc>,
+) -> HashMap<GameId, Imitated> {
+    let established: Vec<(&CachedServer, &ServerId, String)> = servers
+        .iter()
+        .filter_map(|s| {
+            let id = s.server_id.as_ref().filter(|id| verified.contains(*id))?;
+            let old_enough = listed_for(s, now).is_some_and(|age| age >= Duration::days(ESTABLISHED_DAYS));
+            let name = fold_name(&s.name);
+            (old_enough && name.chars().count() >= MIN_NAME_CHARS).then_some((s, id, name))
+        })
+        .collect();
+    if established.is_empty() {
+        return HashMap::new();
+    }
+
+    let mut impostors = HashMap::new();
+    for server in servers {
+        let is_new = listed_for(server, now).is_some_and(|age| age < Duration::days(NEW_LISTING_DAYS));
+        if !is_new || server.server_id.as_ref().is_some_and(|id| verified.contains(id)) {
+            continue;
+        }
+        let name = fold_name(&server.name);
+        let host = server.host_address.as_deref().and_then(host_ip);
+        // The nearest name when several established servers are close
+        let imitated = established
+            .iter()
+            .filter(|(original, _, _)| {
+                let same_host = host.is_some() && host == original.host_address.as_deref().and_then(host_ip);
+                !same_host && server.server_id != original.server_id
+            })
+            .map(|candidate| (candidate, edit_distance(&name, &candidate.2)))
+            .filter(|(_, distance)| *distance <= MAX_EDIT_DISTANCE)
+            .min_by_key(|(_, distance)| *distance);
+        if let Some(((original, id, _), _)) = imitated {
+            impostors.insert(server.game_id, Imitated {
+                game_id: original.game_id,
+                server_id: (*id).clone(),
+                name: strip_all_tags(&original.name),
+            });
+        }
+    }
+    impostors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn server(game_id: u64, server_id: &str, name: &str, host: &str, listed_days: i64) -> CachedServer {
+        serde_json::from_value(serde_json::json!({
+            "game_id": game_id,
+            "server_id": server_id,
+            "name": name,
+            "description": "",
+            "max_players": 10,
+            "player_count": 0,
+            "game_time_elapsed": 0,
+            "has_password": false,
+            "game_version": "2.0.28",
+            "build_version": 0,
+            "host_address": host,
+            "first_seen": (now() - Duration::days(listed_days)).to_rfc3339(),
+            "cached_at": now().to_rfc3339(),
+        }))
+        .unwrap()
+    }
+
+    fn verified(ids: &[&str]) -> HashSet<ServerId> {
+        ids.iter().map(|id| ServerId(id.to_string())).collect()
+    }
+
+    fn imitated(servers: &[CachedServer]) -> Option<String> {
+        find_impostors(servers, &verified(&["orig", "other"]), now())
+            .get(&GameId(2))
+            .map(|i| i.server_id.0.clone())
+    }
+
+    #[test]
+    fn near_duplicate_names_of_established_servers_match() {
+        let original = server(1, "orig", "[color=red]Big Base[/color]", "1.2.3.4:34197", 60);
+        assert_eq!(imitated(&[original.clone(), server(2, "new", "Big Base!", "5.6.7.8:34197", 1)]), Some("orig".into()));
+        assert_eq!(imitated(&[original.clone(), server(2, "new", "Big Bsae", "5.6.7.8:34197", 1)]), Some("orig".into()));
+        assert_eq!(imitated(&[original, server(2, "new", "Big Bass Fishing", "5.6.7.8:34197", 1)]), None);
+    }
+
+    #[test]
+    fn the_operators_own_servers_never_match() {
+        let original = server(1, "orig", "Big Base", "1.2.3.4:34197", 60);
+        // Same host, another port
+        assert_eq!(imitated(&[original.clone(), server(2, "new", "Big Base", "1.2.3.4:34198", 1)]), None);
+        // Same server_id under a new game_id
+        assert_eq!(imitated(&[original, server(2, "orig", "Big Base", "5.6.7.8:34197", 1)]), None);
+    }
+
+    #[test]
+    fn short_names_are_ignored() {
+        let original = server(1, "orig", "Gleba", "1.2.3.4:34197", 60);
+        assert_eq!(imitated(&[original, server(2, "new", "Gleba", "5.6.7.8:34197", 1)]), None);
+    }
+
+    #[test]
+    fn only_new_listings_of_established_names_match() {
+        let established = server(1, "orig", "Big Base", "1.2.3.4:34197", ESTABLISHED_DAYS);
+        let young = server(1, "orig", "Big Base", "1.2.3.4:34197", ESTABLISHED_DAYS - 1);
+        let fresh = server(2, "new", "Big Base", "5.6.7.8:34197", NEW_LISTING_DAYS - 1);
+        let settled = server(2, "new", "Big Base", "5.6.7.8:34197", NEW_LISTING_DAYS);
+        assert_eq!(imitated(&[established.clone(), fresh.clone()]), Some("orig".into()));
+        assert_eq!(imitated(&[young, fresh]), None);
+        assert_eq!(imitated(&[established, settled]), None);
+    }
+
+    #[test]
+    fn the_nearest_established_name_is_picked() {
+        let servers = [
+            server(1, "other", "Big Bases", "1.2.3.4:34197", 60),
+            server(3, "orig", "Big Base", "9.9.9.9:34197", 60),
+            server(2, "new", "Big Base", "5.6.7.8:34197", 1),
+        ];
+        assert_eq!(imitated(&servers), Some("orig".into()));
+    }
+
+    #[test]
+    fn edit_distance_counts_characters() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("bigbase", "bigbase"), 0);
+        assert_eq!(edit_distance("bigbase", "bigbsae"), 2);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        // One edit each, however many bytes the characters take
+        assert_eq!(edit_distance("завод", "заводы"), 1);
+        assert_eq!(edit_distance("工厂服务器", "工场服务器"), 1);
+        assert_eq!(fold_name("Große Fabrik!"), "großefabrik");
+    }
+}
@@ -1,5 +1,60 @@
+pub mod accounts;
+pub mod alerts;
+#[cfg(feature = "server")]
+pub mod assets;
+#[cfg(feature = "server")]
 pub mod api;
+pub mod badge;
+#[cfg(feature = "server")]
+pub mod blocklist;
+#[cfg(feature = "server")]
+pub mod cache;
+pub mod changes;
 pub mod components;
+#[cfg(feature = "server")]
+pub mod config;
+#[cfg(feature = "server")]
+pub mod compression;
 pub mod db;
+pub mod digest;
+#[cfg(feature = "server")]
+pub mod cors;
+#[cfg(feature = "server")]
+pub mod drift;
+#[cfg(feature = "server")]
+pub mod etag;
+#[cfg(feature = "server")]
+pub mod experiments;
+pub mod feed;
+pub mod filter;
+#[cfg(feature = "gemini")]
+pub mod gemini;
+pub mod geo;
+pub mod ids;
+#[cfg(feature = "server")]
+pub mod img_proxy;
+pub mod impostor;
+pub mod metrics;
+pub mod modpack;
+#[cfg(feature = "server")]
+pub mod notify;
+#[cfg(feature = "server")]
+pub mod og;
+pub mod players;
+pub mod prefs;
+#[cfg(feature = "server")]
+pub mod push;
+#[cfg(feature = "server")]
+pub mod qr;
+pub mod ranking;
+#[cfg(feature = "server")]
+pub mod render_cache;
+#[cfg(feature = "server")]
+pub mod schedule;
+pub mod sitemap;
+pub mod stats;
+pub mod tags;
+#[cfg(feature = "server")]
+pub mod trace;
 pub mod utils;
-
+pub mod view;
@@ -1,18 +1,114 @@
-use factorio_browser::api::factorio::FactorioClient;
+use factorio_browser::accounts::routes::{
+    delete_preset, load_preset, load_presets, register_account, save_preset, sign_in, sign_out, AccountsEnabled, SignedIn,
+};
+use factorio_browser::accounts::{status_message, AccountNav};
+use factorio_browser::alerts::{evaluate, new_claim_code, AlertAction, CLAIM_HOURS};
+use factorio_browser::assets::{self, AssetManifest};
+use factorio_browser::api::graphql::{build_schema, graphql, GraphqlSources};
+use factorio_browser::api::factorio::{
+    ApiError, FactorioClient, GameServer, ModInfo, DEFAULT_DETAILS_BURST, DEFAULT_DETAILS_CACHE_TTL, DEFAULT_DETAILS_PER_MINUTE,
+};
+use factorio_browser::api::federation::{
+    import_peer, FederationClient, FederationExport, EXPORT_PAGE_SIZE, FEDERATION_INTERVAL, FEDERATION_PROTOCOL,
+};
+use factorio_browser::api::keys::{api_key_hash, new_api_key, ApiKey, ApiKeys, RetryAfter, DEFAULT_KEY_PER_MINUTE, MAX_KEY_PER_MINUTE};
+use factorio_browser::api::mod_portal::{ModPortalClient, PortalMod, BUILTIN_MODS, MOD_PORTAL_URL};
+use factorio_browser::api::reputation::{
+    ReputationClient, ABUSEIPDB_URL, DEFAULT_ABUSE_REPORTS_THRESHOLD, REPUTATION_LOOKUPS_PER_REFRESH,
+};
+#[cfg(feature = "chaos")]
+use factorio_browser::api::chaos::{ChaosConfig, ChaosSource};
+use factorio_browser::api::rate_limit::TokenBucket;
+use factorio_browser::api::recorder::{list_recordings, read_recording, ResponseRecorder};
+use factorio_browser::api::source::{FixtureSource, ServerSource, DEMO_SERVER_ID_PREFIX};
+use factorio_browser::api::translate::{text_hash, TranslateClient};
+use factorio_browser::cache::{CacheSnapshot, ServerCache};
+use factorio_browser::changes::{ListingChanges, DEFAULT_CHANGES_LIMIT};
+use factorio_browser::badge::{format_uptime, render_badge, uptime_color};
+use factorio_browser::blocklist::{normalize_pattern, Blocklist};
+use factorio_browser::api::routes::{
+    get_cached_details, get_changes, get_player, get_servers, get_stats, get_version_history, health, HealthMaxAge, LastRefreshError, RefreshInterval,
+};
 // TODO: Re-enable API routes later
-// use factorio_browser::api::routes::{get_server, get_server_history, get_servers, health};
+// use factorio_browser::api::routes::{get_server, get_server_history};
+use factorio_browser::components::account::{AccountPage, AccountPageProps};
+use factorio_browser::components::admin::{AdminPage, AdminPageProps};
 use factorio_browser::components::app::{App, AppProps};
-use factorio_browser::components::server_details::ServerDetails;
-use factorio_browser::db::queries::DbClient;
-use factorio_browser::db::models::CachedServer;
-use factorio_browser::utils::strip_all_tags;
-use rocket::form::FromForm;
-use rocket::fs::{FileServer, NamedFile};
-use rocket::http::Header;
+use factorio_browser::components::archive::{ArchivePage, ArchivePageProps};
+use factorio_browser::components::changes::{ChangesPage, ChangesPageProps};
+use factorio_browser::components::dashboard::{Dashboard, DashboardProps};
+use factorio_browser::components::digest::{DigestPage, DigestPageProps};
+use factorio_browser::components::lite::{LiteList, LiteListProps, LiteServer, LiteServerProps, LITE_CSS};
+use factorio_browser::components::player_search::{PlayerSearchPage, PlayerSearchProps};
+use factorio_browser::components::server_details::{DetailsTab, ModEntry, ServerDetails};
+use factorio_browser::components::stats::{StatsPage, StatsPageProps};
+use factorio_browser::components::server_list::ServerListProps;
+use factorio_browser::components::summary_card::{SummaryCard, SummaryCardProps};
+use factorio_browser::components::transparency::{TransparencyPage, TransparencyPageProps};
+use factorio_browser::components::widgets::list_props;
+use factorio_browser::config::{Config, DatabaseConfig, DEFAULT_CONFIG_FILE};
+use factorio_browser::compression::ResponseCompression;
+use factorio_browser::cors::{self, Cors};
+use factorio_browser::db::models::{
+    BlockKind, CachedServer, NewServerReport, ReportReason, ModMetadata, NewApiKeyRecord, NewBlocklistEntry, NewHiddenServer, NewIpReputation, NewModerationAction, NewListingTotals, NewModMetadata, NewModpack, NewPushSubscription,
+    NewServerHistory, NewVersionTotals,
+};
+use factorio_browser::digest::{self, WeeklyDigest, DIGEST_DAYS, WEEKLY_DIGEST_KEY};
+use factorio_browser::drift::SchemaDrift;
+use factorio_browser::etag::{self, IfNoneMatch, Tagged};
+use factorio_browser::experiments::{Experiment, EXPERIMENT_COOKIE, EXPERIMENT_COOKIE_DAYS};
+use factorio_browser::feed::{feed_entries, render_atom};
+use factorio_browser::filter::{versions_newest_first, IndexFilters};
+use factorio_browser::geo::{host_ip, GeoIp};
+use factorio_browser::ids::{GameId, ServerId};
+use factorio_browser::img_proxy::{ImageProxy, OutputFormat, ProxyError, DEFAULT_CACHE_DIR};
+use factorio_browser::impostor::{self, Imitated};
+use factorio_browser::metrics::{redact_urls, Metrics, Snapshot, Subsystem};
+use factorio_browser::modpack::{self, MODPACK_LOOKUPS_PER_REFRESH};
+use factorio_browser::db::sampling::{HistorySampler, SamplingMode};
+use factorio_browser::db::writer::{HistoryQueue, HISTORY_QUEUE_CAPACITY};
+use factorio_browser::db::queries::{
+    DbClient, ARCHIVE_AFTER_HOURS, HISTORY_RETENTION_HOURS, PRESENCE_RETENTION_HOURS, SCHEMA_VERSION,
+    VERSION_HISTORY_RETENTION_HOURS,
+};
+use factorio_browser::notify::{is_allowed_webhook, Notifier, NotifyError};
+use factorio_browser::push::{
+    self, is_allowed_endpoint, subscription_id, valid_keys, PushError, PushSender, VapidKeys, PUSH_COOKIE,
+};
+use factorio_browser::players::{PlayerSearch, RecentPlayers, MAX_PLAYER_NAME_LEN};
+use factorio_browser::qr::render_qr_svg;
+use factorio_browser::ranking::{self, RankingSignals, RankingWeights};
+use factorio_browser::render_cache::{CachedHtml, Lookup, RenderCache, DEFAULT_RENDER_CACHE_ENTRIES};
+use factorio_browser::prefs::{
+    arrange_widgets, parse_columns, parse_favorites, parse_friends, parse_widgets, toggle_favorite, CookieSigner,
+    ListColumn, ListState, Widget, COLUMNS_COOKIE, FAVORITES_COOKIE, FRIENDS_COOKIE, PAGE_SIZE, WIDGETS_COOKIE,
+};
+use factorio_browser::og::{render_card_svg, CardRenderer};
+use factorio_browser::schedule::RefreshSchedule;
+use factorio_browser::sitemap::{render_sitemap, SitemapCache};
+use factorio_browser::stats::{
+    hour_bucket, join_hint, ActivityHeatmap, GlobalStats, PresenceTracker, VersionAdoption, GLOBAL_STATS_KEY,
+};
+use factorio_browser::tags::TagAliases;
+use factorio_browser::trace::RefreshTraces;
+use factorio_browser::utils::format::Locale;
+use factorio_browser::utils::{escape_xml, steam_join_url, strip_all_tags};
+use factorio_browser::view::{ServerView, INACTIVE_DAYS};
+use base64::Engine;
+use rocket::form::{Form, FromForm};
+use rocket::fs::NamedFile;
+use rocket::http::{ContentType, Cookie, CookieJar, Header, SameSite, Status};
 use rocket::response::content::RawHtml;
-use rocket::response::{Responder, Response};
+use rocket::serde::json::Json;
+use rocket::response::{Redirect, Responder, Response};
+use rocket::request::{FromRequest, Outcome};
 use rocket::Request;
-use rocket::{get, routes, State};
+use serde::Deserialize;
+use rocket::{catch, catchers, get, post, routes, uri, Route, Shutdown, State};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::net::IpAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -21,25 +117,361 @@ use yew::ServerRenderer;
 /// Application state
 struct AppState {
     db: Arc<DbClient>,
-    factorio_client: Arc<FactorioClient>,
+    /// Upstream server listing (the Factorio API, or the demo fixture on first run)
+    source: Arc<dyn ServerSource>,
+    /// Showing the bundled demo snapshot because no credentials are configured
+    demo_mode: bool,
+    translator: Option<Arc<TranslateClient>>,
+    /// Budget of TRANSLATIONS_PER_MINUTE backend calls shared by all visitors; cached translations are free
+    translate_limit: TokenBucket,
+    notifier: Arc<Notifier>,
+    downtime_alerts: bool,
+    /// Budget of ALERT_CLAIMS_PER_MINUTE claim codes sent to webhooks, shared by all visitors
+    alert_limit: TokenBucket,
+    presence: Arc<RwLock<PresenceTracker>>,
+    history_sampler: Arc<RwLock<HistorySampler>>,
+    /// Write-behind queue for player history
+    history_queue: Arc<HistoryQueue>,
+    metrics: Arc<Metrics>,
+    /// Stage timings of recent refresh cycles, for the admin page
+    refresh_traces: Arc<RefreshTraces>,
+    /// server_ids hidden by an admin, as of the last refresh or hide/restore
+    hidden_servers: Arc<RwLock<HashSet<ServerId>>>,
+    /// Spam listings dropped at refresh, reloaded when the admin page changes the blocklist
+    blocklist: Arc<RwLock<Blocklist>>,
+    /// Active JSON API keys with their rate limits, reloaded when the admin page changes them
+    api_keys: Arc<ApiKeys>,
+    /// Fetches, resizes and caches third-party images so pages don't hotlink them
+    image_proxy: Arc<ImageProxy>,
+    /// Header logo source (LOGO_URL), shown through the image proxy
+    logo_url: String,
+    /// Chart bucket size for the 24h activity chart, in minutes
+    history_bucket_minutes: i64,
+    /// Serve cached/DB data only: no upstream API calls and no writes
+    read_only: bool,
     last_error: Arc<RwLock<Option<String>>>,
-    // Add cached servers
-    cached_servers: Arc<RwLock<Vec<CachedServer>>>,
+    /// In-memory server list, swapped wholesale on every refresh
+    cache: Arc<ServerCache>,
+    /// Players seen in recent refreshes, for player search
+    recent_players: Arc<RwLock<RecentPlayers>>,
+    /// Servers that joined or left the listing in recent refreshes, for /changes
+    listing_changes: Arc<RwLock<ListingChanges>>,
+    /// Signs the favorites cookie
+    cookie_signer: CookieSigner,
+    /// Accounts with saved filter presets (never in read-only mode)
+    accounts: bool,
+    /// Visitors can report servers to the admin page (never in read-only mode)
+    reports: bool,
+    /// Budget of REPORTS_PER_MINUTE shared by all visitors, on top of REPORTS_PER_REPORTER_HOUR each
+    report_limit: TokenBucket,
+    /// get-games fields we don't model yet, for the admin page
+    schema_drift: Arc<RwLock<SchemaDrift>>,
+    /// Password for the admin page (HTTP Basic auth); the page is off when unset
+    admin_token: Option<String>,
+    /// Public base URL for absolute links (feeds, sitemap), without a trailing slash
+    public_url: Option<String>,
+    /// sitemap.xml for the current cache snapshot
+    sitemap: Arc<RwLock<SitemapCache>>,
+    /// Rasterizes Open Graph preview cards
+    card_renderer: Arc<CardRenderer>,
+    /// Leave inactive (ghost) servers out of the list unless the visitor asks for them
+    hide_inactive: bool,
+    /// Mod Portal lookups for the details page mod list (None when disabled)
+    mod_portal: Option<Arc<ModPortalClient>>,
+    /// Country lookups for server addresses (None without GEOIP_DB)
+    geoip: Option<Arc<GeoIp>>,
+    /// Abuse report lookups for host IPs (None without ABUSEIPDB_API_KEY)
+    reputation: Option<Arc<ReputationClient>>,
+    /// Abuse reports a host IP needs before its servers get the "Reported Host" badge
+    abuse_reports_threshold: u32,
+    /// Discord webhook the weekly digest is posted to (DIGEST_WEBHOOK_URL)
+    digest_webhook: Option<String>,
+    /// Impostor listings whose imitated server's operator has been warned already
+    impostors_notified: Arc<RwLock<HashSet<GameId>>>,
+    /// Tag spellings folded onto one canonical tag at ingest (TAG_ALIASES)
+    tag_aliases: Arc<TagAliases>,
+    /// Signal weights for the "Recommended" sort
+    ranking: RankingWeights,
+    /// server_ids vouched for by the operator, boosted in the "Recommended" sort
+    verified_servers: HashSet<ServerId>,
+    /// Ranking weights being A/B tested on the "Recommended" sort (None when no experiment runs)
+    experiment: Option<Experiment>,
+    /// Web Push delivery for favorites and friends (None without VAPID_PRIVATE_KEY)
+    push: Option<Arc<PushSender>>,
+    /// Homepage widgets shown to visitors who haven't customized the layout
+    widgets: Vec<Widget>,
+    /// The WASM bundle is built (`make wasm`), so the server list hydrates in the browser
+    hydrate: bool,
+    /// Content-hashed names of the files under static/, for links in the page shell
+    assets: AssetManifest,
+    /// Rendered main pages of the current listing, with their compressed copies
+    render_cache: Arc<RenderCache>,
+    /// Time between listing refreshes (refresh_interval_secs), with jitter and error backoff
+    refresh_schedule: RefreshSchedule,
+    /// Name in page titles and link previews (site_name)
+    site_name: String,
 }
 
-/// Query parameters for the main page
-#[derive(Debug, FromForm, Default)]
-struct IndexFilters {
-    search: Option<String>,
-    version: Option<String>,
-    has_players: Option<bool>,
-    no_password: Option<bool>,
-    is_dedicated: Option<bool>,
-    tags: Option<String>, // Comma-separated list of tags for OR filtering
+/// Form for registering a downtime alert
+#[derive(Debug, FromForm)]
+struct AlertForm {
+    webhook_url: String,
+}
+
+/// Form for reporting a server from its details page
+#[derive(Debug, FromForm)]
+struct ReportForm {
+    /// ReportReason key
+    reason: String,
+    #[field(default = String::new())]
+    details: String,
+}
+
+/// Form for hiding a listed server from the admin page
+#[derive(Debug, FromForm)]
+struct HideForm {
+    game_id: String,
+    reason: String,
+}
+
+/// Form for issuing an API key from the admin page
+#[derive(Debug, FromForm)]
+struct ApiKeyForm {
+    name: String,
+    /// Requests a minute; empty for the default
+    per_minute: String,
+}
+
+/// Form for adding a blocklist entry from the admin page
+#[derive(Debug, FromForm)]
+struct BlockForm {
+    /// "server_id", "host" or "name"
+    kind: String,
+    pattern: String,
+    reason: String,
+}
+
+/// Form for customizing the homepage layout
+#[derive(Debug, FromForm)]
+struct WidgetsForm {
+    /// Every widget, in the order the panel listed them
+    order: Vec<String>,
+    /// Ticked widgets
+    show: Vec<String>,
+    /// "key:up" or "key:down" when a move button was pressed
+    #[field(name = "move")]
+    moved: Option<String>,
+    #[field(default = false)]
+    reset: bool,
+}
+
+/// Form for the friends widget
+#[derive(Debug, FromForm)]
+struct FriendsForm {
+    names: String,
+}
+
+/// Browser push subscription (PushSubscription.toJSON()) plus the friends to watch
+#[derive(Debug, Deserialize)]
+struct PushSubscribeRequest {
+    endpoint: String,
+    keys: PushKeys,
+    #[serde(default)]
+    friends: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PushKeys {
+    p256dh: String,
+    auth: String,
+}
+
+/// Display locale negotiated from the Accept-Language header
+struct AcceptLanguage(Locale);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AcceptLanguage {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let locale = req
+            .headers()
+            .get_one("Accept-Language")
+            .map(Locale::from_accept_language)
+            .unwrap_or_default();
+        Outcome::Success(AcceptLanguage(locale))
+    }
+}
+
+/// Absolute base URL of the site for links that leave the page (feeds, sitemap)
+/// PUBLIC_URL when set, otherwise built from the Host and X-Forwarded-Proto headers
+struct SiteUrl(String);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SiteUrl {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        if let Some(url) = req.rocket().state::<Arc<AppState>>().and_then(|s| s.public_url.clone()) {
+            return Outcome::Success(SiteUrl(url));
+        }
+        let scheme = match req.headers().get_one("X-Forwarded-Proto") {
+            Some("https") => "https",
+            _ => "http",
+        };
+        let host = req.host().map(|h| h.to_string()).unwrap_or_else(|| "localhost".to_string());
+        Outcome::Success(SiteUrl(format!("{}://{}", scheme, host)))
+    }
+}
+
+/// Local path and query of the Referer header, for redirecting back after a form post
+/// The scheme and host are dropped so the redirect can never leave the site
+struct RefererPath(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RefererPath {
+    type Error = std::convert::Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let path = req
+            .headers()
+            .get_one("Referer")
+            .and_then(|referer| rocket::http::uri::Absolute::parse(referer).ok())
+            .map(|uri| match uri.query() {
+                Some(query) => format!("{}?{}", uri.path(), query),
+                None => uri.path().to_string(),
+            })
+            .filter(|path| path.starts_with('/') && !path.starts_with("//"));
+        Outcome::Success(RefererPath(path))
+    }
+}
+
+/// Operator signed in to the admin page with HTTP Basic auth (any username, ADMIN_TOKEN as password)
+/// Fails with 401 so the browser prompts for credentials; forwards when no ADMIN_TOKEN is set
+struct Admin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Admin {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        use sha2::{Digest, Sha256};
+
+        let Some(token) = req.rocket().state::<Arc<AppState>>().and_then(|s| s.admin_token.as_deref()) else {
+            return Outcome::Forward(Status::NotFound);
+        };
+        let password = req
+            .headers()
+            .get_one("Authorization")
+            .and_then(|auth| auth.strip_prefix("Basic "))
+            .and_then(|encoded| base64::engine::general_purpose::STANDARD.decode(encoded.trim()).ok())
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .and_then(|credentials| credentials.split_once(':').map(|(_, password)| password.to_string()));
+
+        // Compare digests so the time taken doesn't depend on how much of the token matched
+        match password {
+            Some(password) if Sha256::digest(password.as_bytes()) == Sha256::digest(token.as_bytes()) => {
+                Outcome::Success(Admin)
+            }
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+/// Admin form post sent from one of this site's own pages
+/// Browsers resend Basic auth credentials on cross-site posts, so changes on the admin page need this
+/// on top of Admin: `Sec-Fetch-Site` must say same-origin, or, from browsers without it, `Origin`
+/// must be this site. Requests with neither header don't come from a browser and pass
+struct SameOrigin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SameOrigin {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let headers = req.headers();
+        let same_origin = match (headers.get_one("Sec-Fetch-Site"), headers.get_one("Origin")) {
+            (Some(site), _) => matches!(site, "same-origin" | "none"),
+            (None, Some(origin)) => {
+                let public_url = req.rocket().state::<Arc<AppState>>().and_then(|s| s.public_url.as_deref());
+                let host = origin.split_once("://").map(|(_, host)| host);
+                public_url.is_some_and(|url| url.eq_ignore_ascii_case(origin))
+                    || host.is_some_and(|host| headers.get_one("Host").is_some_and(|h| h.eq_ignore_ascii_case(host)))
+            }
+            (None, None) => true,
+        };
+        if same_origin {
+            Outcome::Success(SameOrigin)
+        } else {
+            eprintln!("Refused a cross-site admin request to {}", req.uri());
+            Outcome::Error((Status::Forbidden, ()))
+        }
+    }
+}
+
+/// 401 response asking the browser for admin credentials
+#[derive(rocket::Responder)]
+#[response(status = 401)]
+struct BasicAuthChallenge(&'static str, Header<'static>);
+
+#[catch(401)]
+fn unauthorized() -> BasicAuthChallenge {
+    BasicAuthChallenge(
+        "Authentication required",
+        Header::new("WWW-Authenticate", "Basic realm=\"admin\", charset=\"UTF-8\""),
+    )
+}
+
+/// 401 for the JSON API: a missing, unknown or revoked key, without the admin login prompt
+#[catch(401)]
+fn api_unauthorized() -> &'static str {
+    "Missing or invalid API key"
+}
+
+/// 429 for the JSON API, with `Retry-After` from the key's rate limit
+#[derive(rocket::Responder)]
+#[response(status = 429)]
+struct ApiRateLimited(&'static str, Header<'static>);
+
+#[catch(429)]
+fn api_rate_limited(req: &Request) -> ApiRateLimited {
+    let RetryAfter(wait) = *req.local_cache(|| RetryAfter(Duration::from_secs(60)));
+    ApiRateLimited(
+        "API key rate limit exceeded",
+        Header::new("Retry-After", (wait.as_secs_f64().ceil() as u64).max(1).to_string()),
+    )
+}
+
+/// Sign-in state for the presets bar (None when accounts are disabled)
+async fn account_nav(state: &AppState, signed_in: Option<&SignedIn>) -> Option<AccountNav> {
+    if !state.accounts {
+        return None;
+    }
+    let presets = match signed_in {
+        Some(SignedIn(email)) => load_presets(&state.db, email).await,
+        None => Vec::new(),
+    };
+    Some(AccountNav {
+        email: signed_in.map(|s| s.0.clone()),
+        presets,
+    })
+}
+
+/// Verified server_ids from the favorites cookie (a tampered cookie counts as empty)
+fn read_favorites(state: &AppState, cookies: &CookieJar<'_>) -> Vec<ServerId> {
+    cookies
+        .get(FAVORITES_COOKIE)
+        .and_then(|c| state.cookie_signer.verify(c.value()).map(parse_favorites))
+        .unwrap_or_default()
 }
 
 /// Wrap HTML content with the page shell, optionally with video background
-fn html_shell_with_video(title: &str, content: String, with_video: bool) -> String {
+fn html_shell_with_video(state: &AppState, title: &str, content: String, with_video: bool) -> String {
+    html_shell_with_image(state, title, content, with_video, &state.assets.url("favicon.svg"))
+}
+
+/// Page shell with a specific Open Graph / Twitter preview image
+fn html_shell_with_image(state: &AppState, title: &str, content: String, with_video: bool, image: &str) -> String {
     let video_url = "https://lambs.cafe/wp-content/uploads/2025/12/space-age.mp4";
     
     let video_element = if with_video {
@@ -68,17 +500,18 @@ fn html_shell_with_video(title: &str, content: String, with_video: bool) -> Stri
     <meta property="og:type" content="website">
     <meta property="og:title" content="{title}">
     <meta property="og:description" content="Find and explore public Factorio multiplayer servers. Browse servers by version, tags, player count, and more.">
-    <meta property="og:image" content="/static/favicon.svg">
-    <meta property="og:site_name" content="Factorio Server Browser">
+    <meta property="og:image" content="{image}">
+    <meta property="og:site_name" content="{site_name}">
     
     <!-- Twitter -->
     <meta name="twitter:card" content="summary_large_image">
     <meta name="twitter:title" content="{title}">
     <meta name="twitter:description" content="Find and explore public Factorio multiplayer servers. Browse servers by version, tags, player count, and more.">
-    <meta name="twitter:image" content="/static/favicon.svg">
+    <meta name="twitter:image" content="{image}">
     
-    <link rel="icon" type="image/svg+xml" href="/static/favicon.svg">
-    <link rel="stylesheet" href="/static/style.css">
+    <link rel="icon" type="image/svg+xml" href="{favicon}">
+    <link rel="alternate" type="application/atom+xml" title="Factorio servers" href="/feed.xml">
+    <link rel="stylesheet" href="{style}">
     <link rel="preconnect" href="https://fonts.googleapis.com">
     <link rel="preconnect" href="https://fonts.gstatic.com" crossorigin>
     <link href="https://fonts.googleapis.com/css2?family=JetBrains+Mono:wght@400;500;600&family=Titillium+Web:wght@300;400;600;700&display=swap" rel="stylesheet">
@@ -86,85 +519,828 @@ fn html_shell_with_video(title: &str, content: String, with_video: bool) -> Stri
 <body{body_class}>
     {video}
     {content}
-    <script src="/static/sort.js" defer></script>
+    <script src="{sort}" defer></script>
 </body>
 </html>"##,
         title = title,
+        image = image,
+        site_name = state.site_name,
+        favicon = state.assets.url("favicon.svg"),
+        style = state.assets.url("style.css"),
+        sort = state.assets.url("sort.js"),
         body_class = body_class,
         video = video_element,
         content = content
     )
 }
 
+/// The request's query string, still encoded, with every `name` parameter dropped
+fn query_without(uri: &rocket::http::uri::Origin<'_>, name: &str) -> String {
+    uri.query()
+        .map(|query| {
+            query
+                .as_str()
+                .split('&')
+                .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some(name))
+                .collect::<Vec<_>>()
+                .join("&")
+        })
+        .unwrap_or_default()
+}
+
+/// Most archived servers listed under a search
+const ARCHIVED_SEARCH_RESULTS: usize = 10;
+
+/// Most unlisted servers listed under a search with include_offline
+const OFFLINE_SEARCH_RESULTS: usize = 50;
+
+/// Header logo unless LOGO_URL names another
+const DEFAULT_LOGO_URL: &str = "https://lambs.cafe/wp-content/uploads/2025/12/factorio-logo.png";
+
+/// The logo is 64px tall; this leaves room for logos up to four times as wide
+const LOGO_SIZE: u32 = 256;
+
+/// Mod thumbnails are shown at 32px
+const MOD_THUMBNAIL_SIZE: u32 = 32;
+
+/// Where `make wasm` puts the hydration bundle, relative to the static directory
+const HYDRATE_WASM: &str = "pkg/hydrate_bg.wasm";
+
+/// The server list's props as JSON, and the module that hydrates the list from them
+fn hydration_scripts(assets: &AssetManifest, props: &ServerListProps) -> String {
+    // `<` is escaped so nothing in a server name can close the script element
+    let data = serde_json::to_string(props).unwrap_or_default().replace('<', "\\u003c");
+    // The glue script would fetch the bundle by its plain name; point it at the fingerprinted one
+    format!(
+        r#"<script id="server-list-data" type="application/json">{}</script>
+<script type="module">import init from '{}'; init({{ module_or_path: '{}' }});</script>"#,
+        data,
+        assets.url("pkg/hydrate.js"),
+        assets.url(HYDRATE_WASM)
+    )
+}
+
 /// Main SSR route - renders the Yew app to HTML
 #[get("/?<filters..>")]
-async fn index(state: &State<Arc<AppState>>, filters: IndexFilters) -> RawHtml<String> {
+async fn index(
+    state: &State<Arc<AppState>>,
+    cookies: &CookieJar<'_>,
+    uri: &rocket::http::uri::Origin<'_>,
+    filters: IndexFilters,
+    lang: AcceptLanguage,
+    signed_in: Option<SignedIn>,
+    if_none_match: IfNoneMatch,
+) -> Tagged<CachedHtml> {
     // Use cached servers instead of querying DB
-    let servers = state.cached_servers.read().await.clone();
+    let snapshot = state.cache.load();
+    let variant = state.experiment.as_ref().map(|experiment| experiment_variant(experiment, cookies));
+    // Only a page actually sent counts towards the variant's click-through rate, not a revalidation
+    let record_impression = || {
+        if let Some((experiment, variant)) = state.experiment.as_ref().zip(variant) {
+            state.metrics.record_impression(&experiment.variants[variant].name);
+        }
+    };
     let error = state.last_error.read().await.clone();
+    let account = account_nav(state, signed_in.as_ref()).await;
+
+    // Everything the page depends on besides the server list; cookies carry favorites, columns and widgets
+    let mut cookie_values: Vec<String> = cookies.iter().map(|c| format!("{}={}", c.name(), c.value())).collect();
+    cookie_values.sort();
+    let inputs = [
+        uri.query().map(|q| q.as_str()).unwrap_or_default(),
+        &cookie_values.join(";"),
+        &format!("{:?}", lang.0),
+        error.as_deref().unwrap_or_default(),
+        &format!("{:?}", account),
+        // A first visit's variant is assigned above, so its cookie isn't among the request's yet
+        &format!("{:?}", variant),
+    ];
+    let etag = etag::etag(&snapshot, &inputs);
+    if if_none_match.matches(&etag) {
+        return Tagged::NotModified(etag);
+    }
+    let ticket = match state.render_cache.lookup(snapshot.version, &etag::cache_key(&inputs)).await {
+        Lookup::Fresh(page) | Lookup::Stale(page) => {
+            record_impression();
+            return Tagged::Fresh(page.etag().to_string(), page);
+        }
+        Lookup::Render(ticket) => ticket,
+    };
+
+    let mut servers = snapshot.servers.clone();
+    if let Some(variant) = variant {
+        for view in &mut servers {
+            if let Some(&score) = view.variant_scores.get(variant) {
+                view.score = score;
+            }
+        }
+    }
+    let mut list_state = ListState::parse(
+        filters.sort.as_deref(),
+        filters.dir.as_deref(),
+        filters.view.as_deref(),
+        filters.columns.as_deref(),
+    )
+    .with_page(filters.page);
+    list_state.sort_servers(&mut servers);
+    // Columns in the URL win over the visitor's saved choice
+    let columns = list_state
+        .columns
+        .clone()
+        .or_else(|| cookies.get(COLUMNS_COOKIE).map(|c| parse_columns(c.value())))
+        .unwrap_or_else(|| ListColumn::DEFAULT.to_vec());
+    // Links keep whatever columns are shown, so a shared URL looks the same for someone without the cookie
+    list_state.columns = (columns != ListColumn::DEFAULT).then(|| columns.clone());
+    let favorites = read_favorites(state, cookies);
+    let widgets = cookies
+        .get(WIDGETS_COOKIE)
+        .and_then(|c| parse_widgets(c.value()))
+        .unwrap_or_else(|| state.widgets.clone());
+
+    let spec = state.db.with_search(filters.spec(state.hide_inactive, &state.tag_aliases)).await;
+    if filters.sort.is_none() {
+        spec.sort_by_relevance(&mut servers);
+    }
+    // Servers gone for good that match the search, linked below the list; with include_offline,
+    // every server off the current listing
+    let archived = if spec.search.is_empty() {
+        Vec::new()
+    } else {
+        let (unlisted_before, limit) = if spec.include_offline {
+            (chrono::Utc::now(), OFFLINE_SEARCH_RESULTS)
+        } else {
+            (chrono::Utc::now() - chrono::Duration::hours(ARCHIVE_AFTER_HOURS), ARCHIVED_SEARCH_RESULTS)
+        };
+        // Hidden servers stay out of search results along with the listed ones
+        let hidden = state.hidden_servers.read().await;
+        let listed: Vec<ServerId> = snapshot
+            .servers
+            .iter()
+            .filter_map(|s| s.server.server_id.clone())
+            .chain(hidden.iter().cloned())
+            .collect();
+        drop(hidden);
+        state
+            .db
+            .search_unlisted_servers(&spec.search, unlisted_before, &listed, limit)
+            .await
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to search archived servers: {}", e);
+                Vec::new()
+            })
+    };
 
     let props = AppProps {
         servers,
+        archived,
         error,
-        search: filters.search.unwrap_or_default(),
-        version: filters.version.unwrap_or_default(),
-        has_players: filters.has_players.unwrap_or(false),
-        no_password: filters.no_password.unwrap_or(false),
-        is_dedicated: filters.is_dedicated.unwrap_or(false),
-        tags: filters.tags.unwrap_or_default(),
+        filters: spec,
+        inactive_hidden_by_default: state.hide_inactive,
+        group_by: filters.group_by.unwrap_or_default(),
+        demo_mode: state.demo_mode,
+        locale: lang.0,
+        columns,
+        list_state,
+        page_query: query_without(uri, "page"),
+        favorites,
+        account,
+        push_public_key: state.push.as_ref().map(|p| p.public_key().to_string()),
+        widgets,
+        friends: read_friends(cookies),
+        hydrate: state.hydrate,
+        logo: state.image_proxy.responsive(&state.logo_url, LOGO_SIZE),
     };
 
+    // Only pages that show the list carry its data
+    let hydration = if props.hydrate && props.widgets.contains(&Widget::List) {
+        hydration_scripts(&state.assets, &list_props(&props))
+    } else {
+        String::new()
+    };
     let renderer = ServerRenderer::<App>::with_props(move || props.clone());
-    let html_content = renderer.render().await;
+    let html_content = renderer.render().await + &hydration;
+
+    let html = html_shell_with_video(state, &state.site_name, html_content, true);
+    record_impression();
+    Tagged::Fresh(etag.clone(), ticket.finish(etag, html))
+}
+
+/// The visitor's ranking experiment variant, assigning one (and setting its cookie) on first visit
+fn experiment_variant(experiment: &Experiment, cookies: &CookieJar<'_>) -> usize {
+    if let Some(variant) = cookies.get(EXPERIMENT_COOKIE).and_then(|c| experiment.find(c.value())) {
+        return variant;
+    }
+
+    let variant = experiment.assign();
+    let cookie = Cookie::build((EXPERIMENT_COOKIE, experiment.variants[variant].name.clone()))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .max_age(rocket::time::Duration::days(EXPERIMENT_COOKIE_DAYS));
+    cookies.add(cookie);
+    variant
+}
+
+/// Atom feed of servers matching the same filters as the main page, newest listing first
+#[get("/feed.xml?<filters..>")]
+async fn feed(
+    state: &State<Arc<AppState>>,
+    filters: IndexFilters,
+    site: SiteUrl,
+    uri: &rocket::http::uri::Origin<'_>,
+) -> (ContentType, String) {
+    let snapshot = state.cache.load();
+    let spec = state.db.with_search(filters.spec(state.hide_inactive, &state.tag_aliases)).await;
+    let latest_version = versions_newest_first(snapshot.servers.iter().map(|s| &s.server))
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let entries = feed_entries(
+        snapshot
+            .servers
+            .iter()
+            .filter(|s| spec.matches(&s.server, &latest_version) && spec.matches_view(s)),
+    );
+
+    let mut title = "Factorio servers".to_string();
+    if !spec.search.is_empty() {
+        title.push_str(&format!(" matching \"{}\"", spec.search));
+    }
+    if !spec.tags.is_empty() {
+        title.push_str(&format!(" tagged {}", spec.tags.join(", ")));
+    }
+
+    let self_url = format!("{}{}", site.0, uri);
+    let updated = snapshot.updated_at.unwrap_or_else(chrono::Utc::now);
+    (
+        ContentType::new("application", "atom+xml"),
+        render_atom(&title, &site.0, &self_url, &entries, updated),
+    )
+}
+
+/// Sitemap of the index, server pages, and tag pages, rebuilt once per cache refresh
+#[get("/sitemap.xml")]
+async fn sitemap(state: &State<Arc<AppState>>, site: SiteUrl) -> (ContentType, String) {
+    let snapshot = state.cache.load();
+    let cached = state.sitemap.read().await.get(snapshot.version, &site.0);
+    let xml = match cached {
+        Some(xml) => xml,
+        None => {
+            let updated = snapshot.updated_at.unwrap_or_else(chrono::Utc::now);
+            let xml = render_sitemap(&site.0, &snapshot.servers, updated);
+            state.sitemap.write().await.store(snapshot.version, &site.0, xml.clone());
+            xml
+        }
+    };
+
+    (ContentType::XML, xml)
+}
+
+/// Bare page for the lite frontend: inline CSS, no scripts, fonts or video
+fn lite_shell(title: &str, content: String) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<title>{}</title>
+<style>{}</style>
+</head>
+<body>
+{}
+</body>
+</html>"#,
+        escape_xml(title),
+        LITE_CSS,
+        content
+    )
+}
+
+/// Text-only server list with the main page's filters, for old or text-mode browsers and slow links
+#[get("/lite?<filters..>")]
+async fn lite_index(
+    state: &State<Arc<AppState>>,
+    uri: &rocket::http::uri::Origin<'_>,
+    filters: IndexFilters,
+) -> RawHtml<String> {
+    let snapshot = state.cache.load();
+    let spec = state.db.with_search(filters.spec(state.hide_inactive, &state.tag_aliases)).await;
+    let latest_version = versions_newest_first(snapshot.servers.iter().map(|s| &s.server))
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let mut servers: Vec<ServerView> = snapshot
+        .servers
+        .iter()
+        .filter(|s| spec.matches(&s.server, &latest_version) && spec.matches_view(s))
+        .cloned()
+        .collect();
+    let list_state = ListState::parse(filters.sort.as_deref(), filters.dir.as_deref(), None, None).with_page(filters.page);
+    list_state.sort_servers(&mut servers);
+    if filters.sort.is_none() {
+        spec.sort_by_relevance(&mut servers);
+    }
+
+    let matching = servers.len();
+    let pages = matching.div_ceil(PAGE_SIZE).max(1);
+    let page = list_state.page.min(pages - 1);
+    let props = LiteListProps {
+        servers: servers.into_iter().skip(page * PAGE_SIZE).take(PAGE_SIZE).collect(),
+        filters: spec,
+        sort: list_state.sort,
+        matching,
+        page,
+        pages,
+        page_query: query_without(uri, "page"),
+        error: state.last_error.read().await.clone(),
+    };
+    let renderer = ServerRenderer::<LiteList>::with_props(move || props.clone());
+    RawHtml(lite_shell(&format!("Lite - {}", state.site_name), renderer.render().await))
+}
+
+/// Text-only page of a listed server
+#[get("/lite/server/<game_id>")]
+async fn lite_server(state: &State<Arc<AppState>>, game_id: GameId) -> Option<RawHtml<String>> {
+    let server = state.cache.load().find(game_id).cloned()?;
+    let title = format!("{} - {}", strip_all_tags(&server.server.name), state.site_name);
+    let renderer = ServerRenderer::<LiteServer>::with_props(move || LiteServerProps { server: server.clone() });
+    Some(RawHtml(lite_shell(&title, renderer.render().await)))
+}
+
+/// Let crawlers in and point them at the sitemap
+#[get("/robots.txt")]
+fn robots(site: SiteUrl) -> String {
+    format!("User-agent: *\nAllow: /\n\nSitemap: {}/sitemap.xml\n", site.0)
+}
+
+/// Star or unstar a server, then go back to the page the form was on
+#[post("/favorites/<server_id>")]
+fn toggle_favorite_route(
+    state: &State<Arc<AppState>>,
+    cookies: &CookieJar<'_>,
+    referer: RefererPath,
+    server_id: ServerId,
+) -> Redirect {
+    let back = referer.0.unwrap_or_else(|| "/".to_string());
+    let favorites = read_favorites(state, cookies);
+
+    // Only servers in the listing can be starred; already-starred ones can always be removed
+    let listed = state.cache.load().servers.iter().any(|s| s.server.server_id.as_ref() == Some(&server_id));
+    if !listed && !favorites.contains(&server_id) {
+        return Redirect::to(back);
+    }
+
+    let value = toggle_favorite(&favorites, &server_id);
+    let cookie = Cookie::build((FAVORITES_COOKIE, state.cookie_signer.sign(&value)))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .max_age(rocket::time::Duration::days(365));
+    cookies.add(cookie);
+
+    // Push notifications follow the favorites
+    if let Some(id) = read_push_subscription(state, cookies) {
+        let db = state.db.clone();
+        let server_ids = parse_favorites(&value);
+        tokio::spawn(async move {
+            if let Err(e) = db.update_push_servers(&id, server_ids).await {
+                eprintln!("Failed to update push subscription: {}", e);
+            }
+        });
+    }
+
+    Redirect::to(back)
+}
+
+/// Player names from the friends cookie
+fn read_friends(cookies: &CookieJar<'_>) -> Vec<String> {
+    cookies
+        .get(FRIENDS_COOKIE)
+        .map(|c| parse_friends(&c.value().split(',').collect::<Vec<_>>()))
+        .unwrap_or_default()
+}
+
+/// Save the homepage layout chosen in the customize panel, then go back home
+#[post("/widgets", data = "<form>")]
+fn save_widgets(cookies: &CookieJar<'_>, form: Form<WidgetsForm>) -> Redirect {
+    if form.reset {
+        cookies.remove(Cookie::build(WIDGETS_COOKIE).path("/"));
+        return Redirect::to("/");
+    }
+
+    let keys = |keys: &[String]| keys.iter().filter_map(|k| Widget::from_key(k)).collect::<Vec<_>>();
+    let moved = form.moved.as_deref().and_then(|m| {
+        let (key, direction) = m.split_once(':')?;
+        Some((Widget::from_key(key)?, direction == "up"))
+    });
+    let layout = arrange_widgets(&keys(&form.order), &keys(&form.show), moved);
+    let value: Vec<&str> = layout.iter().map(Widget::key).collect();
+
+    // Nothing ticked means the default layout, like a cookie that doesn't parse
+    if value.is_empty() {
+        cookies.remove(Cookie::build(WIDGETS_COOKIE).path("/"));
+    } else {
+        let cookie = Cookie::build((WIDGETS_COOKIE, value.join(",")))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .max_age(rocket::time::Duration::days(365));
+        cookies.add(cookie);
+    }
+    Redirect::to("/")
+}
+
+/// Save the player names the friends widget looks for
+#[post("/friends", data = "<form>")]
+fn save_friends(cookies: &CookieJar<'_>, referer: RefererPath, form: Form<FriendsForm>) -> Redirect {
+    let friends = parse_friends(&form.names.split(',').collect::<Vec<_>>());
+    if friends.is_empty() {
+        cookies.remove(Cookie::build(FRIENDS_COOKIE).path("/"));
+    } else {
+        let cookie = Cookie::build((FRIENDS_COOKIE, friends.join(",")))
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .max_age(rocket::time::Duration::days(365));
+        cookies.add(cookie);
+    }
+    Redirect::to(referer.0.unwrap_or_else(|| "/".to_string()))
+}
+
+/// Verified push subscription id from the push cookie
+fn read_push_subscription(state: &AppState, cookies: &CookieJar<'_>) -> Option<String> {
+    state.push.as_ref()?;
+    cookies
+        .get(PUSH_COOKIE)
+        .and_then(|c| state.cookie_signer.verify(c.value()).map(str::to_string))
+}
+
+/// Store the browser's push subscription, watching the visitor's favorites and the given friends
+#[post("/push/subscribe", data = "<request>")]
+async fn push_subscribe(
+    state: &State<Arc<AppState>>,
+    cookies: &CookieJar<'_>,
+    request: Json<PushSubscribeRequest>,
+) -> Status {
+    if state.push.is_none() {
+        return Status::NotFound;
+    }
+    let request = request.into_inner();
+    if !is_allowed_endpoint(&request.endpoint) || !valid_keys(&request.keys.p256dh, &request.keys.auth) {
+        return Status::BadRequest;
+    }
+
+    let id = subscription_id(&request.endpoint);
+    let subscription = NewPushSubscription {
+        endpoint: request.endpoint,
+        p256dh: request.keys.p256dh,
+        auth: request.keys.auth,
+        server_ids: read_favorites(state, cookies),
+        friends: parse_friends(&request.friends),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = state.db.save_push_subscription(&id, subscription).await {
+        eprintln!("Failed to save push subscription: {}", e);
+        return Status::InternalServerError;
+    }
+
+    let cookie = Cookie::build((PUSH_COOKIE, state.cookie_signer.sign(&id)))
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Lax)
+        .max_age(rocket::time::Duration::days(365));
+    cookies.add(cookie);
+    Status::NoContent
+}
+
+/// Forget the visitor's push subscription
+#[post("/push/unsubscribe")]
+async fn push_unsubscribe(state: &State<Arc<AppState>>, cookies: &CookieJar<'_>) -> Status {
+    let Some(id) = read_push_subscription(state, cookies) else {
+        return Status::NoContent;
+    };
+    if let Err(e) = state.db.delete_push_subscription(&id).await {
+        eprintln!("Failed to delete push subscription: {}", e);
+        return Status::InternalServerError;
+    }
+    cookies.remove(Cookie::build(PUSH_COOKIE).path("/"));
+    Status::NoContent
+}
+
+/// Files under static/, by fingerprinted name (as pages link them) or plain name
+#[get("/static/<path..>")]
+async fn static_file(state: &State<Arc<AppState>>, path: PathBuf) -> Option<StaticFile> {
+    let (file, fingerprinted) = state.assets.resolve(&path);
+    let file = NamedFile::open(file).await.ok()?;
+    Some(StaticFile { file, fingerprinted })
+}
+
+/// Service worker showing push notifications, served from the root so its scope covers the whole site
+#[get("/sw.js")]
+async fn service_worker() -> Option<NamedFile> {
+    NamedFile::open("static/sw.js").await.ok()
+}
+
+/// A description as plain text, line by line so rich text markup doesn't confuse the translator
+fn plain_description(description: &str) -> String {
+    description
+        .lines()
+        .map(strip_all_tags)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Whether a description is worth offering a translation of: not already in the target language
+fn can_translate(state: &AppState, description: &str) -> bool {
+    state.translator.as_ref().is_some_and(|translator| {
+        !state.read_only && !description.is_empty() && !translator.is_in_target(&plain_description(description))
+    })
+}
+
+/// Translate a server description, using the DB cache keyed by description hash
+/// Backend calls are limited to TRANSLATIONS_PER_MINUTE; past that the original is shown
+async fn translate_description(state: &AppState, description: &str) -> Option<String> {
+    let translator = state.translator.as_ref()?;
+    let plain = plain_description(description);
+    let hash = text_hash(&plain);
+
+    if let Ok(Some(cached)) = state.db.get_translation(&hash, translator.target()).await {
+        return Some(cached.text);
+    }
+    state.translate_limit.try_acquire().ok()?;
+
+    match translator.translate(&plain).await {
+        Ok(text) => {
+            if let Err(e) = state.db.save_translation(&hash, translator.target(), &text).await {
+                eprintln!("Failed to cache translation: {}", e);
+            }
+            Some(text)
+        }
+        Err(e) => {
+            eprintln!("Failed to translate description: {}", e);
+            None
+        }
+    }
+}
+
+/// Attach Mod Portal details to a server's mods, fetching whatever isn't cached in the DB
+/// Portal failures only cost the extra details; the plain mod list is always returned
+async fn enrich_mods(state: &AppState, mut mods: Vec<ModEntry>) -> Vec<ModEntry> {
+    let Some(ref portal) = state.mod_portal else {
+        return mods;
+    };
+    let names: Vec<String> = mods
+        .iter()
+        .map(|m| m.name.clone())
+        .filter(|name| !BUILTIN_MODS.contains(&name.as_str()))
+        .collect();
+    if names.is_empty() {
+        return mods;
+    }
+
+    let mut known: HashMap<String, ModMetadata> = match state.db.get_mod_metadata(names.clone()).await {
+        Ok(cached) => cached.into_iter().map(|m| (m.name.clone(), m)).collect(),
+        Err(e) => {
+            eprintln!("Failed to load mod metadata: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let missing: Vec<&str> = names.iter().map(String::as_str).filter(|name| !known.contains_key(*name)).collect();
+    if !missing.is_empty() {
+        match portal.get_mods(&missing).await {
+            Ok(found) => {
+                let fetched_at = chrono::Utc::now().to_rfc3339();
+                let mut found: HashMap<String, PortalMod> = found.into_iter().map(|m| (m.name.clone(), m)).collect();
+                // Mods the portal doesn't list are stored too, so they aren't asked for on every view
+                let records: Vec<NewModMetadata> = missing
+                    .iter()
+                    .map(|name| match found.remove(*name) {
+                        Some(m) => NewModMetadata {
+                            name: m.name,
+                            title: Some(m.title),
+                            thumbnail: m.thumbnail,
+                            downloads: m.downloads,
+                            category: m.category,
+                            fetched_at: fetched_at.clone(),
+                        },
+                        None => NewModMetadata {
+                            name: name.to_string(),
+                            title: None,
+                            thumbnail: None,
+                            downloads: 0,
+                            category: None,
+                            fetched_at: fetched_at.clone(),
+                        },
+                    })
+                    .collect();
+                for record in &records {
+                    known.insert(record.name.clone(), ModMetadata {
+                        id: None,
+                        name: record.name.clone(),
+                        title: record.title.clone(),
+                        thumbnail: record.thumbnail.clone(),
+                        downloads: record.downloads,
+                        category: record.category.clone(),
+                        fetched_at: record.fetched_at.clone(),
+                    });
+                }
+                if let Err(e) = state.db.save_mod_metadata(records).await {
+                    eprintln!("Failed to cache mod metadata: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Failed to fetch mod metadata: {}", e),
+        }
+    }
+
+    for entry in &mut mods {
+        entry.portal = known.remove(&entry.name).and_then(|m| {
+            Some(PortalMod {
+                title: m.title?,
+                name: m.name,
+                thumbnail: m.thumbnail,
+                downloads: m.downloads,
+                category: m.category,
+            })
+        });
+        entry.thumbnail = entry
+            .portal
+            .as_ref()
+            .and_then(|portal| portal.thumbnail.as_deref())
+            .map(|url| state.image_proxy.responsive(url, MOD_THUMBNAIL_SIZE));
+    }
+    mods
+}
 
-    RawHtml(html_shell_with_video("Factorio Server Browser", html_content, true))
+/// An HTML page, or a redirect elsewhere (a server's current game_id, a search result)
+#[derive(rocket::Responder)]
+enum PageOrRedirect {
+    Page(RawHtml<String>),
+    Moved(Box<Redirect>),
 }
 
 /// Server details page
-#[get("/server/<game_id>")]
-async fn server_details_page(state: &State<Arc<AppState>>, game_id: u64) -> RawHtml<String> {
-    use factorio_browser::components::server_details::ModEntry;
-    
+#[get("/server/<game_id>?<translate>&<alert>&<report>&<tab>")]
+#[allow(clippy::too_many_arguments)]
+async fn server_details_page(
+    state: &State<Arc<AppState>>,
+    cookies: &CookieJar<'_>,
+    site: SiteUrl,
+    referer: RefererPath,
+    game_id: GameId,
+    translate: Option<bool>,
+    alert: Option<String>,
+    report: Option<String>,
+    tab: Option<&str>,
+) -> PageOrRedirect {
+    let tab = tab.and_then(DetailsTab::from_key).unwrap_or_default();
     // Get server from in-memory cache (avoids race condition during DB refresh)
-    let server = state.cached_servers.read().await
-        .iter()
-        .find(|s| s.game_id == game_id)
-        .cloned();
+    let snapshot = state.cache.load();
+    let server = snapshot.find(game_id).cloned();
+
+    // A click-through from the server list, for the visitor's ranking experiment variant
+    let from_list = referer.0.as_deref().is_some_and(|path| path == "/" || path.starts_with("/?"));
+    if let Some(ref experiment) = state.experiment
+        && from_list
+        && server.is_some()
+        && let Some(variant) = cookies.get(EXPERIMENT_COOKIE).and_then(|c| experiment.find(c.value()))
+    {
+        state.metrics.record_click(&experiment.variants[variant].name);
+    }
+
+    // A restart gives the server a new game_id; follow it via the server_id in history
+    if server.is_none()
+        && let Ok(Some(server_id)) = state.db.find_server_id(game_id).await
+    {
+        let current = snapshot
+            .servers
+            .iter()
+            .find(|s| s.server.server_id.as_deref() == Some(server_id.as_str()))
+            .map(|s| s.server.game_id);
+        if let Some(new_game_id) = current {
+            return PageOrRedirect::Moved(Box::new(Redirect::moved(uri!(server_details_page(new_game_id, translate, _, _, Some(tab.key()))))));
+        }
+    }
+
+    // A server gone for good keeps an archive page under its server_id
+    if server.is_none()
+        && let Ok(Some(server_id)) = state.db.find_archived_server_id(game_id).await
+    {
+        return PageOrRedirect::Moved(Box::new(Redirect::moved(uri!(archived_server_page(server_id)))));
+    }
     
-    // Fetch fresh details from API for players and mods
-    let (players, mods) = match state.factorio_client.get_game_details(game_id).await {
-        Ok(details) => (
-            details.players,
-            details.mods.into_iter().map(|m| ModEntry {
-                name: m.name,
-                version: m.version,
-            }).collect(),
-        ),
-        Err(_) => (Vec::new(), Vec::new()),
+    // Fetch fresh details from API for players and mods, only for the tabs showing them
+    // In read-only mode, fall back to the cached player list (mods aren't cached)
+    let mut details_error = None;
+    let mut modpack = server.as_ref().and_then(|s| s.modpack.clone());
+    let (players, mods) = if !matches!(tab, DetailsTab::Players | DetailsTab::Mods) {
+        (Vec::new(), Vec::new())
+    } else if state.read_only {
+        (server.as_ref().map(|s| s.server.players.clone()).unwrap_or_default(), Vec::new())
+    } else {
+        match state.source.get_game_details(game_id).await {
+            Ok(details) => {
+                if modpack.is_none() && server.is_some() {
+                    modpack = record_modpack(state, game_id, &details.mods).await;
+                }
+                (
+                    details.players,
+                    enrich_mods(state, details.mods.into_iter().map(|m| ModEntry {
+                        name: m.name,
+                        version: m.version,
+                        portal: None,
+                        thumbnail: None,
+                    }).collect()).await,
+                )
+            }
+            Err(e) => {
+                // Keep the page usable with cached players and say why the live data is missing
+                if server.is_some() {
+                    eprintln!("Failed to fetch details for game {}: {}", game_id, e);
+                    details_error = Some(match e {
+                        ApiError::NotFound => "Live details unavailable (server not found upstream), showing cached data",
+                        ApiError::RateLimited(_) => "Live details are busy right now, showing cached data",
+                        _ => "Live details unavailable, showing cached data",
+                    }.to_string());
+                }
+                (server.as_ref().map(|s| s.server.players.clone()).unwrap_or_default(), Vec::new())
+            }
+        }
     };
     
-    // Fetch raw history and fill gaps with 0-player entries
+    // Other listed servers running exactly the same mods
+    let modpack_servers = match modpack {
+        Some(ref fingerprint) if tab == DetailsTab::Mods => match state.db.get_modpack_game_ids(fingerprint).await {
+            Ok(game_ids) => game_ids
+                .into_iter()
+                .filter(|id| *id != game_id && snapshot.find(*id).is_some())
+                .count(),
+            Err(e) => {
+                eprintln!("Failed to load modpack servers: {}", e);
+                0
+            }
+        },
+        _ => 0,
+    };
+
+    // Fetch a week of raw history for the activity heatmap
+    let raw_history = if tab == DetailsTab::Activity {
+        state
+            .db
+            .get_server_history(
+                game_id,
+                server.as_ref().and_then(|s| s.server.server_id.as_ref()),
+                HISTORY_RETENTION_HOURS as u32,
+            )
+            .await
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    // Visitor's UTC offset in minutes, set by sort.js
+    let tz_offset = cookies
+        .get("tz_offset")
+        .and_then(|c| c.value().parse::<i32>().ok())
+        .filter(|offset| offset.abs() <= 14 * 60);
+    let hint = join_hint(&ActivityHeatmap::from_history(&raw_history), tz_offset);
+
+    // Fill gaps with 0-player entries
     // Since we only record when players > 0, we need to fill in the timeline
-    let raw_history = state
-        .db
-        .get_server_history(game_id, 24)
-        .await
-        .unwrap_or_default();
-    
-    let history = fill_history_gaps(raw_history);
+    let carry_forward = state.history_sampler.read().await.mode() == SamplingMode::OnChange;
+    let history = fill_history_gaps(raw_history, state.history_bucket_minutes, carry_forward);
 
     match server {
         Some(server) => {
-            let title = format!("{} - Factorio Server Browser", strip_all_tags(&server.name));
+            let can_translate = can_translate(state, &server.server.description);
+            let translated_description = if translate.unwrap_or(false) && can_translate {
+                translate_description(state, &server.server.description).await
+            } else {
+                None
+            };
+            let title = format!("{} - {}", strip_all_tags(&server.server.name), state.site_name);
             let props = factorio_browser::components::server_details::ServerDetailsProps { 
                 server, 
+                tab,
                 history,
                 players,
                 mods,
+                translated_description,
+                can_translate,
+                join_hint: hint,
+                alerts_enabled: state.downtime_alerts && !state.read_only,
+                alert_status: alert,
+                reports_enabled: state.reports,
+                report_status: report,
+                details_error,
+                modpack,
+                modpack_servers,
             };
             let renderer = ServerRenderer::<ServerDetails>::with_props(move || props.clone());
             let html_content = renderer.render().await;
-            RawHtml(html_shell_with_video(&title, html_content, true))
+            let image = format!("{}/og/{}.png", site.0, game_id);
+            PageOrRedirect::Page(RawHtml(html_shell_with_image(state, &title, html_content, true, &image)))
         }
         None => {
             let html_content = r#"
@@ -178,8 +1354,8 @@ async fn server_details_page(state: &State<Arc<AppState>>, game_id: u64) -> RawH
                         <div class="text-center py-8 bg-status-full/10 border border-status-full/30 rounded-md text-status-full">
                             <p class="mb-4">
                                 The requested server could not be found.<br/>
-                                If you viewed this page previously, the server may have restarted and triggered a new game_id.<br/>
-                                <b>It's a limitation of the Factorio Matchmaking API.</b>
+                                It may be offline or no longer listed.<br/>
+                                Servers that restart with a new game_id are followed automatically while they're still listed.
                             </p>
                             <a href="/" class="text-accent-primary hover:text-accent-secondary transition-colors duration-200">
                                 ← Back to Server List
@@ -189,64 +1365,572 @@ async fn server_details_page(state: &State<Arc<AppState>>, game_id: u64) -> RawH
                 </div>
             "#
             .to_string();
-            RawHtml(html_shell_with_video("Server Not Found", html_content, true))
+            PageOrRedirect::Page(RawHtml(html_shell_with_video(state, "Server Not Found", html_content, true)))
         }
     }
 }
 
-/// Wrapper for NamedFile that adds caching headers
-pub struct CachedFile(NamedFile);
+/// Seconds between dashboard reloads, matching the upstream refresh interval
+const DASHBOARD_REFRESH_SECS: u64 = 60;
+
+/// Most servers a single dashboard will show
+const DASHBOARD_MAX_SERVERS: usize = 24;
 
-impl<'r> Responder<'r, 'static> for CachedFile {
+/// HTML page that asks the browser to reload itself periodically
+pub struct AutoRefresh(RawHtml<String>, u64);
+
+impl<'r> Responder<'r, 'static> for AutoRefresh {
     fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
         Response::build_from(self.0.respond_to(req)?)
-            // Cache for 1 day, revalidate with server
-            .header(Header::new("Cache-Control", "public, max-age=86400, must-revalidate"))
+            .header(Header::new("Refresh", self.1.to_string()))
             .ok()
     }
 }
 
-//// Fill gaps in history data with 0-player entries
-/// Since we only record when players > 0, we need to fill in periods of inactivity
-fn fill_history_gaps(raw_history: Vec<factorio_browser::db::models::ServerHistory>) -> Vec<factorio_browser::components::server_details::HistoryEntry> {
-    use chrono::{DateTime, Duration, Utc};
-    use factorio_browser::components::server_details::HistoryEntry;
-    use std::collections::HashMap;
-    
-    let now = Utc::now();
-    
-    // Create a map of hour -> player counts for that hour
-    let mut hourly_counts: HashMap<i64, Vec<usize>> = HashMap::new();
-    
-    for record in &raw_history {
-        if let Ok(recorded_at) = DateTime::parse_from_rfc3339(&record.recorded_at) {
-            // Calculate hours ago (0 = current hour, 23 = 23 hours ago)
-            let hours_ago = (now - recorded_at.with_timezone(&Utc)).num_hours();
-            if hours_ago >= 0 && hours_ago < 24 {
-                hourly_counts
-                    .entry(hours_ago)
-                    .or_default()
-                    .push(record.player_count);
-            }
-        }
-    }
-    
-    // Generate 24 hourly entries (newest first to match expected order)
-    // Each entry represents the average player count for that hour, or 0 if no data
-    (0..24)
-        .map(|hours_ago| {
-            let avg_count = hourly_counts
-                .get(&hours_ago)
-                .map(|counts| counts.iter().sum::<usize>() / counts.len().max(1))
-                .unwrap_or(0);
+/// Minimal one-card summary of a server for screenshots, printing and Discord announcements
+#[get("/server/<game_id>/card")]
+async fn server_card_page(state: &State<Arc<AppState>>, site: SiteUrl, game_id: GameId) -> Option<RawHtml<String>> {
+    let server = state.cache.load().find(game_id)?.clone();
+    let title = format!("{} - {}", strip_all_tags(&server.server.name), state.site_name);
+    let props = SummaryCardProps {
+        server,
+        page_url: format!("{}/server/{}", site.0, game_id),
+    };
+    let renderer = ServerRenderer::<SummaryCard>::with_props(move || props.clone());
+    let html_content = renderer.render().await;
+    let image = format!("{}/og/{}.png", site.0, game_id);
+    Some(RawHtml(html_shell_with_image(state, &title, html_content, false, &image)))
+}
+
+/// Auto-refreshing board of selected servers for wall displays and screenshares
+#[get("/dashboard?<ids>")]
+async fn dashboard(state: &State<Arc<AppState>>, ids: Option<String>) -> AutoRefresh {
+    let mut game_ids: Vec<GameId> = Vec::new();
+    for id in ids.unwrap_or_default().split(',').filter_map(|id| id.trim().parse().ok()) {
+        if !game_ids.contains(&id) && game_ids.len() < DASHBOARD_MAX_SERVERS {
+            game_ids.push(id);
+        }
+    }
+
+    let snapshot = state.cache.load();
+    let mut servers = Vec::new();
+    let mut missing = Vec::new();
+    for game_id in game_ids {
+        match snapshot.find(game_id) {
+            Some(view) => servers.push(view.clone()),
+            None => missing.push(game_id),
+        }
+    }
+
+    let props = DashboardProps {
+        servers,
+        missing,
+        refresh_secs: DASHBOARD_REFRESH_SECS,
+    };
+    let renderer = ServerRenderer::<Dashboard>::with_props(move || props.clone());
+    let html_content = renderer.render().await;
+
+    AutoRefresh(
+        RawHtml(html_shell_with_video(state, &format!("Dashboard - {}", state.site_name), html_content, false)),
+        DASHBOARD_REFRESH_SECS,
+    )
+}
+
+/// The latest weekly digest with when it was generated, or one computed on the spot before the first weekly run
+async fn latest_digest(state: &AppState) -> (WeeklyDigest, Option<String>) {
+    match state.db.get_materialized::<WeeklyDigest>(WEEKLY_DIGEST_KEY).await {
+        Ok(Some(stored)) => return (stored.data, Some(stored.generated_at)),
+        Ok(None) => {}
+        Err(e) => eprintln!("Failed to load weekly digest: {}", e),
+    }
+    (compute_digest(state).await, None)
+}
+
+/// Summarize the last DIGEST_DAYS from the hourly rollups, each game's peak and the current listing
+async fn compute_digest(state: &AppState) -> WeeklyDigest {
+    let hours = DIGEST_DAYS * 24;
+    let totals = state.db.get_hourly_totals(hours).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load listing totals: {}", e);
+        Vec::new()
+    });
+    let peaks: HashMap<GameId, usize> = match state.db.get_peak_players(hours).await {
+        Ok(peaks) => peaks.into_iter().map(|p| (p.game_id, p.peak)).collect(),
+        Err(e) => {
+            eprintln!("Failed to load peak players: {}", e);
+            HashMap::new()
+        }
+    };
+    WeeklyDigest::compute(&state.cache.load().servers, &peaks, &totals, chrono::Utc::now())
+}
+
+/// Weekly summary of top servers, new servers and ecosystem numbers, with Markdown to copy
+#[get("/digest/latest")]
+async fn digest_page(state: &State<Arc<AppState>>, site: SiteUrl) -> RawHtml<String> {
+    let (digest, generated_at) = latest_digest(state).await;
+    let props = DigestPageProps {
+        markdown: digest.to_markdown(&site.0),
+        digest,
+        generated_at,
+    };
+    let renderer = ServerRenderer::<DigestPage>::with_props(move || props.clone());
+    let html_content = renderer.render().await;
+
+    RawHtml(html_shell_with_video(state, &format!("Weekly Digest - {}", state.site_name), html_content, false))
+}
+
+/// The weekly digest as Markdown, for posting to Reddit or Discord
+#[get("/digest/latest.md")]
+async fn digest_markdown(state: &State<Arc<AppState>>, site: SiteUrl) -> (ContentType, String) {
+    let (digest, _) = latest_digest(state).await;
+    (
+        ContentType::new("text", "markdown").with_params(("charset", "utf-8")),
+        digest.to_markdown(&site.0),
+    )
+}
+
+/// Listing-wide statistics: totals over the last week, version adoption and top tags
+#[get("/stats")]
+async fn stats_page(state: &State<Arc<AppState>>) -> RawHtml<String> {
+    let totals = state.db.get_hourly_totals(HISTORY_RETENTION_HOURS).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load listing totals: {}", e);
+        Vec::new()
+    });
+    let materialized = state
+        .db
+        .get_materialized::<GlobalStats>(GLOBAL_STATS_KEY)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load stats: {}", e);
+            None
+        });
+
+    let version_totals = state.db.get_version_totals(VERSION_HISTORY_RETENTION_HOURS).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load version totals: {}", e);
+        Vec::new()
+    });
+
+    let props = StatsPageProps {
+        totals,
+        adoption: VersionAdoption::from_hourly(&version_totals),
+        adoption_days: VERSION_HISTORY_RETENTION_HOURS / 24,
+        generated_at: materialized.as_ref().map(|m| m.generated_at.clone()),
+        stats: materialized.map(|m| m.data),
+        days: HISTORY_RETENTION_HOURS / 24,
+    };
+    let renderer = ServerRenderer::<StatsPage>::with_props(move || props.clone());
+    let html_content = renderer.render().await;
+
+    RawHtml(html_shell_with_video(state, &format!("Statistics - {}", state.site_name), html_content, false))
+}
+
+/// Player search form target: redirect to the player's page
+#[get("/players?<name>")]
+async fn players_search(state: &State<Arc<AppState>>, name: Option<String>) -> PageOrRedirect {
+    match name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => PageOrRedirect::Moved(Box::new(Redirect::to(format!("/players/{}", urlencoding::encode(name))))),
+        None => PageOrRedirect::Page(render_player_page(state, None).await),
+    }
+}
+
+/// Which servers a player is on right now, or was on recently
+#[get("/players/<name>")]
+async fn player_page(state: &State<Arc<AppState>>, name: &str) -> RawHtml<String> {
+    let search = if name.trim().is_empty() || name.len() > MAX_PLAYER_NAME_LEN {
+        None
+    } else {
+        let snapshot = state.cache.load();
+        Some(state.recent_players.read().await.search(&snapshot.servers, name))
+    };
+    render_player_page(state, search).await
+}
+
+async fn render_player_page(state: &AppState, search: Option<PlayerSearch>) -> RawHtml<String> {
+    // The title is fixed: the player name comes from the URL and the shell doesn't escape it
+    let props = PlayerSearchProps { search };
+    let renderer = ServerRenderer::<PlayerSearchPage>::with_props(move || props.clone());
+    RawHtml(html_shell_with_video(state, &format!("Find a Player - {}", state.site_name), renderer.render().await, true))
+}
+
+/// Sign-in and register forms, or the signed-in account's presets (the forms post to accounts::routes)
+#[get("/account?<status>")]
+async fn account(
+    state: &State<Arc<AppState>>,
+    signed_in: Option<SignedIn>,
+    status: Option<String>,
+) -> PageOrRedirect {
+    if !state.accounts {
+        return PageOrRedirect::Moved(Box::new(Redirect::to("/")));
+    }
+
+    let presets = match signed_in {
+        Some(SignedIn(ref email)) => load_presets(&state.db, email).await,
+        None => Vec::new(),
+    };
+    let props = AccountPageProps {
+        email: signed_in.map(|s| s.0),
+        presets,
+        message: status.as_deref().and_then(status_message),
+    };
+    let renderer = ServerRenderer::<AccountPage>::with_props(move || props.clone());
+    PageOrRedirect::Page(RawHtml(html_shell_with_video(
+        state,
+        &format!("Account - {}", state.site_name),
+        renderer.render().await,
+        true,
+    )))
+}
+
+/// Register a downtime alert webhook for a server
+/// The alert is keyed on the server's server_id and stays unclaimed, sending nothing but the claim
+/// code, until the code shows up in the server's description
+#[post("/server/<game_id>/alerts", data = "<form>")]
+async fn add_downtime_alert(
+    state: &State<Arc<AppState>>,
+    game_id: GameId,
+    form: Form<AlertForm>,
+) -> Redirect {
+    let webhook_url = form.webhook_url.trim();
+    let server = state
+        .cache
+        .load()
+        .find(game_id)
+        .map(|s| (s.server.server_id.clone(), strip_all_tags(&s.server.name)));
+    let status = if !state.downtime_alerts || state.read_only {
+        "disabled"
+    } else if !is_allowed_webhook(webhook_url) {
+        "invalid"
+    } else {
+        match server {
+            None => "invalid",
+            Some((None, _)) => "unidentified",
+            Some((Some(server_id), name)) => register_alert(state, &server_id, &name, webhook_url).await,
+        }
+    };
+
+    Redirect::to(uri!(server_details_page(game_id, _, Some(status), _, Some(DetailsTab::Events.key()))))
+}
+
+/// Send a claim code to a webhook and store its unclaimed alert, returning the status to show
+async fn register_alert(state: &AppState, server_id: &ServerId, name: &str, webhook_url: &str) -> &'static str {
+    match state.db.find_downtime_alert(server_id, webhook_url).await {
+        Ok(Some(alert)) if alert.claimed => return "added",
+        Ok(Some(_)) => return "pending",
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("Failed to look up downtime alert: {}", e);
+            return "error";
+        }
+    }
+    if state.alert_limit.try_acquire().is_err() {
+        return "busy";
+    }
+    let Some(code) = new_claim_code() else {
+        return "error";
+    };
+
+    let message = format!(
+        "To turn on downtime alerts for **{}**, add `{}` to the server's description within {} hours.",
+        name, code, CLAIM_HOURS
+    );
+    if let Err(e) = state.notifier.send_webhook(webhook_url, &message).await {
+        eprintln!("Failed to send alert claim code: {}", e);
+        state.metrics.record_failure(Subsystem::Webhooks, &e);
+        return "unreachable";
+    }
+    state.metrics.record_success(Subsystem::Webhooks);
+
+    match state.db.add_downtime_alert(server_id, name, webhook_url, &code).await {
+        Ok(_) => "pending",
+        Err(e) => {
+            eprintln!("Failed to add downtime alert: {}", e);
+            "error"
+        }
+    }
+}
+
+/// Let the admins know about a spam, offensive or impostor listing
+/// One open report per visitor and server; the visitor is only kept as a keyed hash of their IP
+#[post("/server/<game_id>/report", data = "<form>")]
+async fn report_server(
+    state: &State<Arc<AppState>>,
+    game_id: GameId,
+    ip: Option<IpAddr>,
+    form: Form<ReportForm>,
+) -> Redirect {
+    let listed = state.cache.load().find(game_id).map(|view| view.server.clone());
+    let reason = ReportReason::from_key(&form.reason);
+    let status = match (listed, reason) {
+        _ if !state.reports => "disabled",
+        (None, _) | (_, None) => "invalid",
+        (Some(server), Some(reason)) => {
+            let reporter = state.cookie_signer.digest(&ip.map(|ip| ip.to_string()).unwrap_or_default());
+            let since = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+            let checks = tokio::try_join!(
+                state.db.has_open_report(&reporter, game_id, server.server_id.as_ref()),
+                state.db.count_reports_since(&reporter, &since),
+            );
+            match checks {
+                Ok((true, _)) => "duplicate",
+                Ok((false, recent)) if recent >= REPORTS_PER_REPORTER_HOUR => "too_many",
+                // The shared budget is a backstop against floods from many addresses
+                Ok(_) if state.report_limit.try_acquire().is_err() => "busy",
+                Ok(_) => {
+                    let report = NewServerReport {
+                        game_id,
+                        server_id: server.server_id,
+                        name: server.name,
+                        reason,
+                        details: form.details.trim().chars().take(MAX_REPORT_DETAILS).collect(),
+                        reporter,
+                        reported_at: chrono::Utc::now().to_rfc3339(),
+                        dismissed: false,
+                    };
+                    match state.db.add_server_report(report).await {
+                        Ok(()) => "sent",
+                        Err(e) => {
+                            eprintln!("Failed to save report: {}", e);
+                            "error"
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to check reports: {}", e);
+                    "error"
+                }
+            }
+        }
+    };
+
+    Redirect::to(uri!(server_details_page(game_id, _, _, Some(status), Some(DetailsTab::Events.key()))))
+}
+
+/// A file from the static directory, cached for good when requested by its fingerprinted name
+pub struct StaticFile {
+    file: NamedFile,
+    fingerprinted: bool,
+}
+
+impl<'r> Responder<'r, 'static> for StaticFile {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let cache_control = if self.fingerprinted { assets::IMMUTABLE } else { assets::UNVERSIONED };
+        Response::build_from(self.file.respond_to(req)?)
+            .header(Header::new("Cache-Control", cache_control))
+            .ok()
+    }
+}
+
+/// SVG image response (badges, QR codes) with a short cache lifetime
+pub struct SvgImage(String);
+
+impl<'r> Responder<'r, 'static> for SvgImage {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        Response::build_from(self.0.respond_to(req)?)
+            .header(ContentType::SVG)
+            // Badges are embedded elsewhere and addresses change on restart, cache briefly
+            .header(Header::new("Cache-Control", "public, max-age=300"))
+            .ok()
+    }
+}
+
+/// PNG preview card for link unfurls
+pub struct PngCard(Arc<[u8]>);
+
+impl<'r> Responder<'r, 'static> for PngCard {
+    fn respond_to(self, _req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        Response::build()
+            .sized_body(self.0.len(), Cursor::new(self.0))
+            .header(ContentType::PNG)
+            // Unfurlers fetch once per share; player counts in the card go stale quickly anyway
+            .header(Header::new("Cache-Control", "public, max-age=300"))
+            .ok()
+    }
+}
+
+/// Third-party image through the proxy; the hash pins the upstream image, so it can be cached long
+pub struct ProxiedImage(OutputFormat, Vec<u8>);
+
+impl<'r> Responder<'r, 'static> for ProxiedImage {
+    fn respond_to(self, req: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let content_type = ContentType::parse_flexible(self.0.content_type()).unwrap_or(ContentType::Binary);
+        Response::build_from(self.1.respond_to(req)?)
+            .header(content_type)
+            .header(Header::new("Cache-Control", "public, max-age=86400"))
+            .ok()
+    }
+}
+
+/// Mod thumbnails and the header logo, fetched once, checked, resized and cached on disk as PNG,
+/// WebP and AVIF
+#[get("/img-proxy/<hash>?<size>&<format>")]
+async fn image_proxy(
+    state: &State<Arc<AppState>>,
+    hash: &str,
+    size: Option<u32>,
+    format: Option<&str>,
+) -> Result<ProxiedImage, Status> {
+    let format = match format {
+        None => OutputFormat::Png,
+        Some(key) => OutputFormat::from_key(key).ok_or(Status::NotFound)?,
+    };
+    match state.image_proxy.get(hash, size, format).await {
+        Ok(bytes) => Ok(ProxiedImage(format, bytes)),
+        Err(ProxyError::Unknown) => Err(Status::NotFound),
+        Err(e) => {
+            eprintln!("Failed to proxy image {}: {}", hash, e);
+            Err(Status::BadGateway)
+        }
+    }
+}
+
+/// Open Graph preview card for a server page, e.g. /og/12345.png
+#[get("/og/<file>")]
+async fn og_image(state: &State<Arc<AppState>>, file: &str) -> Option<PngCard> {
+    let game_id: GameId = file.strip_suffix(".png")?.parse().ok()?;
+    let snapshot = state.cache.load();
+    let view = snapshot.find(game_id)?;
+    if let Some(png) = state.card_renderer.cached(game_id, snapshot.version) {
+        return Some(PngCard(png));
+    }
+    let svg = render_card_svg(view);
+
+    // Rasterizing takes tens of milliseconds, keep it off the async workers
+    let renderer = state.card_renderer.clone();
+    match tokio::task::spawn_blocking(move || renderer.render_png(&svg)).await {
+        Ok(Ok(png)) => {
+            let png: Arc<[u8]> = Arc::from(png);
+            state.card_renderer.store(game_id, snapshot.version, png.clone());
+            Some(PngCard(png))
+        }
+        Ok(Err(e)) => {
+            eprintln!("Failed to render preview card: {}", e);
+            None
+        }
+        Err(e) => {
+            eprintln!("Preview card task failed: {}", e);
+            None
+        }
+    }
+}
+
+/// QR code of a server's Steam join link, for joining from another device at LAN events
+#[get("/server/<game_id>/qr.svg")]
+async fn server_qr(state: &State<Arc<AppState>>, game_id: GameId) -> Option<SvgImage> {
+    let snapshot = state.cache.load();
+    let address = snapshot.find(game_id)?.server.host_address.as_deref()?;
+    render_qr_svg(&steam_join_url(address)).map(SvgImage)
+}
+
+/// Public 7-day uptime badge for embedding in Discord/READMEs
+#[get("/badge/<server_id>/uptime.svg")]
+async fn uptime_badge(state: &State<Arc<AppState>>, server_id: ServerId) -> SvgImage {
+    let uptime = state
+        .db
+        .get_uptime(&server_id, PRESENCE_RETENTION_HOURS)
+        .await
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to compute uptime: {}", e);
+            None
+        });
+
+    SvgImage(render_badge("uptime (7d)", &format_uptime(uptime), uptime_color(uptime)))
+}
+
+/// Archive page of a server off the listing; a server listed again redirects to its page
+#[get("/archive/<server_id>")]
+async fn archived_server_page(state: &State<Arc<AppState>>, server_id: ServerId) -> Option<PageOrRedirect> {
+    let snapshot = state.cache.load();
+    if let Some(listed) = snapshot.servers.iter().find(|s| s.server.server_id.as_ref() == Some(&server_id)) {
+        return Some(PageOrRedirect::Moved(Box::new(Redirect::to(uri!(server_details_page(listed.server.game_id, _, _, _, _))))));
+    }
+    if state.hidden_servers.read().await.contains(&server_id) {
+        return None;
+    }
+    let server = state.db.get_server_profile(&server_id).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load archived server: {}", e);
+        None
+    })?;
+    if state.blocklist.read().await.matches(Some(&server.server_id), None, &server.name) {
+        return None;
+    }
+    let archived = chrono::DateTime::parse_from_rfc3339(&server.last_seen)
+        .is_ok_and(|t| t < chrono::Utc::now() - chrono::Duration::hours(ARCHIVE_AFTER_HOURS));
+    let successor = impostor::find_successor(&server.name, &server.server_id, snapshot.servers.iter().map(|s| &s.server)).cloned();
+    let title = format!(
+        "{} ({}) - {}",
+        strip_all_tags(&server.name),
+        if archived { "archived" } else { "offline" },
+        state.site_name
+    );
+    let props = ArchivePageProps { server, archived, successor };
+    let renderer = ServerRenderer::<ArchivePage>::with_props(move || props.clone());
+    let html_content = renderer.render().await;
+
+    Some(PageOrRedirect::Page(RawHtml(html_shell_with_video(state, &title, html_content, false))))
+}
+
+/// Fill gaps in history data and aggregate into chart buckets covering the last 24 hours
+/// Empty buckets are 0 (we only record when players > 0), or carry the previous value
+/// forward when history is only recorded on change.
+fn fill_history_gaps(
+    raw_history: Vec<factorio_browser::db::models::ServerHistory>,
+    bucket_minutes: i64,
+    carry_forward: bool,
+) -> Vec<factorio_browser::components::server_details::HistoryEntry> {
+    use chrono::{DateTime, Duration, Utc};
+    use factorio_browser::components::server_details::HistoryEntry;
+    use std::collections::HashMap;
+    
+    let now = Utc::now();
+    let bucket_minutes = bucket_minutes.clamp(1, 24 * 60);
+    let bucket_count = (24 * 60 / bucket_minutes).max(1);
+    
+    // Create a map of bucket -> player counts for that bucket (newest sample first)
+    let mut bucket_counts: HashMap<i64, Vec<usize>> = HashMap::new();
+    // Most recent sample from before the chart window, used as the starting carry value
+    let mut carry_in: Option<(DateTime<Utc>, usize)> = None;
+    
+    for record in &raw_history {
+        if let Ok(recorded_at) = DateTime::parse_from_rfc3339(&record.recorded_at) {
+            let recorded_at = recorded_at.with_timezone(&Utc);
+            // Calculate buckets ago (0 = current bucket)
+            let buckets_ago = (now - recorded_at).num_minutes() / bucket_minutes;
+            if (0..bucket_count).contains(&buckets_ago) {
+                bucket_counts
+                    .entry(buckets_ago)
+                    .or_default()
+                    .push(record.player_count);
+            } else if buckets_ago >= bucket_count && carry_in.is_none_or(|(t, _)| recorded_at > t) {
+                carry_in = Some((recorded_at, record.player_count));
+            }
+        }
+    }
+    
+    // Walk from oldest to newest so carried values flow forward in time
+    let mut carry = carry_in.map(|(_, count)| count).unwrap_or(0);
+    let mut entries: Vec<HistoryEntry> = (0..bucket_count)
+        .rev()
+        .map(|buckets_ago| {
+            let count = match bucket_counts.get(&buckets_ago) {
+                Some(counts) => {
+                    // History is ordered newest first
+                    carry = counts[0];
+                    counts.iter().sum::<usize>() / counts.len().max(1)
+                }
+                None if carry_forward => carry,
+                None => 0,
+            };
             
-            let timestamp = now - Duration::hours(hours_ago);
+            let timestamp = now - Duration::minutes(buckets_ago * bucket_minutes);
             HistoryEntry {
-                player_count: avg_count,
+                player_count: count,
                 recorded_at: timestamp.to_rfc3339(),
             }
         })
-        .collect()
+        .collect();
+    
+    // Newest first to match expected order
+    entries.reverse();
+    entries
 }
 
 /// Sanitize error messages to remove sensitive information like URLs with credentials
@@ -259,66 +1943,1629 @@ fn sanitize_error(error: &str) -> String {
         }
         return "A network error occurred. Please try again later.".to_string();
     }
-    // For other errors, just return a generic message to be safe
-    "An error occurred while fetching server data.".to_string()
+    // For other errors, just return a generic message to be safe
+    "An error occurred while fetching server data.".to_string()
+}
+
+/// What a checked downtime alert's row needs
+enum AlertChange {
+    /// New empty_since and alerted
+    Update(Option<String>, bool),
+    Claim,
+    Remove,
+}
+
+/// Check registered downtime alerts against the current server cache
+async fn check_downtime_alerts(state: &AppState) {
+    let alerts = match state.db.get_downtime_alerts().await {
+        Ok(alerts) => alerts,
+        Err(e) => {
+            eprintln!("Failed to load downtime alerts: {}", e);
+            state.metrics.record_failure(Subsystem::Alerts, &e);
+            return;
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let snapshot = state.cache.load();
+
+    for alert in alerts {
+        let Some(ref id) = alert.id else { continue };
+        let server = snapshot.find_server_id(&alert.server_id).map(|s| &s.server);
+
+        let (message, mut change) = match evaluate(&alert, server, now) {
+            AlertAction::None => continue,
+            AlertAction::Update { empty_since, alerted } => (None, AlertChange::Update(empty_since, alerted)),
+            AlertAction::Notify { message, empty_since, alerted } => {
+                (Some(message), AlertChange::Update(empty_since, alerted))
+            }
+            AlertAction::NotifyAndRemove { message } => (Some(message), AlertChange::Remove),
+            AlertAction::Claim { message } => (Some(message), AlertChange::Claim),
+            AlertAction::Remove => (None, AlertChange::Remove),
+        };
+
+        if let Some(message) = message {
+            match state.notifier.send_webhook(&alert.webhook_url, &message).await {
+                Ok(_) => state.metrics.record_success(Subsystem::Webhooks),
+                // The owner deleted the webhook, stop tracking it
+                Err(NotifyError::WebhookGone) => change = AlertChange::Remove,
+                Err(e) => {
+                    eprintln!("Failed to send downtime alert: {}", e);
+                    state.metrics.record_failure(Subsystem::Webhooks, &e);
+                    // Retry on the next cycle
+                    continue;
+                }
+            }
+        }
+
+        let result = match change {
+            AlertChange::Update(empty_since, alerted) => state.db.update_downtime_alert(id, empty_since, alerted).await,
+            AlertChange::Claim => state.db.claim_downtime_alert(id).await,
+            AlertChange::Remove => state.db.delete_downtime_alert(id).await,
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to update downtime alert: {}", e);
+        }
+    }
+    state.metrics.record_success(Subsystem::Alerts);
+}
+
+/// Warn operators whose verified servers are being imitated, through the webhooks they registered and
+/// claimed for downtime alerts; each impostor is reported once per run
+async fn notify_impostors(state: &AppState) {
+    let snapshot = state.cache.load();
+    let mut notified = state.impostors_notified.write().await;
+    let new: Vec<(&ServerView, &Imitated)> = snapshot
+        .servers
+        .iter()
+        .filter_map(|v| Some((v, v.impostor_of.as_ref()?)))
+        .filter(|(v, _)| !notified.contains(&v.server.game_id))
+        .collect();
+    if new.is_empty() {
+        state.metrics.record_success(Subsystem::Impostors);
+        return;
+    }
+
+    let alerts = match state.db.get_downtime_alerts().await {
+        Ok(alerts) => alerts,
+        Err(e) => {
+            eprintln!("Failed to load downtime alerts: {}", e);
+            state.metrics.record_failure(Subsystem::Impostors, &e);
+            return;
+        }
+    };
+    let site_url = state.public_url.clone().unwrap_or_default();
+
+    for (view, imitated) in new {
+        let message = format!(
+            "⚠ A new server named **{}** looks like it is imitating **{}**, from a different host: {}/server/{}",
+            strip_all_tags(&view.server.name),
+            imitated.name,
+            site_url,
+            view.server.game_id
+        );
+        for alert in alerts.iter().filter(|a| a.claimed && a.server_id == imitated.server_id) {
+            match state.notifier.send_webhook(&alert.webhook_url, &message).await {
+                Ok(_) => state.metrics.record_success(Subsystem::Webhooks),
+                Err(e) => {
+                    eprintln!("Failed to send impostor warning: {}", e);
+                    state.metrics.record_failure(Subsystem::Webhooks, &e);
+                }
+            }
+        }
+        notified.insert(view.server.game_id);
+    }
+    state.metrics.record_success(Subsystem::Impostors);
+}
+
+/// Notify push subscribers about favorites that came online and friends that joined since the last refresh
+/// Runs off the refresh loop so slow push services can't delay it
+async fn deliver_push(
+    db: Arc<DbClient>,
+    push: Arc<PushSender>,
+    metrics: Arc<Metrics>,
+    previous: Arc<CacheSnapshot>,
+    current: Arc<CacheSnapshot>,
+    site_url: String,
+) {
+    // Everything looks new on the first refresh after startup
+    if previous.servers.is_empty() {
+        return;
+    }
+    let online = push::came_online(&previous.servers, &current.servers);
+    let joined = push::players_joined(&previous.servers, &current.servers);
+    if online.is_empty() && joined.is_empty() {
+        return;
+    }
+
+    let subscriptions = match db.get_push_subscriptions().await {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => {
+            eprintln!("Failed to load push subscriptions: {}", e);
+            metrics.record_failure(Subsystem::Push, &e);
+            return;
+        }
+    };
+
+    for subscription in subscriptions {
+        for message in push::messages_for(&subscription, &online, &joined, &site_url) {
+            match push.send(&subscription, &message).await {
+                Ok(_) => metrics.record_success(Subsystem::Push),
+                // The browser unsubscribed or the subscription expired, stop tracking it
+                Err(PushError::SubscriptionGone) => {
+                    if let Some(ref id) = subscription.id
+                        && let Err(e) = db.delete_push_subscription(&id.id.to_raw()).await
+                    {
+                        eprintln!("Failed to delete push subscription: {}", e);
+                    }
+                    break;
+                }
+                Err(e) => {
+                    eprintln!("Failed to send push notification: {}", e);
+                    metrics.record_failure(Subsystem::Push, &e);
+                }
+            }
+        }
+    }
+}
+
+/// Build display views for the in-memory cache, attaching 24h peaks and recent activity from history,
+/// modpack fingerprints, host abuse reports and possible impostors
+async fn build_views(state: &AppState, servers: Vec<CachedServer>) -> Vec<ServerView> {
+    let servers = drop_hidden(state, servers).await;
+    let db = &state.db;
+    let peaks: HashMap<GameId, usize> = match db.get_peak_players(24).await {
+        Ok(peaks) => peaks.into_iter().map(|p| (p.game_id, p.peak)).collect(),
+        Err(e) => {
+            eprintln!("Failed to load peak players: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let averages: HashMap<GameId, f64> = match db.get_average_players(1).await {
+        Ok(averages) => averages.into_iter().map(|a| (a.game_id, a.average)).collect(),
+        Err(e) => {
+            eprintln!("Failed to load average players: {}", e);
+            HashMap::new()
+        }
+    };
+
+    // Without history nothing can be called inactive, so a failed query hides nothing
+    let (active_games, active_servers) = match db.get_active_servers(INACTIVE_DAYS * 24).await {
+        Ok(active) => {
+            let games: HashSet<GameId> = active.iter().map(|a| a.game_id).collect();
+            let servers: HashSet<ServerId> = active.into_iter().filter_map(|a| a.server_id).collect();
+            (Some(games), servers)
+        }
+        Err(e) => {
+            eprintln!("Failed to load active servers: {}", e);
+            (None, HashSet::new())
+        }
+    };
+
+    let modpacks: HashMap<GameId, String> = match db.get_modpacks().await {
+        Ok(modpacks) => modpacks.into_iter().map(|m| (m.game_id, m.fingerprint)).collect(),
+        Err(e) => {
+            eprintln!("Failed to load modpacks: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let presence_hours: HashMap<ServerId, usize> = match db.get_presence_hours(PRESENCE_RETENTION_HOURS).await {
+        Ok(presence) => presence.into_iter().map(|p| (p.server_id, p.hours)).collect(),
+        Err(e) => {
+            eprintln!("Failed to load presence hours: {}", e);
+            HashMap::new()
+        }
+    };
+
+    let host_ips: Vec<String> = servers
+        .iter()
+        .filter_map(|s| s.host_address.as_deref().and_then(host_ip))
+        .map(|ip| ip.to_string())
+        .collect();
+    let abuse_reports: HashMap<String, u32> = match db.get_ip_reputation(host_ips).await {
+        Ok(reputation) => reputation.into_iter().map(|r| (r.ip, r.reports)).collect(),
+        Err(e) => {
+            eprintln!("Failed to load IP reputation: {}", e);
+            HashMap::new()
+        }
+    };
+    let name_counts = ranking::name_counts(&servers);
+    let mut impostors = impostor::find_impostors(&servers, &state.verified_servers, chrono::Utc::now());
+
+    let mut views: Vec<ServerView> = servers
+        .into_iter()
+        .map(|server| {
+            let peak = peaks.get(&server.game_id).copied();
+            let average = averages.get(&server.game_id).copied();
+            let modpack = modpacks.get(&server.game_id).cloned();
+            let reports = server
+                .host_address
+                .as_deref()
+                .and_then(host_ip)
+                .and_then(|ip| abuse_reports.get(&ip.to_string()).copied());
+
+            // Uptime over the part of the week the server has been listed, as on the uptime badge
+            let uptime = server.server_id.as_ref().and_then(|id| presence_hours.get(id)).map(|&hours| {
+                let tracked_hours = server
+                    .first_seen
+                    .as_deref()
+                    .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                    .map(|t| (chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_hours() + 1)
+                    .unwrap_or(PRESENCE_RETENTION_HOURS);
+                (hours as f64 / tracked_hours.clamp(1, PRESENCE_RETENTION_HOURS) as f64 * 100.0).min(100.0)
+            });
+            let same_name = name_counts
+                .get(&strip_all_tags(&server.name).trim().to_lowercase())
+                .copied()
+                .unwrap_or(1);
+            let signals = RankingSignals {
+                peak_players: peak,
+                uptime,
+                verified: server.server_id.as_ref().is_some_and(|id| state.verified_servers.contains(id)),
+                spam: ranking::spam_penalty(&server, same_name),
+            };
+            let score = ranking::score(&state.ranking, &server, &signals);
+            let variant_scores = state
+                .experiment
+                .iter()
+                .flat_map(|e| &e.variants)
+                .map(|v| ranking::score(&v.weights, &server, &signals))
+                .collect();
+
+            // server_id follows a server across restarts, which change its game_id
+            let seen_recently = match active_games {
+                Some(ref games) => {
+                    games.contains(&server.game_id)
+                        || server.server_id.as_ref().is_some_and(|id| active_servers.contains(id))
+                }
+                None => true,
+            };
+            let game_id = server.game_id;
+            ServerView::from(server)
+                .with_peak(peak)
+                .with_trend(average)
+                .with_activity(seen_recently)
+                .with_modpack(modpack)
+                .with_uptime(uptime)
+                .with_abuse_reports(reports, state.abuse_reports_threshold)
+                .with_impostor_of(impostors.remove(&game_id))
+                .with_score(score, variant_scores)
+        })
+        .collect();
+
+    // Recommended first, so pages render in that order before sort.js runs (or without it)
+    views.sort_by_key(|v| std::cmp::Reverse(v.score));
+    views
+}
+
+/// Leave servers hidden by an admin out of a listing
+/// A hidden server relaunched under a new server_id (same name from the same host) is hidden too,
+/// unless an admin restored that server_id before
+async fn drop_hidden(state: &AppState, servers: Vec<CachedServer>) -> Vec<CachedServer> {
+    let mut hidden = match state.db.get_hidden_servers().await {
+        Ok(records) => {
+            let mut hidden: HashSet<ServerId> = HashSet::new();
+            let restored: HashSet<&ServerId> = records.iter().filter(|r| !r.hidden).map(|r| &r.server_id).collect();
+            let hidden_records: Vec<_> = records.iter().filter(|r| r.hidden).collect();
+            hidden.extend(hidden_records.iter().map(|r| r.server_id.clone()));
+
+            for server in &servers {
+                let Some(id) = server.server_id.as_ref() else {
+                    continue;
+                };
+                if hidden.contains(id) || restored.contains(id) {
+                    continue;
+                }
+                let Some(original) = hidden_records
+                    .iter()
+                    .find(|r| impostor::same_server(server, &r.name, r.host_address.as_deref()))
+                else {
+                    continue;
+                };
+                println!("Hiding server {} ({}): relaunch of hidden server {}", id, server.game_id, original.server_id);
+                if !state.read_only {
+                    let record = NewHiddenServer {
+                        server_id: id.clone(),
+                        game_id: server.game_id,
+                        name: server.name.clone(),
+                        host_address: server.host_address.clone(),
+                        reason: original.reason.clone(),
+                        hidden: true,
+                        hidden_at: chrono::Utc::now().to_rfc3339(),
+                        restored_at: None,
+                        reappeared_from: Some(original.server_id.clone()),
+                    };
+                    match state.db.hide_server(record).await {
+                        Ok(()) => log_moderation(state, "rehidden", &server.name, &original.reason).await,
+                        Err(e) => eprintln!("Failed to hide relaunched server: {}", e),
+                    }
+                }
+                hidden.insert(id.clone());
+            }
+            hidden
+        }
+        // Keep hiding what was hidden at the last refresh
+        Err(e) => {
+            eprintln!("Failed to load hidden servers: {}", e);
+            state.hidden_servers.read().await.clone()
+        }
+    };
+
+    let servers = servers
+        .into_iter()
+        .filter(|s| !s.server_id.as_ref().is_some_and(|id| hidden.contains(id)))
+        .collect();
+    std::mem::swap(&mut *state.hidden_servers.write().await, &mut hidden);
+    servers
+}
+
+/// Moderation actions listed on /transparency
+const TRANSPARENCY_ACTIONS: usize = 500;
+
+/// How long shutdown waits for a refresh cycle in progress to finish its DB writes
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(30);
+
+/// Longest note accepted with a report, in characters
+const MAX_REPORT_DETAILS: usize = 500;
+
+/// Reports accepted a minute across all visitors, so a flood can't fill the admin page
+const REPORTS_PER_MINUTE: u32 = 10;
+
+/// Reports one visitor (by hashed address) can file an hour, so they can't use up the shared budget
+const REPORTS_PER_REPORTER_HOUR: usize = 5;
+
+/// Descriptions sent to the translation backend a minute across all visitors, since any visitor can
+/// ask for one with ?translate=true
+const TRANSLATIONS_PER_MINUTE: u32 = 10;
+
+/// Claim codes posted to new alert webhooks a minute, so the form can't be used to spam webhooks
+const ALERT_CLAIMS_PER_MINUTE: u32 = 10;
+
+/// How often the primary database and its replicas are pinged (only with SURREAL_REPLICAS)
+const DB_HEALTH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Background task to periodically refresh server data
+/// Stops when the server shuts down: during the fetch or the wait between cycles straight away, and
+/// otherwise once the cycle's DB writes are done
+async fn refresh_servers(state: Arc<AppState>, shutdown: Shutdown) {
+    let mut schedule = state.refresh_schedule.clone();
+    loop {
+        let trace = state.refresh_traces.start();
+        println!("Refreshing server data (cycle {})...", trace.cycle());
+        let started = std::time::Instant::now();
+        let mut outcome = "ok".to_string();
+
+        // Nothing has been written yet, so a fetch in flight can just be dropped
+        let fetched = tokio::select! {
+            fetched = trace.stage("fetch", state.source.get_games()) => fetched,
+            _ = shutdown.clone() => {
+                state.refresh_traces.finish(trace, "cancelled by shutdown");
+                break;
+            }
+        };
+
+        let delay = match fetched {
+            Ok(servers) if servers.is_empty() && !state.cache.load().servers.is_empty() => {
+                // An empty listing is an upstream glitch, not every server going offline at once
+                state.metrics.record_refresh_error();
+                eprintln!("Fetched an empty server list, keeping the cached servers");
+                state.metrics.record_failure(Subsystem::Ingest, "empty server list");
+                *state.last_error.write().await = Some("The server list came back empty.".to_string());
+                outcome = "empty server list".to_string();
+                schedule.failed(None)
+            }
+            Ok(servers) => {
+                trace.note("fetch", format!("{} servers", servers.len()));
+
+                let now = chrono::Utc::now();
+
+                // Notice new upstream fields instead of silently dropping them
+                let stage_start = std::time::Instant::now();
+                let unknown_fields = state.schema_drift.write().await.observe(&servers, now);
+                for field in &unknown_fields {
+                    eprintln!("Warning: get-games returned unknown field '{}' (e.g. {})", field.name, field.example);
+                }
+                // Blocked spam never reaches the cache, history or pages
+                let (servers, blocked) = state.blocklist.read().await.filter(servers);
+                let count = servers.len();
+                let notes: Vec<String> = [
+                    (!unknown_fields.is_empty()).then(|| format!("{} new unknown fields", unknown_fields.len())),
+                    (blocked > 0).then(|| format!("{} blocked", blocked)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                trace.record("validate", stage_start, (!notes.is_empty()).then(|| notes.join(", ")));
+
+                // Hand history to the write-behind queue so a slow DB can't hold up the cache
+                let stage_start = std::time::Instant::now();
+                let recorded_at = now.to_rfc3339();
+                let sampled = state.history_sampler.write().await.select(&servers, now);
+                state.history_queue.push(
+                    sampled
+                        .iter()
+                        .map(|server| NewServerHistory::from_server(server, &recorded_at))
+                        .collect(),
+                );
+                trace.record("diff", stage_start, Some(format!("{} changed servers sampled", sampled.len())));
+
+                let hour = hour_bucket(now);
+                let unrecorded = state.presence.write().await.unrecorded(
+                    &hour,
+                    servers.iter().filter_map(|s| s.server_id.clone()),
+                );
+
+                // Presence and the cache are independent writes, so run them together.
+                // Each handles its own error: a failed presence insert must not cancel the cache update
+                // Record hourly presence for uptime tracking
+                let record_presence = async {
+                    let recorded = trace
+                        .stage("presence_write", state.metrics.time_query("record_presence", state.db.record_presence(unrecorded, &hour)))
+                        .await;
+                    if let Err(e) = recorded {
+                        eprintln!("Failed to record presence: {}", e);
+                        state.presence.write().await.reset();
+                    }
+                };
+
+                // Cache the servers in DB, then update the in-memory cache straight away
+                let update_cache = async {
+                    let cached = trace
+                        .stage(
+                            "db_write",
+                            state
+                                .metrics
+                                .time_query("cache_servers", state.db.cache_servers(servers.clone(), state.geoip.as_deref(), &state.tag_aliases)),
+                        )
+                        .await;
+                    match cached {
+                        Ok(_) => {
+                            println!("Cached {} servers (cycle {})", count, trace.cycle());
+                            *state.last_error.write().await = None;
+
+                            let all_servers = trace
+                                .stage("db_read", state.metrics.time_query("get_all_servers", state.db.get_all_servers()))
+                                .await;
+                            if let Ok(all_servers) = all_servers {
+                                // Peaks, activity, modpacks, host reports and impostor matching
+                                let views = trace.stage("identity_match", build_views(&state, all_servers)).await;
+                                state.recent_players.write().await.record(&views, now);
+                                let totals = NewListingTotals {
+                                    recorded_at: now.to_rfc3339(),
+                                    hour: hour_bucket(now),
+                                    servers: views.len(),
+                                    active_servers: views.iter().filter(|v| v.server.player_count > 0).count(),
+                                    players: views.iter().map(|v| v.server.player_count).sum(),
+                                };
+                                let version_totals: Vec<NewVersionTotals> = GlobalStats::compute(views.iter().map(|v| &v.server))
+                                    .versions
+                                    .into_iter()
+                                    .map(|v| NewVersionTotals {
+                                        hour: hour_bucket(now),
+                                        version: v.version,
+                                        servers: v.servers,
+                                        players: v.players,
+                                    })
+                                    .collect();
+                                let stage_start = std::time::Instant::now();
+                                let previous = state.cache.load();
+                                state.cache.publish(views);
+                                trace.record("cache_swap", stage_start, None);
+                                state
+                                    .listing_changes
+                                    .write()
+                                    .await
+                                    .record(&previous.servers, &state.cache.load().servers, now);
+                                if let Some(ref push) = state.push {
+                                    tokio::spawn(deliver_push(
+                                        state.db.clone(),
+                                        push.clone(),
+                                        state.metrics.clone(),
+                                        previous,
+                                        state.cache.load(),
+                                        state.public_url.clone().unwrap_or_default(),
+                                    ));
+                                }
+                                let stage_start = std::time::Instant::now();
+                                if let Err(e) = state.db.record_listing_totals(totals).await {
+                                    eprintln!("Failed to record listing totals: {}", e);
+                                }
+                                if let Err(e) = state.db.record_version_totals(version_totals).await {
+                                    eprintln!("Failed to record version totals: {}", e);
+                                }
+                                trace.record("totals_write", stage_start, None);
+                            }
+                            true
+                        }
+                        Err(e) => {
+                            let raw_msg = format!("Failed to cache servers: {}", e);
+                            eprintln!("{}", raw_msg);
+                            state.metrics.record_failure(Subsystem::Ingest, &raw_msg);
+                            trace.note("db_write", e.to_string());
+                            // Display sanitized message to users
+                            *state.last_error.write().await = Some("Failed to update server cache.".to_string());
+                            false
+                        }
+                    }
+                };
+
+                let ((), cached) = tokio::join!(record_presence, update_cache);
+
+                if cached {
+                    state.metrics.observe_refresh(started.elapsed());
+                    state.metrics.record_success(Subsystem::Ingest);
+                    if state.geoip.is_some() {
+                        record_geo_lookups(&state);
+                    }
+                } else {
+                    state.metrics.record_refresh_error();
+                    outcome = "cache write failed".to_string();
+                }
+
+                if cached && state.downtime_alerts {
+                    trace.stage("alerts", check_downtime_alerts(&state)).await;
+                    trace.stage("impostor_warnings", notify_impostors(&state)).await;
+                }
+
+                if cached {
+                    trace.stage("modpacks", fingerprint_modpacks(&state)).await;
+                    trace.stage("reputation", check_host_reputation(&state)).await;
+                }
+                schedule.succeeded()
+            }
+            Err(e) => {
+                state.metrics.record_refresh_error();
+                let raw_msg = format!("Failed to fetch servers: {}", e);
+                eprintln!("{}", raw_msg);
+                state.metrics.record_failure(Subsystem::Ingest, &raw_msg);
+                // Display sanitized message to users - never expose raw error with URLs/credentials
+                *state.last_error.write().await = Some(sanitize_error(&raw_msg));
+                outcome = redact_urls(&raw_msg);
+                let retry_after = match e {
+                    ApiError::RateLimited(retry_after) => retry_after,
+                    _ => None,
+                };
+                let delay = schedule.failed(retry_after);
+                eprintln!("Refresh failed {} time(s) in a row, retrying in {}s", schedule.failures(), delay.as_secs());
+                delay
+            }
+        };
+        state.refresh_traces.finish(trace, outcome);
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.clone() => break,
+        }
+    }
+    println!("Refresh loop stopped");
+}
+
+/// Count the hosts of the current listing GeoIP could and couldn't place
+fn record_geo_lookups(state: &AppState) {
+    let snapshot = state.cache.load();
+    let (resolved, unresolved) = snapshot
+        .servers
+        .iter()
+        .filter(|s| s.server.host_address.is_some())
+        .fold((0, 0), |(resolved, unresolved), s| match s.server.continent {
+            Some(_) => (resolved + 1, unresolved),
+            None => (resolved, unresolved + 1),
+        });
+    let error = (unresolved > 0).then(|| format!("{} hosts not in the GeoIP database", unresolved));
+    state.metrics.record_outcomes(Subsystem::Geo, resolved, unresolved, error);
+}
+
+/// Look up the mods of a few modded servers that have no fingerprint yet and record their modpacks
+/// get-games doesn't list mods, so this costs a details call per server; the rest follow on later refreshes
+async fn fingerprint_modpacks(state: &AppState) {
+    let pending: Vec<GameId> = state
+        .cache
+        .load()
+        .servers
+        .iter()
+        .filter(|s| s.server.mod_count > 0 && s.modpack.is_none())
+        .map(|s| s.server.game_id)
+        .take(MODPACK_LOOKUPS_PER_REFRESH)
+        .collect();
+
+    for game_id in pending {
+        match state.source.get_game_details(game_id).await {
+            Ok(details) => {
+                record_modpack(state, game_id, &details.mods).await;
+                state.metrics.record_success(Subsystem::Modpacks);
+            }
+            Err(e) => {
+                eprintln!("Failed to fetch mods for game {}: {}", game_id, e);
+                state.metrics.record_failure(Subsystem::Modpacks, &e);
+            }
+        }
+    }
+}
+
+/// Look up abuse reports for a few host IPs that haven't been checked within IP_REPUTATION_TTL_HOURS
+/// Results show up as badges from the next refresh; the rest of the hosts follow on later refreshes
+async fn check_host_reputation(state: &AppState) {
+    let Some(ref reputation) = state.reputation else {
+        return;
+    };
+    let mut ips: Vec<IpAddr> = state
+        .cache
+        .load()
+        .servers
+        .iter()
+        .filter_map(|s| s.server.host_address.as_deref().and_then(host_ip))
+        .collect();
+    ips.sort();
+    ips.dedup();
+
+    let checked: HashSet<String> = match state.db.get_ip_reputation(ips.iter().map(|ip| ip.to_string()).collect()).await {
+        Ok(checked) => checked.into_iter().map(|r| r.ip).collect(),
+        Err(e) => {
+            eprintln!("Failed to load IP reputation: {}", e);
+            state.metrics.record_failure(Subsystem::Reputation, &e);
+            return;
+        }
+    };
+
+    let mut records = Vec::new();
+    for ip in ips.into_iter().filter(|ip| !checked.contains(&ip.to_string())).take(REPUTATION_LOOKUPS_PER_REFRESH) {
+        match reputation.check(ip).await {
+            Ok(report) => {
+                records.push(NewIpReputation {
+                    ip: ip.to_string(),
+                    reports: report.reports,
+                    confidence: report.confidence,
+                    fetched_at: chrono::Utc::now().to_rfc3339(),
+                });
+                state.metrics.record_success(Subsystem::Reputation);
+            }
+            Err(e) => {
+                // Most likely the daily quota; try again next refresh
+                eprintln!("Failed to check IP reputation of {}: {}", ip, e);
+                state.metrics.record_failure(Subsystem::Reputation, &e);
+                break;
+            }
+        }
+    }
+    if let Err(e) = state.db.save_ip_reputation(records).await {
+        eprintln!("Failed to cache IP reputation: {}", e);
+    }
+}
+
+/// Store a game's modpack fingerprint, returning it (None for vanilla servers)
+async fn record_modpack(state: &AppState, game_id: GameId, mods: &[ModInfo]) -> Option<String> {
+    let fingerprint = modpack::fingerprint(mods)?;
+    let record = NewModpack {
+        game_id,
+        fingerprint: fingerprint.clone(),
+        mod_count: mods.len() as u32,
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = state.db.save_modpack(record).await {
+        eprintln!("Failed to save modpack: {}", e);
+    }
+    Some(fingerprint)
+}
+
+/// One page of this instance's server profiles and history, for other instances to import
+/// `since` is clamped to the history retention; `after` is the previous page's `next`
+#[get("/api/federation/export?<since>&<after>")]
+async fn federation_export(
+    state: &State<Arc<AppState>>,
+    _key: ApiKey,
+    since: Option<&str>,
+    after: Option<&str>,
+) -> Result<Json<FederationExport>, Status> {
+    let now = chrono::Utc::now();
+    let oldest = now - chrono::Duration::hours(HISTORY_RETENTION_HOURS);
+    let since = since
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|t| t.with_timezone(&chrono::Utc).max(oldest))
+        .unwrap_or(oldest)
+        .to_rfc3339();
+    if after.is_some_and(|a| chrono::DateTime::parse_from_rfc3339(a).is_err()) {
+        return Err(Status::BadRequest);
+    }
+
+    // Profiles only go out with the first page
+    let profiles = match after {
+        Some(_) => Vec::new(),
+        None => state.db.get_federated_profiles(&since).await.map_err(|e| {
+            eprintln!("Failed to load federated profiles: {}", e);
+            Status::InternalServerError
+        })?,
+    };
+    let (history, more) = state
+        .db
+        .get_federated_history(after.unwrap_or(&since), EXPORT_PAGE_SIZE)
+        .await
+        .map_err(|e| {
+            eprintln!("Failed to load federated history: {}", e);
+            Status::InternalServerError
+        })?;
+    let next = history.last().filter(|_| more).map(|h| h.recorded_at.clone());
+
+    // Hidden servers stay hidden on other instances too
+    let hidden = state.hidden_servers.read().await;
+    Ok(Json(FederationExport {
+        protocol: FEDERATION_PROTOCOL,
+        generated_at: now.to_rfc3339(),
+        profiles: profiles.into_iter().filter(|p| !hidden.contains(&p.server_id)).collect(),
+        history: history.into_iter().filter(|h| !hidden.contains(&h.server_id)).collect(),
+        next,
+    }))
+}
+
+/// Background task importing server profiles and history from FEDERATION_PEERS, hourly
+/// Each peer is read from where the last run stopped (the start of the retention window after a restart)
+async fn import_federation(state: Arc<AppState>, client: Arc<FederationClient>, peers: Vec<String>) {
+    let mut cursors: HashMap<String, String> = HashMap::new();
+    loop {
+        let oldest = (chrono::Utc::now() - chrono::Duration::hours(HISTORY_RETENTION_HOURS)).to_rfc3339();
+        for peer in &peers {
+            let since = cursors.get(peer).filter(|cursor| **cursor > oldest).unwrap_or(&oldest).clone();
+            match import_peer(&state.db, &client, peer, &since, &state.tag_aliases).await {
+                Ok(summary) => {
+                    println!(
+                        "Imported {} profiles and {} history records from {}",
+                        summary.profiles, summary.history, peer
+                    );
+                    cursors.insert(peer.clone(), summary.cursor);
+                    state.metrics.record_success(Subsystem::Federation);
+                }
+                Err(e) => {
+                    eprintln!("Failed to import from {}: {}", peer, e);
+                    state.metrics.record_failure(Subsystem::Federation, format!("{}: {}", peer, e));
+                }
+            }
+        }
+
+        tokio::time::sleep(FEDERATION_INTERVAL).await;
+    }
+}
+
+/// Background task that prunes old history, presence, and sighting records and expired sessions
+/// Runs separately from the refresh loop so deletes never hold up the visible cache
+async fn cleanup_old_data(state: Arc<AppState>) {
+    loop {
+        let mut failed = false;
+        if let Err(e) = state.db.cleanup_old_history().await {
+            eprintln!("Failed to cleanup history: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("history: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_old_presence().await {
+            eprintln!("Failed to cleanup presence: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("presence: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_old_listing_totals().await {
+            eprintln!("Failed to cleanup listing totals: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("listing totals: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_old_version_totals().await {
+            eprintln!("Failed to cleanup version totals: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("version totals: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_old_sightings().await {
+            eprintln!("Failed to cleanup sightings: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("sightings: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_expired_sessions().await {
+            eprintln!("Failed to cleanup sessions: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("sessions: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_old_mod_metadata().await {
+            eprintln!("Failed to cleanup mod metadata: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("mod metadata: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_old_ip_reputation().await {
+            eprintln!("Failed to cleanup IP reputation: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("IP reputation: {}", e));
+            failed = true;
+        }
+        if let Err(e) = state.db.cleanup_unlisted_modpacks().await {
+            eprintln!("Failed to cleanup modpacks: {}", e);
+            state.metrics.record_failure(Subsystem::Cleanup, format!("modpacks: {}", e));
+            failed = true;
+        }
+        if !failed {
+            state.metrics.record_success(Subsystem::Cleanup);
+        }
+
+        // Retention is measured in days, hourly is plenty
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+    }
+}
+
+/// Background task pinging the primary database and its replicas, so reads fail over within seconds
+async fn check_database_health(db: Arc<DbClient>) {
+    loop {
+        db.check_health().await;
+        tokio::time::sleep(DB_HEALTH_INTERVAL).await;
+    }
+}
+
+/// Background task for read-only mode: periodically reload the in-memory cache from the DB
+async fn reload_cache_from_db(state: Arc<AppState>) {
+    let mut schedule = state.refresh_schedule.clone();
+    loop {
+        match state.db.get_all_servers().await {
+            Ok(servers) => {
+                println!("Loaded {} servers from database (read-only)", servers.len());
+                let views = build_views(&state, servers).await;
+                let now = chrono::Utc::now();
+                state.recent_players.write().await.record(&views, now);
+                let previous = state.cache.load();
+                state.cache.publish(views);
+                state
+                    .listing_changes
+                    .write()
+                    .await
+                    .record(&previous.servers, &state.cache.load().servers, now);
+                *state.last_error.write().await = None;
+            }
+            Err(e) => {
+                eprintln!("Failed to load servers: {}", e);
+                *state.last_error.write().await = Some("Failed to load server cache.".to_string());
+            }
+        }
+
+        tokio::time::sleep(schedule.succeeded()).await;
+    }
+}
+
+/// Background task to periodically recompute materialized aggregates for stats routes
+async fn refresh_materialized_stats(state: Arc<AppState>) {
+    loop {
+        let snapshot = state.cache.load();
+
+        // Wait for the first server refresh before computing anything
+        if snapshot.servers.is_empty() {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            continue;
+        }
+
+        let stats = GlobalStats::compute(snapshot.servers.iter().map(|s| &s.server));
+        match state.db.store_materialized(GLOBAL_STATS_KEY, &stats).await {
+            Ok(()) => state.metrics.record_success(Subsystem::Stats),
+            Err(e) => {
+                eprintln!("Failed to store global stats: {}", e);
+                state.metrics.record_failure(Subsystem::Stats, &e);
+            }
+        }
+
+        // Aggregates don't need to be fresher than every 5 minutes
+        tokio::time::sleep(Duration::from_secs(300)).await;
+    }
+}
+
+/// Background task that publishes a digest at the start of every week (Monday, UTC), storing it for
+/// /digest/latest and posting it to DIGEST_WEBHOOK_URL
+async fn publish_weekly_digest(state: Arc<AppState>) {
+    loop {
+        let now = chrono::Utc::now();
+        let published = match state.db.get_materialized::<WeeklyDigest>(WEEKLY_DIGEST_KEY).await {
+            Ok(stored) => stored.is_some_and(|d| d.data.week_of == digest::week_of(now).to_string()),
+            Err(e) => {
+                eprintln!("Failed to load weekly digest: {}", e);
+                state.metrics.record_failure(Subsystem::Digest, &e);
+                true
+            }
+        };
+
+        // Wait for the first server refresh so the digest isn't empty
+        if !published && state.cache.load().servers.is_empty() {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            continue;
+        }
+        if !published {
+            let digest = compute_digest(&state).await;
+            match state.db.store_materialized(WEEKLY_DIGEST_KEY, &digest).await {
+                Ok(()) => {
+                    println!("Published the weekly digest for the week of {}", digest.week_of);
+                    state.metrics.record_success(Subsystem::Digest);
+                    if let Some(ref webhook) = state.digest_webhook {
+                        let message = digest.to_discord(state.public_url.as_deref().unwrap_or_default());
+                        match state.notifier.send_webhook(webhook, &message).await {
+                            Ok(_) => state.metrics.record_success(Subsystem::Webhooks),
+                            Err(e) => {
+                                eprintln!("Failed to post weekly digest: {}", e);
+                                state.metrics.record_failure(Subsystem::Webhooks, &e);
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Failed to store weekly digest: {}", e);
+                    state.metrics.record_failure(Subsystem::Digest, &e);
+                }
+            }
+        }
+
+        // A new week is noticed within the hour
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+    }
+}
+
+/// Operator page: subsystem health, refresh cycle timings (?cycle=N for one cycle's stages) and
+/// unknown get-games fields
+#[get("/admin?<cycle>&<status>")]
+async fn admin(state: &State<Arc<AppState>>, _admin: Admin, cycle: Option<u64>, status: Option<String>) -> RawHtml<String> {
+    let mut props = admin_props(state, cycle).await;
+    props.message = status.as_deref().and_then(admin_status_message).map(str::to_string);
+    render_admin(state, props).await
+}
+
+/// Open reports listed on the admin page, newest first
+const ADMIN_REPORTS_SHOWN: usize = 100;
+
+/// Admin page contents as of now, without a message
+async fn admin_props(state: &AppState, cycle: Option<u64>) -> AdminPageProps {
+    let (unknown_fields, responses_checked) = {
+        let drift = state.schema_drift.read().await;
+        (drift.fields(), drift.responses())
+    };
+    let hidden = state.db.get_hidden_servers().await.unwrap_or_else(|e| {
+        eprintln!("Failed to load hidden servers: {}", e);
+        Vec::new()
+    });
+    let api_keys = state.db.get_api_keys().await.unwrap_or_else(|e| {
+        eprintln!("Failed to load API keys: {}", e);
+        Vec::new()
+    });
+    let blocklist = state.db.get_blocklist().await.unwrap_or_else(|e| {
+        eprintln!("Failed to load blocklist: {}", e);
+        Vec::new()
+    });
+    let reports = state.db.get_open_reports(ADMIN_REPORTS_SHOWN).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load reports: {}", e);
+        Vec::new()
+    });
+    AdminPageProps {
+        unknown_fields,
+        responses_checked,
+        subsystems: state.metrics.subsystem_health(),
+        cycles: state.refresh_traces.recent(),
+        selected_cycle: cycle.and_then(|c| state.refresh_traces.get(c)),
+        hidden,
+        reports,
+        blocklist,
+        api_keys,
+        api_key_usage: state.api_keys.usage(),
+        api_keys_required: state.api_keys.required(),
+        writable: !state.read_only,
+        ..Default::default()
+    }
+}
+
+async fn render_admin(state: &AppState, props: AdminPageProps) -> RawHtml<String> {
+    let renderer = ServerRenderer::<AdminPage>::with_props(move || props.clone());
+    RawHtml(html_shell_with_video(state, &format!("Admin - {}", state.site_name), renderer.render().await, false))
+}
+
+/// Message for a `?status=` code on the admin page (unknown codes show nothing)
+fn admin_status_message(status: &str) -> Option<&'static str> {
+    match status {
+        "hidden" => Some("Server hidden"),
+        "restored" => Some("Server restored, it returns with the next refresh"),
+        "not_found" => Some("No listed server has that game ID"),
+        "not_hidden" => Some("That server isn't hidden"),
+        "no_server_id" => Some("That server has no server ID, so it can't be hidden"),
+        "no_reason" => Some("Give a reason for hiding the server"),
+        "read_only" => Some("Nothing can be changed in read-only mode"),
+        "report_dismissed" => Some("Report dismissed"),
+        "report_not_found" => Some("No open report has that ID"),
+        "unblocked" => Some("Blocklist entry removed, matching servers return with the next refresh"),
+        "block_not_found" => Some("No blocklist entry has that ID"),
+        "key_revoked" => Some("API key revoked"),
+        "key_not_found" => Some("No active API key has that ID"),
+        "error" => Some("Something went wrong, please try again"),
+        _ => None,
+    }
+}
+
+/// Hide a listed server: it stays in the DB but drops off the listing, search and archive straight away
+#[post("/admin/hidden", data = "<form>")]
+async fn hide_server(state: &State<Arc<AppState>>, _admin: Admin, _origin: SameOrigin, form: Form<HideForm>) -> Redirect {
+    let reason = form.reason.trim();
+    let snapshot = state.cache.load();
+    let listed = form.game_id.trim().parse::<GameId>().ok().and_then(|id| snapshot.find(id));
+    let status = match listed {
+        _ if state.read_only => "read_only",
+        _ if reason.is_empty() => "no_reason",
+        None => "not_found",
+        Some(view) => match view.server.server_id.clone() {
+            None => "no_server_id",
+            Some(server_id) => {
+                let record = NewHiddenServer {
+                    server_id: server_id.clone(),
+                    game_id: view.server.game_id,
+                    name: view.server.name.clone(),
+                    host_address: view.server.host_address.clone(),
+                    reason: reason.to_string(),
+                    hidden: true,
+                    hidden_at: chrono::Utc::now().to_rfc3339(),
+                    restored_at: None,
+                    reappeared_from: None,
+                };
+                match state.db.hide_server(record).await {
+                    Ok(()) => {
+                        log_moderation(state, "hidden", &view.server.name, reason).await;
+                        state.hidden_servers.write().await.insert(server_id.clone());
+                        let servers = snapshot
+                            .servers
+                            .iter()
+                            .filter(|s| s.server.server_id.as_ref() != Some(&server_id))
+                            .cloned()
+                            .collect();
+                        state.cache.publish(servers);
+                        "hidden"
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to hide server: {}", e);
+                        "error"
+                    }
+                }
+            }
+        },
+    };
+
+    Redirect::to(uri!(admin(_, Some(status))))
+}
+
+/// Put a hidden server back; it is listed again from the next refresh
+#[post("/admin/hidden/<server_id>/restore")]
+async fn restore_server(state: &State<Arc<AppState>>, _admin: Admin, _origin: SameOrigin, server_id: ServerId) -> Redirect {
+    let status = if state.read_only {
+        "read_only"
+    } else {
+        match state.db.restore_hidden_server(&server_id).await {
+            Ok(Some(restored)) => {
+                log_moderation(state, "restored", &restored.name, &restored.reason).await;
+                state.hidden_servers.write().await.remove(&server_id);
+                "restored"
+            }
+            Ok(None) => "not_hidden",
+            Err(e) => {
+                eprintln!("Failed to restore server: {}", e);
+                "error"
+            }
+        }
+    };
+
+    Redirect::to(uri!(admin(_, Some(status))))
+}
+
+/// Block spam listings by server_id, host or name pattern; matches leave the listing straight away
+/// The page is rendered directly so a pattern that doesn't parse can be explained
+#[post("/admin/blocklist", data = "<form>")]
+async fn add_blocklist_entry(state: &State<Arc<AppState>>, _admin: Admin, _origin: SameOrigin, form: Form<BlockForm>) -> RawHtml<String> {
+    let reason = form.reason.trim();
+    let kind = match form.kind.as_str() {
+        "server_id" => Some(BlockKind::ServerId),
+        "host" => Some(BlockKind::Host),
+        "name" => Some(BlockKind::Name),
+        _ => None,
+    };
+    let message = match kind {
+        _ if state.read_only => "Nothing can be changed in read-only mode".to_string(),
+        _ if reason.is_empty() => "Give a reason for blocking".to_string(),
+        None => "Pick what to block by".to_string(),
+        Some(kind) => match normalize_pattern(kind, &form.pattern) {
+            Err(e) => format!("Not blocked: {}", e),
+            Ok(pattern) => {
+                let entry = NewBlocklistEntry {
+                    kind,
+                    pattern: pattern.clone(),
+                    reason: reason.to_string(),
+                    added_at: chrono::Utc::now().to_rfc3339(),
+                };
+                match state.db.add_blocklist_entry(entry).await {
+                    Ok(()) => {
+                        reload_blocklist(state).await;
+                        format!("Blocked {} {}", kind.label().to_lowercase(), pattern)
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to add blocklist entry: {}", e);
+                        "Something went wrong, please try again".to_string()
+                    }
+                }
+            }
+        },
+    };
+
+    let mut props = admin_props(state, None).await;
+    props.message = Some(message);
+    render_admin(state, props).await
+}
+
+/// Close a report, whether or not anything was done about the server
+#[post("/admin/reports/<id>/dismiss")]
+async fn dismiss_report(state: &State<Arc<AppState>>, _admin: Admin, _origin: SameOrigin, id: &str) -> Redirect {
+    let status = if state.read_only {
+        "read_only"
+    } else {
+        match state.db.dismiss_report(id).await {
+            Ok(true) => "report_dismissed",
+            Ok(false) => "report_not_found",
+            Err(e) => {
+                eprintln!("Failed to dismiss report: {}", e);
+                "error"
+            }
+        }
+    };
+
+    Redirect::to(uri!(admin(_, Some(status))))
+}
+
+/// Remove a blocklist entry; servers it matched are listed again from the next refresh
+#[post("/admin/blocklist/<id>/remove")]
+async fn remove_blocklist_entry(state: &State<Arc<AppState>>, _admin: Admin, _origin: SameOrigin, id: &str) -> Redirect {
+    let status = if state.read_only {
+        "read_only"
+    } else {
+        match state.db.remove_blocklist_entry(id).await {
+            Ok(Some(_)) => {
+                reload_blocklist(state).await;
+                "unblocked"
+            }
+            Ok(None) => "block_not_found",
+            Err(e) => {
+                eprintln!("Failed to remove blocklist entry: {}", e);
+                "error"
+            }
+        }
+    };
+
+    Redirect::to(uri!(admin(_, Some(status))))
+}
+
+/// Pick up blocklist changes from the admin page, dropping newly blocked servers from the listing
+async fn reload_blocklist(state: &AppState) {
+    let entries = match state.db.get_blocklist().await {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("Failed to reload blocklist: {}", e);
+            return;
+        }
+    };
+    let blocklist = Blocklist::new(&entries);
+    let snapshot = state.cache.load();
+    let servers: Vec<ServerView> = snapshot
+        .servers
+        .iter()
+        .filter(|view| !blocklist.matches(view.server.server_id.as_ref(), view.server.host_address.as_deref(), &view.server.name))
+        .cloned()
+        .collect();
+    if servers.len() < snapshot.servers.len() {
+        state.cache.publish(servers);
+    }
+    *state.blocklist.write().await = blocklist;
+}
+
+/// Issue an API key; the page is rendered right away, the only time the key itself is shown
+#[post("/admin/api-keys", data = "<form>")]
+async fn create_api_key(state: &State<Arc<AppState>>, _admin: Admin, _origin: SameOrigin, form: Form<ApiKeyForm>) -> RawHtml<String> {
+    let name = form.name.trim();
+    let per_minute = match form.per_minute.trim() {
+        "" => Some(DEFAULT_KEY_PER_MINUTE),
+        limit => limit.parse::<u32>().ok().filter(|l| (1..=MAX_KEY_PER_MINUTE).contains(l)),
+    };
+    let mut new_key = None;
+    let message = match per_minute {
+        _ if state.read_only => "Nothing can be changed in read-only mode".to_string(),
+        _ if name.is_empty() => "Give the API key a name".to_string(),
+        None => format!("The limit must be between 1 and {} requests a minute", MAX_KEY_PER_MINUTE),
+        Some(per_minute) => match new_api_key() {
+            None => "Something went wrong, please try again".to_string(),
+            Some(key) => {
+                let record = NewApiKeyRecord {
+                    name: name.to_string(),
+                    key_hash: api_key_hash(&key),
+                    per_minute,
+                    created_at: chrono::Utc::now().to_rfc3339(),
+                    revoked: false,
+                };
+                match state.db.create_api_key(record).await {
+                    Ok(()) => {
+                        reload_api_keys(state).await;
+                        new_key = Some(key);
+                        format!("API key for {} created. Copy it now, it won't be shown again.", name)
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to create API key: {}", e);
+                        "Something went wrong, please try again".to_string()
+                    }
+                }
+            }
+        },
+    };
+
+    let mut props = admin_props(state, None).await;
+    props.message = Some(message);
+    props.new_api_key = new_key;
+    render_admin(state, props).await
+}
+
+/// Revoke an API key; requests with it are refused from now on
+#[post("/admin/api-keys/<id>/revoke")]
+async fn revoke_api_key(state: &State<Arc<AppState>>, _admin: Admin, _origin: SameOrigin, id: &str) -> Redirect {
+    let status = if state.read_only {
+        "read_only"
+    } else {
+        match state.db.revoke_api_key(id).await {
+            Ok(true) => {
+                reload_api_keys(state).await;
+                "key_revoked"
+            }
+            Ok(false) => "key_not_found",
+            Err(e) => {
+                eprintln!("Failed to revoke API key: {}", e);
+                "error"
+            }
+        }
+    };
+
+    Redirect::to(uri!(admin(_, Some(status))))
+}
+
+/// Pick up API keys issued or revoked on the admin page
+async fn reload_api_keys(state: &AppState) {
+    match state.db.get_api_keys().await {
+        Ok(records) => state.api_keys.load(&records),
+        Err(e) => eprintln!("Failed to reload API keys: {}", e),
+    }
+}
+
+/// Append to the public moderation log; a failed write is only logged, the action itself stands
+async fn log_moderation(state: &AppState, action: &str, name: &str, reason: &str) {
+    let entry = NewModerationAction {
+        action: action.to_string(),
+        name: strip_all_tags(name),
+        reason: reason.to_string(),
+        at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = state.db.log_moderation_action(entry).await {
+        eprintln!("Failed to log moderation action: {}", e);
+    }
+}
+
+/// Public log of hidden and restored servers, with reasons and dates
+#[get("/transparency")]
+async fn transparency(state: &State<Arc<AppState>>) -> RawHtml<String> {
+    let actions = state.db.get_moderation_actions(TRANSPARENCY_ACTIONS).await.unwrap_or_else(|e| {
+        eprintln!("Failed to load moderation log: {}", e);
+        Vec::new()
+    });
+    let props = TransparencyPageProps {
+        actions,
+        hidden_now: state.hidden_servers.read().await.len(),
+    };
+    let renderer = ServerRenderer::<TransparencyPage>::with_props(move || props.clone());
+    RawHtml(html_shell_with_video(
+        state,
+        &format!("Moderation Log - {}", state.site_name),
+        renderer.render().await,
+        false,
+    ))
+}
+
+/// Servers that recently joined or left the listing
+#[get("/changes")]
+async fn changes_page(state: &State<Arc<AppState>>) -> RawHtml<String> {
+    let props = ChangesPageProps {
+        changes: state.listing_changes.read().await.latest(DEFAULT_CHANGES_LIMIT),
+    };
+    let renderer = ServerRenderer::<ChangesPage>::with_props(move || props.clone());
+    RawHtml(html_shell_with_video(
+        state,
+        &format!("Listing Changes - {}", state.site_name),
+        renderer.render().await,
+        false,
+    ))
+}
+
+/// Re-ingest recorded get-games responses, oldest first, as if each had just been fetched
+/// Meant for a fresh DB (point SURREAL_URL somewhere new) to reproduce ingestion bugs
+async fn replay(db: &DbClient, dir: &std::path::Path, tag_aliases: &TagAliases) {
+    let recordings = match list_recordings(dir) {
+        Ok(recordings) => recordings,
+        Err(e) => {
+            eprintln!("Failed to read recordings in {}: {}", dir.display(), e);
+            return;
+        }
+    };
+    if db.get_all_servers().await.map(|s| !s.is_empty()).unwrap_or(false) {
+        eprintln!("Warning: replaying into a database that already has servers");
+    }
+    println!("Replaying {} recorded responses from {}", recordings.len(), dir.display());
+
+    for (at, path) in recordings {
+        let servers: Vec<GameServer> = match read_recording(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|body| serde_json::from_str(&body).map_err(|e| e.to_string()))
+        {
+            Ok(servers) => servers,
+            Err(e) => {
+                eprintln!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let recorded_at = at.to_rfc3339();
+        let history = servers.iter().map(|s| NewServerHistory::from_server(s, &recorded_at)).collect();
+        let server_ids = servers.iter().filter_map(|s| s.server_id.clone()).collect();
+        let count = servers.len();
+
+        if let Err(e) = db.cache_servers_at(servers, at, None, tag_aliases).await {
+            eprintln!("Failed to cache servers from {}: {}", path.display(), e);
+            continue;
+        }
+        if let Err(e) = db.insert_history(history).await {
+            eprintln!("Failed to insert history from {}: {}", path.display(), e);
+        }
+        if let Err(e) = db.record_presence(server_ids, &hour_bucket(at)).await {
+            eprintln!("Failed to record presence from {}: {}", path.display(), e);
+        }
+        println!("Replayed {} ({} servers)", path.display(), count);
+    }
+}
+
+/// Outcome lines of `check`, counting the ones that should stop a deploy
+#[derive(Default)]
+struct CheckReport {
+    failures: usize,
+}
+
+impl CheckReport {
+    fn pass(&self, what: &str, detail: impl std::fmt::Display) {
+        println!("ok    {}: {}", what, detail);
+    }
+
+    fn warn(&self, what: &str, detail: impl std::fmt::Display) {
+        println!("warn  {}: {}", what, detail);
+    }
+
+    fn fail(&mut self, what: &str, detail: impl std::fmt::Display) {
+        println!("FAIL  {}: {}", what, detail);
+        self.failures += 1;
+    }
 }
 
-/// Background task to periodically refresh server data
-async fn refresh_servers(state: Arc<AppState>) {
-    loop {
-        println!("Refreshing server data...");
+/// Validate the configuration, the database and the Factorio credentials without starting the site,
+/// so deploy scripts find out about a bad token before the first refresh does; returns whether everything passed
+/// Never writes: the database is opened the way read-only mode opens it
+async fn self_check() -> bool {
+    let mut report = CheckReport::default();
 
-        match state.factorio_client.get_games().await {
-            Ok(servers) => {
-                let count = servers.len();
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            report.fail("config", format!("{} (fix the file or the environment variable it names)", e));
+            println!("1 problem found");
+            return false;
+        }
+    };
+    let config_file = std::env::var("CONFIG_FILE").ok().filter(|path| !path.is_empty());
+    match config_file {
+        Some(path) => report.pass("config", format!("loaded {}", path)),
+        None if std::path::Path::new(DEFAULT_CONFIG_FILE).exists() => {
+            report.pass("config", format!("loaded {}", DEFAULT_CONFIG_FILE))
+        }
+        None => report.pass("config", "no config file, using defaults and the environment"),
+    }
+    let known_features: Vec<&str> = feature_routes().iter().map(|(name, _)| *name).collect();
+    for feature in &config.disabled_features {
+        if !known_features.contains(&feature.to_lowercase().as_str()) {
+            report.warn("config", format!("unknown feature '{}' in disabled_features, it has no effect", feature));
+        }
+    }
+    if let Err(e) = TagAliases::parse(&std::env::var("TAG_ALIASES").unwrap_or_default()) {
+        report.warn("config", format!("invalid TAG_ALIASES ({}), tags will only be folded", e));
+    }
+    if std::env::var("COOKIE_SECRET").map_or(true, |secret| secret.is_empty()) {
+        report.warn("config", "COOKIE_SECRET is not set, favorites and sign-ins are lost on every restart");
+    }
+    let read_only = std::env::var("READ_ONLY").map(|v| v == "true" || v == "1").unwrap_or(false);
 
-                // Record history before caching
-                if let Err(e) = state.db.record_player_counts(&servers).await {
-                    eprintln!("Failed to record history: {}", e);
+    // Database: reachable, and set up by this release
+    let db = &config.database;
+    match DbClient::connect_read_only(&db.url, &db.namespace, &db.name, db.username.as_deref(), db.password.as_deref())
+        .await
+    {
+        Ok(client) => {
+            report.pass("database", format!("connected to {}", db.url));
+            match client.get_schema_version().await {
+                Ok(Some(version)) if version == SCHEMA_VERSION => {
+                    report.pass("schema", format!("version {}", version))
                 }
-
-                // Cache the servers in DB
-                match state.db.cache_servers(servers).await {
-                    Ok(_) => {
-                        println!("Cached {} servers", count);
-                        *state.last_error.write().await = None;
-                        
-                        // Update in-memory cache from DB
-                        if let Ok(all_servers) = state.db.get_all_servers().await {
-                            *state.cached_servers.write().await = all_servers;
-                        }
-                    }
-                    Err(e) => {
-                        let raw_msg = format!("Failed to cache servers: {}", e);
-                        eprintln!("{}", raw_msg);
-                        // Display sanitized message to users
-                        *state.last_error.write().await = Some("Failed to update server cache.".to_string());
+                Ok(Some(version)) if version > SCHEMA_VERSION => report.fail(
+                    "schema",
+                    format!(
+                        "version {} was set up by a newer release (this one knows version {}); deploy that release or point SURREAL_URL at another database",
+                        version, SCHEMA_VERSION
+                    ),
+                ),
+                Ok(found) => {
+                    let found = found.map_or("no version".to_string(), |version| format!("version {}", version));
+                    if read_only {
+                        report.fail(
+                            "schema",
+                            format!(
+                                "{} recorded and READ_ONLY never sets up the schema; start the browser once without READ_ONLY to upgrade it to version {}",
+                                found, SCHEMA_VERSION
+                            ),
+                        )
+                    } else {
+                        report.pass("schema", format!("{} recorded, set up as version {} on start", found, SCHEMA_VERSION))
                     }
                 }
-
-                // Clean up old history
-                if let Err(e) = state.db.cleanup_old_history().await {
-                    eprintln!("Failed to cleanup history: {}", e);
-                }
-            }
-            Err(e) => {
-                let raw_msg = format!("Failed to fetch servers: {}", e);
-                eprintln!("{}", raw_msg);
-                // Display sanitized message to users - never expose raw error with URLs/credentials
-                *state.last_error.write().await = Some(sanitize_error(&raw_msg));
+                Err(e) => report.fail("schema", format!("couldn't read the schema version: {}", e)),
             }
         }
+        Err(e) => report.fail(
+            "database",
+            format!(
+                "can't connect to {}: {} (check SURREAL_URL, SURREAL_USER and SURREAL_PASS; an embedded rocksdb database can't be opened while the browser is running)",
+                db.url, e
+            ),
+        ),
+    }
+    for url in &db.replicas {
+        let reachable = match DbClient::connect_read_only(url, &db.namespace, &db.name, db.username.as_deref(), db.password.as_deref()).await {
+            Ok(replica) => replica.ping().await,
+            Err(e) => Err(e),
+        };
+        match reachable {
+            Ok(()) => report.pass("replica", format!("connected to {}", url)),
+            Err(e) => report.warn("replica", format!("can't connect to {} ({}), the browser will start without it", url, e)),
+        }
+    }
+
+    // Matchmaking API: one credentialed get-games call, the same one every refresh makes
+    let username = std::env::var("FACTORIO_USERNAME").unwrap_or_default();
+    let token = std::env::var("FACTORIO_TOKEN").unwrap_or_default();
+    if read_only {
+        report.pass("factorio api", "skipped, READ_ONLY never calls the API");
+    } else if username.is_empty() || token.is_empty() {
+        report.fail(
+            "factorio api",
+            "FACTORIO_USERNAME and FACTORIO_TOKEN must both be set (the token is on https://factorio.com/profile); without them only the demo snapshot is shown",
+        );
+    } else {
+        let mut builder = FactorioClient::builder(username.clone(), token);
+        let api_url = std::env::var("FACTORIO_API_URL").ok();
+        if let Some(ref url) = api_url {
+            builder = builder.base_url(url.clone());
+        }
+        let api_url = api_url.unwrap_or_else(|| "the matchmaking API".to_string());
+        match builder.build() {
+            Err(e) => report.fail("factorio api", e),
+            Ok(client) => match client.get_games().await {
+                Ok(servers) => report.pass("factorio api", format!("signed in as {}, {} servers listed", username, servers.len())),
+                Err(ApiError::AuthenticationFailed) => report.fail(
+                    "factorio api",
+                    format!(
+                        "{} rejected the credentials for {}; copy a fresh token from https://factorio.com/profile into FACTORIO_TOKEN (tokens change when you log out everywhere)",
+                        api_url, username
+                    ),
+                ),
+                Err(ApiError::RateLimited(_)) => report.warn(
+                    "factorio api",
+                    "rate limited, so the credentials couldn't be checked; try again in a minute",
+                ),
+                Err(ApiError::RequestFailed(e)) => report.fail(
+                    "factorio api",
+                    format!("couldn't reach {} ({}); check the network, proxy and FACTORIO_API_URL", api_url, e.without_url()),
+                ),
+                Err(e) => report.fail("factorio api", format!("{} answered get-games with an error: {}", api_url, e)),
+            },
+        }
+    }
 
-        // Wait before next refresh (60 seconds)
-        tokio::time::sleep(Duration::from_secs(60)).await;
+    match report.failures {
+        0 => println!("All checks passed"),
+        1 => println!("1 problem found"),
+        n => println!("{} problems found", n),
     }
+    report.failures == 0
+}
+
+/// Operational metrics in the Prometheus text format
+#[get("/metrics")]
+async fn metrics(state: &State<Arc<AppState>>) -> (ContentType, String) {
+    let cached = state.cache.load();
+    let snapshot = Snapshot {
+        servers: cached.servers.len(),
+        players: cached.servers.iter().map(|s| s.server.player_count).sum(),
+        history_queue_depth: state.history_queue.depth(),
+        history_dropped: state.history_queue.dropped(),
+        unknown_fields: state.schema_drift.read().await.fields().len(),
+        render_cache: state.render_cache.stats(),
+    };
+
+    (
+        ContentType::new("text", "plain").with_params(("version", "0.0.4")),
+        state.metrics.render(&snapshot),
+    )
+}
+
+/// Optional route groups, each of which can be switched off with DISABLED_FEATURES
+/// The browser itself (index, server pages, static files) is always mounted
+fn feature_routes() -> Vec<(&'static str, Vec<Route>)> {
+    vec![
+        ("alerts", routes![add_downtime_alert]),
+        ("badges", routes![uptime_badge]),
+        ("dashboard", routes![dashboard]),
+        ("stats", routes![stats_page]),
+        ("digest", routes![digest_page, digest_markdown]),
+        ("push", routes![push_subscribe, push_unsubscribe, service_worker]),
+        ("players", routes![players_search, player_page]),
+        ("changes", routes![changes_page]),
+        ("reports", routes![report_server]),
+        ("federation", routes![federation_export]),
+        ("api", routes![get_servers, get_stats, get_version_history, get_player, get_changes, get_cached_details]),
+        ("graphql", routes![graphql]),
+        ("metrics", routes![metrics]),
+        (
+            "admin",
+            routes![
+                admin,
+                hide_server,
+                restore_server,
+                add_blocklist_entry,
+                remove_blocklist_entry,
+                dismiss_report,
+                create_api_key,
+                revoke_api_key
+            ],
+        ),
+        ("transparency", routes![transparency]),
+        (
+            "accounts",
+            routes![account, register_account, sign_in, sign_out, save_preset, load_preset, delete_preset],
+        ),
+    ]
 }
 
 #[rocket::main]
+#[allow(clippy::result_large_err)]
 async fn main() -> Result<(), rocket::Error> {
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
 
+    // `factorio-browser vapid-keys` prints a fresh key pair for VAPID_PRIVATE_KEY
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("vapid-keys") {
+        match VapidKeys::generate() {
+            Ok(keys) => {
+                println!("VAPID_PRIVATE_KEY={}", keys.private_key());
+                println!("Public key: {}", keys.public_key());
+            }
+            Err(e) => {
+                eprintln!("Failed to generate VAPID keys: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // `factorio-browser check` validates the deployment and exits non-zero if something needs fixing
+    if args.get(1).map(String::as_str) == Some("check") {
+        std::process::exit(if self_check().await { 0 } else { 1 });
+    }
+
+    // Settings from the config file, overridden by environment variables
+    let config = Config::load().expect("Failed to load configuration");
+
     // Get configuration from environment variables
     let username = std::env::var("FACTORIO_USERNAME").unwrap_or_else(|_| {
         eprintln!("Warning: FACTORIO_USERNAME not set, API calls will fail");
@@ -330,55 +3577,1057 @@ async fn main() -> Result<(), rocket::Error> {
         String::new()
     });
 
-    let db_url = std::env::var("SURREAL_URL").unwrap_or_else(|_| "mem://".to_string());
-    let db_ns = std::env::var("SURREAL_NS").unwrap_or_else(|_| "factorio".to_string());
-    let db_name = std::env::var("SURREAL_DB").unwrap_or_else(|_| "browser".to_string());
-    let db_user = std::env::var("SURREAL_USER").ok();
-    let db_pass = std::env::var("SURREAL_PASS").ok();
+    let DatabaseConfig {
+        url: db_url,
+        namespace: db_ns,
+        name: db_name,
+        username: db_user,
+        password: db_pass,
+        replicas: db_replicas,
+    } = config.database.clone();
+
+    // Key for signed cookies (favorites); without it favorites reset whenever the browser restarts
+    let cookie_signer = match std::env::var("COOKIE_SECRET") {
+        Ok(secret) if !secret.is_empty() => CookieSigner::new(secret.as_bytes()),
+        _ => {
+            eprintln!("Warning: COOKIE_SECRET not set, favorites will be lost on restart");
+            CookieSigner::random().expect("Failed to generate a cookie key, set COOKIE_SECRET")
+        }
+    };
+
+    // Base URL for absolute links in feeds and the sitemap, e.g. https://servers.example.com (defaults to the Host header)
+    let public_url = std::env::var("PUBLIC_URL")
+        .ok()
+        .map(|url| url.trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty());
+
+    // Password for /admin; without it the admin page isn't served
+    let admin_token = std::env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+
+    // Read-only mode: no upstream API calls and no DB writes (demo/preview deployments)
+    let read_only = std::env::var("READ_ONLY")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
 
     // Initialize database
-    let db = DbClient::connect(
-        &db_url,
-        &db_ns,
-        &db_name,
-        db_user.as_deref(),
-        db_pass.as_deref(),
-    )
-    .await
-    .expect("Failed to connect to database");
+    let db = if read_only {
+        println!("Running in read-only mode");
+        DbClient::connect_read_only(
+            &db_url,
+            &db_ns,
+            &db_name,
+            db_user.as_deref(),
+            db_pass.as_deref(),
+        )
+        .await
+    } else {
+        DbClient::connect(
+            &db_url,
+            &db_ns,
+            &db_name,
+            db_user.as_deref(),
+            db_pass.as_deref(),
+        )
+        .await
+    }
+    .expect("Failed to connect to database")
+    .with_replicas(&db_replicas, &db_ns, &db_name, db_user.as_deref(), db_pass.as_deref())
+    .await;
 
     let db = Arc::new(db);
 
-    // Initialize Factorio API client
-    let factorio_client = FactorioClient::new_shared(username, token);
+    // Health checks move reads to a replica while the primary is down
+    if db.has_replicas() {
+        let health_db = db.clone();
+        tokio::spawn(async move {
+            check_database_health(health_db).await;
+        });
+    }
 
-    // Create application state with empty cache
-    let app_state = Arc::new(AppState {
-        db: db.clone(),
-        factorio_client: factorio_client.clone(),
-        last_error: Arc::new(RwLock::new(None)),
-        cached_servers: Arc::new(RwLock::new(Vec::new())),
+    // Raw get-games responses kept for `replay` (optional)
+    let record_dir = std::env::var("RECORD_RESPONSES_DIR").ok().filter(|d| !d.is_empty());
+    let record_days = std::env::var("RECORD_RESPONSES_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(3);
+
+    // Alternative tag spellings, e.g. "player vs player=pvp"
+    let tag_aliases = TagAliases::parse(&std::env::var("TAG_ALIASES").unwrap_or_default()).unwrap_or_else(|e| {
+        eprintln!("Warning: invalid TAG_ALIASES ({}), only folding case and markup", e);
+        TagAliases::default()
+    });
+    let tag_aliases = Arc::new(tag_aliases.with_excluded(&config.excluded_tags));
+
+    // `factorio-browser replay [dir]` re-ingests recorded responses instead of serving
+    if args.get(1).map(String::as_str) == Some("replay") {
+        match args.get(2).cloned().or(record_dir) {
+            Some(dir) if !read_only => replay(&db, std::path::Path::new(&dir), &tag_aliases).await,
+            Some(_) => eprintln!("Cannot replay in read-only mode"),
+            None => eprintln!("Usage: factorio-browser replay <dir> (or set RECORD_RESPONSES_DIR)"),
+        }
+        return Ok(());
+    }
+
+    // Use the bundled demo snapshot without credentials, so the UI isn't blank, and clear it out
+    // once credentials are configured
+    let has_credentials = !username.is_empty() && !token.is_empty();
+    if has_credentials && !read_only {
+        match db.clear_demo_data(DEMO_SERVER_ID_PREFIX).await {
+            Ok(true) => println!("Credentials configured, cleared the demo data"),
+            Ok(false) => {}
+            Err(e) => eprintln!("Failed to clear demo data: {}", e),
+        }
+    }
+    let demo_source = if !has_credentials && !read_only {
+        match FixtureSource::demo() {
+            Ok(fixture) => Some(fixture),
+            Err(e) => {
+                eprintln!("Failed to load demo snapshot: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let demo_mode = demo_source.is_some();
+
+    // Initialize upstream source (Factorio API client unless running the demo)
+    let source: Arc<dyn ServerSource> = match demo_source {
+        Some(fixture) => {
+            println!("No Factorio credentials configured, loading demo data");
+            if let Err(e) = db.mark_demo_data().await {
+                eprintln!("Failed to mark demo data: {}", e);
+            }
+            Arc::new(fixture)
+        }
+        None => {
+            // Optional mirror or mock of the matchmaking API
+            let mut builder = FactorioClient::builder(username, token);
+            if let Ok(url) = std::env::var("FACTORIO_API_URL") {
+                println!("Using Factorio API at {}", url);
+                builder = builder.base_url(url);
+            }
+            let env_u32 = |name: &str, default: u32| match std::env::var(name) {
+                Ok(v) => v.parse().unwrap_or_else(|_| {
+                    eprintln!("Warning: invalid {} '{}', using {}", name, v, default);
+                    default
+                }),
+                Err(_) => default,
+            };
+            builder = builder.details_rate_limit(
+                env_u32("DETAILS_RATE_PER_MINUTE", DEFAULT_DETAILS_PER_MINUTE),
+                env_u32("DETAILS_RATE_BURST", DEFAULT_DETAILS_BURST),
+            );
+            builder = builder.details_cache_ttl(Duration::from_secs(u64::from(env_u32(
+                "DETAILS_CACHE_SECS",
+                DEFAULT_DETAILS_CACHE_TTL.as_secs() as u32,
+            ))));
+            if let Some(dir) = record_dir {
+                match ResponseRecorder::new_shared(&dir, record_days) {
+                    Ok(recorder) => {
+                        println!("Recording get-games responses to {} for {} days", dir, record_days);
+                        builder = builder.recorder(recorder);
+                    }
+                    Err(e) => eprintln!("Failed to set up response recording in {}: {}", dir, e),
+                }
+            }
+            builder.build_shared().expect("Failed to configure Factorio API client")
+        }
+    };
+
+    // Fault injection for testing how the site copes with a failing API (only built with `--features chaos`)
+    #[cfg(feature = "chaos")]
+    let source: Arc<dyn ServerSource> = match ChaosConfig::from_env() {
+        chaos if chaos.is_active() => {
+            eprintln!("Warning: injecting upstream faults {:?}, do not run this in production", chaos);
+            ChaosSource::new_shared(source, chaos)
+        }
+        _ => source,
+    };
+
+    // Optional LibreTranslate backend for description translations
+    let translator = std::env::var("TRANSLATE_URL").ok().map(|url| {
+        let api_key = std::env::var("TRANSLATE_API_KEY").ok();
+        let target = std::env::var("TRANSLATE_TARGET").unwrap_or_else(|_| "en".to_string());
+        TranslateClient::new_shared(url, api_key, target)
+    });
+
+    // Feature groups to leave unmounted, e.g. "alerts,badges" for a minimal kiosk instance
+    let disabled_features: HashSet<String> = config.disabled_features.iter().map(|f| f.to_lowercase()).collect();
+    let known_features: Vec<&str> = feature_routes().iter().map(|(name, _)| *name).collect();
+    for feature in &disabled_features {
+        if !known_features.contains(&feature.as_str()) {
+            eprintln!("Warning: unknown feature '{}' in disabled_features", feature);
+        }
+    }
+
+    // Downtime alerts for server operators (delivered via Discord webhooks)
+    let downtime_alerts = std::env::var("DOWNTIME_ALERTS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+        && !disabled_features.contains("alerts");
+
+    // Accounts with saved filter presets (stores emails and password hashes, so opt-in)
+    let accounts = std::env::var("ACCOUNTS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false)
+        && !read_only
+        && !disabled_features.contains("accounts");
+
+    // JSON API keys are issued on the admin page; without this, requests without a key are served too
+    let api_keys = ApiKeys::new_shared(
+        std::env::var("API_KEYS_REQUIRED")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false),
+    );
+    match db.get_api_keys().await {
+        Ok(records) => api_keys.load(&records),
+        Err(e) => eprintln!("Failed to load API keys: {}", e),
+    }
+
+    // Spam listings blocked on the admin page
+    let blocklist = match db.get_blocklist().await {
+        Ok(entries) => Blocklist::new(&entries),
+        Err(e) => {
+            eprintln!("Failed to load blocklist: {}", e);
+            Blocklist::default()
+        }
+    };
+
+    // History sampling: every_refresh (default), interval (every HISTORY_SAMPLE_MINUTES), or on_change
+    let sample_minutes = std::env::var("HISTORY_SAMPLE_MINUTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(5);
+    let sampling_mode = std::env::var("HISTORY_SAMPLING").unwrap_or_default();
+    let sampling_mode = SamplingMode::parse(&sampling_mode, sample_minutes).unwrap_or_else(|| {
+        eprintln!("Warning: unknown HISTORY_SAMPLING '{}', using every_refresh", sampling_mode);
+        SamplingMode::EveryRefresh
+    });
+    // Mod Portal for mod titles, thumbnails and download counts; set MOD_PORTAL_URL empty to turn off
+    let mod_portal = match std::env::var("MOD_PORTAL_URL") {
+        Ok(url) if url.trim().is_empty() => None,
+        Ok(url) => Some(ModPortalClient::new_shared(&url)),
+        Err(_) => Some(ModPortalClient::new_shared(MOD_PORTAL_URL)),
+    };
+
+    // MaxMind GeoLite2/GeoIP2 Country or City database for server regions
+    let geoip = std::env::var("GEOIP_DB").ok().filter(|p| !p.is_empty()).and_then(|path| {
+        match GeoIp::open_shared(std::path::Path::new(&path)) {
+            Ok(geoip) => Some(geoip),
+            Err(e) => {
+                eprintln!("Warning: failed to open GeoIP database {}: {}", path, e);
+                None
+            }
+        }
+    });
+
+    // AbuseIPDB lookups of host IPs, flagging servers on hosts with many abuse reports
+    let reputation = std::env::var("ABUSEIPDB_API_KEY")
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+        .map(|key| {
+            let url = std::env::var("ABUSEIPDB_URL").unwrap_or_else(|_| ABUSEIPDB_URL.to_string());
+            ReputationClient::new_shared(&url, key.trim())
+        });
+    let abuse_reports_threshold = match std::env::var("ABUSE_REPORTS_THRESHOLD") {
+        Ok(v) => match v.parse::<u32>() {
+            Ok(threshold) if threshold > 0 => threshold,
+            _ => {
+                eprintln!("Warning: invalid ABUSE_REPORTS_THRESHOLD '{}', using {}", v, DEFAULT_ABUSE_REPORTS_THRESHOLD);
+                DEFAULT_ABUSE_REPORTS_THRESHOLD
+            }
+        },
+        Err(_) => DEFAULT_ABUSE_REPORTS_THRESHOLD,
+    };
+
+    // Discord webhook for the weekly digest (the digest page works without it)
+    let digest_webhook = std::env::var("DIGEST_WEBHOOK_URL").ok().filter(|url| !url.trim().is_empty()).and_then(|url| {
+        if is_allowed_webhook(&url) {
+            Some(url)
+        } else {
+            eprintln!("Warning: DIGEST_WEBHOOK_URL must be a Discord webhook URL, not posting digests");
+            None
+        }
     });
 
-    // Start background refresh task
-    let refresh_state = app_state.clone();
-    tokio::spawn(async move {
-        refresh_servers(refresh_state).await;
+    // Hide servers that have been empty all week from the default list (a toggle shows them)
+    let hide_inactive = std::env::var("HIDE_INACTIVE_SERVERS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(true);
+
+    // Signal weights for the "Recommended" sort, e.g. "players=2,spam=0"
+    let ranking = std::env::var("RANKING_WEIGHTS").unwrap_or_default();
+    let ranking = RankingWeights::parse(&ranking).unwrap_or_else(|e| {
+        eprintln!("Warning: invalid RANKING_WEIGHTS ({}), using the defaults", e);
+        RankingWeights::default()
     });
+    let verified_servers: HashSet<ServerId> = std::env::var("VERIFIED_SERVERS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| ServerId(id.to_string()))
+        .collect();
+
+    // A/B test of ranking weights, e.g. "control:;uptime:uptime=1" (variants override RANKING_WEIGHTS)
+    let experiment = std::env::var("RANKING_EXPERIMENT")
+        .ok()
+        .filter(|spec| !spec.trim().is_empty())
+        .and_then(|spec| match Experiment::parse(&spec, ranking) {
+            Ok(experiment) => Some(experiment),
+            Err(e) => {
+                eprintln!("Warning: invalid RANKING_EXPERIMENT ({}), not running an experiment", e);
+                None
+            }
+        });
+
+    // Web Push for favorites and friends; generate a key with `factorio-browser vapid-keys`
+    let push = match std::env::var("VAPID_PRIVATE_KEY") {
+        Ok(key) if !key.trim().is_empty() && !read_only && !disabled_features.contains("push") => {
+            match VapidKeys::from_base64(&key) {
+                Ok(keys) => {
+                    let subject = std::env::var("VAPID_SUBJECT").unwrap_or_else(|_| {
+                        eprintln!("Warning: VAPID_SUBJECT not set, push services may reject notifications");
+                        "mailto:admin@localhost".to_string()
+                    });
+                    Some(PushSender::new_shared(keys, &subject))
+                }
+                Err(e) => {
+                    eprintln!("Warning: invalid VAPID_PRIVATE_KEY ({}), push notifications disabled", e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Default homepage layout, e.g. "stats,spotlight,favorites,list" (visitors can customize theirs)
+    let widgets = match std::env::var("HOMEPAGE_WIDGETS") {
+        Ok(spec) if !spec.trim().is_empty() => {
+            for key in spec.split(',').map(str::trim).filter(|k| Widget::from_key(k).is_none()) {
+                eprintln!("Warning: unknown widget '{}' in HOMEPAGE_WIDGETS", key);
+            }
+            parse_widgets(&spec).unwrap_or_else(|| Widget::DEFAULT.to_vec())
+        }
+        _ => Widget::DEFAULT.to_vec(),
+    };
+
+    let history_bucket_minutes = std::env::var("HISTORY_BUCKET_MINUTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+
+    // Third-party images are served from here after a size and type check, resized and cached on disk
+    let image_proxy = ImageProxy::new_shared(PathBuf::from(
+        std::env::var("IMAGE_CACHE_DIR")
+            .ok()
+            .filter(|d| !d.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_CACHE_DIR.to_string()),
+    ));
+    let logo_url = std::env::var("LOGO_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_LOGO_URL.to_string());
 
     let cwd = std::env::current_dir().expect("Cannot get current directory");
     let static_dir = cwd.join("static");
+    let hydrate = static_dir.join(HYDRATE_WASM).exists();
+    if hydrate {
+        println!("Hydration bundle found, the server list updates in the browser");
+    }
+    let assets = AssetManifest::build(&static_dir);
+
+    // Rendered main pages kept per refresh (0 turns the cache off)
+    let render_cache_entries = match std::env::var("RENDER_CACHE_ENTRIES") {
+        Ok(v) => v.parse::<usize>().unwrap_or_else(|_| {
+            eprintln!("Warning: invalid RENDER_CACHE_ENTRIES '{}', using {}", v, DEFAULT_RENDER_CACHE_ENTRIES);
+            DEFAULT_RENDER_CACHE_ENTRIES
+        }),
+        Err(_) => DEFAULT_RENDER_CACHE_ENTRIES,
+    };
+
+    // Other instances to import server history from, as comma-separated base URLs
+    let federation_peers: Vec<String> = std::env::var("FEDERATION_PEERS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|peer| peer.trim().trim_end_matches('/').to_string())
+        .filter(|peer| !peer.is_empty())
+        .collect();
+    // API key sent to peers that require one for their export
+    let federation_api_key = std::env::var("FEDERATION_API_KEY").ok().filter(|key| !key.trim().is_empty());
+
+    // Create application state with empty cache
+    let app_state = Arc::new(AppState {
+        db: db.clone(),
+        source,
+        demo_mode,
+        translator,
+        translate_limit: TokenBucket::new(TRANSLATIONS_PER_MINUTE, TRANSLATIONS_PER_MINUTE),
+        notifier: Notifier::new_shared(),
+        downtime_alerts,
+        presence: Arc::new(RwLock::new(PresenceTracker::default())),
+        history_sampler: Arc::new(RwLock::new(HistorySampler::new(sampling_mode))),
+        history_queue: HistoryQueue::spawn(db.clone(), HISTORY_QUEUE_CAPACITY),
+        metrics: Metrics::new_shared(),
+        refresh_traces: RefreshTraces::new_shared(),
+        hidden_servers: Arc::new(RwLock::new(HashSet::new())),
+        blocklist: Arc::new(RwLock::new(blocklist)),
+        api_keys,
+        image_proxy,
+        logo_url,
+        history_bucket_minutes,
+        read_only,
+        last_error: Arc::new(RwLock::new(None)),
+        cache: ServerCache::new_shared(),
+        recent_players: Arc::new(RwLock::new(RecentPlayers::default())),
+        listing_changes: Arc::new(RwLock::new(ListingChanges::default())),
+        cookie_signer,
+        accounts,
+        reports: !read_only && !disabled_features.contains("reports"),
+        report_limit: TokenBucket::new(REPORTS_PER_MINUTE, REPORTS_PER_MINUTE),
+        alert_limit: TokenBucket::new(ALERT_CLAIMS_PER_MINUTE, ALERT_CLAIMS_PER_MINUTE),
+        schema_drift: Arc::new(RwLock::new(SchemaDrift::default())),
+        admin_token,
+        public_url,
+        sitemap: Arc::new(RwLock::new(SitemapCache::default())),
+        card_renderer: CardRenderer::new_shared(),
+        hide_inactive,
+        mod_portal,
+        geoip,
+        reputation,
+        abuse_reports_threshold,
+        impostors_notified: Arc::new(RwLock::new(HashSet::new())),
+        digest_webhook,
+        tag_aliases,
+        ranking,
+        verified_servers,
+        experiment,
+        push,
+        widgets,
+        hydrate,
+        assets,
+        render_cache: RenderCache::new_shared(render_cache_entries),
+        refresh_schedule: RefreshSchedule::new(
+            Duration::from_secs(config.refresh_interval_secs.max(1)),
+            Duration::from_secs(config.refresh_jitter_secs),
+            Duration::from_secs(config.refresh_max_backoff_secs),
+        ),
+        site_name: config.site_name.clone(),
+    });
 
-    // Build and launch Rocket server
-    rocket::build()
+    // Build Rocket server with the core browser routes
+    let mut server = rocket::build()
         .manage(app_state.db.clone())
-        .manage(app_state)
-        .mount("/", routes![index, server_details_page])
-        .mount("/static", FileServer::from(static_dir))
+        .manage(app_state.tag_aliases.clone())
+        .manage(app_state.cache.clone())
+        .manage(app_state.recent_players.clone())
+        .manage(app_state.listing_changes.clone())
+        .manage(RefreshInterval(app_state.refresh_schedule.longest_wait()))
+        .manage(HealthMaxAge(Duration::from_secs(config.health_max_age_secs.max(1))))
+        .manage(LastRefreshError(app_state.last_error.clone()))
+        .manage(AccountsEnabled(app_state.accounts))
+        .manage(app_state.api_keys.clone())
+        .manage(build_schema(GraphqlSources {
+            cache: app_state.cache.clone(),
+            db: app_state.db.clone(),
+            source: app_state.source.clone(),
+            tag_aliases: app_state.tag_aliases.clone(),
+            live_details: !app_state.read_only,
+        }))
+        .manage(app_state.clone())
+        .mount("/", routes![
+            index,
+            server_details_page,
+            archived_server_page,
+            server_card_page,
+            lite_index,
+            lite_server,
+            health,
+            server_qr,
+            toggle_favorite_route,
+            save_widgets,
+            save_friends,
+            feed,
+            sitemap,
+            robots,
+            og_image,
+            image_proxy,
+            static_file
+        ])
+        .register("/", catchers![unauthorized])
+        .register("/api", catchers![api_unauthorized, api_rate_limited])
+        .register("/graphql", catchers![api_unauthorized, api_rate_limited])
+        .attach(ResponseCompression);
         // TODO: Re-enable API routes later
-        // .mount("/", routes![health, get_servers, get_server, get_server_history])
-        .launch()
-        .await?;
+        // .mount("/", routes![get_server, get_server_history])
+
+    // Let other sites call the JSON API from the browser, e.g. "https://example.com" or "*"
+    let env_or = |name: &str, default: &str| {
+        std::env::var(name).ok().filter(|v| !v.trim().is_empty()).unwrap_or_else(|| default.to_string())
+    };
+    if let Some(cors) = Cors::new(
+        &env_or("CORS_ALLOWED_ORIGINS", ""),
+        &env_or("CORS_ALLOWED_METHODS", cors::DEFAULT_METHODS),
+        &env_or("CORS_ALLOWED_HEADERS", cors::DEFAULT_HEADERS),
+    ) {
+        server = server.attach(cors);
+    }
+
+    // Mount optional feature groups unless disabled
+    for (feature, routes) in feature_routes() {
+        if disabled_features.contains(feature) {
+            println!("Feature '{}' disabled", feature);
+        } else {
+            server = server.mount("/", routes);
+        }
+    }
+
+    let rocket = server.ignite().await?;
+    let mut refresh_task = None;
+    if read_only {
+        // Serve whatever is in the DB, reloading periodically
+        let reload_state = app_state.clone();
+        tokio::spawn(async move {
+            reload_cache_from_db(reload_state).await;
+        });
+    } else {
+        // Start background refresh task; it stops with the server, finishing any write in progress
+        let refresh_state = app_state.clone();
+        let shutdown = rocket.shutdown();
+        refresh_task = Some(tokio::spawn(async move {
+            refresh_servers(refresh_state, shutdown).await;
+        }));
+
+        // Start background retention cleanup task
+        let cleanup_state = app_state.clone();
+        tokio::spawn(async move {
+            cleanup_old_data(cleanup_state).await;
+        });
+
+        // Start background stats aggregation task
+        let stats_state = app_state.clone();
+        tokio::spawn(async move {
+            refresh_materialized_stats(stats_state).await;
+        });
+
+        if !disabled_features.contains("digest") {
+            let digest_state = app_state.clone();
+            tokio::spawn(async move {
+                publish_weekly_digest(digest_state).await;
+            });
+        }
+
+        // Import history from other instances, filling in refreshes this one missed
+        if !federation_peers.is_empty() && !disabled_features.contains("federation") {
+            let federation_state = app_state.clone();
+            let client = FederationClient::new_shared(federation_api_key);
+            tokio::spawn(async move {
+                import_federation(federation_state, client, federation_peers).await;
+            });
+        }
+    }
+
+    // Gemini mirror of the listing, built with `--features gemini` and on when a certificate is set
+    #[cfg(feature = "gemini")]
+    if let (Ok(cert), Ok(key)) = (std::env::var("GEMINI_CERT"), std::env::var("GEMINI_KEY")) {
+        use factorio_browser::gemini::{self, DEFAULT_GEMINI_ADDR};
+        match gemini::tls_acceptor(std::path::Path::new(&cert), std::path::Path::new(&key)) {
+            Ok(tls) => {
+                let addr = std::env::var("GEMINI_ADDR").unwrap_or_else(|_| DEFAULT_GEMINI_ADDR.to_string());
+                let cache = app_state.cache.clone();
+                let site_name = app_state.site_name.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = gemini::serve(&addr, tls, cache, site_name).await {
+                        eprintln!("Failed to start the Gemini mirror on {}: {}", addr, e);
+                    }
+                });
+            }
+            Err(e) => eprintln!("Failed to set up the Gemini mirror: {}", e),
+        }
+    }
+
+    rocket.launch().await?;
+
+    // Let a refresh cycle that was writing to the DB finish before the runtime goes away
+    if let Some(task) = refresh_task {
+        println!("Waiting for the refresh in progress to finish...");
+        if tokio::time::timeout(SHUTDOWN_GRACE, task).await.is_err() {
+            eprintln!("Warning: refresh still running after {}s, exiting anyway", SHUTDOWN_GRACE.as_secs());
+        }
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// App state around an in-memory database, refreshing every few milliseconds
+    fn test_state(db: Arc<DbClient>, cache: Arc<ServerCache>, source: Arc<dyn ServerSource>) -> AppState {
+        AppState {
+            db: db.clone(),
+            source,
+            demo_mode: false,
+            translator: None,
+            translate_limit: TokenBucket::new(TRANSLATIONS_PER_MINUTE, TRANSLATIONS_PER_MINUTE),
+            notifier: Notifier::new_shared(),
+            downtime_alerts: false,
+            presence: Arc::new(RwLock::new(PresenceTracker::default())),
+            history_sampler: Arc::new(RwLock::new(HistorySampler::new(SamplingMode::EveryRefresh))),
+            history_queue: HistoryQueue::spawn(db, HISTORY_QUEUE_CAPACITY),
+            metrics: Metrics::new_shared(),
+            refresh_traces: RefreshTraces::new_shared(),
+            hidden_servers: Arc::new(RwLock::new(HashSet::new())),
+            blocklist: Arc::new(RwLock::new(Blocklist::default())),
+            api_keys: ApiKeys::new_shared(false),
+            image_proxy: ImageProxy::new_shared(std::env::temp_dir().join("factorio-browser-test-images")),
+            logo_url: DEFAULT_LOGO_URL.to_string(),
+            history_bucket_minutes: 60,
+            read_only: false,
+            last_error: Arc::new(RwLock::new(None)),
+            cache,
+            recent_players: Arc::new(RwLock::new(RecentPlayers::default())),
+            listing_changes: Arc::new(RwLock::new(ListingChanges::default())),
+            cookie_signer: CookieSigner::new(b"test"),
+            accounts: false,
+            reports: false,
+            report_limit: TokenBucket::new(REPORTS_PER_MINUTE, REPORTS_PER_MINUTE),
+            alert_limit: TokenBucket::new(ALERT_CLAIMS_PER_MINUTE, ALERT_CLAIMS_PER_MINUTE),
+            schema_drift: Arc::new(RwLock::new(SchemaDrift::default())),
+            admin_token: None,
+            public_url: None,
+            sitemap: Arc::new(RwLock::new(SitemapCache::default())),
+            card_renderer: CardRenderer::new_shared(),
+            hide_inactive: false,
+            mod_portal: None,
+            geoip: None,
+            reputation: None,
+            abuse_reports_threshold: DEFAULT_ABUSE_REPORTS_THRESHOLD,
+            digest_webhook: None,
+            impostors_notified: Arc::new(RwLock::new(HashSet::new())),
+            tag_aliases: Arc::new(TagAliases::default()),
+            ranking: RankingWeights::default(),
+            verified_servers: HashSet::new(),
+            experiment: None,
+            push: None,
+            widgets: Widget::DEFAULT.to_vec(),
+            hydrate: false,
+            assets: AssetManifest::build(std::path::Path::new("static")),
+            render_cache: RenderCache::new_shared(DEFAULT_RENDER_CACHE_ENTRIES),
+            refresh_schedule: RefreshSchedule::new(Duration::from_millis(1), Duration::ZERO, Duration::from_millis(5)),
+            site_name: "Test".to_string(),
+        }
+    }
+
+    async fn test_db() -> Arc<DbClient> {
+        Arc::new(DbClient::connect("mem://", "test", "test", None, None).await.expect("in-memory database"))
+    }
+
+    /// Run the refresh loop for `cycles` cycles, returning the fewest cached servers seen meanwhile
+    async fn run_cycles(state: &Arc<AppState>, cycles: usize) -> usize {
+        let rocket = rocket::build().ignite().await.expect("rocket ignites");
+        let shutdown = rocket.shutdown();
+        let task = tokio::spawn(refresh_servers(state.clone(), shutdown.clone()));
+
+        let mut fewest = state.cache.load().servers.len();
+        tokio::time::timeout(Duration::from_secs(60), async {
+            while state.refresh_traces.recent().len() < cycles {
+                fewest = fewest.min(state.cache.load().servers.len());
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await
+        .expect("refresh cycles finish");
+
+        shutdown.notify();
+        task.await.expect("refresh loop stops");
+        fewest.min(state.cache.load().servers.len())
+    }
+
+    /// "admin:secret" as HTTP Basic credentials
+    const ADMIN_AUTH: &str = "Basic YWRtaW46c2VjcmV0";
+
+    /// Client for the admin routes, with ADMIN_TOKEN set to "secret"
+    async fn admin_client() -> rocket::local::asynchronous::Client {
+        let demo = Arc::new(FixtureSource::demo().expect("demo snapshot"));
+        let state = AppState {
+            admin_token: Some("secret".to_string()),
+            ..test_state(test_db().await, ServerCache::new_shared(), demo)
+        };
+        let rocket = rocket::build()
+            .manage(Arc::new(state))
+            .mount("/", routes![hide_server, restore_server, revoke_api_key, remove_blocklist_entry, dismiss_report])
+            .register("/", catchers![unauthorized]);
+        rocket::local::asynchronous::Client::untracked(rocket).await.expect("valid rocket")
+    }
+
+    async fn admin_post(
+        client: &rocket::local::asynchronous::Client,
+        path: &'static str,
+        headers: &[(&'static str, &'static str)],
+    ) -> Status {
+        let mut request = client
+            .post(path)
+            .header(Header::new("Authorization", ADMIN_AUTH))
+            .header(Header::new("Host", "servers.example.com"));
+        for (name, value) in headers {
+            request = request.header(Header::new(*name, *value));
+        }
+        request.dispatch().await.status()
+    }
+
+    async fn restore_status(client: &rocket::local::asynchronous::Client, headers: &[(&'static str, &'static str)]) -> Status {
+        admin_post(client, "/admin/hidden/some-server/restore", headers).await
+    }
+
+    #[rocket::async_test]
+    async fn admin_posts_from_other_sites_are_refused() {
+        let client = admin_client().await;
+        assert_eq!(restore_status(&client, &[("Sec-Fetch-Site", "cross-site")]).await, Status::Forbidden);
+        assert_eq!(restore_status(&client, &[("Sec-Fetch-Site", "same-site")]).await, Status::Forbidden);
+        assert_eq!(restore_status(&client, &[("Origin", "https://evil.example")]).await, Status::Forbidden);
+        assert_eq!(restore_status(&client, &[("Origin", "null")]).await, Status::Forbidden);
+        // Sec-Fetch-Site wins over a spoofable-looking Origin
+        let headers = [("Sec-Fetch-Site", "cross-site"), ("Origin", "https://servers.example.com")];
+        assert_eq!(restore_status(&client, &headers).await, Status::Forbidden);
+    }
+
+    #[rocket::async_test]
+    async fn admin_posts_from_the_admin_page_pass() {
+        let client = admin_client().await;
+        assert_eq!(restore_status(&client, &[("Sec-Fetch-Site", "same-origin")]).await, Status::SeeOther);
+        assert_eq!(restore_status(&client, &[("Origin", "https://servers.example.com")]).await, Status::SeeOther);
+        assert_eq!(restore_status(&client, &[]).await, Status::SeeOther);
+        // The origin check never stands in for the password
+        let response = client
+            .post("/admin/hidden/some-server/restore")
+            .header(Header::new("Sec-Fetch-Site", "same-origin"))
+            .dispatch()
+            .await;
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[rocket::async_test]
+    async fn api_keys_cant_be_revoked_from_other_sites() {
+        let client = admin_client().await;
+        let path = "/admin/api-keys/api_key:abc/revoke";
+        assert_eq!(admin_post(&client, path, &[("Sec-Fetch-Site", "cross-site")]).await, Status::Forbidden);
+        assert_eq!(admin_post(&client, path, &[("Sec-Fetch-Site", "same-origin")]).await, Status::SeeOther);
+    }
+
+    #[rocket::async_test]
+    async fn blocklist_cant_be_changed_from_other_sites() {
+        let client = admin_client().await;
+        let path = "/admin/blocklist/blocklist:abc/remove";
+        assert_eq!(admin_post(&client, path, &[("Origin", "https://evil.example")]).await, Status::Forbidden);
+        assert_eq!(admin_post(&client, path, &[("Origin", "https://servers.example.com")]).await, Status::SeeOther);
+    }
+
+    #[rocket::async_test]
+    async fn reports_cant_be_dismissed_from_other_sites() {
+        let client = admin_client().await;
+        let path = "/admin/reports/server_reports:abc/dismiss";
+        assert_eq!(admin_post(&client, path, &[("Sec-Fetch-Site", "cross-site")]).await, Status::Forbidden);
+        assert_eq!(admin_post(&client, path, &[("Sec-Fetch-Site", "same-origin")]).await, Status::SeeOther);
+    }
+
+    /// Report the demo server at `index`, returning the status the details page is sent back with
+    async fn report(client: &rocket::local::asynchronous::Client, index: usize) -> String {
+        let game_id = client.rocket().state::<Arc<AppState>>().unwrap().cache.load().servers[index].server.game_id;
+        let response = client
+            .post(format!("/server/{}/report", game_id))
+            .header(ContentType::Form)
+            .body("reason=spam&details=")
+            .dispatch()
+            .await;
+        let location = response.headers().get_one("Location").expect("redirects back").to_string();
+        location
+            .split(['?', '&'])
+            .find_map(|param| param.strip_prefix("report="))
+            .expect("report status")
+            .to_string()
+    }
+
+    #[rocket::async_test]
+    async fn reports_are_limited_per_reporter() {
+        let demo = Arc::new(FixtureSource::demo().expect("demo snapshot"));
+        let state = Arc::new(AppState {
+            reports: true,
+            ..test_state(test_db().await, ServerCache::new_shared(), demo)
+        });
+        run_cycles(&state, 1).await;
+        let rocket = rocket::build().manage(state).mount("/", routes![report_server]);
+        let client = rocket::local::asynchronous::Client::untracked(rocket).await.expect("valid rocket");
+
+        assert_eq!(report(&client, 0).await, "sent");
+        assert_eq!(report(&client, 0).await, "duplicate");
+        for index in 1..REPORTS_PER_REPORTER_HOUR {
+            assert_eq!(report(&client, index).await, "sent");
+        }
+        assert_eq!(report(&client, REPORTS_PER_REPORTER_HOUR).await, "too_many");
+    }
+
+    #[rocket::async_test]
+    async fn open_reports_follow_the_server_across_restarts() {
+        let db = test_db().await;
+        let server_id = ServerId("demo-ee29d94d".to_string());
+        let report = NewServerReport {
+            game_id: GameId(1),
+            server_id: Some(server_id.clone()),
+            name: "Demo".to_string(),
+            reason: ReportReason::Spam,
+            details: String::new(),
+            reporter: "reporter".to_string(),
+            reported_at: chrono::Utc::now().to_rfc3339(),
+            dismissed: false,
+        };
+        db.add_server_report(report).await.unwrap();
+
+        // A restart gives the server a new game_id
+        assert!(db.has_open_report("reporter", GameId(2), Some(&server_id)).await.unwrap());
+        assert!(!db.has_open_report("someone else", GameId(2), Some(&server_id)).await.unwrap());
+        assert!(!db.has_open_report("reporter", GameId(1), Some(&ServerId("other".to_string()))).await.unwrap());
+        assert!(db.has_open_report("reporter", GameId(1), None).await.unwrap());
+        let hour_ago = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        assert_eq!(db.count_reports_since("reporter", &hour_ago).await.unwrap(), 1);
+        assert_eq!(db.count_reports_since("someone else", &hour_ago).await.unwrap(), 0);
+    }
+
+    #[rocket::async_test]
+    async fn alerts_are_stored_by_server_id_and_claimed() {
+        let db = test_db().await;
+        let server_id = ServerId("abc".to_string());
+        let url = "https://discord.com/api/webhooks/1/x";
+        db.add_downtime_alert(&server_id, "Big Base", url, "alerts-0123abcd").await.unwrap();
+        assert!(db.add_downtime_alert(&server_id, "Big Base", url, "alerts-0123abcd").await.is_err());
+
+        let alert = db.find_downtime_alert(&server_id, url).await.unwrap().expect("stored");
+        assert!(!alert.claimed);
+        db.claim_downtime_alert(alert.id.as_ref().unwrap()).await.unwrap();
+        assert!(db.find_downtime_alert(&server_id, url).await.unwrap().unwrap().claimed);
+        assert!(db.find_downtime_alert(&ServerId("other".to_string()), url).await.unwrap().is_none());
+    }
+
+    #[rocket::async_test]
+    async fn demo_data_is_cleared_once_credentials_are_set() {
+        let db = test_db().await;
+        assert!(!db.clear_demo_data(DEMO_SERVER_ID_PREFIX).await.unwrap());
+        db.mark_demo_data().await.unwrap();
+        let demo = Arc::new(FixtureSource::demo().expect("demo snapshot"));
+        let state = Arc::new(test_state(db.clone(), ServerCache::new_shared(), demo));
+        run_cycles(&state, 1).await;
+        let demo_id = state.cache.load().servers[0].server.server_id.clone().expect("demo server_id");
+        assert!(db.get_server_profile(&demo_id).await.unwrap().is_some());
+
+        let real = ServerId("real".to_string());
+        db.insert_history(vec![NewServerHistory {
+            game_id: GameId(1),
+            server_id: Some(real.clone()),
+            player_count: 3,
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+        }])
+        .await
+        .unwrap();
+
+        assert!(db.clear_demo_data(DEMO_SERVER_ID_PREFIX).await.unwrap());
+        assert!(db.get_all_servers().await.unwrap().is_empty());
+        assert!(db.get_server_profile(&demo_id).await.unwrap().is_none());
+        assert_eq!(db.get_server_history(GameId(1), Some(&real), 24).await.unwrap().len(), 1);
+        assert!(!db.clear_demo_data(DEMO_SERVER_ID_PREFIX).await.unwrap());
+    }
+
+    #[rocket::async_test]
+    async fn federated_profiles_merge_by_time_not_text() {
+        let db = test_db().await;
+        let profile = |name: &str, first_seen: &str, last_seen: &str| factorio_browser::db::models::FederatedProfile {
+            server_id: ServerId("peer".to_string()),
+            game_id: GameId(1),
+            name: name.to_string(),
+            description: String::new(),
+            game_version: "2.0.28".to_string(),
+            tags: Vec::new(),
+            max_players: 10,
+            mod_count: 0,
+            peak_players: 1,
+            first_seen: first_seen.to_string(),
+            last_seen: last_seen.to_string(),
+        };
+        db.merge_federated_profiles(vec![profile("Local", "2026-01-01T09:00:00+00:00", "2026-01-01T10:00:00+00:00")])
+            .await
+            .unwrap();
+        // Both times sort after the stored ones as text, but are earlier
+        db.merge_federated_profiles(vec![profile("Stale", "2026-01-01T09:30:00+02:00", "2026-01-01T11:00:00+02:00")])
+            .await
+            .unwrap();
+
+        let stored = db.get_server_profile(&ServerId("peer".to_string())).await.unwrap().unwrap();
+        assert_eq!(stored.name, "Local");
+        assert_eq!(stored.first_seen, "2026-01-01T09:30:00+02:00");
+        assert_eq!(stored.last_seen, "2026-01-01T10:00:00+00:00");
+    }
+
+    #[rocket::async_test]
+    async fn revalidated_pages_arent_impressions() {
+        let demo = Arc::new(FixtureSource::demo().expect("demo snapshot"));
+        let experiment = Experiment::parse("control:;uptime:uptime=1", RankingWeights::default()).unwrap();
+        let state = Arc::new(AppState {
+            experiment: Some(experiment),
+            ..test_state(test_db().await, ServerCache::new_shared(), demo)
+        });
+        run_cycles(&state, 1).await;
+        let rocket = rocket::build().manage(state.clone()).mount("/", routes![index]);
+        let client = rocket::local::asynchronous::Client::untracked(rocket).await.expect("valid rocket");
+        let get = |etag: Option<String>| {
+            let mut request = client.get("/").cookie(Cookie::new(EXPERIMENT_COOKIE, "control"));
+            if let Some(etag) = etag {
+                request = request.header(Header::new("If-None-Match", etag));
+            }
+            request.dispatch()
+        };
+
+        let first = get(None).await;
+        assert_eq!(first.status(), Status::Ok);
+        let etag = first.headers().get_one("ETag").expect("etag").to_string();
+        assert_eq!(get(Some(etag)).await.status(), Status::NotModified);
+        // Served from the render cache, but still a list shown
+        assert_eq!(get(None).await.status(), Status::Ok);
+
+        let metrics = state.metrics.render(&Default::default());
+        assert!(metrics.contains("ranking_impressions_total{variant=\"control\"} 2"), "{}", metrics);
+    }
+
+    #[rocket::async_test]
+    async fn cached_servers_keep_first_seen_across_refreshes() {
+        let db = test_db().await;
+        let servers = FixtureSource::demo().expect("demo snapshot").get_games().await.unwrap();
+        let aliases = TagAliases::default();
+        let first = chrono::Utc::now() - chrono::Duration::hours(2);
+        let later = first + chrono::Duration::hours(1);
+        db.cache_servers_at(servers.clone(), first, None, &aliases).await.unwrap();
+        // The first server drops off for a refresh and comes back
+        db.cache_servers_at(servers[1..].to_vec(), first + chrono::Duration::minutes(30), None, &aliases)
+            .await
+            .unwrap();
+        db.cache_servers_at(servers.clone(), later, None, &aliases).await.unwrap();
+
+        let cached = db.get_all_servers().await.unwrap();
+        assert_eq!(cached.len(), servers.len());
+        for server in cached {
+            assert_eq!(server.first_seen.as_deref(), Some(first.to_rfc3339().as_str()));
+            assert_eq!(server.last_seen.as_deref(), Some(later.to_rfc3339().as_str()));
+        }
+    }
+
+    #[rocket::async_test]
+    async fn searches_the_index_cant_answer_match_substrings() {
+        let db = test_db().await;
+        let demo = Arc::new(FixtureSource::demo().expect("demo snapshot"));
+        let state = Arc::new(test_state(db.clone(), ServerCache::new_shared(), demo));
+        run_cycles(&state, 1).await;
+        let snapshot = state.cache.load();
+        let found = |spec: &factorio_browser::filter::FilterSpec| -> Vec<String> {
+            snapshot
+                .servers
+                .iter()
+                .filter(|s| spec.matches(&s.server, ""))
+                .map(|s| s.server.name.clone())
+                .collect()
+        };
+        let search = |text: &str| {
+            factorio_browser::filter::FilterSpec::from(&factorio_browser::filter::ServerFilters {
+                search: Some(text.to_string()),
+                ..Default::default()
+            })
+        };
+
+        // Word starts come from the index
+        assert_eq!(found(&db.with_search(search("pyano")).await), ["Pyanodons Marathon"]);
+        // "2" is too short to be indexed, and "anodons" starts no word
+        assert_eq!(found(&db.with_search(search("krastorio 2")).await), ["Krastorio 2 Vanilla+"]);
+        assert_eq!(found(&db.with_search(search("anodons")).await), ["Pyanodons Marathon"]);
+    }
+
+    /// The refresh loop against the fault-injecting source
+    #[cfg(feature = "chaos")]
+    mod chaos {
+        use super::*;
+
+        /// Shared database and cache holding the demo listing, as after a good refresh
+        async fn seeded() -> (Arc<DbClient>, Arc<ServerCache>, usize) {
+            let db = test_db().await;
+            let cache = ServerCache::new_shared();
+            let demo = Arc::new(FixtureSource::demo().expect("demo snapshot"));
+            let state = Arc::new(test_state(db.clone(), cache.clone(), demo));
+            run_cycles(&state, 1).await;
+            let count = cache.load().servers.len();
+            assert!(count > 0, "the demo listing is cached");
+            assert!(state.last_error.read().await.is_none());
+            (db, cache, count)
+        }
+
+        fn chaos(config: ChaosConfig) -> Arc<dyn ServerSource> {
+            ChaosSource::new_shared(Arc::new(FixtureSource::demo().expect("demo snapshot")), config)
+        }
+
+        #[rocket::async_test]
+        async fn failing_upstream_keeps_cache_and_shows_banner() {
+            let (db, cache, count) = seeded().await;
+            let source = chaos(ChaosConfig {
+                error_rate: 1.0,
+                ..Default::default()
+            });
+            let state = Arc::new(test_state(db, cache, source));
+
+            assert_eq!(run_cycles(&state, 3).await, count);
+            assert_eq!(state.cache.load().servers.len(), count);
+            assert_eq!(
+                state.last_error.read().await.as_deref(),
+                Some("An error occurred while fetching server data.")
+            );
+            assert!(state.refresh_traces.recent().iter().all(|trace| trace.outcome != "ok"));
+        }
+
+        #[rocket::async_test]
+        async fn empty_listing_keeps_cache_and_shows_banner() {
+            let (db, cache, count) = seeded().await;
+            let source = chaos(ChaosConfig {
+                empty_rate: 1.0,
+                ..Default::default()
+            });
+            let state = Arc::new(test_state(db, cache, source));
+
+            assert_eq!(run_cycles(&state, 3).await, count);
+            assert_eq!(state.last_error.read().await.as_deref(), Some("The server list came back empty."));
+        }
+
+        #[rocket::async_test]
+        async fn malformed_servers_still_list() {
+            let (db, cache, count) = seeded().await;
+            let source = chaos(ChaosConfig {
+                malformed_rate: 1.0,
+                ..Default::default()
+            });
+            let state = Arc::new(test_state(db, cache, source));
+
+            assert!(run_cycles(&state, 2).await > 0);
+            assert_eq!(state.cache.load().servers.len(), count);
+            assert!(state.last_error.read().await.is_none());
+        }
+
+        #[rocket::async_test]
+        async fn flaky_upstream_never_blanks_cache() {
+            let (db, cache, _) = seeded().await;
+            let source = chaos(ChaosConfig {
+                error_rate: 0.3,
+                empty_rate: 0.3,
+                malformed_rate: 0.3,
+                latency: Duration::from_millis(1),
+            });
+            let state = Arc::new(test_state(db, cache, source));
+
+            assert!(run_cycles(&state, 10).await > 0);
+        }
+    }
+}
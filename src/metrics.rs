@@ -0,0 +1,384 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Metric name prefix shared by everything on /metrics
+const PREFIX: &str = "factorio_browser";
+
+/// Accumulated timings of one named DB query
+#[derive(Debug, Clone, Copy, Default)]
+struct QueryTiming {
+    count: u64,
+    sum_secs: f64,
+    last_secs: f64,
+}
+
+/// Values that are only known at scrape time (cache contents, queue depth)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Snapshot {
+    pub servers: usize,
+    pub players: usize,
+    pub history_queue_depth: usize,
+    pub history_dropped: u64,
+    /// Distinct get-games fields seen that GameServer doesn't model
+    pub unknown_fields: usize,
+    pub render_cache: RenderCacheStats,
+}
+
+/// Lookups and compressions of the rendered page cache since startup
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderCacheStats {
+    pub entries: usize,
+    pub hits: u64,
+    /// Lookups that rendered the page
+    pub misses: u64,
+    /// Lookups answered with the previous rendering while another request rendered the page
+    pub stale: u64,
+    /// Lookups that waited for another request's rendering
+    pub coalesced: u64,
+    pub brotli_compressions: u64,
+    pub gzip_compressions: u64,
+    /// Time spent compressing cached pages
+    pub compression_seconds: f64,
+}
+
+/// Optional and background parts of the browser, each with a row in the admin health matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Subsystem {
+    /// Fetching and caching get-games
+    Ingest,
+    /// GeoIP lookups of server hosts (each lookup counts)
+    Geo,
+    /// Modpack fingerprints from game details
+    Modpacks,
+    /// AbuseIPDB host checks (each lookup counts)
+    Reputation,
+    /// Warnings about servers imitating verified ones
+    Impostors,
+    /// Downtime alert checks
+    Alerts,
+    /// Discord webhook posts from alerts, impostor warnings and the digest (each post counts)
+    Webhooks,
+    /// Web push notifications (each message counts)
+    Push,
+    /// Materialized listing statistics
+    Stats,
+    /// Weekly digest publishing
+    Digest,
+    /// Hourly pruning of old records
+    Cleanup,
+    /// Imports from federated instances (each peer counts)
+    Federation,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 12] = [
+        Subsystem::Ingest,
+        Subsystem::Geo,
+        Subsystem::Modpacks,
+        Subsystem::Reputation,
+        Subsystem::Impostors,
+        Subsystem::Alerts,
+        Subsystem::Webhooks,
+        Subsystem::Push,
+        Subsystem::Stats,
+        Subsystem::Digest,
+        Subsystem::Cleanup,
+        Subsystem::Federation,
+    ];
+
+    /// Metric label
+    pub fn key(self) -> &'static str {
+        match self {
+            Subsystem::Ingest => "ingest",
+            Subsystem::Geo => "geo",
+            Subsystem::Modpacks => "modpacks",
+            Subsystem::Reputation => "reputation",
+            Subsystem::Impostors => "impostors",
+            Subsystem::Alerts => "alerts",
+            Subsystem::Webhooks => "webhooks",
+            Subsystem::Push => "push",
+            Subsystem::Stats => "stats",
+            Subsystem::Digest => "digest",
+            Subsystem::Cleanup => "cleanup",
+            Subsystem::Federation => "federation",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Subsystem::Ingest => "Listing refresh",
+            Subsystem::Geo => "GeoIP",
+            Subsystem::Modpacks => "Modpack fingerprints",
+            Subsystem::Reputation => "Host reputation",
+            Subsystem::Impostors => "Impostor warnings",
+            Subsystem::Alerts => "Downtime alerts",
+            Subsystem::Webhooks => "Webhooks",
+            Subsystem::Push => "Push notifications",
+            Subsystem::Stats => "Statistics",
+            Subsystem::Digest => "Weekly digest",
+            Subsystem::Cleanup => "Cleanup",
+            Subsystem::Federation => "Federation import",
+        }
+    }
+}
+
+/// Outcomes of one subsystem since startup
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubsystemHealth {
+    pub successes: u64,
+    pub failures: u64,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+    /// Message of the most recent failure
+    pub last_error: Option<String>,
+}
+
+impl SubsystemHealth {
+    /// Ran, and the latest outcome was a failure
+    pub fn failing(&self) -> bool {
+        match (self.last_success, self.last_failure) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(success), Some(failure)) => failure > success,
+        }
+    }
+}
+
+/// Operational counters for the Prometheus /metrics endpoint
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Duration of the last completed refresh, in microseconds
+    refresh_duration_micros: AtomicU64,
+    refresh_errors: AtomicU64,
+    queries: Mutex<BTreeMap<&'static str, QueryTiming>>,
+    /// Ranking experiment list views and server clicks from them, by variant
+    variants: Mutex<BTreeMap<String, VariantCounts>>,
+    subsystems: Mutex<BTreeMap<Subsystem, SubsystemHealth>>,
+}
+
+/// Click-through counters of one ranking experiment variant
+#[derive(Debug, Clone, Copy, Default)]
+struct VariantCounts {
+    impressions: u64,
+    clicks: u64,
+}
+
+impl Metrics {
+    pub fn new_shared() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Record how long a full refresh took
+    pub fn observe_refresh(&self, elapsed: Duration) {
+        self.refresh_duration_micros
+            .store(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Count a refresh that failed to fetch or cache servers
+    pub fn record_refresh_error(&self) {
+        self.refresh_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the latency of one DB query
+    pub fn observe_query(&self, query: &'static str, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64();
+        let mut queries = self.queries.lock().unwrap_or_else(|e| e.into_inner());
+        let timing = queries.entry(query).or_default();
+        timing.count += 1;
+        timing.sum_secs += secs;
+        timing.last_secs = secs;
+    }
+
+    /// Count a server list shown to a visitor in a ranking experiment variant
+    pub fn record_impression(&self, variant: &str) {
+        let mut variants = self.variants.lock().unwrap_or_else(|e| e.into_inner());
+        variants.entry(variant.to_string()).or_default().impressions += 1;
+    }
+
+    /// Count a click from the server list through to a server page
+    pub fn record_click(&self, variant: &str) {
+        let mut variants = self.variants.lock().unwrap_or_else(|e| e.into_inner());
+        variants.entry(variant.to_string()).or_default().clicks += 1;
+    }
+
+    /// Count a successful run (or lookup, or delivery) of a subsystem
+    pub fn record_success(&self, subsystem: Subsystem) {
+        self.record_outcomes(subsystem, 1, 0, None);
+    }
+
+    /// Count a failed run of a subsystem, keeping the error for the admin page
+    pub fn record_failure(&self, subsystem: Subsystem, error: impl std::fmt::Display) {
+        self.record_outcomes(subsystem, 0, 1, Some(redact_urls(&error.to_string())));
+    }
+
+    /// Count a batch of outcomes at once, e.g. every GeoIP lookup of a refresh
+    pub fn record_outcomes(&self, subsystem: Subsystem, successes: u64, failures: u64, error: Option<String>) {
+        let now = Utc::now();
+        let mut subsystems = self.subsystems.lock().unwrap_or_else(|e| e.into_inner());
+        let health = subsystems.entry(subsystem).or_default();
+        health.successes += successes;
+        health.failures += failures;
+        if successes > 0 {
+            health.last_success = Some(now);
+        }
+        if failures > 0 {
+            health.last_failure = Some(now);
+            if error.is_some() {
+                health.last_error = error;
+            }
+        }
+    }
+
+    /// Every subsystem with its outcomes so far, in matrix order (never-run ones are empty)
+    pub fn subsystem_health(&self) -> Vec<(Subsystem, SubsystemHealth)> {
+        let subsystems = self.subsystems.lock().unwrap_or_else(|e| e.into_inner());
+        Subsystem::ALL
+            .iter()
+            .map(|s| (*s, subsystems.get(s).cloned().unwrap_or_default()))
+            .collect()
+    }
+
+    /// Run a DB query and record its latency, whatever the outcome
+    pub async fn time_query<F: Future>(&self, query: &'static str, fut: F) -> F::Output {
+        let start = Instant::now();
+        let output = fut.await;
+        self.observe_query(query, start.elapsed());
+        output
+    }
+
+    /// Render every metric in the Prometheus text exposition format
+    pub fn render(&self, snapshot: &Snapshot) -> String {
+        let mut out = String::new();
+
+        write_metric(&mut out, "servers", "gauge", "Servers in the cache", snapshot.servers);
+        write_metric(&mut out, "players", "gauge", "Players online across cached servers", snapshot.players);
+        write_metric(
+            &mut out,
+            "refresh_duration_seconds",
+            "gauge",
+            "Duration of the last completed refresh",
+            self.refresh_duration_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+        );
+        write_metric(
+            &mut out,
+            "refresh_errors_total",
+            "counter",
+            "Refreshes that failed to fetch or cache servers",
+            self.refresh_errors.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut out,
+            "history_queue_depth",
+            "gauge",
+            "History records waiting to be written",
+            snapshot.history_queue_depth,
+        );
+        write_metric(
+            &mut out,
+            "history_dropped_total",
+            "counter",
+            "History records dropped because the queue was full",
+            snapshot.history_dropped,
+        );
+        write_metric(
+            &mut out,
+            "api_unknown_fields",
+            "gauge",
+            "Distinct get-games fields not modelled by the browser",
+            snapshot.unknown_fields,
+        );
+
+        let cache = &snapshot.render_cache;
+        write_metric(&mut out, "render_cache_entries", "gauge", "Rendered pages in the cache", cache.entries);
+        let _ = writeln!(out, "# HELP {PREFIX}_render_cache_lookups_total Rendered page cache lookups by outcome");
+        let _ = writeln!(out, "# TYPE {PREFIX}_render_cache_lookups_total counter");
+        let _ = writeln!(out, "{PREFIX}_render_cache_lookups_total{{outcome=\"hit\"}} {}", cache.hits);
+        let _ = writeln!(out, "{PREFIX}_render_cache_lookups_total{{outcome=\"miss\"}} {}", cache.misses);
+        let _ = writeln!(out, "{PREFIX}_render_cache_lookups_total{{outcome=\"stale\"}} {}", cache.stale);
+        let _ = writeln!(out, "{PREFIX}_render_cache_lookups_total{{outcome=\"coalesced\"}} {}", cache.coalesced);
+        let _ = writeln!(out, "# HELP {PREFIX}_render_cache_compressions_total Cached pages compressed, by encoding");
+        let _ = writeln!(out, "# TYPE {PREFIX}_render_cache_compressions_total counter");
+        let _ = writeln!(out, "{PREFIX}_render_cache_compressions_total{{encoding=\"br\"}} {}", cache.brotli_compressions);
+        let _ = writeln!(out, "{PREFIX}_render_cache_compressions_total{{encoding=\"gzip\"}} {}", cache.gzip_compressions);
+        write_metric(
+            &mut out,
+            "render_cache_compression_seconds_total",
+            "counter",
+            "Time spent compressing cached pages",
+            cache.compression_seconds,
+        );
+
+        let queries = self.queries.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let _ = writeln!(out, "# HELP {PREFIX}_db_query_seconds Latency of DB queries");
+        let _ = writeln!(out, "# TYPE {PREFIX}_db_query_seconds summary");
+        for (query, timing) in &queries {
+            let _ = writeln!(out, "{PREFIX}_db_query_seconds_sum{{query=\"{query}\"}} {}", timing.sum_secs);
+            let _ = writeln!(out, "{PREFIX}_db_query_seconds_count{{query=\"{query}\"}} {}", timing.count);
+        }
+        let _ = writeln!(out, "# HELP {PREFIX}_db_query_last_seconds Latency of the most recent DB query");
+        let _ = writeln!(out, "# TYPE {PREFIX}_db_query_last_seconds gauge");
+        for (query, timing) in &queries {
+            let _ = writeln!(out, "{PREFIX}_db_query_last_seconds{{query=\"{query}\"}} {}", timing.last_secs);
+        }
+
+        let _ = writeln!(out, "# HELP {PREFIX}_subsystem_outcomes_total Runs, lookups or deliveries per subsystem and outcome");
+        let _ = writeln!(out, "# TYPE {PREFIX}_subsystem_outcomes_total counter");
+        for (subsystem, health) in self.subsystem_health() {
+            let key = subsystem.key();
+            let _ = writeln!(out, "{PREFIX}_subsystem_outcomes_total{{subsystem=\"{key}\",outcome=\"success\"}} {}", health.successes);
+            let _ = writeln!(out, "{PREFIX}_subsystem_outcomes_total{{subsystem=\"{key}\",outcome=\"failure\"}} {}", health.failures);
+        }
+        let _ = writeln!(out, "# HELP {PREFIX}_subsystem_last_success_timestamp_seconds When each subsystem last succeeded");
+        let _ = writeln!(out, "# TYPE {PREFIX}_subsystem_last_success_timestamp_seconds gauge");
+        for (subsystem, health) in self.subsystem_health() {
+            if let Some(at) = health.last_success {
+                let _ = writeln!(out, "{PREFIX}_subsystem_last_success_timestamp_seconds{{subsystem=\"{}\"}} {}", subsystem.key(), at.timestamp());
+            }
+        }
+
+        let variants = self.variants.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if !variants.is_empty() {
+            let _ = writeln!(out, "# HELP {PREFIX}_ranking_impressions_total Server lists shown per ranking experiment variant");
+            let _ = writeln!(out, "# TYPE {PREFIX}_ranking_impressions_total counter");
+            for (variant, counts) in &variants {
+                let _ = writeln!(out, "{PREFIX}_ranking_impressions_total{{variant=\"{variant}\"}} {}", counts.impressions);
+            }
+            let _ = writeln!(out, "# HELP {PREFIX}_ranking_clicks_total Server pages opened from the list per ranking experiment variant");
+            let _ = writeln!(out, "# TYPE {PREFIX}_ranking_clicks_total counter");
+            for (variant, counts) in &variants {
+                let _ = writeln!(out, "{PREFIX}_ranking_clicks_total{{variant=\"{variant}\"}} {}", counts.clicks);
+            }
+        }
+
+        out
+    }
+}
+
+/// An error message with URLs cut out, since get-games URLs carry the API token and webhook URLs
+/// are secrets of their own
+pub fn redact_urls(message: &str) -> String {
+    message
+        .split(' ')
+        .map(|word| match word.find("://") {
+            Some(_) => {
+                let leading = word.len() - word.trim_start_matches(['(', '"', '\'']).len();
+                let trailing = word.len() - word.trim_end_matches([')', '"', '\'', ',', ':', '.']).len();
+                format!("{}<url>{}", &word[..leading], &word[word.len() - trailing..])
+            }
+            None => word.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Append one unlabelled metric with its HELP and TYPE lines
+fn write_metric(out: &mut String, name: &str, kind: &str, help: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(out, "# HELP {PREFIX}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {PREFIX}_{name} {kind}");
+    let _ = writeln!(out, "{PREFIX}_{name} {value}");
+}
@@ -0,0 +1,37 @@
+#[cfg(feature = "server")]
+use crate::api::factorio::ModInfo;
+#[cfg(feature = "server")]
+use crate::api::mod_portal::BUILTIN_MODS;
+#[cfg(feature = "server")]
+use sha2::{Digest, Sha256};
+
+/// Hex characters kept from the hash (64 bits, plenty for a few thousand modpacks)
+const FINGERPRINT_LEN: usize = 16;
+
+/// Modded servers fingerprinted per refresh, since each one costs a get-game-details call
+pub const MODPACK_LOOKUPS_PER_REFRESH: usize = 20;
+
+/// Fingerprint of a server's exact mod set (names and versions, in any order)
+/// None for vanilla servers, which only run the built-in mods
+#[cfg(feature = "server")]
+pub fn fingerprint(mods: &[ModInfo]) -> Option<String> {
+    let mut entries: Vec<String> = mods
+        .iter()
+        .filter(|m| !BUILTIN_MODS.contains(&m.name.as_str()))
+        .map(|m| format!("{}@{}", m.name, m.version))
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+    entries.sort();
+    entries.dedup();
+
+    let digest = Sha256::digest(entries.join("\n").as_bytes());
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    Some(hex[..FINGERPRINT_LEN].to_string())
+}
+
+/// Whether a modpack query parameter looks like a fingerprint
+pub fn is_fingerprint(value: &str) -> bool {
+    value.len() == FINGERPRINT_LEN && value.chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -0,0 +1,87 @@
+use reqwest::Client;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Only Discord webhooks are accepted so visitors can't make us POST to arbitrary URLs
+const ALLOWED_WEBHOOK_PREFIXES: &[&str] = &[
+    "https://discord.com/api/webhooks/",
+    "https://discordapp.com/api/webhooks/",
+];
+
+/// Webhook notification sender
+#[derive(Clone)]
+pub struct Notifier {
+    client: Client,
+}
+
+/// Discord-compatible webhook payload
+#[derive(Debug, Serialize)]
+struct WebhookMessage<'a> {
+    content: &'a str,
+    username: &'a str,
+}
+
+/// Error type for notification delivery
+#[derive(Debug)]
+pub enum NotifyError {
+    RequestFailed(reqwest::Error),
+    /// The webhook no longer exists (deleted by its owner)
+    WebhookGone,
+    Rejected(String),
+}
+
+impl std::fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotifyError::RequestFailed(e) => write!(f, "Request failed: {}", e),
+            NotifyError::WebhookGone => write!(f, "Webhook no longer exists"),
+            NotifyError::Rejected(msg) => write!(f, "Webhook rejected message: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for NotifyError {}
+
+impl From<reqwest::Error> for NotifyError {
+    fn from(err: reqwest::Error) -> Self {
+        NotifyError::RequestFailed(err)
+    }
+}
+
+/// Check that a webhook URL points to an allowed provider
+pub fn is_allowed_webhook(url: &str) -> bool {
+    ALLOWED_WEBHOOK_PREFIXES
+        .iter()
+        .any(|prefix| url.starts_with(prefix) && url.len() > prefix.len())
+}
+
+impl Notifier {
+    /// Create a new notifier wrapped in Arc for sharing
+    pub fn new_shared() -> Arc<Self> {
+        Arc::new(Self {
+            client: Client::new(),
+        })
+    }
+
+    /// Post a plain text message to a webhook
+    pub async fn send_webhook(&self, url: &str, message: &str) -> Result<(), NotifyError> {
+        if !is_allowed_webhook(url) {
+            return Err(NotifyError::Rejected("webhook URL not allowed".to_string()));
+        }
+
+        let body = WebhookMessage {
+            content: message,
+            username: "Factorio Server Browser",
+        };
+        let response = self.client.post(url).json(&body).send().await?;
+
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND | reqwest::StatusCode::GONE => Err(NotifyError::WebhookGone),
+            status if !status.is_success() => {
+                let body = response.text().await.unwrap_or_default();
+                Err(NotifyError::Rejected(format!("{}: {}", status, body)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::ids::GameId;
+use crate::utils::{escape_xml, strip_all_tags};
+use crate::view::{PlayerStatus, ServerView};
+use resvg::{tiny_skia, usvg};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Preview card size recommended by Discord and Twitter (1.91:1)
+pub const CARD_WIDTH: u32 = 1200;
+pub const CARD_HEIGHT: u32 = 630;
+
+/// Longest server name drawn before it is cut with an ellipsis
+const MAX_NAME_CHARS: usize = 40;
+
+/// Rendered cards kept per listing; a card is ~50 KB, and shares cluster on a few servers
+const MAX_CACHED_CARDS: usize = 256;
+
+/// Hex color matching the status-* theme colors
+fn status_color(status: PlayerStatus) -> &'static str {
+    match status {
+        PlayerStatus::Full => "#ff4444",
+        PlayerStatus::Medium => "#ffaa00",
+        PlayerStatus::Low => "#7fcd33",
+        PlayerStatus::Empty => "#666666",
+    }
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let cut: String = text.chars().take(max - 1).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+/// One label/value column along the bottom of the card
+fn stat(x: u32, label: &str, value: &str, color: &str) -> String {
+    format!(
+        r##"<text x="{x}" y="470" font-size="30" fill="#888888">{label}</text><text x="{x}" y="540" font-size="60" font-weight="bold" fill="{color}">{value}</text>"##,
+        x = x,
+        label = escape_xml(label),
+        value = escape_xml(value),
+        color = color,
+    )
+}
+
+/// Preview card for a server as SVG: name, players, version and mods
+pub fn render_card_svg(view: &ServerView) -> String {
+    let server = &view.server;
+    let name = truncate(strip_all_tags(&server.name).trim(), MAX_NAME_CHARS);
+    let tags = truncate(&view.display_tags.join(" · "), 60);
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="Titillium Web, DejaVu Sans, sans-serif">
+<rect width="{w}" height="{h}" fill="#1a1a1a"/>
+<rect x="40" y="40" width="{iw}" height="{ih}" rx="12" fill="#3c3c3c" stroke="#555555" stroke-width="2"/>
+<rect x="40" y="40" width="12" height="{ih}" rx="6" fill="#f4a200"/>
+<text x="90" y="120" font-size="30" fill="#f4a200">Factorio Server Browser</text>
+<text x="90" y="220" font-size="72" font-weight="bold" fill="#ffffff">{name}</text>
+<text x="90" y="290" font-size="32" fill="#d4d4d4">{tags}</text>
+{players}{version}{mods}
+</svg>"##,
+        w = CARD_WIDTH,
+        h = CARD_HEIGHT,
+        iw = CARD_WIDTH - 80,
+        ih = CARD_HEIGHT - 80,
+        name = escape_xml(&name),
+        tags = escape_xml(&tags),
+        players = stat(90, "Players", &view.players_display, status_color(view.status)),
+        version = stat(480, "Version", &server.game_version, "#d4d4d4"),
+        mods = stat(820, "Mods", &view.mods_display, "#d4d4d4"),
+    )
+}
+
+/// Cards rendered from one listing, keyed by game_id
+#[derive(Debug, Default)]
+struct CachedCards {
+    /// Cache generation the cards were rendered from
+    generation: u64,
+    cards: HashMap<GameId, Arc<[u8]>>,
+    /// Game IDs least recently rendered first, for dropping one when full
+    order: VecDeque<GameId>,
+}
+
+/// Rasterizes preview cards to PNG, since Discord and Twitter don't accept SVG images
+/// System fonts are loaded once; without any installed the text is simply left out
+/// Cards are kept until the next refresh, so a link shared in a busy channel is rendered once
+pub struct CardRenderer {
+    options: usvg::Options<'static>,
+    cached: Mutex<CachedCards>,
+}
+
+impl CardRenderer {
+    pub fn new_shared() -> Arc<Self> {
+        let mut options = usvg::Options::default();
+        options.fontdb_mut().load_system_fonts();
+        Arc::new(Self {
+            options,
+            cached: Mutex::new(CachedCards::default()),
+        })
+    }
+
+    /// The card for `game_id` rendered from cache generation `generation`, if there is one
+    pub fn cached(&self, game_id: GameId, generation: u64) -> Option<Arc<[u8]>> {
+        let cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        if cached.generation != generation {
+            return None;
+        }
+        cached.cards.get(&game_id).cloned()
+    }
+
+    /// Keep a card rendered from cache generation `generation`, dropping those of older generations
+    pub fn store(&self, game_id: GameId, generation: u64, png: Arc<[u8]>) {
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        // A request still holding an older listing mustn't replace the newer cards
+        if generation < cached.generation {
+            return;
+        }
+        if generation > cached.generation {
+            *cached = CachedCards {
+                generation,
+                ..CachedCards::default()
+            };
+        }
+        cached.order.retain(|id| *id != game_id);
+        while cached.cards.len() >= MAX_CACHED_CARDS && !cached.cards.contains_key(&game_id) {
+            let Some(oldest) = cached.order.pop_front() else {
+                break;
+            };
+            cached.cards.remove(&oldest);
+        }
+        cached.order.push_back(game_id);
+        cached.cards.insert(game_id, png);
+    }
+
+    /// Render a card SVG to PNG bytes
+    pub fn render_png(&self, svg: &str) -> Result<Vec<u8>, String> {
+        let tree = usvg::Tree::from_str(svg, &self.options).map_err(|e| e.to_string())?;
+        let mut pixmap =
+            tiny_skia::Pixmap::new(CARD_WIDTH, CARD_HEIGHT).ok_or_else(|| "invalid card size".to_string())?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap.encode_png().map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,119 @@
+use crate::ids::{GameId, ServerId};
+use crate::view::ServerView;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Hours a player stays findable after leaving a server
+pub const RECENT_PLAYER_HOURS: i64 = 24;
+
+/// Longest player name accepted in a search (Factorio caps names well below this)
+pub const MAX_PLAYER_NAME_LEN: usize = 64;
+
+/// A player seen on a server
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlayerSighting {
+    /// Player name as the server reported it
+    pub name: String,
+    pub game_id: GameId,
+    pub server_id: Option<ServerId>,
+    pub server_name: String,
+    /// When the player was last listed on this server (RFC 3339)
+    pub last_seen: String,
+}
+
+/// Where a player is now and where they were recently
+#[derive(Debug, Clone, PartialEq, Serialize, Default)]
+pub struct PlayerSearch {
+    pub query: String,
+    /// Servers the player is on right now
+    pub online: Vec<PlayerSighting>,
+    /// Other servers the player was on in the last RECENT_PLAYER_HOURS, most recent first
+    pub recent: Vec<PlayerSighting>,
+}
+
+impl PlayerSearch {
+    pub fn is_empty(&self) -> bool {
+        self.online.is_empty() && self.recent.is_empty()
+    }
+}
+
+/// Stable key of the server a sighting belongs to (game_id changes on restart)
+fn server_key(view: &ServerView) -> String {
+    match view.server.server_id {
+        Some(ref server_id) => server_id.to_string(),
+        None => format!("game-{}", view.server.game_id),
+    }
+}
+
+/// Recent player sightings, fed from each cache refresh
+/// Kept in memory only, so "recently" starts over when the browser restarts
+#[derive(Debug, Default)]
+pub struct RecentPlayers {
+    /// Keyed by (lowercased player name, server key)
+    seen: HashMap<(String, String), (PlayerSighting, DateTime<Utc>)>,
+}
+
+impl RecentPlayers {
+    /// Record everyone currently listed and forget sightings older than RECENT_PLAYER_HOURS
+    pub fn record(&mut self, servers: &[ServerView], now: DateTime<Utc>) {
+        let last_seen = now.to_rfc3339();
+        for view in servers {
+            let key = server_key(view);
+            for name in &view.server.players {
+                let sighting = PlayerSighting {
+                    name: name.clone(),
+                    game_id: view.server.game_id,
+                    server_id: view.server.server_id.clone(),
+                    server_name: view.server.name.clone(),
+                    last_seen: last_seen.clone(),
+                };
+                self.seen.insert((name.to_lowercase(), key.clone()), (sighting, now));
+            }
+        }
+
+        let cutoff = now - Duration::hours(RECENT_PLAYER_HOURS);
+        self.seen.retain(|_, (_, seen_at)| *seen_at >= cutoff);
+    }
+
+    /// Look a player up by name (case-insensitive) in the current listing and recent sightings
+    pub fn search(&self, servers: &[ServerView], name: &str) -> PlayerSearch {
+        let query = name.trim().to_lowercase();
+        let mut online = Vec::new();
+        let mut online_keys = Vec::new();
+
+        for view in servers {
+            let Some(player) = view.server.players.iter().find(|p| p.to_lowercase() == query) else {
+                continue;
+            };
+            let key = server_key(view);
+            let last_seen = self
+                .seen
+                .get(&(query.clone(), key.clone()))
+                .map(|(sighting, _)| sighting.last_seen.clone())
+                .unwrap_or_else(|| view.server.cached_at.clone());
+            online.push(PlayerSighting {
+                name: player.clone(),
+                game_id: view.server.game_id,
+                server_id: view.server.server_id.clone(),
+                server_name: view.server.name.clone(),
+                last_seen,
+            });
+            online_keys.push(key);
+        }
+
+        let mut recent: Vec<(&PlayerSighting, DateTime<Utc>)> = self
+            .seen
+            .iter()
+            .filter(|((player, key), _)| *player == query && !online_keys.contains(key))
+            .map(|(_, (sighting, seen_at))| (sighting, *seen_at))
+            .collect();
+        recent.sort_by_key(|(_, seen_at)| std::cmp::Reverse(*seen_at));
+
+        PlayerSearch {
+            query: name.trim().to_string(),
+            online,
+            recent: recent.into_iter().map(|(sighting, _)| sighting.clone()).collect(),
+        }
+    }
+}
@@ -0,0 +1,454 @@
+use crate::ids::ServerId;
+use crate::players::MAX_PLAYER_NAME_LEN;
+use crate::view::ServerView;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+/// Cookie holding the comma-separated list-view columns, set client-side by sort.js
+pub const COLUMNS_COOKIE: &str = "list_columns";
+
+/// Signed cookie holding the visitor's starred server_ids
+pub const FAVORITES_COOKIE: &str = "favorites";
+
+/// Most servers a visitor can star (keeps the cookie well under browser limits)
+pub const MAX_FAVORITES: usize = 50;
+
+/// Cookie holding the visitor's homepage layout: visible widgets, comma-separated, in order
+pub const WIDGETS_COOKIE: &str = "widgets";
+
+/// Cookie holding the player names the visitor follows, comma-separated
+pub const FRIENDS_COOKIE: &str = "friends";
+
+/// Most friend names a visitor can follow
+pub const MAX_FRIENDS: usize = 20;
+
+/// Server cards rendered per page of the list
+pub const PAGE_SIZE: usize = 100;
+
+/// Optional columns in the list view (the server name is always shown)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ListColumn {
+    Players,
+    Version,
+    GameTime,
+    Mods,
+    Tags,
+    /// Highest player count seen in the last 24 hours
+    Peak,
+    /// Share of the last week the server was listed, as on the uptime badge
+    Uptime,
+    /// Host country and continent (needs GEOIP_DB)
+    Region,
+    /// Round trip estimated from the visitor's time zone and the host's continent, filled in by sort.js
+    Latency,
+}
+
+impl ListColumn {
+    /// All columns, in display order
+    pub const ALL: &'static [ListColumn] = &[
+        ListColumn::Players,
+        ListColumn::Peak,
+        ListColumn::Version,
+        ListColumn::GameTime,
+        ListColumn::Mods,
+        ListColumn::Tags,
+        ListColumn::Uptime,
+        ListColumn::Region,
+        ListColumn::Latency,
+    ];
+
+    /// Columns shown when no preference is set
+    pub const DEFAULT: &'static [ListColumn] = &[
+        ListColumn::Players,
+        ListColumn::Version,
+        ListColumn::GameTime,
+        ListColumn::Mods,
+    ];
+
+    /// Stable identifier used in the preferences cookie
+    pub fn key(&self) -> &'static str {
+        match self {
+            ListColumn::Players => "players",
+            ListColumn::Version => "version",
+            ListColumn::GameTime => "time",
+            ListColumn::Mods => "mods",
+            ListColumn::Tags => "tags",
+            ListColumn::Peak => "peak",
+            ListColumn::Uptime => "uptime",
+            ListColumn::Region => "region",
+            ListColumn::Latency => "latency",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|c| c.key() == key)
+    }
+
+    /// Header label in the list view
+    pub fn label(&self) -> &'static str {
+        match self {
+            ListColumn::Players => "Players",
+            ListColumn::Version => "Version",
+            ListColumn::GameTime => "Time",
+            ListColumn::Mods => "Mods",
+            ListColumn::Tags => "Tags",
+            ListColumn::Peak => "Peak 24h",
+            ListColumn::Uptime => "Uptime 7d",
+            ListColumn::Region => "Region",
+            ListColumn::Latency => "Latency",
+        }
+    }
+
+    /// Tailwind width/alignment classes shared by the header and row cells
+    pub fn cell_class(&self) -> &'static str {
+        match self {
+            ListColumn::Players => "w-[80px] text-center",
+            ListColumn::Version => "w-[70px] text-center",
+            ListColumn::GameTime => "w-[80px] text-center",
+            ListColumn::Mods => "w-[80px] text-right",
+            ListColumn::Tags => "w-[160px] text-left",
+            ListColumn::Peak => "w-[70px] text-center",
+            ListColumn::Uptime => "w-[80px] text-center",
+            ListColumn::Region => "w-[80px] text-center",
+            ListColumn::Latency => "w-[80px] text-center",
+        }
+    }
+}
+
+/// Parse the columns cookie, ignoring unknown keys and keeping display order
+/// Falls back to the default columns when nothing valid is selected
+pub fn parse_columns(value: &str) -> Vec<ListColumn> {
+    let selected: Vec<ListColumn> = value
+        .split(',')
+        .filter_map(|key| ListColumn::from_key(key.trim()))
+        .collect();
+
+    if selected.is_empty() {
+        return ListColumn::DEFAULT.to_vec();
+    }
+
+    ListColumn::ALL
+        .iter()
+        .copied()
+        .filter(|c| selected.contains(c))
+        .collect()
+}
+
+/// Blocks the homepage is built from (see components::widgets)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Widget {
+    /// Server, active server and player counts
+    Stats,
+    /// Servers gaining players fastest
+    Trending,
+    /// One highly ranked server, changing every hour
+    Spotlight,
+    /// Which of the visitor's friends are playing, and where
+    Friends,
+    Favorites,
+    /// Filters and the full server list
+    List,
+}
+
+impl Widget {
+    /// All widgets, in the order the customize panel lists them
+    pub const ALL: &'static [Widget] = &[
+        Widget::Stats,
+        Widget::Trending,
+        Widget::Spotlight,
+        Widget::Friends,
+        Widget::Favorites,
+        Widget::List,
+    ];
+
+    /// Layout when neither HOMEPAGE_WIDGETS nor the visitor says otherwise
+    pub const DEFAULT: &'static [Widget] = &[Widget::Stats, Widget::Trending, Widget::Favorites, Widget::List];
+
+    /// Stable identifier used in config and the layout cookie
+    pub fn key(&self) -> &'static str {
+        match self {
+            Widget::Stats => "stats",
+            Widget::Trending => "trending",
+            Widget::Spotlight => "spotlight",
+            Widget::Friends => "friends",
+            Widget::Favorites => "favorites",
+            Widget::List => "list",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|w| w.key() == key)
+    }
+
+    /// Name in the customize panel
+    pub fn label(&self) -> &'static str {
+        match self {
+            Widget::Stats => "Statistics",
+            Widget::Trending => "Trending",
+            Widget::Spotlight => "Spotlight",
+            Widget::Friends => "Friends online",
+            Widget::Favorites => "Favorites",
+            Widget::List => "Server list",
+        }
+    }
+}
+
+/// Parse a widget layout (HOMEPAGE_WIDGETS or the layout cookie), keeping its order
+/// Unknown and repeated keys are skipped; None when nothing valid is left
+pub fn parse_widgets(value: &str) -> Option<Vec<Widget>> {
+    let mut widgets: Vec<Widget> = Vec::new();
+    for widget in value.split(',').filter_map(|key| Widget::from_key(key.trim())) {
+        if !widgets.contains(&widget) {
+            widgets.push(widget);
+        }
+    }
+    (!widgets.is_empty()).then_some(widgets)
+}
+
+/// New layout from the customize form: `order` is every widget as the form listed them,
+/// `shown` the ticked ones, and `moved` a widget to shift one place up (true) or down among the shown ones
+pub fn arrange_widgets(order: &[Widget], shown: &[Widget], moved: Option<(Widget, bool)>) -> Vec<Widget> {
+    let mut layout: Vec<Widget> = order.iter().copied().filter(|w| shown.contains(w)).collect();
+    if let Some((widget, up)) = moved
+        && let Some(i) = layout.iter().position(|w| *w == widget)
+    {
+        if up && i > 0 {
+            layout.swap(i, i - 1);
+        } else if !up && i + 1 < layout.len() {
+            layout.swap(i, i + 1);
+        }
+    }
+    layout
+}
+
+/// Friend names: trimmed, deduplicated (ignoring case), capped at MAX_FRIENDS
+pub fn parse_friends<S: AsRef<str>>(names: &[S]) -> Vec<String> {
+    let mut friends: Vec<String> = Vec::new();
+    for name in names.iter().map(|n| n.as_ref().trim()) {
+        if name.is_empty() || name.len() > MAX_PLAYER_NAME_LEN || friends.iter().any(|f| f.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+        if friends.len() == MAX_FRIENDS {
+            break;
+        }
+        friends.push(name.to_string());
+    }
+    friends
+}
+
+/// Orders for the server list, matching the "Sort by" buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortKey {
+    /// The "Recommended" ranking score
+    #[default]
+    Score,
+    Name,
+    Players,
+    GameTime,
+}
+
+impl SortKey {
+    /// All sorts, in button order
+    pub const ALL: &'static [SortKey] = &[SortKey::Score, SortKey::Name, SortKey::Players, SortKey::GameTime];
+
+    /// Stable identifier used in the URL and sort.js (`data-sort`)
+    pub fn key(&self) -> &'static str {
+        match self {
+            SortKey::Score => "score",
+            SortKey::Name => "name",
+            SortKey::Players => "players",
+            SortKey::GameTime => "time",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|s| s.key() == key)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SortKey::Score => "Recommended",
+            SortKey::Name => "Name",
+            SortKey::Players => "Players",
+            SortKey::GameTime => "Game Time",
+        }
+    }
+}
+
+/// How the server list is ordered and laid out, as given in the URL so any list can be
+/// bookmarked and shared exactly as seen (sort.js keeps the URL in step with the page)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ListState {
+    pub sort: SortKey,
+    /// Flip the sort; every sort starts descending (▼), which for names means A→Z
+    pub ascending: bool,
+    /// List view instead of the card grid
+    pub list_view: bool,
+    /// Columns from the URL, taking precedence over the columns cookie
+    pub columns: Option<Vec<ListColumn>>,
+    /// Zero-based page of the list; `page` in the URL counts from 1
+    pub page: usize,
+}
+
+impl ListState {
+    /// Parse the `sort`, `dir`, `view` and `columns` parameters, ignoring unknown values
+    pub fn parse(sort: Option<&str>, dir: Option<&str>, view: Option<&str>, columns: Option<&str>) -> Self {
+        Self {
+            sort: sort.and_then(SortKey::from_key).unwrap_or_default(),
+            ascending: dir == Some("asc"),
+            list_view: view == Some("list"),
+            columns: columns.map(parse_columns),
+            page: 0,
+        }
+    }
+
+    /// This state on the given `page` parameter (1-based; 0 and missing mean the first page)
+    pub fn with_page(mut self, page: Option<usize>) -> Self {
+        self.page = page.unwrap_or(1).saturating_sub(1);
+        self
+    }
+
+    /// Query parameters for everything that differs from the defaults, for links that keep the state
+    /// The page is left out: a link that changes the filters or sort starts over on the first page
+    pub fn params(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        if self.sort != SortKey::default() {
+            params.push(("sort".to_string(), self.sort.key().to_string()));
+        }
+        if self.ascending {
+            params.push(("dir".to_string(), "asc".to_string()));
+        }
+        if self.list_view {
+            params.push(("view".to_string(), "list".to_string()));
+        }
+        if let Some(ref columns) = self.columns {
+            let keys: Vec<&str> = columns.iter().map(ListColumn::key).collect();
+            params.push(("columns".to_string(), keys.join(",")));
+        }
+        params
+    }
+
+    /// Order servers the way sort.js would, so the page is right before (or without) JavaScript
+    pub fn sort_servers(&self, servers: &mut [ServerView]) {
+        match self.sort {
+            SortKey::Score => servers.sort_by_key(|s| std::cmp::Reverse(s.score)),
+            SortKey::Name => servers.sort_by_cached_key(|s| s.server.name.to_lowercase()),
+            SortKey::Players => servers.sort_by_key(|s| std::cmp::Reverse(s.server.player_count)),
+            SortKey::GameTime => servers.sort_by_key(|s| std::cmp::Reverse(s.server.game_time_elapsed)),
+        }
+        if self.ascending {
+            servers.reverse();
+        }
+    }
+}
+
+/// Signs cookie values with HMAC-SHA256 so the server can trust what it set earlier
+pub struct CookieSigner {
+    key: Vec<u8>,
+}
+
+impl CookieSigner {
+    pub fn new(secret: &[u8]) -> Self {
+        Self { key: secret.to_vec() }
+    }
+
+    /// Signer with a random key; values it signs don't survive a restart
+    /// Fails rather than fall back to a guessable key when the OS has no randomness to give
+    #[cfg(feature = "server")]
+    pub fn random() -> Result<Self, getrandom::Error> {
+        let mut key = [0u8; 32];
+        getrandom::fill(&mut key)?;
+        Ok(Self::new(&key))
+    }
+
+    fn mac(&self, value: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(value.as_bytes());
+        mac
+    }
+
+    /// Hex signature of `value`: a keyed hash, for storing identifiers that shouldn't be readable
+    pub fn digest(&self, value: &str) -> String {
+        self.mac(value)
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// `value` followed by "." and its hex signature
+    pub fn sign(&self, value: &str) -> String {
+        format!("{}.{}", value, self.digest(value))
+    }
+
+    /// The original value, if the signature matches
+    pub fn verify<'a>(&self, signed: &'a str) -> Option<&'a str> {
+        let (value, signature) = signed.rsplit_once('.')?;
+        if signature.len() % 2 != 0 || !signature.is_ascii() {
+            return None;
+        }
+        let bytes: Vec<u8> = (0..signature.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&signature[i..i + 2], 16).ok())
+            .collect::<Option<_>>()?;
+        self.mac(value).verify_slice(&bytes).ok()?;
+        Some(value)
+    }
+}
+
+/// Parse the (already verified) favorites cookie value into server_ids
+pub fn parse_favorites(value: &str) -> Vec<ServerId> {
+    let mut favorites: Vec<ServerId> = Vec::new();
+    for id in value.split(',').map(str::trim).filter(|id| !id.is_empty()) {
+        if !favorites.iter().any(|f| f == id) && favorites.len() < MAX_FAVORITES {
+            favorites.push(ServerId::from(id));
+        }
+    }
+    favorites
+}
+
+/// Star or unstar a server, returning the new favorites cookie value (unsigned)
+/// Newly starred servers go last; once MAX_FAVORITES is reached nothing new is added
+pub fn toggle_favorite(favorites: &[ServerId], server_id: &ServerId) -> String {
+    let mut updated: Vec<&str> = favorites.iter().map(ServerId::as_str).filter(|f| *f != server_id.as_str()).collect();
+    if updated.len() == favorites.len() && updated.len() < MAX_FAVORITES {
+        updated.push(server_id);
+    }
+    updated.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_values_verify() {
+        let signer = CookieSigner::new(b"secret");
+        let signed = signer.sign("a,b");
+        assert_eq!(signer.verify(&signed), Some("a,b"));
+        assert_eq!(signer.verify(&signed.replacen("a,b", "a,c", 1)), None);
+        assert_eq!(CookieSigner::new(b"other").verify(&signed), None);
+        assert_eq!(signer.verify("a,b"), None);
+    }
+
+    #[test]
+    fn columns_parse_in_display_order() {
+        assert_eq!(
+            parse_columns("latency,uptime,bogus,players,region"),
+            vec![ListColumn::Players, ListColumn::Uptime, ListColumn::Region, ListColumn::Latency]
+        );
+        assert_eq!(parse_columns("bogus"), ListColumn::DEFAULT);
+        assert!(ListColumn::ALL.iter().all(|c| ListColumn::from_key(c.key()) == Some(*c)));
+    }
+
+    #[cfg(feature = "server")]
+    #[test]
+    fn random_keys_differ() {
+        let first = CookieSigner::random().expect("randomness");
+        let second = CookieSigner::random().expect("randomness");
+        assert_ne!(first.digest("value"), second.digest("value"));
+        assert_ne!(first.digest("value"), CookieSigner::new(&[0; 32]).digest("value"));
+    }
+}
@@ -0,0 +1,383 @@
+use crate::db::models::PushSubscription;
+use crate::ids::ServerId;
+use crate::utils::strip_all_tags;
+use crate::view::ServerView;
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes128Gcm, KeyInit, Nonce};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hkdf::Hkdf;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::{PublicKey, SecretKey};
+use reqwest::Client;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Signed cookie holding the visitor's push subscription id
+pub const PUSH_COOKIE: &str = "push";
+
+/// Only browser push services are accepted, so visitors can't make us POST to arbitrary URLs
+const PUSH_SERVICE_HOSTS: &[&str] = &[
+    "fcm.googleapis.com",
+    "push.services.mozilla.com",
+    "notify.windows.com",
+    "push.apple.com",
+];
+
+/// Longest subscription endpoint accepted (real ones are a few hundred bytes)
+const MAX_ENDPOINT_LEN: usize = 1024;
+
+/// How long the push service keeps an undelivered message, in seconds
+const MESSAGE_TTL_SECS: u32 = 3600;
+
+/// VAPID tokens are valid for up to 24 hours; stay well inside that
+const JWT_LIFETIME_SECS: i64 = 12 * 3600;
+
+/// Record size advertised in the aes128gcm header (one record is always enough here)
+const RECORD_SIZE: u32 = 4096;
+
+/// Error type for push delivery
+#[derive(Debug)]
+pub enum PushError {
+    RequestFailed(reqwest::Error),
+    /// The subscription expired or was revoked by the browser
+    SubscriptionGone,
+    /// The stored keys can't be used for encryption
+    InvalidSubscription,
+    Rejected(String),
+    /// The OS had no randomness for a key or salt
+    Randomness(getrandom::Error),
+}
+
+impl std::fmt::Display for PushError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PushError::RequestFailed(e) => write!(f, "Request failed: {}", e),
+            PushError::SubscriptionGone => write!(f, "Subscription no longer exists"),
+            PushError::InvalidSubscription => write!(f, "Subscription keys are invalid"),
+            PushError::Rejected(msg) => write!(f, "Push service rejected message: {}", msg),
+            PushError::Randomness(e) => write!(f, "No randomness for encryption: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PushError {}
+
+impl From<reqwest::Error> for PushError {
+    fn from(err: reqwest::Error) -> Self {
+        PushError::RequestFailed(err)
+    }
+}
+
+impl From<getrandom::Error> for PushError {
+    fn from(err: getrandom::Error) -> Self {
+        PushError::Randomness(err)
+    }
+}
+
+/// Check that a subscription endpoint belongs to a known browser push service
+pub fn is_allowed_endpoint(endpoint: &str) -> bool {
+    if endpoint.len() > MAX_ENDPOINT_LEN {
+        return false;
+    }
+    let Some(rest) = endpoint.strip_prefix("https://") else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    PUSH_SERVICE_HOSTS
+        .iter()
+        .any(|allowed| host == *allowed || host.ends_with(&format!(".{}", allowed)))
+        && rest.len() > host.len() + 1
+}
+
+/// Check that a browser's subscription keys are usable: a P-256 public key and a 16-byte auth secret
+pub fn valid_keys(p256dh: &str, auth: &str) -> bool {
+    let key_ok = URL_SAFE_NO_PAD
+        .decode(p256dh.trim_end_matches('='))
+        .is_ok_and(|bytes| PublicKey::from_sec1_bytes(&bytes).is_ok());
+    let auth_ok = URL_SAFE_NO_PAD.decode(auth.trim_end_matches('=')).is_ok_and(|bytes| bytes.len() == 16);
+    key_ok && auth_ok
+}
+
+/// Record id for a subscription, derived from its endpoint so resubscribing updates it in place
+pub fn subscription_id(endpoint: &str) -> String {
+    Sha256::digest(endpoint.as_bytes())
+        .iter()
+        .take(16)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A fresh random P-256 secret key
+fn random_secret_key() -> Result<SecretKey, PushError> {
+    loop {
+        let mut bytes = [0u8; 32];
+        getrandom::fill(&mut bytes)?;
+        // Out-of-range scalars are astronomically rare, but possible
+        if let Ok(key) = SecretKey::from_slice(&bytes) {
+            return Ok(key);
+        }
+    }
+}
+
+/// Application server (VAPID) key pair identifying this site to push services
+pub struct VapidKeys {
+    signing: SigningKey,
+    /// Uncompressed public key, base64url, as the browser's applicationServerKey
+    public_key: String,
+}
+
+impl VapidKeys {
+    /// Keys from the raw private scalar in base64url, as printed by `vapid-keys`
+    pub fn from_base64(private_key: &str) -> Result<Self, String> {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(private_key.trim().trim_end_matches('='))
+            .map_err(|e| e.to_string())?;
+        let secret = SecretKey::from_slice(&bytes).map_err(|e| e.to_string())?;
+        Ok(Self::from_secret(secret))
+    }
+
+    pub fn generate() -> Result<Self, PushError> {
+        Ok(Self::from_secret(random_secret_key()?))
+    }
+
+    fn from_secret(secret: SecretKey) -> Self {
+        let public_key = URL_SAFE_NO_PAD.encode(secret.public_key().to_encoded_point(false).as_bytes());
+        Self {
+            signing: SigningKey::from(secret),
+            public_key,
+        }
+    }
+
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    pub fn private_key(&self) -> String {
+        URL_SAFE_NO_PAD.encode(self.signing.to_bytes())
+    }
+
+    /// ES256 JWT for the `Authorization: vapid` header
+    fn token(&self, audience: &str, subject: &str) -> String {
+        let header = URL_SAFE_NO_PAD.encode(br#"{"typ":"JWT","alg":"ES256"}"#);
+        let claims = serde_json::json!({
+            "aud": audience,
+            "exp": chrono::Utc::now().timestamp() + JWT_LIFETIME_SECS,
+            "sub": subject,
+        });
+        let claims = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let signing_input = format!("{}.{}", header, claims);
+        let signature: Signature = self.signing.sign(signing_input.as_bytes());
+        format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature.to_bytes()))
+    }
+}
+
+/// Encrypt a payload for one subscription (RFC 8291, aes128gcm content encoding)
+fn encrypt(p256dh: &str, auth: &str, payload: &[u8]) -> Result<Vec<u8>, PushError> {
+    let ua_public_bytes = URL_SAFE_NO_PAD.decode(p256dh.trim_end_matches('=')).map_err(|_| PushError::InvalidSubscription)?;
+    let auth_secret = URL_SAFE_NO_PAD.decode(auth.trim_end_matches('=')).map_err(|_| PushError::InvalidSubscription)?;
+    let ua_public = PublicKey::from_sec1_bytes(&ua_public_bytes).map_err(|_| PushError::InvalidSubscription)?;
+
+    let as_secret = random_secret_key()?;
+    let as_public = as_secret.public_key().to_encoded_point(false);
+    let shared = p256::ecdh::diffie_hellman(as_secret.to_nonzero_scalar(), ua_public.as_affine());
+
+    // IKM = HKDF(auth_secret, ecdh_secret, "WebPush: info" || 0 || ua_public || as_public)
+    let mut key_info = b"WebPush: info\0".to_vec();
+    key_info.extend_from_slice(&ua_public_bytes);
+    key_info.extend_from_slice(as_public.as_bytes());
+    let mut ikm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(&auth_secret), shared.raw_secret_bytes())
+        .expand(&key_info, &mut ikm)
+        .map_err(|_| PushError::InvalidSubscription)?;
+
+    let mut salt = [0u8; 16];
+    getrandom::fill(&mut salt)?;
+    let prk = Hkdf::<Sha256>::new(Some(&salt), &ikm);
+    let mut cek = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    prk.expand(b"Content-Encoding: aes128gcm\0", &mut cek)
+        .and_then(|_| prk.expand(b"Content-Encoding: nonce\0", &mut nonce))
+        .map_err(|_| PushError::InvalidSubscription)?;
+
+    // A single record, so the padding delimiter is 2 (last record)
+    let mut plaintext = payload.to_vec();
+    plaintext.push(2);
+    let ciphertext = Aes128Gcm::new(&cek.into())
+        .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+        .map_err(|_| PushError::InvalidSubscription)?;
+
+    let mut body = Vec::with_capacity(16 + 4 + 1 + 65 + ciphertext.len());
+    body.extend_from_slice(&salt);
+    body.extend_from_slice(&RECORD_SIZE.to_be_bytes());
+    body.push(as_public.as_bytes().len() as u8);
+    body.extend_from_slice(as_public.as_bytes());
+    body.extend_from_slice(&ciphertext);
+    Ok(body)
+}
+
+/// Notification shown by the service worker
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PushMessage {
+    pub title: String,
+    pub body: String,
+    /// Page opened when the notification is clicked
+    pub url: String,
+}
+
+/// Web Push sender
+pub struct PushSender {
+    client: Client,
+    vapid: VapidKeys,
+    /// Contact for push services, a mailto: or https: URL
+    subject: String,
+}
+
+impl PushSender {
+    /// Create a new sender wrapped in Arc for sharing
+    pub fn new_shared(vapid: VapidKeys, subject: &str) -> Arc<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("default HTTP client settings are valid");
+        Arc::new(Self {
+            client,
+            vapid,
+            subject: subject.to_string(),
+        })
+    }
+
+    /// Public key the browser subscribes with
+    pub fn public_key(&self) -> &str {
+        self.vapid.public_key()
+    }
+
+    /// Deliver one notification to one subscription
+    pub async fn send(&self, subscription: &PushSubscription, message: &PushMessage) -> Result<(), PushError> {
+        let payload = serde_json::to_vec(message).expect("push messages always serialize");
+        let body = encrypt(&subscription.p256dh, &subscription.auth, &payload)?;
+
+        // The audience is the push service's origin
+        let audience: String = subscription.endpoint.splitn(4, '/').take(3).collect::<Vec<_>>().join("/");
+        let token = self.vapid.token(&audience, &self.subject);
+
+        let response = self
+            .client
+            .post(&subscription.endpoint)
+            .header("TTL", MESSAGE_TTL_SECS.to_string())
+            .header("Content-Encoding", "aes128gcm")
+            .header("Content-Type", "application/octet-stream")
+            .header("Authorization", format!("vapid t={}, k={}", token, self.vapid.public_key()))
+            .body(body)
+            .send()
+            .await?;
+
+        match response.status().as_u16() {
+            200..=299 => Ok(()),
+            404 | 410 => Err(PushError::SubscriptionGone),
+            _ => {
+                let status = response.status();
+                let text = response.text().await.unwrap_or_default();
+                Err(PushError::Rejected(format!("{}: {}", status, text)))
+            }
+        }
+    }
+}
+
+/// Servers listed now whose server_id wasn't listed in the previous refresh
+pub fn came_online<'a>(previous: &[ServerView], current: &'a [ServerView]) -> Vec<&'a ServerView> {
+    let before: HashSet<&ServerId> = previous.iter().filter_map(|s| s.server.server_id.as_ref()).collect();
+    current
+        .iter()
+        .filter(|s| s.server.server_id.as_ref().is_some_and(|id| !before.contains(id)))
+        .collect()
+}
+
+/// Players (lowercased) who are on a server now but weren't on it in the previous refresh
+pub fn players_joined<'a>(previous: &[ServerView], current: &'a [ServerView]) -> Vec<(String, &'a ServerView)> {
+    let before: HashMap<&ServerId, HashSet<String>> = previous
+        .iter()
+        .filter_map(|s| {
+            let id = s.server.server_id.as_ref()?;
+            Some((id, s.server.players.iter().map(|p| p.to_lowercase()).collect()))
+        })
+        .collect();
+
+    let mut joined = Vec::new();
+    for view in current {
+        let Some(id) = view.server.server_id.as_ref() else {
+            continue;
+        };
+        for player in &view.server.players {
+            let name = player.to_lowercase();
+            if !before.get(id).is_some_and(|players| players.contains(&name)) {
+                joined.push((name, view));
+            }
+        }
+    }
+    joined
+}
+
+/// Notifications one subscriber gets for a refresh: watched servers coming online and friends joining
+/// `site_url` makes the links absolute; it has no trailing slash
+pub fn messages_for(
+    subscription: &PushSubscription,
+    online: &[&ServerView],
+    joined: &[(String, &ServerView)],
+    site_url: &str,
+) -> Vec<PushMessage> {
+    let mut messages = Vec::new();
+    for view in online {
+        if view.server.server_id.as_ref().is_some_and(|id| subscription.server_ids.contains(id)) {
+            messages.push(PushMessage {
+                title: format!("{} is online", strip_all_tags(&view.server.name)),
+                body: format!("{} players · {}", view.players_display, view.server.game_version),
+                url: format!("{}/server/{}", site_url, view.server.game_id),
+            });
+        }
+    }
+    for (player, view) in joined {
+        if let Some(friend) = subscription.friends.iter().find(|f| f.to_lowercase() == *player) {
+            messages.push(PushMessage {
+                title: format!("{} joined a server", friend),
+                body: strip_all_tags(&view.server.name),
+                url: format!("{}/server/{}", site_url, view.server.game_id),
+            });
+        }
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_keys_round_trip() {
+        let keys = VapidKeys::generate().expect("randomness");
+        let loaded = VapidKeys::from_base64(&keys.private_key()).expect("valid key");
+        assert_eq!(loaded.public_key(), keys.public_key());
+        assert_ne!(VapidKeys::generate().expect("randomness").public_key(), keys.public_key());
+    }
+
+    #[test]
+    fn every_message_gets_a_fresh_salt_and_key() {
+        let browser = random_secret_key().expect("randomness");
+        let p256dh = URL_SAFE_NO_PAD.encode(browser.public_key().to_encoded_point(false).as_bytes());
+        let auth = URL_SAFE_NO_PAD.encode([7u8; 16]);
+        assert!(valid_keys(&p256dh, &auth));
+
+        let first = encrypt(&p256dh, &auth, b"hello").expect("encrypts");
+        let second = encrypt(&p256dh, &auth, b"hello").expect("encrypts");
+        // salt (16) + record size (4) + key length (1) + key (65) + payload, delimiter and tag
+        assert_eq!(first.len(), 16 + 4 + 1 + 65 + 5 + 1 + 16);
+        assert_ne!(first[..16], [0u8; 16]);
+        assert_ne!(first[..16], second[..16]);
+        assert_ne!(first[21..86], second[21..86]);
+    }
+}
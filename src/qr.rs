@@ -0,0 +1,19 @@
+use qrcode::render::svg;
+use qrcode::{EcLevel, QrCode};
+
+/// Smallest rendered size of a QR code, in pixels
+const QR_MIN_SIZE: u32 = 200;
+
+/// Render `data` as a QR code in a standalone SVG (black on white, with the standard quiet zone)
+/// None when the data doesn't fit in a QR code
+pub fn render_qr_svg(data: &str) -> Option<String> {
+    let code = QrCode::with_error_correction_level(data.as_bytes(), EcLevel::M).ok()?;
+    Some(
+        code.render::<svg::Color>()
+            .min_dimensions(QR_MIN_SIZE, QR_MIN_SIZE)
+            .dark_color(svg::Color("#000000"))
+            .light_color(svg::Color("#ffffff"))
+            .quiet_zone(true)
+            .build(),
+    )
+}
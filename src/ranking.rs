@@ -0,0 +1,214 @@
+use crate::db::models::CachedServer;
+use crate::utils::strip_all_tags;
+use std::collections::HashMap;
+
+/// Player counts at or above this score the full players (and activity) component
+const PLAYERS_CEILING: f64 = 100.0;
+
+/// Uptime assumed for servers without presence records yet, so new servers aren't buried
+const UNKNOWN_UPTIME: f64 = 0.5;
+
+/// Tags beyond this are keyword stuffing rather than description
+const MAX_REASONABLE_TAGS: usize = 12;
+
+/// Visible name length beyond which a name counts as spammy
+const MAX_REASONABLE_NAME_CHARS: usize = 60;
+
+/// Listings sharing one name beyond this are treated as a flood
+const MAX_SAME_NAME: usize = 3;
+
+/// How much each signal counts towards the "Recommended" sort
+/// Every signal is scaled to 0..1 before weighting, so the weights compare directly
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankingWeights {
+    /// Players online right now
+    pub players: f64,
+    /// Highest player count in the last 24 hours
+    pub activity: f64,
+    /// Share of the last 7 days the server was listed
+    pub uptime: f64,
+    /// Bonus for servers on the VERIFIED_SERVERS list
+    pub verified: f64,
+    /// Penalty for spammy listings (name flooding, tag stuffing, shouting)
+    pub spam: f64,
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            players: 1.0,
+            activity: 0.5,
+            uptime: 0.3,
+            verified: 0.5,
+            spam: 1.0,
+        }
+    }
+}
+
+impl RankingWeights {
+    /// Parse from config: comma-separated `signal=weight` pairs, e.g. "players=2,spam=0"
+    /// Signals left out keep their default weight
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        Self::default().with_overrides(spec)
+    }
+
+    /// These weights with the signals named in `spec` (same format as parse) replaced
+    pub fn with_overrides(self, spec: &str) -> Result<Self, String> {
+        let mut weights = self;
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (name, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected signal=weight, got '{}'", pair))?;
+            let value: f64 = value
+                .trim()
+                .parse()
+                .ok()
+                .filter(|v: &f64| v.is_finite() && *v >= 0.0)
+                .ok_or_else(|| format!("invalid weight for {}: '{}'", name.trim(), value.trim()))?;
+            match name.trim().to_lowercase().as_str() {
+                "players" => weights.players = value,
+                "activity" => weights.activity = value,
+                "uptime" => weights.uptime = value,
+                "verified" => weights.verified = value,
+                "spam" => weights.spam = value,
+                other => return Err(format!("unknown signal '{}'", other)),
+            }
+        }
+        Ok(weights)
+    }
+}
+
+/// Per-server inputs to the ranking score, gathered at refresh time
+#[derive(Debug, Clone, Default)]
+pub struct RankingSignals {
+    pub peak_players: Option<usize>,
+    /// Percentage of the last 7 days the server was listed, when presence is tracked
+    pub uptime: Option<f64>,
+    pub verified: bool,
+    /// 0 for a clean listing, up to 1 for an obvious spammer
+    pub spam: f64,
+}
+
+/// Player count scaled to 0..1 on a log curve, so 5 vs 10 players matters more than 105 vs 110
+fn scale_players(count: usize) -> f64 {
+    ((count as f64).ln_1p() / PLAYERS_CEILING.ln_1p()).min(1.0)
+}
+
+/// Number of listings per visible name (lowercased), for spotting name floods
+pub fn name_counts(servers: &[CachedServer]) -> HashMap<String, usize> {
+    let mut counts = HashMap::new();
+    for server in servers {
+        *counts.entry(strip_all_tags(&server.name).trim().to_lowercase()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Spam penalty for a listing, 0..1, from cheap heuristics on its name and tags
+/// `same_name` is how many listings (including this one) share its visible name
+pub fn spam_penalty(server: &CachedServer, same_name: usize) -> f64 {
+    let name = strip_all_tags(&server.name);
+    let letters: Vec<char> = name.chars().filter(|c| c.is_alphabetic()).collect();
+    let shouting = letters.len() >= 10 && letters.iter().all(|c| !c.is_lowercase());
+
+    let flags = [
+        same_name > MAX_SAME_NAME,
+        server.tags.len() > MAX_REASONABLE_TAGS,
+        name.chars().count() > MAX_REASONABLE_NAME_CHARS,
+        shouting,
+    ];
+    (flags.iter().filter(|&&f| f).count() as f64 / 2.0).min(1.0)
+}
+
+/// Ranking score for the "Recommended" sort, higher first
+/// Returned in hundredths so it sorts as an integer on the client
+pub fn score(weights: &RankingWeights, server: &CachedServer, signals: &RankingSignals) -> i64 {
+    let players = scale_players(server.player_count);
+    let activity = signals.peak_players.map(scale_players).unwrap_or(players);
+    let uptime = signals.uptime.map(|u| (u / 100.0).clamp(0.0, 1.0)).unwrap_or(UNKNOWN_UPTIME);
+    let verified = if signals.verified { 1.0 } else { 0.0 };
+
+    let total = weights.players * players
+        + weights.activity * activity
+        + weights.uptime * uptime
+        + weights.verified * verified
+        - weights.spam * signals.spam;
+    (total * 100.0).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str, player_count: usize, tags: usize) -> CachedServer {
+        serde_json::from_value(serde_json::json!({
+            "game_id": 1,
+            "name": name,
+            "max_players": 0,
+            "player_count": player_count,
+            "game_time_elapsed": 0,
+            "has_password": false,
+            "tags": (0..tags).map(|i| format!("tag{}", i)).collect::<Vec<_>>(),
+            "game_version": "2.0.28",
+            "build_version": 0,
+            "cached_at": "2026-01-01T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn overrides_replace_only_named_signals() {
+        let weights = RankingWeights::parse(" Players = 2 , spam=0,").unwrap();
+        assert_eq!(weights, RankingWeights { players: 2.0, spam: 0.0, ..RankingWeights::default() });
+        assert_eq!(RankingWeights::parse("").unwrap(), RankingWeights::default());
+    }
+
+    #[test]
+    fn bad_overrides_are_rejected() {
+        assert_eq!(RankingWeights::parse("fame=1").unwrap_err(), "unknown signal 'fame'");
+        assert!(RankingWeights::parse("players").is_err());
+        assert!(RankingWeights::parse("players=-1").is_err());
+        assert!(RankingWeights::parse("players=NaN").is_err());
+        assert!(RankingWeights::parse("players=inf").is_err());
+        assert!(RankingWeights::parse("players=lots").is_err());
+    }
+
+    #[test]
+    fn spam_penalty_adds_up_and_saturates() {
+        assert_eq!(spam_penalty(&server("Big Base", 0, 3), 1), 0.0);
+        assert_eq!(spam_penalty(&server("Big Base", 0, 3), MAX_SAME_NAME + 1), 0.5);
+        assert_eq!(spam_penalty(&server("[color=red]BEST SERVER EVER[/color]", 0, 3), 1), 0.5);
+        // Short names in capitals aren't shouting
+        assert_eq!(spam_penalty(&server("PVP EU", 0, 3), 1), 0.0);
+        let everything = server(&"FREE DIAMONDS ".repeat(6), 0, MAX_REASONABLE_TAGS + 1);
+        assert_eq!(spam_penalty(&everything, MAX_SAME_NAME + 1), 1.0);
+    }
+
+    #[test]
+    fn unknown_uptime_scores_as_half() {
+        let weights = RankingWeights { players: 0.0, activity: 0.0, uptime: 1.0, verified: 0.0, spam: 0.0 };
+        let signals = |uptime| RankingSignals { uptime, ..RankingSignals::default() };
+        assert_eq!(score(&weights, &server("Big Base", 0, 0), &signals(None)), (UNKNOWN_UPTIME * 100.0) as i64);
+        assert_eq!(score(&weights, &server("Big Base", 0, 0), &signals(Some(100.0))), 100);
+        assert_eq!(score(&weights, &server("Big Base", 0, 0), &signals(Some(250.0))), 100);
+    }
+
+    #[test]
+    fn missing_peak_counts_current_players() {
+        let weights = RankingWeights { players: 0.0, activity: 1.0, uptime: 0.0, verified: 0.0, spam: 0.0 };
+        let busy = server("Big Base", 100, 0);
+        assert_eq!(score(&weights, &busy, &RankingSignals::default()), 100);
+        let peaked = RankingSignals { peak_players: Some(0), ..RankingSignals::default() };
+        assert_eq!(score(&weights, &busy, &peaked), 0);
+    }
+
+    #[test]
+    fn spam_and_verification_shift_the_score() {
+        let weights = RankingWeights::default();
+        let base = server("Big Base", 10, 0);
+        let plain = score(&weights, &base, &RankingSignals::default());
+        let verified = RankingSignals { verified: true, ..RankingSignals::default() };
+        let spammy = RankingSignals { spam: 1.0, ..RankingSignals::default() };
+        assert_eq!(score(&weights, &base, &verified), plain + 50);
+        assert_eq!(score(&weights, &base, &spammy), plain - 100);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::compression::{Encoding, MIN_COMPRESS_BYTES};
+use crate::metrics::RenderCacheStats;
+use rocket::http::{ContentType, Header};
+use rocket::response::{self, Responder, Response};
+use rocket::Request;
+use std::collections::{HashMap, VecDeque};
+use std::io::Cursor;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+use tokio::sync::watch;
+
+/// Pages kept unless RENDER_CACHE_ENTRIES says otherwise; a server list page is a few hundred KB of
+/// HTML plus its compressed copies
+pub const DEFAULT_RENDER_CACHE_ENTRIES: usize = 64;
+
+/// One rendered page, with its brotli and gzip copies made the first time a client asks for each
+#[derive(Debug)]
+struct RenderedPage {
+    /// Cache generation the page was rendered from
+    generation: u64,
+    etag: String,
+    html: Arc<[u8]>,
+    /// None when compressing failed, so the page is sent as is rather than compressed again
+    brotli: OnceLock<Option<Arc<[u8]>>>,
+    gzip: OnceLock<Option<Arc<[u8]>>>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    /// Latest rendering of each page, current or not
+    pages: HashMap<String, Arc<RenderedPage>>,
+    /// Keys least recently rendered first, for dropping one when full
+    order: VecDeque<String>,
+    /// Pages being rendered right now; the channel closes when the render ends, however it ends
+    rendering: HashMap<String, watch::Receiver<()>>,
+}
+
+/// What a request should do about a page
+pub enum Lookup {
+    /// Rendered from the current listing (or a newer one)
+    Fresh(CachedHtml),
+    /// The previous rendering, served while another request renders the current one
+    Stale(CachedHtml),
+    /// Nobody is rendering the page: this request does, and hands it to `RenderTicket::finish`
+    Render(RenderTicket),
+}
+
+/// Server-rendered pages kept across requests, keyed by everything they depend on besides the
+/// listing, each kept compressed per encoding so a hot page is rendered and compressed once per
+/// refresh instead of on every request
+/// Only one request renders a page at a time: while it does, others get the previous rendering, or
+/// wait for this one when there is none, so a refresh doesn't set every visitor rendering at once
+#[derive(Debug)]
+pub struct RenderCache {
+    /// 0 turns caching off (pages are still compressed, just not kept)
+    capacity: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    stale: AtomicU64,
+    coalesced: AtomicU64,
+    brotli_compressions: AtomicU64,
+    gzip_compressions: AtomicU64,
+    compression_micros: AtomicU64,
+}
+
+impl RenderCache {
+    pub fn new_shared(capacity: usize) -> Arc<Self> {
+        Arc::new(Self {
+            capacity,
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            stale: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+            brotli_compressions: AtomicU64::new(0),
+            gzip_compressions: AtomicU64::new(0),
+            compression_micros: AtomicU64::new(0),
+        })
+    }
+
+    /// The page under `key` for cache generation `generation`, or the go-ahead to render it
+    pub async fn lookup(self: &Arc<Self>, generation: u64, key: &str) -> Lookup {
+        let mut waited = false;
+        loop {
+            let mut done = {
+                let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+                let page = entries.pages.get(key).cloned();
+                match (page, entries.rendering.get(key)) {
+                    (Some(page), _) if page.generation >= generation => {
+                        let counter = if waited { &self.coalesced } else { &self.hits };
+                        counter.fetch_add(1, Ordering::Relaxed);
+                        return Lookup::Fresh(self.cached(page));
+                    }
+                    (Some(page), Some(_)) => {
+                        self.stale.fetch_add(1, Ordering::Relaxed);
+                        return Lookup::Stale(self.cached(page));
+                    }
+                    (None, Some(rendering)) => rendering.clone(),
+                    (_, None) => {
+                        self.misses.fetch_add(1, Ordering::Relaxed);
+                        let (done, rendering) = watch::channel(());
+                        if self.capacity > 0 {
+                            entries.rendering.insert(key.to_string(), rendering);
+                        }
+                        return Lookup::Render(RenderTicket {
+                            cache: self.clone(),
+                            generation,
+                            key: key.to_string(),
+                            _done: done,
+                        });
+                    }
+                }
+            };
+            // Wakes when the other render ends; if it was abandoned, this request takes over
+            waited = true;
+            let _ = done.changed().await;
+        }
+    }
+
+    pub fn stats(&self) -> RenderCacheStats {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner()).pages.len();
+        RenderCacheStats {
+            entries,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            stale: self.stale.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            brotli_compressions: self.brotli_compressions.load(Ordering::Relaxed),
+            gzip_compressions: self.gzip_compressions.load(Ordering::Relaxed),
+            compression_seconds: self.compression_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0,
+        }
+    }
+
+    fn cached(self: &Arc<Self>, page: Arc<RenderedPage>) -> CachedHtml {
+        CachedHtml {
+            page,
+            cache: self.clone(),
+        }
+    }
+
+    /// Keep a freshly rendered page in place of its previous rendering, dropping the least recently
+    /// rendered page when full
+    fn store(&self, key: &str, page: &Arc<RenderedPage>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        // A request still holding an older listing mustn't replace a newer rendering
+        if entries.pages.get(key).is_some_and(|kept| kept.generation > page.generation) {
+            return;
+        }
+        entries.order.retain(|k| k != key);
+        while entries.pages.len() >= self.capacity && !entries.pages.contains_key(key) {
+            let Some(oldest) = entries.order.pop_front() else {
+                break;
+            };
+            entries.pages.remove(&oldest);
+        }
+        entries.order.push_back(key.to_string());
+        entries.pages.insert(key.to_string(), page.clone());
+    }
+
+    /// A page's body in `encoding`, compressing it the first time it's asked for
+    fn encoded(&self, page: &RenderedPage, encoding: Encoding) -> Option<Arc<[u8]>> {
+        let (slot, counter) = match encoding {
+            Encoding::Brotli => (&page.brotli, &self.brotli_compressions),
+            Encoding::Gzip => (&page.gzip, &self.gzip_compressions),
+        };
+        slot.get_or_init(|| {
+            let start = Instant::now();
+            let compressed = encoding.compress(&page.html);
+            counter.fetch_add(1, Ordering::Relaxed);
+            self.compression_micros
+                .fetch_add(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+            match compressed {
+                Ok(body) => Some(Arc::from(body)),
+                Err(e) => {
+                    eprintln!("Failed to compress cached page: {}", e);
+                    None
+                }
+            }
+        })
+        .clone()
+    }
+}
+
+/// The one request rendering a page; requests waiting on it are released when it's finished or dropped
+pub struct RenderTicket {
+    cache: Arc<RenderCache>,
+    generation: u64,
+    key: String,
+    /// Closing this wakes the waiting requests
+    _done: watch::Sender<()>,
+}
+
+impl RenderTicket {
+    /// Keep the rendered page (tagged `etag`) and serve it
+    pub fn finish(self, etag: String, html: String) -> CachedHtml {
+        let page = Arc::new(RenderedPage {
+            generation: self.generation,
+            etag,
+            html: Arc::from(html.into_bytes()),
+            brotli: OnceLock::new(),
+            gzip: OnceLock::new(),
+        });
+        if self.cache.capacity > 0 {
+            self.cache.store(&self.key, &page);
+        }
+        self.cache.cached(page)
+    }
+}
+
+impl Drop for RenderTicket {
+    fn drop(&mut self) {
+        let mut entries = self.cache.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.rendering.remove(&self.key);
+    }
+}
+
+/// An HTML page from the render cache, sent in the best encoding the client accepts
+/// Leaves the response compression fairing nothing to do, since the body is already encoded
+pub struct CachedHtml {
+    page: Arc<RenderedPage>,
+    cache: Arc<RenderCache>,
+}
+
+impl CachedHtml {
+    /// The ETag the page was rendered under, older than the current one for a stale page
+    pub fn etag(&self) -> &str {
+        &self.page.etag
+    }
+}
+
+impl<'r> Responder<'r, 'static> for CachedHtml {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let encoding = req
+            .headers()
+            .get_one("Accept-Encoding")
+            .and_then(Encoding::negotiate)
+            .filter(|_| self.page.html.len() >= MIN_COMPRESS_BYTES);
+        let encoded = encoding.and_then(|encoding| Some((encoding, self.cache.encoded(&self.page, encoding)?)));
+
+        let mut response = Response::build();
+        response.header(ContentType::HTML);
+        match encoded {
+            Some((encoding, body)) => {
+                response
+                    .header(Header::new("Content-Encoding", encoding.name()))
+                    .raw_header_adjoin("Vary", "Accept-Encoding")
+                    .sized_body(body.len(), Cursor::new(body));
+            }
+            None => {
+                let html = self.page.html.clone();
+                response.sized_body(html.len(), Cursor::new(html));
+            }
+        }
+        response.ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Render `key` at `generation`, which must be a miss
+    async fn render(cache: &Arc<RenderCache>, generation: u64, key: &str, html: &str) {
+        match cache.lookup(generation, key).await {
+            Lookup::Render(ticket) => {
+                ticket.finish(format!("\"{}\"", html), html.to_string());
+            }
+            _ => panic!("expected a miss for {}", key),
+        }
+    }
+
+    fn body(page: &CachedHtml) -> &[u8] {
+        &page.page.html
+    }
+
+    /// Poll a lookup until it blocks, so it's waiting on the rendering before the test goes on
+    async fn wait_briefly<F: std::future::Future<Output = Lookup> + Unpin>(lookup: &mut F) -> Option<Lookup> {
+        tokio::time::timeout(std::time::Duration::from_millis(20), lookup).await.ok()
+    }
+
+    #[rocket::async_test]
+    async fn previous_rendering_is_served_while_rerendering() {
+        let cache = RenderCache::new_shared(4);
+        render(&cache, 1, "/", "one").await;
+        assert!(matches!(cache.lookup(1, "/").await, Lookup::Fresh(page) if body(&page) == b"one"));
+
+        let Lookup::Render(ticket) = cache.lookup(2, "/").await else {
+            panic!("expected the newer listing to render");
+        };
+        assert!(matches!(cache.lookup(2, "/").await, Lookup::Stale(page) if page.etag() == "\"one\""));
+        ticket.finish("\"two\"".to_string(), "two".to_string());
+        assert!(matches!(cache.lookup(2, "/").await, Lookup::Fresh(page) if body(&page) == b"two"));
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.stale), (2, 2, 1));
+    }
+
+    #[rocket::async_test]
+    async fn waiters_get_the_finished_rendering() {
+        let cache = RenderCache::new_shared(4);
+        let Lookup::Render(ticket) = cache.lookup(1, "/").await else {
+            panic!("expected a miss");
+        };
+        let mut waiter = std::pin::pin!(cache.lookup(1, "/"));
+        assert!(wait_briefly(&mut waiter).await.is_none());
+        ticket.finish("\"one\"".to_string(), "one".to_string());
+        assert!(matches!(waiter.await, Lookup::Fresh(page) if body(&page) == b"one"));
+        assert_eq!(cache.stats().coalesced, 1);
+    }
+
+    #[rocket::async_test]
+    async fn waiter_takes_over_an_abandoned_rendering() {
+        let cache = RenderCache::new_shared(4);
+        let Lookup::Render(ticket) = cache.lookup(1, "/").await else {
+            panic!("expected a miss");
+        };
+        let mut waiter = std::pin::pin!(cache.lookup(1, "/"));
+        assert!(wait_briefly(&mut waiter).await.is_none());
+        // The first request failed without finishing
+        drop(ticket);
+        let Lookup::Render(ticket) = waiter.await else {
+            panic!("expected the waiter to render");
+        };
+        ticket.finish("\"mine\"".to_string(), "mine".to_string());
+        assert!(matches!(cache.lookup(1, "/").await, Lookup::Fresh(page) if body(&page) == b"mine"));
+    }
+
+    #[rocket::async_test]
+    async fn older_rendering_never_replaces_a_newer_one() {
+        let cache = RenderCache::new_shared(4);
+        render(&cache, 2, "/", "new").await;
+        // A request that loaded the listing before the refresh finishes last
+        let late = RenderTicket {
+            cache: cache.clone(),
+            generation: 1,
+            key: "/".to_string(),
+            _done: watch::channel(()).0,
+        };
+        let served = late.finish("\"old\"".to_string(), "old".to_string());
+        assert_eq!(body(&served), b"old");
+        assert!(matches!(cache.lookup(2, "/").await, Lookup::Fresh(page) if body(&page) == b"new"));
+    }
+
+    #[rocket::async_test]
+    async fn least_recently_rendered_page_is_dropped_at_capacity() {
+        let cache = RenderCache::new_shared(2);
+        render(&cache, 1, "a", "a").await;
+        render(&cache, 1, "b", "b").await;
+        render(&cache, 1, "c", "c").await;
+        assert_eq!(cache.stats().entries, 2);
+        assert!(matches!(cache.lookup(1, "b").await, Lookup::Fresh(_)));
+        assert!(matches!(cache.lookup(1, "c").await, Lookup::Fresh(_)));
+        assert!(matches!(cache.lookup(1, "a").await, Lookup::Render(_)));
+    }
+
+    #[rocket::async_test]
+    async fn zero_capacity_keeps_nothing() {
+        let cache = RenderCache::new_shared(0);
+        render(&cache, 1, "/", "one").await;
+        assert_eq!(cache.stats().entries, 0);
+        // Nothing is marked as rendering either, so concurrent requests each render
+        let Lookup::Render(_first) = cache.lookup(1, "/").await else {
+            panic!("expected a miss");
+        };
+        assert!(matches!(cache.lookup(1, "/").await, Lookup::Render(_)));
+    }
+}
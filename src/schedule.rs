@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+/// Consecutive failures past this don't double the delay any further (2^6 intervals)
+const MAX_BACKOFF_DOUBLINGS: u32 = 6;
+
+/// Delay before the next refresh: the configured interval plus random jitter, so instances started
+/// together don't poll the Factorio API in lockstep, doubling while refreshes keep failing
+#[derive(Debug, Clone)]
+pub struct RefreshSchedule {
+    interval: Duration,
+    jitter: Duration,
+    max_backoff: Duration,
+    /// Failed refreshes in a row
+    failures: u32,
+}
+
+impl RefreshSchedule {
+    pub fn new(interval: Duration, jitter: Duration, max_backoff: Duration) -> Self {
+        Self {
+            interval,
+            jitter,
+            max_backoff: max_backoff.max(interval),
+            failures: 0,
+        }
+    }
+
+    /// Delay after a successful refresh, which also ends any backoff
+    pub fn succeeded(&mut self) -> Duration {
+        self.failures = 0;
+        self.jittered(self.interval)
+    }
+
+    /// Delay after a failed refresh: the interval doubled for every failure in a row, up to
+    /// max_backoff, and never shorter than the API's Retry-After
+    pub fn failed(&mut self, retry_after: Option<Duration>) -> Duration {
+        self.failures = self.failures.saturating_add(1);
+        let backoff = self
+            .interval
+            .saturating_mul(1 << self.failures.min(MAX_BACKOFF_DOUBLINGS))
+            .min(self.max_backoff);
+        self.jittered(backoff.max(retry_after.unwrap_or_default()))
+    }
+
+    /// Longest wait between successful refreshes: the interval plus the most jitter
+    pub fn longest_wait(&self) -> Duration {
+        self.interval + self.jitter
+    }
+
+    /// Failed refreshes in a row, 0 when the last one worked
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    fn jittered(&self, delay: Duration) -> Duration {
+        if self.jitter.is_zero() {
+            return delay;
+        }
+        let mut bytes = [0u8; 8];
+        if let Err(e) = getrandom::fill(&mut bytes) {
+            eprintln!("Failed to pick a refresh jitter: {}", e);
+        }
+        let fraction = u64::from_le_bytes(bytes) as f64 / u64::MAX as f64;
+        delay + self.jitter.mul_f64(fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn schedule(max_backoff: Duration) -> RefreshSchedule {
+        RefreshSchedule::new(Duration::from_secs(10), Duration::ZERO, max_backoff)
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let mut schedule = schedule(Duration::from_secs(3600));
+        let delays: Vec<u64> = (0..8).map(|_| schedule.failed(None).as_secs()).collect();
+        assert_eq!(delays, [20, 40, 80, 160, 320, 640, 640, 640]);
+        assert_eq!(schedule.failures(), 8);
+        assert_eq!(10 << MAX_BACKOFF_DOUBLINGS, 640);
+    }
+
+    #[test]
+    fn backoff_is_clamped_to_max_backoff() {
+        let mut schedule = schedule(MINUTE);
+        let delays: Vec<u64> = (0..4).map(|_| schedule.failed(None).as_secs()).collect();
+        assert_eq!(delays, [20, 40, 60, 60]);
+        // max_backoff is never shorter than the interval
+        assert_eq!(RefreshSchedule::new(MINUTE, Duration::ZERO, Duration::from_secs(1)).failed(None), MINUTE);
+    }
+
+    #[test]
+    fn retry_after_takes_priority() {
+        let mut schedule = schedule(MINUTE);
+        assert_eq!(schedule.failed(Some(Duration::from_secs(300))), Duration::from_secs(300));
+        // A shorter Retry-After doesn't cut the backoff
+        assert_eq!(schedule.failed(Some(Duration::from_secs(1))), Duration::from_secs(40));
+    }
+
+    #[test]
+    fn success_ends_the_backoff() {
+        let mut schedule = schedule(MINUTE);
+        schedule.failed(None);
+        schedule.failed(None);
+        assert_eq!(schedule.succeeded(), Duration::from_secs(10));
+        assert_eq!(schedule.failures(), 0);
+        assert_eq!(schedule.failed(None), Duration::from_secs(20));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let mut schedule = RefreshSchedule::new(MINUTE, Duration::from_secs(5), MINUTE);
+        for _ in 0..20 {
+            let delay = schedule.succeeded();
+            assert!(delay >= MINUTE && delay <= schedule.longest_wait(), "{:?}", delay);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::utils::escape_xml;
+use crate::view::ServerView;
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Most tag pages listed (the long tail is one-off tags nobody searches for)
+const MAX_TAG_PAGES: usize = 100;
+
+/// Tags used by fewer servers than this don't get a page
+const MIN_TAG_SERVERS: usize = 2;
+
+/// Tags that say nothing about a server
+const EXCLUDED_TAGS: &[&str] = &["", "game", "tags"];
+
+/// Most common tags across the listing, most used first
+fn tag_pages(servers: &[ServerView]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for view in servers {
+        let unique: HashSet<&str> = view.server.tags.iter().map(|t| t.trim()).collect();
+        for tag in unique {
+            *counts.entry(tag).or_insert(0) += 1;
+        }
+    }
+
+    let mut tags: Vec<(&str, usize)> = counts
+        .into_iter()
+        .filter(|(tag, count)| *count >= MIN_TAG_SERVERS && !EXCLUDED_TAGS.contains(tag))
+        .collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    tags.into_iter().take(MAX_TAG_PAGES).map(|(tag, _)| tag.to_string()).collect()
+}
+
+fn write_url(out: &mut String, loc: &str, lastmod: &str, changefreq: &str) {
+    let _ = writeln!(out, "  <url>");
+    let _ = writeln!(out, "    <loc>{}</loc>", escape_xml(loc));
+    let _ = writeln!(out, "    <lastmod>{}</lastmod>", lastmod);
+    let _ = writeln!(out, "    <changefreq>{}</changefreq>", changefreq);
+    let _ = writeln!(out, "  </url>");
+}
+
+/// Render a sitemap of the index, every cached server page, and the common tag pages
+/// `site_url` has no trailing slash
+pub fn render_sitemap(site_url: &str, servers: &[ServerView], updated: DateTime<Utc>) -> String {
+    let lastmod = updated.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let mut out = String::new();
+    let _ = writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(out, r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">"#);
+
+    write_url(&mut out, &format!("{}/", site_url), &lastmod, "always");
+    for tag in tag_pages(servers) {
+        let loc = format!("{}/?tags={}", site_url, urlencoding::encode(&tag));
+        write_url(&mut out, &loc, &lastmod, "hourly");
+    }
+    for view in servers {
+        let loc = format!("{}/server/{}", site_url, view.server.game_id);
+        write_url(&mut out, &loc, &lastmod, "hourly");
+    }
+
+    let _ = writeln!(out, "</urlset>");
+    out
+}
+
+/// Last rendered sitemap, reused until the server cache publishes a new snapshot
+#[derive(Debug, Default)]
+pub struct SitemapCache {
+    /// Cache snapshot version and site URL the XML was rendered for
+    key: Option<(u64, String)>,
+    xml: String,
+}
+
+impl SitemapCache {
+    /// The stored sitemap, if it was rendered for this snapshot and site URL
+    pub fn get(&self, version: u64, site_url: &str) -> Option<String> {
+        match self.key {
+            Some((v, ref url)) if v == version && url == site_url => Some(self.xml.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn store(&mut self, version: u64, site_url: &str, xml: String) {
+        self.key = Some((version, site_url.to_string()));
+        self.xml = xml;
+    }
+}
@@ -0,0 +1,312 @@
+use crate::db::models::{CachedServer, ServerHistory, VersionTotals};
+use crate::ids::ServerId;
+use crate::utils::version::sort_newest_first;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Neighbouring hours must reach this share of the peak to be part of the busy window
+const BUSY_THRESHOLD: f64 = 0.6;
+
+/// Longest busy window reported, in hours
+const MAX_WINDOW_HOURS: u32 = 6;
+
+/// Player activity bucketed by weekday (Monday first) and hour of day, in UTC
+/// Each cell holds the sum of recorded player counts (roughly player-minutes)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ActivityHeatmap {
+    pub cells: [[u64; 24]; 7],
+}
+
+/// Busiest contiguous range of hours in the visitor's local time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BusiestWindow {
+    /// First busy hour (0-23)
+    pub start_hour: u32,
+    /// Hour the window ends (exclusive, 0-23, may wrap past midnight)
+    pub end_hour: u32,
+}
+
+impl ActivityHeatmap {
+    /// Build a heatmap from raw history records
+    pub fn from_history(history: &[ServerHistory]) -> Self {
+        let mut heatmap = Self::default();
+        for record in history {
+            if let Ok(recorded_at) = DateTime::parse_from_rfc3339(&record.recorded_at) {
+                let utc = recorded_at.with_timezone(&Utc);
+                let day = utc.weekday().num_days_from_monday() as usize;
+                let hour = utc.hour() as usize;
+                heatmap.cells[day][hour] += record.player_count as u64;
+            }
+        }
+        heatmap
+    }
+
+    /// Whether any activity was recorded
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().flatten().all(|&c| c == 0)
+    }
+
+    /// Total activity per local hour of day across all weekdays
+    /// `offset_minutes` is the visitor's offset from UTC (e.g. 120 for UTC+2)
+    pub fn hourly_totals(&self, offset_minutes: i32) -> [u64; 24] {
+        let mut totals = [0u64; 24];
+        for day in &self.cells {
+            for (hour, &count) in day.iter().enumerate() {
+                let local_minutes = (hour as i32 * 60 + offset_minutes).rem_euclid(24 * 60);
+                totals[(local_minutes / 60) as usize] += count;
+            }
+        }
+        totals
+    }
+
+    /// Find the busiest window of hours in the visitor's local time
+    pub fn busiest_window(&self, offset_minutes: i32) -> Option<BusiestWindow> {
+        let totals = self.hourly_totals(offset_minutes);
+        let (peak_hour, &peak) = totals.iter().enumerate().max_by_key(|&(_, c)| *c)?;
+        if peak == 0 {
+            return None;
+        }
+
+        let threshold = (peak as f64 * BUSY_THRESHOLD) as u64;
+        let is_busy = |hour: u32| totals[(hour % 24) as usize] >= threshold;
+
+        // Grow the window around the peak, preferring the busier side first
+        let mut start = peak_hour as u32 + 24;
+        let mut end = peak_hour as u32 + 25;
+        while end - start < MAX_WINDOW_HOURS {
+            let before = start - 1;
+            let after = end;
+            let before_busy = is_busy(before);
+            let after_busy = is_busy(after);
+            if before_busy && (!after_busy || totals[(before % 24) as usize] >= totals[(after % 24) as usize]) {
+                start -= 1;
+            } else if after_busy {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+
+        Some(BusiestWindow {
+            start_hour: start % 24,
+            end_hour: end % 24,
+        })
+    }
+}
+
+/// Human-readable "good time to join" hint, e.g. "Usually busiest 19:00–23:00 your time"
+/// Falls back to UTC when the visitor's timezone is unknown
+pub fn join_hint(heatmap: &ActivityHeatmap, offset_minutes: Option<i32>) -> Option<String> {
+    let window = heatmap.busiest_window(offset_minutes.unwrap_or(0))?;
+    let zone = if offset_minutes.is_some() { "your time" } else { "UTC" };
+    Some(format!(
+        "Usually busiest {:02}:00–{:02}:00 {}",
+        window.start_hour, window.end_hour, zone
+    ))
+}
+
+/// Truncate a timestamp to the start of its hour (RFC 3339, UTC)
+pub fn hour_bucket(time: DateTime<Utc>) -> String {
+    time.with_minute(0)
+        .and_then(|t| t.with_second(0))
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(time)
+        .to_rfc3339()
+}
+
+/// Tracks which servers already have a presence record for the current hour,
+/// so each refresh only writes servers that are new this hour
+#[derive(Debug, Default)]
+pub struct PresenceTracker {
+    hour: String,
+    seen: HashSet<ServerId>,
+}
+
+impl PresenceTracker {
+    /// Return the server_ids not yet recorded for `hour`, marking them as recorded
+    pub fn unrecorded(&mut self, hour: &str, server_ids: impl IntoIterator<Item = ServerId>) -> Vec<ServerId> {
+        if self.hour != hour {
+            self.hour = hour.to_string();
+            self.seen.clear();
+        }
+        server_ids
+            .into_iter()
+            .filter(|id| self.seen.insert(id.clone()))
+            .collect()
+    }
+
+    /// Forget recorded servers so the next refresh retries them (e.g. after a DB error)
+    pub fn reset(&mut self) {
+        self.hour.clear();
+        self.seen.clear();
+    }
+}
+
+/// Materialized result key for global statistics
+pub const GLOBAL_STATS_KEY: &str = "global_stats";
+
+/// Number of entries kept in each breakdown
+const TOP_N: usize = 20;
+
+/// Server and player totals for a single game version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionCount {
+    pub version: String,
+    pub servers: usize,
+    pub players: usize,
+}
+
+/// Number of servers using a tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub servers: usize,
+}
+
+/// Listing-wide aggregates, computed by the scheduler and served from the DB
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct GlobalStats {
+    pub total_servers: usize,
+    pub active_servers: usize,
+    pub total_players: usize,
+    pub versions: Vec<VersionCount>,
+    pub top_tags: Vec<TagCount>,
+}
+
+impl GlobalStats {
+    /// Aggregate the current server list
+    pub fn compute<'a>(servers: impl IntoIterator<Item = &'a CachedServer>) -> Self {
+        let mut versions: HashMap<&str, VersionCount> = HashMap::new();
+        let mut tags: HashMap<&str, usize> = HashMap::new();
+        let mut totals = Self::default();
+
+        for server in servers {
+            totals.total_servers += 1;
+            totals.total_players += server.player_count;
+            if server.player_count > 0 {
+                totals.active_servers += 1;
+            }
+
+            let entry = versions
+                .entry(server.game_version.as_str())
+                .or_insert_with(|| VersionCount {
+                    version: server.game_version.clone(),
+                    servers: 0,
+                    players: 0,
+                });
+            entry.servers += 1;
+            entry.players += server.player_count;
+
+            let unique_tags: HashSet<&str> = server.tags.iter().map(|t| t.trim()).collect();
+            for tag in unique_tags.into_iter().filter(|t| !t.is_empty()) {
+                *tags.entry(tag).or_insert(0) += 1;
+            }
+        }
+
+        let mut versions: Vec<VersionCount> = versions.into_values().collect();
+        versions.sort_by(|a, b| b.servers.cmp(&a.servers).then_with(|| a.version.cmp(&b.version)));
+        versions.truncate(TOP_N);
+
+        let mut top_tags: Vec<TagCount> = tags
+            .into_iter()
+            .map(|(tag, servers)| TagCount { tag: tag.to_string(), servers })
+            .collect();
+        top_tags.sort_by(|a, b| b.servers.cmp(&a.servers).then_with(|| a.tag.cmp(&b.tag)));
+        top_tags.truncate(TOP_N);
+
+        Self {
+            versions,
+            top_tags,
+            ..totals
+        }
+    }
+}
+
+/// Versions drawn separately in the adoption history chart; the rest are grouped as "Other"
+const ADOPTION_TOP_VERSIONS: usize = 6;
+
+/// Share of players per version for one day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AdoptionDay {
+    /// Date as YYYY-MM-DD (UTC)
+    pub day: String,
+    /// Percentage of players per entry in VersionAdoption::versions, then "Other" last
+    pub shares: Vec<f64>,
+    /// Average players online that day
+    pub players: f64,
+}
+
+/// How the player base spread across game versions, day by day
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct VersionAdoption {
+    /// Versions charted on their own, newest first
+    pub versions: Vec<String>,
+    pub days: Vec<AdoptionDay>,
+}
+
+impl VersionAdoption {
+    /// Average hourly per-version totals into days, keeping the versions that were ever most played
+    pub fn from_hourly(totals: &[VersionTotals]) -> Self {
+        // day -> version -> sum of hourly player counts
+        let mut days: BTreeMap<&str, HashMap<&str, f64>> = BTreeMap::new();
+        let mut hours_per_day: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for point in totals {
+            let day = point.hour.get(..10).unwrap_or(&point.hour);
+            *days.entry(day).or_default().entry(point.version.as_str()).or_insert(0.0) += point.players as f64;
+            hours_per_day.entry(day).or_default().insert(point.hour.as_str());
+        }
+
+        // Daily average players per version (hours a version had no servers count as zero)
+        let daily: Vec<(&str, HashMap<&str, f64>)> = days
+            .into_iter()
+            .map(|(day, versions)| {
+                let hours = hours_per_day.get(day).map(HashSet::len).unwrap_or(1).max(1) as f64;
+                (day, versions.into_iter().map(|(v, sum)| (v, sum / hours)).collect())
+            })
+            .collect();
+
+        // Rank versions by their best day, so a release that took over recently isn't outranked by history
+        let mut best: HashMap<&str, f64> = HashMap::new();
+        for (_, versions) in &daily {
+            let total: f64 = versions.values().sum();
+            if total <= 0.0 {
+                continue;
+            }
+            for (version, players) in versions {
+                let share = players / total;
+                let entry = best.entry(version).or_insert(0.0);
+                *entry = entry.max(share);
+            }
+        }
+        let mut ranked: Vec<(&str, f64)> = best.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let mut versions: Vec<String> = ranked
+            .into_iter()
+            .take(ADOPTION_TOP_VERSIONS)
+            .map(|(v, _)| v.to_string())
+            .collect();
+        sort_newest_first(&mut versions);
+
+        let days = daily
+            .into_iter()
+            .map(|(day, players_by_version)| {
+                let players: f64 = players_by_version.values().sum();
+                let share = |p: f64| if players > 0.0 { p / players * 100.0 } else { 0.0 };
+                let mut shares: Vec<f64> = versions
+                    .iter()
+                    .map(|v| share(players_by_version.get(v.as_str()).copied().unwrap_or(0.0)))
+                    .collect();
+                let charted: f64 = shares.iter().sum();
+                shares.push(if players > 0.0 { (100.0 - charted).max(0.0) } else { 0.0 });
+                AdoptionDay {
+                    day: day.to_string(),
+                    shares,
+                    players,
+                }
+            })
+            .collect();
+
+        Self { versions, days }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::utils::strip_all_tags;
+use std::collections::{HashMap, HashSet};
+
+/// A tag with its rich text removed, whitespace collapsed and case folded, so "PvP",
+/// "pvp" and "[color=red]PVP[/color]" are the same tag; empty when only markup was left
+pub fn fold_tag(tag: &str) -> String {
+    strip_all_tags(tag).to_lowercase()
+}
+
+/// Different words for the same tag (TAG_ALIASES), mapped onto one canonical tag, and tags to drop
+/// Applied when servers are cached, so the list, filters, API and sitemap all see one spelling
+#[derive(Debug, Clone, Default)]
+pub struct TagAliases {
+    /// Folded alias -> folded canonical tag
+    aliases: HashMap<String, String>,
+    /// Folded tags removed altogether (excluded_tags in the config)
+    excluded: HashSet<String>,
+}
+
+impl TagAliases {
+    /// Parse from config: comma-separated `alias=canonical` pairs, e.g. "player vs player=pvp,rp=roleplay"
+    /// Both sides are folded like the tags themselves
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut aliases = HashMap::new();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (alias, canonical) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected alias=tag, got '{}'", pair))?;
+            let (alias, canonical) = (fold_tag(alias), fold_tag(canonical));
+            if alias.is_empty() || canonical.is_empty() {
+                return Err(format!("empty tag in '{}'", pair));
+            }
+            if alias != canonical {
+                aliases.insert(alias, canonical);
+            }
+        }
+        Ok(Self { aliases, excluded: HashSet::new() })
+    }
+
+    /// These aliases, also dropping the given tags (after folding and aliasing)
+    pub fn with_excluded(mut self, tags: &[String]) -> Self {
+        self.excluded = tags.iter().map(|t| self.normalize(t)).filter(|t| !t.is_empty()).collect();
+        self
+    }
+
+    /// The canonical form of one tag (empty when the tag was only markup)
+    pub fn normalize(&self, tag: &str) -> String {
+        let folded = fold_tag(tag);
+        self.aliases.get(&folded).cloned().unwrap_or(folded)
+    }
+
+    /// Canonical forms of a server's tags, in order, without empty or repeated ones
+    pub fn normalize_all<S: AsRef<str>>(&self, tags: &[S]) -> Vec<String> {
+        let mut normalized: Vec<String> = Vec::new();
+        for tag in tags.iter().map(|t| self.normalize(t.as_ref())) {
+            if !tag.is_empty() && !self.excluded.contains(&tag) && !normalized.contains(&tag) {
+                normalized.push(tag);
+            }
+        }
+        normalized
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Completed refresh cycles kept for the admin page
+pub const TRACE_CAPACITY: usize = 50;
+
+/// Cycles slower than this are logged with their stage timings
+pub const SLOW_CYCLE: Duration = Duration::from_secs(3);
+
+/// One timed stage of a refresh cycle
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageSpan {
+    pub stage: &'static str,
+    /// Offset from the start of the cycle
+    pub started: Duration,
+    pub elapsed: Duration,
+    /// What the stage handled or why it failed, e.g. "5321 servers"
+    pub note: Option<String>,
+}
+
+/// A finished refresh cycle with the stages it went through, in start order
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleTrace {
+    pub cycle: u64,
+    pub started_at: DateTime<Utc>,
+    pub total: Duration,
+    /// "ok", or what went wrong
+    pub outcome: String,
+    pub spans: Vec<StageSpan>,
+}
+
+impl CycleTrace {
+    /// The stage that took longest, if any ran
+    pub fn slowest(&self) -> Option<&StageSpan> {
+        self.spans.iter().max_by_key(|s| s.elapsed)
+    }
+
+    /// Stage timings on one line, e.g. "fetch 312ms, db_write 1840ms"
+    pub fn summary(&self) -> String {
+        self.spans
+            .iter()
+            .map(|s| format!("{} {}ms", s.stage, s.elapsed.as_millis()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// A refresh cycle in progress; stages may run concurrently, so spans are recorded through `&self`
+#[derive(Debug)]
+pub struct CycleTracer {
+    cycle: u64,
+    started_at: DateTime<Utc>,
+    start: Instant,
+    spans: Mutex<Vec<StageSpan>>,
+}
+
+impl CycleTracer {
+    /// Cycle ID, for log lines
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    /// Run one stage and record how long it took
+    pub async fn stage<F: Future>(&self, stage: &'static str, fut: F) -> F::Output {
+        let start = Instant::now();
+        let output = fut.await;
+        self.record(stage, start, None);
+        output
+    }
+
+    /// Record a stage that started at `start` and just ended
+    pub fn record(&self, stage: &'static str, start: Instant, note: Option<String>) {
+        let span = StageSpan {
+            stage,
+            started: start.saturating_duration_since(self.start),
+            elapsed: start.elapsed(),
+            note,
+        };
+        self.spans.lock().unwrap_or_else(|e| e.into_inner()).push(span);
+    }
+
+    /// Attach a note to the latest span of a stage
+    pub fn note(&self, stage: &'static str, note: impl Into<String>) {
+        let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(span) = spans.iter_mut().rev().find(|s| s.stage == stage) {
+            span.note = Some(note.into());
+        }
+    }
+
+    fn finish(self, outcome: String) -> CycleTrace {
+        let mut spans = self.spans.into_inner().unwrap_or_else(|e| e.into_inner());
+        spans.sort_by_key(|s| s.started);
+        CycleTrace {
+            cycle: self.cycle,
+            started_at: self.started_at,
+            total: self.start.elapsed(),
+            outcome,
+            spans,
+        }
+    }
+}
+
+/// The most recent refresh cycles, newest last
+#[derive(Debug, Default)]
+pub struct RefreshTraces {
+    next_cycle: AtomicU64,
+    cycles: Mutex<VecDeque<CycleTrace>>,
+}
+
+impl RefreshTraces {
+    pub fn new_shared() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Begin tracing a new cycle, numbered from 1 since startup
+    pub fn start(&self) -> CycleTracer {
+        CycleTracer {
+            cycle: self.next_cycle.fetch_add(1, Ordering::Relaxed) + 1,
+            started_at: Utc::now(),
+            start: Instant::now(),
+            spans: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Keep a finished cycle, logging it when it was slow
+    pub fn finish(&self, tracer: CycleTracer, outcome: impl Into<String>) {
+        let trace = tracer.finish(outcome.into());
+        if trace.total >= SLOW_CYCLE {
+            eprintln!(
+                "Warning: refresh cycle {} took {}ms ({})",
+                trace.cycle,
+                trace.total.as_millis(),
+                trace.summary()
+            );
+        }
+        let mut cycles = self.cycles.lock().unwrap_or_else(|e| e.into_inner());
+        if cycles.len() == TRACE_CAPACITY {
+            cycles.pop_front();
+        }
+        cycles.push_back(trace);
+    }
+
+    /// Kept cycles, newest first
+    pub fn recent(&self) -> Vec<CycleTrace> {
+        self.cycles.lock().unwrap_or_else(|e| e.into_inner()).iter().rev().cloned().collect()
+    }
+
+    pub fn get(&self, cycle: u64) -> Option<CycleTrace> {
+        self.cycles.lock().unwrap_or_else(|e| e.into_inner()).iter().find(|c| c.cycle == cycle).cloned()
+    }
+}
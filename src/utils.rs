@@ -1,7 +1,10 @@
 use yew::prelude::*;
 
-/// List of Factorio rich text tags that render icons/images (which we can't display)
-/// These will be stripped from the text entirely
+pub mod format;
+pub mod version;
+
+/// List of Factorio rich text tags that render icons/images in game
+/// Those in SHOWN_ICON_TAGS become inline placeholders; the rest are stripped from the text entirely
 const ICON_TAGS: &[&str] = &[
     "img",
     "item",
@@ -28,60 +31,133 @@ const ICON_TAGS: &[&str] = &[
     "space-age"
 ];
 
-/// Strip all unsupported Factorio rich text tags (icons, images, etc.)
-/// These tags are self-closing: [item=iron-plate] (no closing tag)
-fn strip_icon_tags(text: &str) -> String {
-    let mut result = text.to_string();
-    
-    for tag in ICON_TAGS {
-        let pattern = format!("[{}=", tag);
-        while let Some(start) = result.find(&pattern) {
-            // Find the closing bracket
-            if let Some(end_offset) = result[start..].find(']') {
-                let end = start + end_offset + 1;
-                result.replace_range(start..end, "");
-            } else {
-                // No closing bracket, stop processing this tag type
-                break;
-            }
+/// Icon tags parse_rich_text shows as small inline placeholders, named in their tooltip
+const SHOWN_ICON_TAGS: &[&str] = &["img", "item", "entity", "fluid", "virtual-signal", "technology"];
+
+/// Glyph standing in for each kind of icon (the game's sprites can't be bundled)
+const ICON_GLYPHS: &[(&str, &str, &str)] = &[
+    // (kind, glyph, label)
+    ("item", "◆", "Item"),
+    ("entity", "⚙", "Entity"),
+    ("fluid", "●", "Fluid"),
+    ("virtual-signal", "◈", "Signal"),
+    ("technology", "✦", "Technology"),
+    ("recipe", "◇", "Recipe"),
+    ("tile", "▦", "Tile"),
+];
+
+/// Glyph for image kinds not in ICON_GLYPHS, e.g. [img=utility/warning_icon]
+const DEFAULT_ICON_GLYPH: &str = "▣";
+
+/// Inline style of icon placeholders: a small rounded chip sitting on the text line
+const ICON_STYLE: &str = "display: inline-block; min-width: 1.3em; padding: 0 0.2em; margin: 0 0.1em; \
+    border-radius: 3px; background: rgba(255, 255, 255, 0.1); font-size: 0.8em; line-height: 1.4; \
+    text-align: center; vertical-align: middle";
+
+/// Tags that have opening and closing pairs: [color=...][/color], [font=...][/font]
+const PAIRED_TAGS: &[&str] = &["color", "font"];
+
+/// Most color/font spans rendered inside one another; tags nested deeper still pair up, they just
+/// don't add a span, so hostile names can't build arbitrarily deep markup
+const MAX_NESTING: usize = 8;
+
+/// Bytes of text whose tags are parsed; anything after is shown as typed
+/// Descriptions are the longest rich text servers send and stay far below this
+const MAX_RICH_TEXT_LEN: usize = 16 * 1024;
+
+/// Characters searched for a tag's closing bracket before the `[` is taken as plain text
+const MAX_TAG_LEN: usize = 256;
+
+/// A piece of rich text, borrowing from the source
+#[derive(Debug, Clone, Copy)]
+enum Token<'a> {
+    Text(&'a str),
+    /// [color=red] or [font=default-bold], with the raw tag for when it's never closed
+    Open { tag: &'static str, value: &'a str, raw: &'a str },
+    /// [/color] or [/font]
+    Close { tag: &'static str, raw: &'a str },
+    /// A tag in SHOWN_ICON_TAGS, e.g. [item=iron-plate]
+    Icon { tag: &'static str, value: &'a str },
+    /// Any other icon tag, e.g. [gps=10,20], which is dropped
+    Hidden,
+}
+
+/// Split rich text into tokens in one pass; brackets that don't form a known tag stay text
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+    while let Some(offset) = text[pos..].find('[') {
+        let open = pos + offset + 1;
+        // A tag ends at the first bracket; another `[` first means this one was plain text
+        let end = text[open..]
+            .char_indices()
+            .take(MAX_TAG_LEN)
+            .find(|(_, c)| matches!(c, '[' | ']'))
+            .filter(|(_, c)| *c == ']')
+            .map(|(i, _)| open + i);
+        let Some((end, token)) = end.and_then(|end| Some((end, tag_token(&text[open..end], &text[open - 1..=end])?)))
+        else {
+            pos = open;
+            continue;
+        };
+        if open - 1 > text_start {
+            tokens.push(Token::Text(&text[text_start..open - 1]));
         }
+        tokens.push(token);
+        pos = end + 1;
+        text_start = pos;
+    }
+    if text_start < text.len() {
+        tokens.push(Token::Text(&text[text_start..]));
     }
-    
-    result
+    tokens
 }
 
-/// Tags that have opening and closing pairs: [color=...][/color], [font=...][/font]
-const PAIRED_TAGS: &[&str] = &["color", "font"];
+/// The token for a tag's contents (between the brackets), or None when it isn't a known tag
+fn tag_token<'a>(inner: &'a str, raw: &'a str) -> Option<Token<'a>> {
+    let known = |list: &[&'static str], name: &str| list.iter().copied().find(|tag| *tag == name);
+    if let Some(name) = inner.strip_prefix('/') {
+        return known(PAIRED_TAGS, name).map(|tag| Token::Close { tag, raw });
+    }
+    let (name, value) = inner.split_once('=')?;
+    if let Some(tag) = known(PAIRED_TAGS, name) {
+        Some(Token::Open { tag, value, raw })
+    } else if let Some(tag) = known(SHOWN_ICON_TAGS, name) {
+        Some(Token::Icon { tag, value })
+    } else {
+        known(ICON_TAGS, name).map(|_| Token::Hidden)
+    }
+}
+
+/// Steam link that launches Factorio and connects to `host_address` (ip:port)
+pub fn steam_join_url(host_address: &str) -> String {
+    format!("steam://run/427520//--mp-connect%20{}", host_address)
+}
+
+/// Escape text for inclusion in XML (SVG badges, Atom feeds)
+/// Control characters XML 1.0 doesn't allow are dropped
+pub fn escape_xml(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
 /// Strip ALL Factorio rich text tags and return plain text
 /// Use this for titles, meta tags, or anywhere HTML can't be rendered
 pub fn strip_all_tags(text: &str) -> String {
-    // First strip icon tags
-    let mut result = strip_icon_tags(text);
-    
-    // Then strip paired tags (keep content, remove tags)
-    for tag in PAIRED_TAGS {
-        let open_pattern = format!("[{}=", tag);
-        let close_pattern = format!("[/{}]", tag);
-        
-        // Remove opening tags: [color=...] or [font=...]
-        while let Some(start) = result.find(&open_pattern) {
-            if let Some(end_offset) = result[start..].find(']') {
-                let end = start + end_offset + 1;
-                result.replace_range(start..end, "");
-            } else {
-                break;
-            }
-        }
-        
-        // Remove closing tags: [/color] or [/font]
-        while let Some(start) = result.find(&close_pattern) {
-            result.replace_range(start..start + close_pattern.len(), "");
-        }
-    }
-    
-    // Clean up any extra whitespace
-    result.split_whitespace().collect::<Vec<_>>().join(" ")
+    let plain: String = tokenize(text)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect();
+    plain.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
 /// Convert plain text to Html, preserving newlines as <br> tags
@@ -100,93 +176,127 @@ fn text_with_newlines(text: &str) -> Html {
     html! { <>{for parts}</> }
 }
 
-/// Find the next rich text tag ([color=...] or [font=...])
-fn find_next_tag(text: &str) -> Option<(usize, &str)> {
-    let color_pos = text.find("[color=");
-    let font_pos = text.find("[font=");
-    
-    match (color_pos, font_pos) {
-        (Some(c), Some(f)) => {
-            if c < f {
-                Some((c, "color"))
-            } else {
-                Some((f, "font"))
-            }
-        }
-        (Some(c), None) => Some((c, "color")),
-        (None, Some(f)) => Some((f, "font")),
-        (None, None) => None,
+/// "iron-gear-wheel" as "Iron gear wheel"
+fn prototype_label(name: &str) -> String {
+    let words = name.replace(['-', '_'], " ");
+    let mut chars = words.trim().chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Placeholder for an icon tag: a glyph for its kind (or a signal's letter), named in the tooltip
+/// `value` is what follows the `=`, e.g. "iron-plate,quality=rare" or, for img, "item/iron-plate"
+fn rich_icon(tag: &str, value: &str) -> Html {
+    let value = value.split(',').next().unwrap_or_default().trim();
+    let (kind, name) = match tag {
+        "img" => value.split_once('/').unwrap_or(("img", value)),
+        _ => (tag, value),
+    };
+    let (glyph, kind_label) = ICON_GLYPHS
+        .iter()
+        .find(|(k, _, _)| *k == kind)
+        .map(|(_, glyph, label)| (*glyph, *label))
+        .unwrap_or((DEFAULT_ICON_GLYPH, "Image"));
+    // Letter and digit signals read better as themselves: [virtual-signal=signal-A] shows "A"
+    let glyph = match name.strip_prefix("signal-") {
+        Some(letter) if kind == "virtual-signal" && letter.chars().count() == 1 => letter.to_string(),
+        _ => glyph.to_string(),
+    };
+    let title = format!("{}: {}", kind_label, prototype_label(name));
+    html! {
+        <span role="img" title={title.clone()} aria-label={title} style={ICON_STYLE}>{glyph}</span>
     }
 }
 
+/// A color/font tag still open while parsing
+struct OpenTag<'a> {
+    tag: &'static str,
+    value: &'a str,
+    raw: &'a str,
+    /// Whether it gets a span, false past MAX_NESTING
+    styled: bool,
+    children: Vec<Html>,
+}
+
+/// Where parsed content goes: the innermost open tag, or the top level
+fn current<'s>(stack: &'s mut [OpenTag<'_>], root: &'s mut Vec<Html>) -> &'s mut Vec<Html> {
+    stack.last_mut().map_or(root, |open| &mut open.children)
+}
+
+/// Close the innermost open tag, wrapping its content in a span
+fn close_tag(stack: &mut Vec<OpenTag<'_>>, root: &mut Vec<Html>) {
+    let Some(open) = stack.pop() else {
+        return;
+    };
+    let children = open.children;
+    let parent = current(stack, root);
+    if !open.styled {
+        parent.extend(children);
+        return;
+    }
+    let style = match open.tag {
+        "color" => format!("color: {}", factorio_color_to_css(open.value)),
+        _ => factorio_font_to_css(open.value),
+    };
+    parent.push(html! { <span style={style}>{for children}</span> });
+}
+
 /// Parse Factorio rich text tags: [color=...][/color] and [font=...][/font]
 /// Also converts newlines to <br> tags
-/// Strips unsupported icon tags like [item=...], [entity=...], etc.
+/// Icon tags in SHOWN_ICON_TAGS ([item=...], [img=...], ...) become inline placeholders; other icon
+/// tags like [gps=...] are stripped
+/// Runs in one pass over the text, however it's nested, and renders at most MAX_NESTING spans deep
 pub fn parse_rich_text(text: &str) -> Html {
-    // First, strip all icon tags that we can't render
-    let cleaned = strip_icon_tags(text);
-    
-    let mut result: Vec<Html> = Vec::new();
-    let mut remaining = cleaned.as_str();
-
-    while !remaining.is_empty() {
-        if let Some((start, tag_type)) = find_next_tag(remaining) {
-            // Add text before the tag
-            if start > 0 {
-                let before = &remaining[..start];
-                result.push(text_with_newlines(before));
-            }
+    let mut split = text.len().min(MAX_RICH_TEXT_LEN);
+    while !text.is_char_boundary(split) {
+        split -= 1;
+    }
+    let (rich, rest) = text.split_at(split);
 
-            let tag_prefix = format!("[{}=", tag_type);
-            let close_tag = format!("[/{}]", tag_type);
-            let prefix_len = tag_prefix.len();
-            let close_len = close_tag.len();
-
-            // Find the end of the opening tag
-            let after_start = &remaining[start + prefix_len..];
-            if let Some(tag_end) = after_start.find(']') {
-                let value = &after_start[..tag_end];
-                let after_tag = &after_start[tag_end + 1..];
-
-                // Find the closing tag
-                if let Some(close) = after_tag.find(&close_tag) {
-                    let content = &after_tag[..close];
-                    
-                    // Recursively parse content (for nested tags)
-                    let inner = parse_rich_text(content);
-                    
-                    let styled = match tag_type {
-                        "color" => {
-                            let css_color = factorio_color_to_css(value);
-                            html! {
-                                <span style={format!("color: {}", css_color)}>{inner}</span>
-                            }
-                        }
-                        "font" => {
-                            let css_style = factorio_font_to_css(value);
-                            html! {
-                                <span style={css_style}>{inner}</span>
-                            }
-                        }
-                        _ => inner,
-                    };
-                    
-                    result.push(styled);
-                    remaining = &after_tag[close + close_len..];
-                    continue;
-                }
+    let mut root: Vec<Html> = Vec::new();
+    let mut stack: Vec<OpenTag> = Vec::new();
+    let mut styled_depth = 0;
+    for token in tokenize(rich) {
+        match token {
+            Token::Text(text) => current(&mut stack, &mut root).push(text_with_newlines(text)),
+            Token::Icon { tag, value } => current(&mut stack, &mut root).push(rich_icon(tag, value)),
+            Token::Hidden => {}
+            Token::Open { tag, value, raw } => {
+                let styled = styled_depth < MAX_NESTING;
+                styled_depth += usize::from(styled);
+                stack.push(OpenTag {
+                    tag,
+                    value,
+                    raw,
+                    styled,
+                    children: Vec::new(),
+                });
             }
-            // Malformed tag, treat as plain text
-            result.push(text_with_newlines(&remaining[..start + 1]));
-            remaining = &remaining[start + 1..];
-        } else {
-            // No more tags, add remaining text
-            result.push(text_with_newlines(remaining));
-            break;
+            // Closes the innermost open tag of its kind, along with any opened inside it
+            Token::Close { tag, raw } => match stack.iter().rposition(|open| open.tag == tag) {
+                Some(index) => {
+                    while stack.len() > index {
+                        styled_depth -= usize::from(stack.last().is_some_and(|open| open.styled));
+                        close_tag(&mut stack, &mut root);
+                    }
+                }
+                None => current(&mut stack, &mut root).push(text_with_newlines(raw)),
+            },
         }
     }
+    // Tags never closed are shown as typed, their content unstyled
+    while let Some(open) = stack.pop() {
+        let parent = current(&mut stack, &mut root);
+        parent.push(text_with_newlines(open.raw));
+        parent.extend(open.children);
+    }
+    if !rest.is_empty() {
+        root.push(text_with_newlines(rest));
+    }
 
-    html! { <>{for result}</> }
+    html! { <>{for root}</> }
 }
 
 /// Convert Factorio font names to CSS styles
@@ -275,3 +385,297 @@ fn parse_rgb_color(color: &str) -> String {
 
     format!("rgb({}, {}, {})", r, g, b)
 }
+
+/// Maximum number of rules extracted from a description
+const MAX_RULES: usize = 20;
+
+/// Strip a leading bullet or list marker ("-", "*", "•", "1.", "2)") from a line
+/// Returns None if the line doesn't start with a recognized marker
+fn strip_list_marker(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+    for marker in ["-", "*", "•", "·", "+", ">"] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            return Some(rest.trim());
+        }
+    }
+
+    // Numbered markers: "1." "1)" "1:"
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 && digits <= 3 {
+        let rest = &line[digits..];
+        for marker in [".", ")", ":"] {
+            if let Some(rest) = rest.strip_prefix(marker) {
+                return Some(rest.trim());
+            }
+        }
+    }
+
+    None
+}
+
+/// Check if a line is a "Rules" header, returning any inline rules text after the colon
+fn rules_header_remainder(line: &str) -> Option<&str> {
+    let trimmed = line.trim_matches(|c: char| c.is_whitespace() || "#=-*_~[]".contains(c));
+    let lower = trimmed.to_lowercase();
+
+    let word_end = if lower.starts_with("server rules") {
+        "server rules".len()
+    } else if lower.starts_with("rules") {
+        "rules".len()
+    } else {
+        return None;
+    };
+
+    let rest = trimmed[word_end..].trim_start();
+    if rest.is_empty() {
+        Some("")
+    } else {
+        rest.strip_prefix(':').map(str::trim)
+    }
+}
+
+/// Split inline rules ("Rules: no griefing; be nice") into separate entries
+fn split_inline_rules(text: &str) -> Vec<String> {
+    text.split(';')
+        .map(|part| strip_list_marker(part).unwrap_or(part).trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Extract a "Rules:" section from a server description (heuristic)
+/// Looks for a line starting with "Rules" or "Server rules" and collects the following
+/// list items until a blank line or the next section header. Returns an empty list if
+/// no rules section is found.
+pub fn extract_rules(description: &str) -> Vec<String> {
+    let lines: Vec<String> = description.lines().map(strip_all_tags).collect();
+
+    let Some((header_idx, inline)) = lines
+        .iter()
+        .enumerate()
+        .find_map(|(i, line)| rules_header_remainder(line).map(|rest| (i, rest)))
+    else {
+        return Vec::new();
+    };
+
+    let mut rules = split_inline_rules(inline);
+
+    for line in &lines[header_idx + 1..] {
+        let line = line.trim();
+        if line.is_empty() {
+            if rules.is_empty() {
+                continue;
+            }
+            break;
+        }
+        // Stop at the next section header, e.g. "Discord:"
+        if line.ends_with(':') && strip_list_marker(line).is_none() {
+            break;
+        }
+        let rule = strip_list_marker(line).unwrap_or(line);
+        if !rule.is_empty() {
+            rules.push(rule.to_string());
+        }
+        if rules.len() >= MAX_RULES {
+            break;
+        }
+    }
+
+    rules.truncate(MAX_RULES);
+    rules
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[derive(Properties, PartialEq)]
+    struct RichTextProps {
+        text: String,
+    }
+
+    #[function_component]
+    fn RichText(props: &RichTextProps) -> Html {
+        parse_rich_text(&props.text)
+    }
+
+    fn render(text: &str) -> String {
+        let props = RichTextProps { text: text.to_string() };
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(yew::LocalServerRenderer::<RichText>::with_props(props).hydratable(false).render())
+    }
+
+    /// Deterministic xorshift, so failures reproduce
+    fn next(seed: &mut u64) -> u64 {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 7;
+        *seed ^= *seed << 17;
+        *seed
+    }
+
+    #[test]
+    fn styles_paired_tags() {
+        let html = render("[color=red]red[/color] [font=default-bold]bold[/font]");
+        assert!(html.contains(r#"<span style="color: #ff0000">red</span>"#), "{}", html);
+        assert!(html.contains(r#"<span style="font-weight: 700">bold</span>"#), "{}", html);
+    }
+
+    #[test]
+    fn deep_nesting_stops_adding_spans() {
+        // As deep as fits in MAX_RICH_TEXT_LEN
+        let depth = 700;
+        let text = format!("{}deep{}", "[color=red]".repeat(depth), "[/color]".repeat(depth));
+        let html = render(&text);
+        assert_eq!(html.matches("<span").count(), MAX_NESTING);
+        assert!(html.contains("deep"));
+        assert!(!html.contains("[color"));
+        assert!(!html.contains("[/color]"));
+    }
+
+    #[test]
+    fn unclosed_tags_are_shown_as_typed() {
+        let html = render("[color=red]hello [font=default-bold]world");
+        assert!(!html.contains("<span"), "{}", html);
+        assert!(html.contains("[color=red]"));
+        assert!(html.contains("[font=default-bold]"));
+        assert!(html.contains("hello") && html.contains("world"));
+
+        let html = render(&format!("{}end", "[color=red]".repeat(5_000)));
+        assert!(!html.contains("<span"));
+        assert_eq!(html.matches("[color=red]").count(), 5_000);
+    }
+
+    #[test]
+    fn unmatched_closing_tags_are_text() {
+        let html = render(&format!("a{}b", "[/color]".repeat(10_000)));
+        assert_eq!(html.matches("[/color]").count(), 10_000);
+        assert!(!html.contains("<span"));
+
+        // A stray close inside an open color doesn't end it
+        let html = render("[color=red]x[/font]y[/color]");
+        assert!(html.contains(r#"<span style="color: #ff0000">x[/font]y</span>"#), "{}", html);
+    }
+
+    #[test]
+    fn closing_outer_tag_closes_inner_ones() {
+        let html = render("[color=red]a[font=default-bold]b[/color]c");
+        assert_eq!(html.matches("<span").count(), 2);
+        assert!(html.ends_with("c"), "{}", html);
+    }
+
+    #[test]
+    fn text_past_limit_splits_on_char_boundary() {
+        // "é" is two bytes, straddling MAX_RICH_TEXT_LEN
+        let text = format!("{}é[color=red]tail[/color]", "a".repeat(MAX_RICH_TEXT_LEN - 1));
+        let html = render(&text);
+        assert!(html.contains('é'));
+        assert!(html.contains("[color=red]tail[/color]"));
+        assert!(!html.contains("<span"));
+
+        let text = "ü".repeat(MAX_RICH_TEXT_LEN);
+        assert_eq!(render(&text).matches('ü').count(), MAX_RICH_TEXT_LEN);
+    }
+
+    #[test]
+    fn tags_longer_than_limit_are_text() {
+        let text = format!("[color={}]x[/color]", "r".repeat(MAX_TAG_LEN));
+        let html = render(&text);
+        assert!(!html.contains("<span"));
+        assert!(html.contains("[color=rrr"));
+        assert_eq!(strip_all_tags(&text), format!("[color={}]x", "r".repeat(MAX_TAG_LEN)));
+    }
+
+    #[test]
+    fn markup_in_text_is_escaped() {
+        let html = render("[color=red]<script>alert(1)</script>[/color]");
+        assert!(!html.contains("<script>"), "{}", html);
+        let html = render("[color=red;background:url(x)]x[/color]");
+        assert!(!html.contains("background"), "{}", html);
+    }
+
+    #[test]
+    fn icon_tags() {
+        let html = render("[item=iron-plate] [gps=10,20]");
+        assert!(html.contains(r#"title="Item: Iron plate""#), "{}", html);
+        assert!(!html.contains("gps"));
+        assert_eq!(strip_all_tags("[item=iron-plate] Iron [gps=1,2]"), "Iron");
+    }
+
+    #[test]
+    fn random_tag_soup_never_panics() {
+        const PIECES: &[&str] = &[
+            "[color=red]", "[/color]", "[font=default-bold]", "[/font]", "[item=iron-plate]", "[img=item/coal]",
+            "[gps=1,2]", "[color=r=1,g=NaN,b=-3]", "[", "]", "[/", "=", "é", "🙂", "\n", "text", "<b>", "&",
+        ];
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+        for _ in 0..200 {
+            let len = next(&mut seed) % 200;
+            let text: String = (0..len).map(|_| PIECES[(next(&mut seed) % PIECES.len() as u64) as usize]).collect();
+            let html = render(&text);
+            assert!(html.matches("<span style").count() <= text.matches("[color=").count() + text.matches("[font=").count());
+            assert!(!html.contains("<b>"), "{}", html);
+            strip_all_tags(&text);
+        }
+    }
+
+    #[test]
+    fn rgb_colors_are_clamped() {
+        assert_eq!(parse_rgb_color("r=1,g=0.5,b=0"), "rgb(255, 127, 0)");
+        assert_eq!(parse_rgb_color("r=-1,g=-0.5,b=-1e30"), "rgb(0, 0, 0)");
+        assert_eq!(parse_rgb_color("r=2,g=1e40,b=inf"), "rgb(255, 255, 255)");
+        assert_eq!(parse_rgb_color("r=NaN,g=nan,b=-NaN"), "rgb(0, 0, 0)");
+        assert_eq!(parse_rgb_color("r=abc,g=,b"), "rgb(255, 255, 255)");
+    }
+
+    #[test]
+    fn colors_reject_css_injection() {
+        assert_eq!(factorio_color_to_css("#12abEF"), "#12abEF");
+        assert_eq!(factorio_color_to_css("#12abEF;x"), "inherit");
+        assert_eq!(factorio_color_to_css("red;background:red"), "inherit");
+        assert_eq!(factorio_color_to_css("Acid"), "#b0ff00");
+    }
+
+    #[test]
+    fn rules_headings_are_recognized() {
+        assert_eq!(rules_header_remainder("Rules"), Some(""));
+        assert_eq!(rules_header_remainder("=== Server Rules ==="), Some(""));
+        assert_eq!(rules_header_remainder("## rules:"), Some(""));
+        assert_eq!(rules_header_remainder("Rules: be nice"), Some("be nice"));
+        assert_eq!(rules_header_remainder("Rules are simple"), None);
+        assert_eq!(rules_header_remainder("Our rules"), None);
+    }
+
+    #[test]
+    fn list_markers_are_stripped() {
+        assert_eq!(strip_list_marker("- no griefing"), Some("no griefing"));
+        assert_eq!(strip_list_marker("  • be nice"), Some("be nice"));
+        assert_eq!(strip_list_marker("1. no spam"), Some("no spam"));
+        assert_eq!(strip_list_marker("12) ask first"), Some("ask first"));
+        assert_eq!(strip_list_marker("3: have fun"), Some("have fun"));
+        assert_eq!(strip_list_marker("1234. too long a number"), None);
+        assert_eq!(strip_list_marker("2024 edition"), None);
+        assert_eq!(strip_list_marker("no marker"), None);
+    }
+
+    #[test]
+    fn descriptions_without_rules_have_none() {
+        assert!(extract_rules("").is_empty());
+        assert!(extract_rules("Friendly vanilla server\n- daily resets\n- EU hosted").is_empty());
+    }
+
+    #[test]
+    fn rules_sections_are_collected() {
+        let description = "Welcome!\n\n[color=orange]Rules:[/color]\n- No griefing\n2) Be nice\n\nHave fun";
+        assert_eq!(extract_rules(description), ["No griefing", "Be nice"]);
+        assert_eq!(extract_rules("Rules: no griefing; - be nice"), ["no griefing", "be nice"]);
+        // Stops at the next section header
+        assert_eq!(extract_rules("Rules\n* No spam\nDiscord:\nlink"), ["No spam"]);
+    }
+
+    #[test]
+    fn rules_section_may_end_the_description() {
+        let description = "Megabase co-op\nServer rules\n\n1. Ask before changing the main bus\n2. No nukes";
+        assert_eq!(extract_rules(description), ["Ask before changing the main bus", "No nukes"]);
+        let many: String = (1..=30).map(|n| format!("\n- rule {}", n)).collect();
+        assert_eq!(extract_rules(&format!("Rules:{}", many)).len(), MAX_RULES);
+    }
+}
@@ -0,0 +1,235 @@
+/// Display locale for numbers and durations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+/// Abbreviations for year/day/hour/minute in a locale
+struct TimeUnits {
+    year: &'static str,
+    day: &'static str,
+    hour: &'static str,
+    minute: &'static str,
+}
+
+impl Locale {
+    /// Pick the first supported language from an Accept-Language header
+    /// e.g. "de-DE,de;q=0.9,en;q=0.8" -> Locale::De
+    pub fn from_accept_language(header: &str) -> Self {
+        header
+            .split(',')
+            .filter_map(|part| part.split(';').next())
+            .map(|lang| lang.trim().to_lowercase())
+            .find_map(|lang| match lang.split('-').next().unwrap_or("") {
+                "en" => Some(Locale::En),
+                "de" => Some(Locale::De),
+                "fr" => Some(Locale::Fr),
+                "es" => Some(Locale::Es),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn thousands_separator(&self) -> &'static str {
+        match self {
+            Locale::En => ",",
+            Locale::De | Locale::Es => ".",
+            // Narrow no-break space
+            Locale::Fr => "\u{202f}",
+        }
+    }
+
+    fn decimal_separator(&self) -> &'static str {
+        match self {
+            Locale::En => ".",
+            Locale::De | Locale::Fr | Locale::Es => ",",
+        }
+    }
+
+    fn time_units(&self) -> TimeUnits {
+        match self {
+            Locale::En => TimeUnits { year: "y", day: "d", hour: "h", minute: "m" },
+            Locale::De => TimeUnits { year: "J", day: "T", hour: "h", minute: "min" },
+            Locale::Fr => TimeUnits { year: "a", day: "j", hour: "h", minute: "min" },
+            Locale::Es => TimeUnits { year: "a", day: "d", hour: "h", minute: "min" },
+        }
+    }
+}
+
+const MINUTES_PER_HOUR: u64 = 60;
+const MINUTES_PER_DAY: u64 = 24 * MINUTES_PER_HOUR;
+const MINUTES_PER_YEAR: u64 = 365 * MINUTES_PER_DAY;
+
+/// Format elapsed game time given in minutes, e.g. "3d 4h 12m"
+pub fn format_game_time(minutes: u64) -> String {
+    format_game_time_in(minutes, Locale::En)
+}
+
+/// Format elapsed game time in minutes for a locale
+/// Shows the three most significant units: "1y 12d 3h", "3d 4h 12m", "4h 12m" ("0h 0m" for zero)
+pub fn format_game_time_in(minutes: u64, locale: Locale) -> String {
+    let units = locale.time_units();
+    let years = minutes / MINUTES_PER_YEAR;
+    let days = (minutes % MINUTES_PER_YEAR) / MINUTES_PER_DAY;
+    let hours = (minutes % MINUTES_PER_DAY) / MINUTES_PER_HOUR;
+    let mins = minutes % MINUTES_PER_HOUR;
+
+    if years > 0 {
+        format!(
+            "{}{} {}{} {}{}",
+            format_number_in(years, locale),
+            units.year,
+            days,
+            units.day,
+            hours,
+            units.hour
+        )
+    } else if days > 0 {
+        format!("{}{} {}{} {}{}", days, units.day, hours, units.hour, mins, units.minute)
+    } else {
+        format!("{}{} {}{}", hours, units.hour, mins, units.minute)
+    }
+}
+
+/// Format a duration in minutes as its largest whole unit, e.g. "3 hours", "12 days"
+pub fn format_age(minutes: u64) -> String {
+    let (value, unit) = if minutes >= MINUTES_PER_YEAR {
+        (minutes / MINUTES_PER_YEAR, "year")
+    } else if minutes >= MINUTES_PER_DAY {
+        (minutes / MINUTES_PER_DAY, "day")
+    } else if minutes >= MINUTES_PER_HOUR {
+        (minutes / MINUTES_PER_HOUR, "hour")
+    } else if minutes > 0 {
+        (minutes, "minute")
+    } else {
+        return "less than a minute".to_string();
+    };
+
+    if value == 1 {
+        format!("1 {}", unit)
+    } else {
+        format!("{} {}s", value, unit)
+    }
+}
+
+/// Format a number with thousands separators, e.g. 12345 -> "12,345"
+pub fn format_number(n: u64) -> String {
+    format_number_in(n, Locale::En)
+}
+
+/// Format a number with the locale's thousands separator
+pub fn format_number_in(n: u64, locale: Locale) -> String {
+    let digits = n.to_string();
+    let mut result = String::with_capacity(digits.len() + digits.len() / 3 * 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            result.push_str(locale.thousands_separator());
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Format a large number compactly, e.g. 1234 -> "1.2k", 2500000 -> "2.5M"
+pub fn format_compact(n: u64) -> String {
+    format_compact_in(n, Locale::En)
+}
+
+/// Format a large number compactly using the locale's decimal separator
+pub fn format_compact_in(n: u64, locale: Locale) -> String {
+    const UNITS: &[(u64, &str)] = &[(1_000_000_000, "B"), (1_000_000, "M"), (1_000, "k")];
+
+    let Some(idx) = UNITS.iter().position(|&(size, _)| n >= size) else {
+        return n.to_string();
+    };
+
+    // Rounding can push a value up to the next unit (999_960 -> "1000k" -> "1M")
+    let (mut size, mut suffix) = UNITS[idx];
+    let mut tenths = (n as f64 / size as f64 * 10.0).round() as u64;
+    if tenths >= 10_000 && idx > 0 {
+        (size, suffix) = UNITS[idx - 1];
+        tenths = (n as f64 / size as f64 * 10.0).round() as u64;
+    }
+
+    let whole = format_number_in(tenths / 10, locale);
+    if tenths.is_multiple_of(10) {
+        format!("{}{}", whole, suffix)
+    } else {
+        format!("{}{}{}{}", whole, locale.decimal_separator(), tenths % 10, suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_time_zero_minutes() {
+        assert_eq!(format_game_time(0), "0h 0m");
+        assert_eq!(format_game_time_in(0, Locale::De), "0h 0min");
+        assert_eq!(format_game_time(59), "0h 59m");
+    }
+
+    #[test]
+    fn game_time_shows_three_largest_units() {
+        assert_eq!(format_game_time(MINUTES_PER_DAY), "1d 0h 0m");
+        assert_eq!(format_game_time(3 * MINUTES_PER_DAY + 4 * MINUTES_PER_HOUR + 12), "3d 4h 12m");
+        assert_eq!(format_game_time_in(4 * MINUTES_PER_HOUR + 12, Locale::Fr), "4h 12min");
+    }
+
+    #[test]
+    fn game_time_over_a_year() {
+        let minutes = MINUTES_PER_YEAR + 12 * MINUTES_PER_DAY + 3 * MINUTES_PER_HOUR + 59;
+        assert_eq!(format_game_time(minutes), "1y 12d 3h");
+        assert_eq!(format_game_time_in(minutes, Locale::De), "1J 12T 3h");
+        assert_eq!(format_game_time(MINUTES_PER_YEAR - 1), "364d 23h 59m");
+        assert_eq!(format_game_time(1000 * MINUTES_PER_YEAR), "1,000y 0d 0h");
+        assert_eq!(format_game_time_in(1000 * MINUTES_PER_YEAR, Locale::Es), "1.000a 0d 0h");
+    }
+
+    #[test]
+    fn age_uses_largest_unit() {
+        assert_eq!(format_age(0), "less than a minute");
+        assert_eq!(format_age(1), "1 minute");
+        assert_eq!(format_age(MINUTES_PER_DAY * 12), "12 days");
+        assert_eq!(format_age(MINUTES_PER_YEAR * 2 + 5), "2 years");
+    }
+
+    #[test]
+    fn number_separators() {
+        assert_eq!(format_number(0), "0");
+        assert_eq!(format_number(999), "999");
+        assert_eq!(format_number(1000), "1,000");
+        assert_eq!(format_number_in(1_234_567, Locale::De), "1.234.567");
+        assert_eq!(format_number_in(12_345, Locale::Fr), "12\u{202f}345");
+        assert_eq!(format_number(u64::MAX), "18,446,744,073,709,551,615");
+    }
+
+    #[test]
+    fn compact_numbers() {
+        assert_eq!(format_compact(0), "0");
+        assert_eq!(format_compact(999), "999");
+        assert_eq!(format_compact(1000), "1k");
+        assert_eq!(format_compact(1234), "1.2k");
+        assert_eq!(format_compact_in(1234, Locale::De), "1,2k");
+        assert_eq!(format_compact(2_500_000), "2.5M");
+        assert_eq!(format_compact(u64::MAX), "18,446,744,073.7B");
+    }
+
+    #[test]
+    fn compact_rounding_moves_to_next_unit() {
+        assert_eq!(format_compact(999_960), "1M");
+        assert_eq!(format_compact(999_949), "999.9k");
+    }
+
+    #[test]
+    fn locale_from_accept_language() {
+        assert_eq!(Locale::from_accept_language("de-DE,de;q=0.9,en;q=0.8"), Locale::De);
+        assert_eq!(Locale::from_accept_language("ja, fr;q=0.5"), Locale::Fr);
+        assert_eq!(Locale::from_accept_language(""), Locale::En);
+    }
+}
@@ -0,0 +1,168 @@
+use std::cmp::Ordering;
+
+/// A game version as a server reports it, e.g. "2.0.72", ordered the way Factorio numbers releases
+/// Compared number by number, so "2.0.10" is newer than "2.0.8"; unlike semver this also takes
+/// missing or extra parts ("2.0", "1.1.110.1") and suffixes ("1.1.110-1", "2.0.8-experimental"),
+/// which sort right after the plain version. build_version breaks the remaining ties, and strings
+/// without a leading number sort oldest
+#[derive(Debug, Clone, Default)]
+pub struct GameVersion {
+    /// Leading dot-separated numbers; missing ones count as 0, so "2.0" equals "2.0.0"
+    numbers: Vec<u64>,
+    /// Whatever follows the numbers, e.g. "-1"
+    suffix: String,
+    build: u32,
+}
+
+impl GameVersion {
+    /// Parse any version string; nothing is rejected, odd parts just end up in the suffix
+    pub fn parse(version: &str) -> Self {
+        let mut numbers = Vec::new();
+        let mut rest = version.trim();
+        loop {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                break;
+            }
+            numbers.push(rest[..digits].parse().unwrap_or(u64::MAX));
+            rest = &rest[digits..];
+            match rest.strip_prefix('.') {
+                Some(after) if after.starts_with(|c: char| c.is_ascii_digit()) => rest = after,
+                _ => break,
+            }
+        }
+        Self {
+            numbers,
+            suffix: rest.to_string(),
+            build: 0,
+        }
+    }
+
+    /// Break ties between otherwise equal versions by build number
+    pub fn with_build(mut self, build: u32) -> Self {
+        self.build = build;
+        self
+    }
+
+    /// Whether the version starts with a number, as every real release does
+    pub fn is_numbered(&self) -> bool {
+        !self.numbers.is_empty()
+    }
+
+    /// The major.minor release series, e.g. "2.0" for "2.0.72" (the whole string without numbers)
+    pub fn series(&self) -> String {
+        if self.numbers.is_empty() {
+            return self.suffix.clone();
+        }
+        let mut parts: Vec<String> = self.numbers.iter().take(2).map(u64::to_string).collect();
+        parts.resize(2, "0".to_string());
+        parts.join(".")
+    }
+
+    /// Whether this version belongs to `prefix`'s release, part by part: "2.0.1" and "2.0.1-1"
+    /// are under "2.0" and "2.0.1", but "2.0.10" isn't under "2.0.1"
+    pub fn starts_with(&self, prefix: &GameVersion) -> bool {
+        !prefix.numbers.is_empty()
+            && self.numbers.starts_with(&prefix.numbers)
+            && (prefix.suffix.is_empty() || self.numbers.len() == prefix.numbers.len() && self.suffix == prefix.suffix)
+    }
+
+    fn number(&self, i: usize) -> u64 {
+        self.numbers.get(i).copied().unwrap_or(0)
+    }
+}
+
+impl Ord for GameVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.numbers.len().max(other.numbers.len());
+        other
+            .numbers
+            .is_empty()
+            .cmp(&self.numbers.is_empty())
+            .then_with(|| {
+                (0..len)
+                    .map(|i| self.number(i).cmp(&other.number(i)))
+                    .find(|order| order.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .then_with(|| self.suffix.cmp(&other.suffix))
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for GameVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for GameVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for GameVersion {}
+
+/// Sort version strings newest first
+pub fn sort_newest_first(versions: &mut [String]) {
+    versions.sort_by_cached_key(|v| std::cmp::Reverse(GameVersion::parse(v)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(version: &str) -> GameVersion {
+        GameVersion::parse(version)
+    }
+
+    #[test]
+    fn compares_number_by_number() {
+        assert!(v("2.0.8") < v("2.0.10"));
+        assert!(v("1.1.110") < v("2.0.0"));
+        assert!(v("2.0.72") > v("2.0.9"));
+    }
+
+    #[test]
+    fn suffix_sorts_after_plain_version() {
+        assert!(v("1.1.110") < v("1.1.110-1"));
+        assert!(v("1.1.110-1") < v("1.1.111"));
+    }
+
+    #[test]
+    fn missing_parts_count_as_zero() {
+        assert_eq!(v("2.0"), v("2.0.0"));
+        assert!(v("2.0") < v("2.0.1"));
+        assert_eq!(v("2.0").series(), "2.0");
+        assert_eq!(v("2").series(), "2.0");
+    }
+
+    #[test]
+    fn unnumbered_strings_sort_oldest() {
+        assert!(!v("unknown").is_numbered());
+        assert!(v("unknown") < v("0.0.1"));
+        assert!(v("") < v("0.12.35"));
+
+        let mut versions = vec!["beta".to_string(), "2.0.8".to_string(), "2.0.10".to_string(), "1.1.110".to_string()];
+        sort_newest_first(&mut versions);
+        assert_eq!(versions, ["2.0.10", "2.0.8", "1.1.110", "beta"]);
+    }
+
+    #[test]
+    fn build_breaks_ties() {
+        assert!(v("2.0.8").with_build(80000) < v("2.0.8").with_build(80100));
+        assert_ne!(v("2.0.8").with_build(1), v("2.0.8").with_build(2));
+        // The build never outranks the version itself
+        assert!(v("2.0.8").with_build(u32::MAX) < v("2.0.9").with_build(0));
+    }
+
+    #[test]
+    fn starts_with_matches_whole_parts() {
+        assert!(v("2.0.1").starts_with(&v("2.0.1")));
+        assert!(v("2.0.1-1").starts_with(&v("2.0.1")));
+        assert!(v("2.0.10").starts_with(&v("2.0")));
+        assert!(!v("2.0.10").starts_with(&v("2.0.1")));
+        assert!(!v("2.0.1").starts_with(&v("beta")));
+    }
+}
@@ -0,0 +1,262 @@
+use crate::db::models::CachedServer;
+use crate::impostor::Imitated;
+use crate::utils::format::{format_age, format_game_time};
+use crate::utils::strip_all_tags;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Days a server must sit empty, and have been listed, before it counts as inactive
+pub const INACTIVE_DAYS: i64 = 7;
+
+/// Player load of a server, used to pick status colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlayerStatus {
+    /// 80%+ of slots taken
+    Full,
+    /// 50%+ of slots taken
+    Medium,
+    /// Has players
+    Low,
+    /// Nobody online
+    Empty,
+}
+
+impl PlayerStatus {
+    /// Tailwind text color class for this status
+    pub fn text_class(&self) -> &'static str {
+        match self {
+            PlayerStatus::Full => "text-status-full",
+            PlayerStatus::Medium => "text-status-medium",
+            PlayerStatus::Low => "text-status-low",
+            PlayerStatus::Empty => "text-status-empty",
+        }
+    }
+
+    /// Tailwind background color class for this status
+    pub fn bg_class(&self) -> &'static str {
+        match self {
+            PlayerStatus::Full => "bg-status-full",
+            PlayerStatus::Medium => "bg-status-medium",
+            PlayerStatus::Low => "bg-status-low",
+            PlayerStatus::Empty => "bg-status-empty",
+        }
+    }
+}
+
+/// Short labels describing notable server properties
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerBadge {
+    Password,
+    Dedicated,
+    Modded,
+    Vanilla,
+    /// The host IP has many abuse reports (see ABUSEIPDB_API_KEY), e.g. an impostor using a popular name
+    Reported,
+    /// A new listing named like a long-established verified server on another host
+    Impostor,
+}
+
+impl ServerBadge {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServerBadge::Password => "Password Protected",
+            ServerBadge::Dedicated => "Dedicated",
+            ServerBadge::Modded => "Modded",
+            ServerBadge::Vanilla => "Vanilla",
+            ServerBadge::Reported => "Reported Host",
+            ServerBadge::Impostor => "Possible Impostor",
+        }
+    }
+}
+
+/// Display-ready server read model, computed once per refresh from the cached row
+/// Components, the API, and exports should use these fields instead of formatting themselves
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerView {
+    #[serde(flatten)]
+    pub server: CachedServer,
+    /// Elapsed game time, e.g. "3d 4h 12m"
+    pub game_time: String,
+    /// Player count as "current/max", or "current/∞" without a player limit
+    pub players_display: String,
+    /// Percentage of slots taken (always 0 without a player limit)
+    pub player_ratio: u32,
+    /// No player limit (the API reports max_players 0)
+    pub unlimited: bool,
+    pub status: PlayerStatus,
+    /// "12 mods" or "Vanilla"
+    pub mods_display: String,
+    pub badges: Vec<ServerBadge>,
+    /// Tags with rich text stripped, trimmed, and deduplicated
+    pub display_tags: Vec<String>,
+    /// Highest player count in the last 24 hours, when history is available
+    pub peak_players: Option<usize>,
+    /// How long the server has been listed, e.g. "12 days", from first_seen
+    pub listed_for: Option<String>,
+    /// Ghost listing: empty now, listed for at least INACTIVE_DAYS, and nobody seen on it in that time
+    pub inactive: bool,
+    /// Fingerprint of the exact mod set, once the server's mods have been looked up
+    pub modpack: Option<String>,
+    /// Share of the last week the server was listed, in percent (None without presence data)
+    pub uptime: Option<f64>,
+    /// Abuse reports against the host IP, set only when there are enough to warn about
+    pub abuse_reports: Option<u32>,
+    /// The verified server this listing's name imitates (see impostor::find_impostors)
+    pub impostor_of: Option<Imitated>,
+    /// Ranking for the "Recommended" sort (see ranking::score), higher first
+    pub score: i64,
+    /// Players gained over the last hour's average (negative when the server is emptying)
+    pub trend: i64,
+    /// Score under each ranking experiment variant, in the experiment's variant order
+    #[serde(skip)]
+    pub variant_scores: Vec<i64>,
+}
+
+impl ServerView {
+    /// Attach the 24h peak from history, never lower than the current count
+    pub fn with_peak(mut self, peak: Option<usize>) -> Self {
+        self.peak_players = peak.map(|p| p.max(self.server.player_count));
+        self
+    }
+
+    pub fn with_uptime(mut self, uptime: Option<f64>) -> Self {
+        self.uptime = uptime;
+        self
+    }
+
+    pub fn with_modpack(mut self, modpack: Option<String>) -> Self {
+        self.modpack = modpack;
+        self
+    }
+
+    pub fn with_impostor_of(mut self, imitated: Option<Imitated>) -> Self {
+        if imitated.is_some() {
+            self.badges.push(ServerBadge::Impostor);
+        }
+        self.impostor_of = imitated;
+        self
+    }
+
+    /// Flag the host as reported when its IP has at least `threshold` abuse reports
+    pub fn with_abuse_reports(mut self, reports: Option<u32>, threshold: u32) -> Self {
+        self.abuse_reports = reports.filter(|&r| r >= threshold);
+        if self.abuse_reports.is_some() {
+            self.badges.push(ServerBadge::Reported);
+        }
+        self
+    }
+
+    /// Compare the current player count with the average over the last hour
+    pub fn with_trend(mut self, average: Option<f64>) -> Self {
+        self.trend = average.map(|a| (self.server.player_count as f64 - a).round() as i64).unwrap_or(0);
+        self
+    }
+
+    pub fn with_score(mut self, score: i64, variant_scores: Vec<i64>) -> Self {
+        self.score = score;
+        self.variant_scores = variant_scores;
+        self
+    }
+
+    /// Mark the server inactive unless it had players in the last INACTIVE_DAYS
+    /// Servers listed for less than that are never inactive, since their history is too short to tell
+    pub fn with_activity(mut self, seen_recently: bool) -> Self {
+        let listed_long_enough = self
+            .server
+            .first_seen
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| chrono::Utc::now() - t.with_timezone(&chrono::Utc) >= chrono::Duration::days(INACTIVE_DAYS));
+        self.inactive = self.server.player_count == 0 && !seen_recently && listed_long_enough;
+        self
+    }
+}
+
+impl From<CachedServer> for ServerView {
+    fn from(server: CachedServer) -> Self {
+        let unlimited = server.max_players == 0;
+        // Unlimited servers are never full, so status only says whether anyone is on
+        let player_ratio = if !unlimited {
+            (server.player_count as f32 / server.max_players as f32 * 100.0) as u32
+        } else {
+            0
+        };
+
+        let status = if player_ratio >= 80 {
+            PlayerStatus::Full
+        } else if player_ratio >= 50 {
+            PlayerStatus::Medium
+        } else if server.player_count > 0 {
+            PlayerStatus::Low
+        } else {
+            PlayerStatus::Empty
+        };
+
+        // API returns game time in minutes
+        let game_time = format_game_time(server.game_time_elapsed);
+
+        let mods_display = if server.mod_count > 0 {
+            format!("{} mods", server.mod_count)
+        } else {
+            "Vanilla".to_string()
+        };
+
+        let mut badges = Vec::new();
+        if server.has_password {
+            badges.push(ServerBadge::Password);
+        }
+        if server.headless_server {
+            badges.push(ServerBadge::Dedicated);
+        }
+        badges.push(if server.mod_count > 0 {
+            ServerBadge::Modded
+        } else {
+            ServerBadge::Vanilla
+        });
+
+        let mut seen = HashSet::new();
+        let display_tags = server
+            .tags
+            .iter()
+            .map(|t| strip_all_tags(t))
+            .filter(|t| !t.is_empty() && seen.insert(t.to_lowercase()))
+            .collect();
+
+        let listed_for = server
+            .first_seen
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| {
+                let minutes = (chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_minutes();
+                format_age(minutes.max(0) as u64)
+            });
+
+        Self {
+            players_display: if unlimited {
+                format!("{}/∞", server.player_count)
+            } else {
+                format!("{}/{}", server.player_count, server.max_players)
+            },
+            server,
+            game_time,
+            player_ratio,
+            unlimited,
+            status,
+            mods_display,
+            badges,
+            display_tags,
+            peak_players: None,
+            listed_for,
+            inactive: false,
+            modpack: None,
+            uptime: None,
+            abuse_reports: None,
+            impostor_of: None,
+            score: 0,
+            trend: 0,
+            variant_scores: Vec::new(),
+        }
+    }
+}
@@ -41,6 +41,14 @@
     });
 })();
 
+// Share the visitor's UTC offset (minutes) so the server can localize times
+(function() {
+    try {
+        const offset = -new Date().getTimezoneOffset();
+        document.cookie = `tz_offset=${offset}; path=/; max-age=31536000; SameSite=Lax`;
+    } catch (e) {}
+})();
+
 // Tag filtering toggle function
 function toggleTag(tag) {
     const input = document.getElementById('tags-input');
@@ -62,40 +70,70 @@ function toggleTag(tag) {
 }
 
 // Client-side sorting and view toggle for server list
+// The current sort and view are mirrored into the URL (and the filter links) so the page can be bookmarked as seen
 (function() {
-    const grid = document.querySelector('.server-grid');
+    // The hydrated list (hydrate.wasm) sorts and switches views itself
+    if (document.querySelector('#server-list[data-hydrate]')) return;
+
+    // One grid per group when the list is grouped
+    const grids = document.querySelectorAll('.server-grid');
     const sortButtons = document.querySelectorAll('.sort-button');
     const viewButtons = document.querySelectorAll('.view-btn');
+    // With more than one page, sorting has to happen on the server across every page
+    const paged = document.querySelector('.pager') !== null;
     
-    if (!grid) return;
+    if (!grids.length) return;
     
     const STORAGE_KEY_VIEW = 'factorio-browser-view';
-    const STORAGE_KEY_SORT = 'factorio-browser-sort';
+    // Bumped when Recommended became the default, since the old default (players) was saved for everyone
+    const STORAGE_KEY_SORT = 'factorio-browser-sort-v2';
+    const DEFAULT_SORT = 'score';
+    const DEFAULT_DIR = 'desc';
+    const DEFAULT_VIEW = 'grid';
+
+    let currentSort = DEFAULT_SORT;
+    let currentDir = DEFAULT_DIR;
+    let currentView = DEFAULT_VIEW;
     
-    // Load saved preferences
+    // Load preferences: the URL wins (a shared or bookmarked link), then what this visitor picked last
     function loadPreferences() {
+        const params = new URLSearchParams(window.location.search);
+        let view = params.get('view');
+        let sortBy = params.get('sort');
+        let dir = params.get('dir');
+
         try {
-            // Load view preference
-            const savedView = localStorage.getItem(STORAGE_KEY_VIEW);
-            if (savedView === 'list') {
-                setView('list');
+            if (!view) {
+                view = localStorage.getItem(STORAGE_KEY_VIEW);
             }
-            
-            // Load sort preference
-            const savedSort = localStorage.getItem(STORAGE_KEY_SORT);
-            if (savedSort) {
-                const [sortBy, dir] = savedSort.split(':');
-                if (sortBy && dir) {
-                    applySort(sortBy, dir);
-                    return;
+            if (!sortBy && !dir) {
+                const savedSort = localStorage.getItem(STORAGE_KEY_SORT);
+                if (savedSort) {
+                    [sortBy, dir] = savedSort.split(':');
                 }
             }
         } catch (e) {
             // localStorage not available
         }
-        
-        // Default sort
-        applySort('players', 'desc');
+
+        setView(view === 'list' ? 'list' : DEFAULT_VIEW, false);
+        sortBy = sortBy || DEFAULT_SORT;
+        dir = dir === 'asc' ? 'asc' : DEFAULT_DIR;
+        if (paged && (sortBy !== currentSort || dir !== currentDir) && !params.get('sort') && !params.get('dir')) {
+            reloadSorted(sortBy, dir);
+            return;
+        }
+        applySort(sortBy, dir, false);
+    }
+
+    // Fetch the first page in the new order
+    function reloadSorted(sortBy, dir) {
+        currentSort = sortBy;
+        currentDir = dir;
+        const params = new URLSearchParams(window.location.search);
+        writeParams(params);
+        params.delete('page');
+        window.location.search = params.toString();
     }
     
     // Save preferences
@@ -110,30 +148,100 @@ function toggleTag(tag) {
             localStorage.setItem(STORAGE_KEY_SORT, `${sortBy}:${dir}`);
         } catch (e) {}
     }
+
+    // Put the sort and view into params, leaving defaults out
+    function writeParams(params) {
+        const values = {
+            sort: currentSort === DEFAULT_SORT ? null : currentSort,
+            dir: currentDir === DEFAULT_DIR ? null : currentDir,
+            view: currentView === DEFAULT_VIEW ? null : currentView,
+        };
+        Object.entries(values).forEach(([name, value]) => {
+            if (value) {
+                params.set(name, value);
+            } else {
+                params.delete(name);
+            }
+        });
+    }
+
+    // Keep the address bar, filter links and filter form in step with what's on screen
+    function syncUrl() {
+        const params = new URLSearchParams(window.location.search);
+        writeParams(params);
+        // Columns the page was rendered with, when not the defaults (possibly from the cookie)
+        const columns = document.querySelector('.list-param[name="columns"]');
+        if (columns) params.set('columns', columns.value);
+        const query = params.toString();
+        history.replaceState(history.state, '', query ? `/?${query}` : '/');
+
+        // Sort links go to the first page in their order, flipping the direction of the active one
+        sortButtons.forEach(btn => {
+            const url = new URL(btn.href);
+            const sortBy = btn.dataset.sort;
+            const dir = sortBy === currentSort && currentDir === 'desc' ? 'asc' : 'desc';
+            url.searchParams.delete('page');
+            if (sortBy === DEFAULT_SORT) url.searchParams.delete('sort'); else url.searchParams.set('sort', sortBy);
+            if (dir === DEFAULT_DIR) url.searchParams.delete('dir'); else url.searchParams.set('dir', dir);
+            if (currentView === DEFAULT_VIEW) url.searchParams.delete('view'); else url.searchParams.set('view', currentView);
+            const linkQuery = url.searchParams.toString();
+            btn.setAttribute('href', linkQuery ? `/?${linkQuery}` : '/');
+        });
+
+        document.querySelectorAll('.pager a').forEach(link => {
+            const url = new URL(link.href);
+            writeParams(url.searchParams);
+            const linkQuery = url.searchParams.toString();
+            link.setAttribute('href', linkQuery ? `/?${linkQuery}` : '/');
+        });
+
+        const form = document.getElementById('filter-form');
+        if (!form) return;
+        form.querySelectorAll('a[href^="/?"], a[href="/"]').forEach(link => {
+            const url = new URL(link.href);
+            writeParams(url.searchParams);
+            const linkQuery = url.searchParams.toString();
+            link.setAttribute('href', linkQuery ? `/?${linkQuery}` : '/');
+        });
+        form.querySelectorAll('.list-param').forEach(input => {
+            if (['sort', 'dir', 'view'].includes(input.name)) input.remove();
+        });
+        const formParams = new URLSearchParams();
+        writeParams(formParams);
+        formParams.forEach((value, name) => {
+            const input = document.createElement('input');
+            input.type = 'hidden';
+            input.className = 'list-param';
+            input.name = name;
+            input.value = value;
+            form.appendChild(input);
+        });
+    }
     
     // View toggle
-    function setView(view) {
-        if (view === 'list') {
-            grid.classList.add('list-view');
-        } else {
-            grid.classList.remove('list-view');
-        }
+    function setView(view, save) {
+        currentView = view;
+        grids.forEach(grid => grid.classList.toggle('list-view', view === 'list'));
         
         viewButtons.forEach(btn => {
             btn.classList.toggle('active', btn.dataset.view === view);
         });
         
-        saveViewPref(view);
+        if (save) saveViewPref(view);
+        syncUrl();
     }
     
     viewButtons.forEach(btn => {
         btn.addEventListener('click', () => {
-            setView(btn.dataset.view);
+            setView(btn.dataset.view, true);
         });
     });
     
     // Sorting
-    function applySort(sortBy, dir) {
+    function applySort(sortBy, dir, save) {
+        currentSort = sortBy;
+        currentDir = dir;
+
         // Update button states
         sortButtons.forEach(btn => {
             const isActive = btn.dataset.sort === sortBy;
@@ -144,11 +252,14 @@ function toggleTag(tag) {
         
         // Sort the items
         sortItems(sortBy, dir);
-        saveSortPref(sortBy, dir);
+        if (save) saveSortPref(sortBy, dir);
+        syncUrl();
     }
     
     sortButtons.forEach(btn => {
-        btn.addEventListener('click', () => {
+        btn.addEventListener('click', (e) => {
+            // The links sort on the server; here a single page is simply reordered in place
+            e.preventDefault();
             const sortBy = btn.dataset.sort;
             const wasActive = btn.classList.contains('active');
             
@@ -160,11 +271,20 @@ function toggleTag(tag) {
                 dir = 'desc';
             }
             
-            applySort(sortBy, dir);
+            if (paged) {
+                saveSortPref(sortBy, dir);
+                reloadSorted(sortBy, dir);
+                return;
+            }
+            applySort(sortBy, dir, true);
         });
     });
     
     function sortItems(sortBy, dir) {
+        grids.forEach(grid => sortGrid(grid, sortBy, dir));
+    }
+    
+    function sortGrid(grid, sortBy, dir) {
         const items = Array.from(grid.querySelectorAll('.server-item'));
         
         items.sort((a, b) => {
@@ -176,7 +296,7 @@ function toggleTag(tag) {
                 const cmp = aVal.localeCompare(bVal);
                 return dir === 'desc' ? cmp : -cmp;
             } else {
-                // Numeric comparison for players, time, etc.
+                // Numeric comparison for score, players, time, etc.
                 const aVal = parseInt(a.dataset[sortBy]) || 0;
                 const bVal = parseInt(b.dataset[sortBy]) || 0;
                 return dir === 'desc' ? bVal - aVal : aVal - bVal;
@@ -190,3 +310,134 @@ function toggleTag(tag) {
     // Initialize
     loadPreferences();
 })();
+
+// List view column picker: store the selection in a cookie and re-render with it in the URL
+(function() {
+    const toggles = document.querySelectorAll('.column-toggle');
+    if (!toggles.length || document.querySelector('#server-list[data-hydrate]')) return;
+
+    toggles.forEach(toggle => {
+        toggle.addEventListener('change', () => {
+            const selected = Array.from(toggles)
+                .filter(t => t.checked)
+                .map(t => t.value);
+            document.cookie = `list_columns=${selected.join(',')}; path=/; max-age=31536000; SameSite=Lax`;
+            const params = new URLSearchParams(window.location.search);
+            params.set('columns', selected.join(','));
+            window.location.search = params.toString();
+        });
+    });
+})();
+
+// Latency column: estimate the round trip from the visitor's time zone and each host's continent
+(function() {
+    const header = document.querySelector('[data-rtt]');
+    if (!header) return;
+
+    const southAmerica = ['Argentina', 'Sao_Paulo', 'Santiago', 'Lima', 'Bogota', 'Caracas', 'Montevideo', 'Asuncion', 'La_Paz', 'Guayaquil', 'Cayenne', 'Paramaribo', 'Manaus', 'Recife', 'Fortaleza', 'Belem', 'Cuiaba', 'Porto_Velho'];
+    let visitor = null;
+    try {
+        const [area, city] = (Intl.DateTimeFormat().resolvedOptions().timeZone || '').split('/');
+        visitor = {
+            Europe: 'EU', Atlantic: 'EU', Asia: 'AS', Indian: 'AS', Australia: 'OC', Pacific: 'OC', Africa: 'AF',
+            America: southAmerica.includes(city) ? 'SA' : 'NA',
+        }[area] || null;
+    } catch (e) {}
+    if (!visitor) return;
+
+    const rtt = {};
+    header.dataset.rtt.split(',').forEach(entry => {
+        const [a, b, ms] = entry.split(':');
+        rtt[`${a}:${b}`] = rtt[`${b}:${a}`] = ms;
+    });
+    document.querySelectorAll('.latency-cell[data-continent]').forEach(cell => {
+        const ms = rtt[`${visitor}:${cell.dataset.continent}`];
+        if (!ms) return;
+        cell.textContent = `~${ms} ms`;
+        cell.title = 'Estimated from your time zone and the host\'s continent';
+    });
+})();
+
+// Push notifications: register the service worker and send the browser's subscription to the server
+(function() {
+    const panel = document.getElementById('push-panel');
+    if (!panel || !('serviceWorker' in navigator) || !('PushManager' in window)) return;
+    panel.classList.remove('hidden');
+
+    const enable = document.getElementById('push-enable');
+    const disable = document.getElementById('push-disable');
+    const friends = document.getElementById('push-friends');
+    const status = document.getElementById('push-status');
+    const FRIENDS_KEY = 'factorio-browser-push-friends';
+    friends.value = friends.value || localStorage.getItem(FRIENDS_KEY) || '';
+
+    // The VAPID key is base64url; subscribe() wants raw bytes
+    function keyBytes(base64) {
+        const padded = base64.replace(/-/g, '+').replace(/_/g, '/') + '='.repeat((4 - base64.length % 4) % 4);
+        return Uint8Array.from(atob(padded), c => c.charCodeAt(0));
+    }
+
+    function showSubscribed(subscribed) {
+        enable.textContent = subscribed ? 'Update' : 'Enable notifications';
+        disable.classList.toggle('hidden', !subscribed);
+    }
+
+    const registration = navigator.serviceWorker.register('/sw.js');
+    registration
+        .then(reg => reg.pushManager.getSubscription())
+        .then(sub => showSubscribed(!!sub))
+        .catch(() => {});
+
+    enable.addEventListener('click', async () => {
+        status.textContent = '';
+        try {
+            const reg = await registration;
+            if (await Notification.requestPermission() !== 'granted') {
+                status.textContent = 'Notifications are blocked in this browser.';
+                return;
+            }
+            const sub = await reg.pushManager.getSubscription() || await reg.pushManager.subscribe({
+                userVisibleOnly: true,
+                applicationServerKey: keyBytes(panel.dataset.publicKey),
+            });
+            localStorage.setItem(FRIENDS_KEY, friends.value);
+            const body = Object.assign(sub.toJSON(), {
+                friends: friends.value.split(',').map(f => f.trim()).filter(f => f),
+            });
+            const response = await fetch('/push/subscribe', {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify(body),
+            });
+            if (!response.ok) throw new Error(response.status);
+            status.textContent = 'Notifications on.';
+            showSubscribed(true);
+        } catch (e) {
+            status.textContent = 'Could not turn on notifications.';
+        }
+    });
+
+    disable.addEventListener('click', async () => {
+        try {
+            const sub = await (await registration).pushManager.getSubscription();
+            if (sub) await sub.unsubscribe();
+            await fetch('/push/unsubscribe', { method: 'POST' });
+            status.textContent = 'Notifications off.';
+            showSubscribed(false);
+        } catch (e) {
+            status.textContent = 'Could not turn off notifications.';
+        }
+    });
+})();
+
+// Server pages render one tab at a time: load the tab a #fragment names (e.g. /server/123#mods)
+(function() {
+    const tabs = document.querySelector('.details-tabs');
+    const key = location.hash.slice(1);
+    if (!tabs || !key) return;
+    const params = new URLSearchParams(location.search);
+    if ((params.get('tab') || 'overview') === key) return;
+    const link = Array.from(tabs.querySelectorAll('[role="tab"]'))
+        .find(a => (new URL(a.href).searchParams.get('tab') || 'overview') === key);
+    if (link) location.replace(link.href + location.hash);
+})();
@@ -0,0 +1,25 @@
+// Service worker for push notifications (favorites coming online, friends joining)
+// Payloads are {title, body, url}, sent by the refresh loop
+
+self.addEventListener('push', event => {
+    if (!event.data) return;
+    let message;
+    try {
+        message = event.data.json();
+    } catch (e) {
+        return;
+    }
+    event.waitUntil(
+        self.registration.showNotification(message.title, {
+            body: message.body,
+            icon: '/static/favicon.svg',
+            data: { url: message.url || '/' },
+        })
+    );
+});
+
+self.addEventListener('notificationclick', event => {
+    event.notification.close();
+    const url = (event.notification.data && event.notification.data.url) || '/';
+    event.waitUntil(self.clients.openWindow(url));
+});